@@ -3,13 +3,25 @@ use crate::{
     domain::{Gas, YoctoNear, TGAS},
     interface,
 };
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
 
 /// min contract balance required above the contract's locked balance used for storage staking to
 /// ensure the contract is operational
 pub const CONTRACT_MIN_OPERATIONAL_BALANCE: YoctoNear = YoctoNear(YOCTO);
 
-#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, Copy)]
+/// the staked NEAR balance can tick down by a few yoctoNEAR because of how staking pools round when
+/// converting NEAR into shares - a drop beyond the tolerance is treated as a staking loss
+pub const STAKED_NEAR_ROUNDING_TOLERANCE: YoctoNear = YoctoNear(1_000_000_000_000);
+
+/// max number of accounts that can be exempted from a fee
+/// - the exemption allowlists are stored in contract state, which the contract owner pays for
+pub const MAX_FEE_EXEMPT_ACCOUNTS: usize = 20;
+
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone)]
 pub struct Config {
     storage_cost_per_byte: YoctoNear,
     gas_config: GasConfig,
@@ -18,6 +30,33 @@ pub struct Config {
     /// - the rest of the contract earnings are staked to boost the staking rewards for user accounts
     /// - must be a number between 0-100
     contract_owner_earnings_percentage: u8,
+
+    keeper_reward: KeeperRewardConfig,
+
+    /// fee that is charged for [instant_redeem](crate::interface::StakingService::instant_redeem)
+    /// - the fee is specified in basis points, i.e., 1/100th of a percent
+    /// - must be a number between 0-1000, i.e., max fee is 10%
+    instant_redeem_fee_basis_points: u16,
+
+    /// protocol fee that is charged on staking rewards - the fee is minted as STAKE to the treasury
+    /// - the fee is specified in basis points, i.e., 1/100th of a percent
+    /// - must be a number between 0-2000, i.e., max fee is 20%
+    /// - zero disables the fee
+    performance_fee_basis_points: u16,
+
+    /// percentage of contract earnings that are set aside to fund the [InsuranceBuffer](crate::domain::InsuranceBuffer),
+    /// which absorbs staking losses before they are passed on to STAKE holders
+    /// - must be a number between 0-100
+    /// - zero disables funding the insurance buffer
+    insurance_buffer_earnings_percentage: u8,
+
+    /// fees that are charged on deposit, redeem, withdraw and ft_transfer - see [FeeSchedule]
+    fee_schedule: FeeSchedule,
+
+    /// number of epochs after which a batch receipt that has not been fully claimed can be settled
+    /// on behalf of the accounts via [settle_receipts](crate::interface::Keeper::settle_receipts)
+    /// - must be > 0
+    receipt_expiry_epochs: u32,
 }
 
 impl Default for Config {
@@ -28,6 +67,13 @@ impl Default for Config {
             storage_cost_per_byte: 100_000_000_000_000_000_000.into(),
             gas_config: GasConfig::default(),
             contract_owner_earnings_percentage: 50,
+            keeper_reward: KeeperRewardConfig::default(),
+            instant_redeem_fee_basis_points: 30,
+            performance_fee_basis_points: 0,
+            insurance_buffer_earnings_percentage: 0,
+            fee_schedule: FeeSchedule::default(),
+            // ~90 days
+            receipt_expiry_epochs: 180,
         }
     }
 }
@@ -48,6 +94,45 @@ impl Config {
         self.contract_owner_earnings_percentage
     }
 
+    pub fn keeper_reward(&self) -> KeeperRewardConfig {
+        self.keeper_reward
+    }
+
+    pub fn instant_redeem_fee_basis_points(&self) -> u16 {
+        self.instant_redeem_fee_basis_points
+    }
+
+    /// computes the instant redeem fee for the specified NEAR amount
+    pub fn instant_redeem_fee(&self, amount: YoctoNear) -> YoctoNear {
+        (amount.value() / 10_000 * self.instant_redeem_fee_basis_points as u128).into()
+    }
+
+    pub fn performance_fee_basis_points(&self) -> u16 {
+        self.performance_fee_basis_points
+    }
+
+    /// computes the performance fee for the specified NEAR staking rewards
+    pub fn performance_fee(&self, rewards: YoctoNear) -> YoctoNear {
+        (rewards.value() / 10_000 * self.performance_fee_basis_points as u128).into()
+    }
+
+    pub fn insurance_buffer_earnings_percentage(&self) -> u8 {
+        self.insurance_buffer_earnings_percentage
+    }
+
+    /// computes the share of the earnings that is set aside for the insurance buffer
+    pub fn insurance_buffer_funding(&self, earnings: YoctoNear) -> YoctoNear {
+        (earnings.value() / 100 * self.insurance_buffer_earnings_percentage as u128).into()
+    }
+
+    pub fn fee_schedule(&self) -> &FeeSchedule {
+        &self.fee_schedule
+    }
+
+    pub fn receipt_expiry_epochs(&self) -> u32 {
+        self.receipt_expiry_epochs
+    }
+
     /// ## Panics
     /// if validation fails
     pub fn merge(&mut self, config: interface::Config) {
@@ -61,6 +146,42 @@ impl Config {
         if let Some(gas_config) = config.gas_config {
             self.gas_config.merge(gas_config, true);
         }
+        if let Some(keeper_reward) = config.keeper_reward {
+            self.keeper_reward.merge(keeper_reward, true);
+        }
+        if let Some(instant_redeem_fee_basis_points) = config.instant_redeem_fee_basis_points {
+            assert!(
+                instant_redeem_fee_basis_points <= 1000,
+                "instant_redeem_fee_basis_points must be <= 1000"
+            );
+            self.instant_redeem_fee_basis_points = instant_redeem_fee_basis_points;
+        }
+        if let Some(performance_fee_basis_points) = config.performance_fee_basis_points {
+            assert!(
+                performance_fee_basis_points <= 2000,
+                "performance_fee_basis_points must be <= 2000"
+            );
+            self.performance_fee_basis_points = performance_fee_basis_points;
+        }
+        if let Some(insurance_buffer_earnings_percentage) =
+            config.insurance_buffer_earnings_percentage
+        {
+            assert!(
+                insurance_buffer_earnings_percentage <= 100,
+                "insurance_buffer_earnings_percentage must be <= 100"
+            );
+            self.insurance_buffer_earnings_percentage = insurance_buffer_earnings_percentage;
+        }
+        if let Some(fee_schedule) = config.fee_schedule {
+            self.fee_schedule.merge(fee_schedule, true);
+        }
+        if let Some(receipt_expiry_epochs) = config.receipt_expiry_epochs {
+            assert!(
+                receipt_expiry_epochs > 0,
+                "receipt_expiry_epochs must be > 0"
+            );
+            self.receipt_expiry_epochs = receipt_expiry_epochs;
+        }
     }
 
     /// performas no validation
@@ -71,6 +192,252 @@ impl Config {
         if let Some(gas_config) = config.gas_config {
             self.gas_config.merge(gas_config, false);
         }
+        if let Some(keeper_reward) = config.keeper_reward {
+            self.keeper_reward.merge(keeper_reward, false);
+        }
+        if let Some(instant_redeem_fee_basis_points) = config.instant_redeem_fee_basis_points {
+            self.instant_redeem_fee_basis_points = instant_redeem_fee_basis_points;
+        }
+        if let Some(performance_fee_basis_points) = config.performance_fee_basis_points {
+            self.performance_fee_basis_points = performance_fee_basis_points;
+        }
+        if let Some(insurance_buffer_earnings_percentage) =
+            config.insurance_buffer_earnings_percentage
+        {
+            self.insurance_buffer_earnings_percentage = insurance_buffer_earnings_percentage;
+        }
+        if let Some(fee_schedule) = config.fee_schedule {
+            self.fee_schedule.merge(fee_schedule, false);
+        }
+        if let Some(receipt_expiry_epochs) = config.receipt_expiry_epochs {
+            self.receipt_expiry_epochs = receipt_expiry_epochs;
+        }
+    }
+}
+
+/// operations that fees are charged on - see [FeeSchedule]
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum FeeCategory {
+    Deposit,
+    Redeem,
+    Withdraw,
+    FtTransfer,
+}
+
+/// fees that are charged on account operations - all fees are disabled by default
+/// - NEAR fees are added to the collected earnings
+/// - STAKE fees are held by the contract and split between the contract owner and the STAKE holders
+///   when earnings are distributed
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, Default)]
+pub struct FeeSchedule {
+    /// charged on the NEAR that is deposited to be staked
+    deposit: Fee,
+    /// charged on the STAKE that is redeemed
+    redeem: Fee,
+    /// charged on the NEAR that is withdrawn or transferred out of the account
+    withdraw: Fee,
+    /// charged on the STAKE that is transferred - the fee is debited from the sender on top of the
+    /// transfer amount
+    ft_transfer: Fee,
+}
+
+impl FeeSchedule {
+    pub fn deposit(&self) -> &Fee {
+        &self.deposit
+    }
+
+    pub fn redeem(&self) -> &Fee {
+        &self.redeem
+    }
+
+    pub fn withdraw(&self) -> &Fee {
+        &self.withdraw
+    }
+
+    pub fn ft_transfer(&self) -> &Fee {
+        &self.ft_transfer
+    }
+
+    pub fn fee(&self, category: FeeCategory) -> &Fee {
+        match category {
+            FeeCategory::Deposit => &self.deposit,
+            FeeCategory::Redeem => &self.redeem,
+            FeeCategory::Withdraw => &self.withdraw,
+            FeeCategory::FtTransfer => &self.ft_transfer,
+        }
+    }
+
+    /// ## Panics
+    /// if validation fails
+    pub fn merge(&mut self, config: interface::FeeSchedule, validate: bool) {
+        if let Some(fee) = config.deposit {
+            self.deposit.merge(fee, validate, "fee_schedule::deposit");
+        }
+        if let Some(fee) = config.redeem {
+            self.redeem.merge(fee, validate, "fee_schedule::redeem");
+        }
+        if let Some(fee) = config.withdraw {
+            self.withdraw.merge(fee, validate, "fee_schedule::withdraw");
+        }
+        if let Some(fee) = config.ft_transfer {
+            self.ft_transfer
+                .merge(fee, validate, "fee_schedule::ft_transfer");
+        }
+    }
+}
+
+/// fee = flat fee + basis points on the NEAR value of the amount
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, Default)]
+pub struct Fee {
+    flat: YoctoNear,
+    /// basis points, i.e., 1/100th of a percent
+    /// - must be a number between 0-1000, i.e., max fee is 10%
+    basis_points: u16,
+    /// accounts that are not charged the fee
+    exempt_account_ids: Vec<AccountId>,
+}
+
+impl Fee {
+    pub fn flat(&self) -> YoctoNear {
+        self.flat
+    }
+
+    pub fn basis_points(&self) -> u16 {
+        self.basis_points
+    }
+
+    pub fn exempt_account_ids(&self) -> &[AccountId] {
+        &self.exempt_account_ids
+    }
+
+    pub fn is_exempt(&self, account_id: &str) -> bool {
+        self.exempt_account_ids.iter().any(|id| id == account_id)
+    }
+
+    /// computes the fee that the account is charged for the specified NEAR amount
+    pub fn fee(&self, account_id: &str, amount: YoctoNear) -> YoctoNear {
+        if self.is_exempt(account_id) {
+            return 0.into();
+        }
+        self.flat + (amount.value() / 10_000 * self.basis_points as u128).into()
+    }
+
+    /// ## Panics
+    /// if validation fails
+    pub fn merge(&mut self, config: interface::Fee, validate: bool, field: &str) {
+        if let Some(flat) = config.flat {
+            if validate {
+                assert!(flat.value() <= YOCTO, "{}::flat must be <= 1 NEAR", field);
+            }
+            self.flat = flat.into();
+        }
+        if let Some(basis_points) = config.basis_points {
+            if validate {
+                assert!(
+                    basis_points <= 1000,
+                    "{}::basis_points must be <= 1000",
+                    field
+                );
+            }
+            self.basis_points = basis_points;
+        }
+        if let Some(exempt_account_ids) = config.exempt_account_ids {
+            if validate {
+                assert!(
+                    exempt_account_ids.len() <= MAX_FEE_EXEMPT_ACCOUNTS,
+                    "{}::exempt_account_ids must not exceed {} accounts",
+                    field,
+                    MAX_FEE_EXEMPT_ACCOUNTS
+                );
+            }
+            self.exempt_account_ids = exempt_account_ids.into_iter().map(Into::into).collect();
+        }
+    }
+}
+
+/// how keeper rewards are paid out
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum KeeperRewardPayout {
+    /// NEAR is credited to the keeper's account
+    Near,
+    /// STAKE is minted into the keeper's account - the NEAR backing the STAKE is added to the
+    /// NEAR liquidity pool, which gets staked when the next stake batch is run
+    Stake,
+}
+
+/// Keepers are rewarded for running [stake()](crate::interface::StakingService::stake) and
+/// [unstake()](crate::interface::StakingService::unstake) when the batch completes successfully.
+///
+/// Keeper rewards are paid out of the contract earnings, which are set aside into the keeper rewards
+/// pool when earnings are distributed.
+#[derive(Debug, BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub struct KeeperRewardConfig {
+    /// NEAR value of the reward that is paid per completed batch
+    /// - zero means keeper rewards are disabled
+    amount: YoctoNear,
+    payout: KeeperRewardPayout,
+    /// max number of keeper rewards that are paid out per epoch
+    max_rewards_per_epoch: u32,
+}
+
+impl Default for KeeperRewardConfig {
+    fn default() -> Self {
+        Self {
+            amount: 0.into(),
+            payout: KeeperRewardPayout::Near,
+            max_rewards_per_epoch: 10,
+        }
+    }
+}
+
+impl KeeperRewardConfig {
+    pub fn amount(&self) -> YoctoNear {
+        self.amount
+    }
+
+    pub fn payout(&self) -> KeeperRewardPayout {
+        self.payout
+    }
+
+    pub fn max_rewards_per_epoch(&self) -> u32 {
+        self.max_rewards_per_epoch
+    }
+
+    /// the keeper rewards pool is topped up to cover the max number of rewards for an epoch
+    pub fn max_rewards_per_epoch_amount(&self) -> YoctoNear {
+        (self.amount.value() * self.max_rewards_per_epoch as u128).into()
+    }
+
+    /// ## Panics
+    /// if validation fails
+    pub fn merge(&mut self, config: interface::KeeperRewardConfig, validate: bool) {
+        if let Some(amount) = config.amount {
+            if validate {
+                assert!(
+                    amount.value() <= YOCTO,
+                    "keeper_reward::amount must be <= 1 NEAR"
+                );
+            }
+            self.amount = amount.into();
+        }
+        if let Some(payout) = config.payout {
+            self.payout = payout;
+        }
+        if let Some(max_rewards_per_epoch) = config.max_rewards_per_epoch {
+            if validate {
+                assert!(
+                    max_rewards_per_epoch > 0,
+                    "keeper_reward::max_rewards_per_epoch must be > 0"
+                );
+            }
+            self.max_rewards_per_epoch = max_rewards_per_epoch;
+        }
     }
 }
 
@@ -91,6 +458,10 @@ pub struct GasConfig {
 
     function_call_promise: Gas,
     function_call_promise_data_dependency: Gas,
+
+    /// gas required to credit a single receiver in a batch transfer
+    /// - used to cap the batch size based on the prepaid gas
+    ft_batch_transfer_receiver: Gas,
 }
 
 impl GasConfig {
@@ -110,6 +481,10 @@ impl GasConfig {
         self.function_call_promise_data_dependency
     }
 
+    pub fn ft_batch_transfer_receiver(&self) -> Gas {
+        self.ft_batch_transfer_receiver
+    }
+
     /// if validate is true, then merge performs some sanity checks on the config to
     /// catch mis-configurations.
     ///
@@ -129,6 +504,13 @@ impl GasConfig {
         if let Some(gas) = config.function_call_promise_data_dependency {
             self.function_call_promise_data_dependency = gas.into();
         }
+        if let Some(gas) = config.ft_batch_transfer_receiver {
+            let gas = gas.into();
+            if validate {
+                assert_gas_range(gas, 1, 10, "ft_batch_transfer_receiver");
+            }
+            self.ft_batch_transfer_receiver = gas;
+        }
 
         if validate {
             // check that the numbers add up for cross-contract workflows
@@ -154,6 +536,19 @@ impl GasConfig {
                 "callbacks.on_redeeming_stake_pending_withdrawal must be >= \
             staking_pool.withdraw + callbacks.on_redeeming_stake_post_withdrawal + 5 TGas"
             );
+            assert!(
+                self.callbacks.on_run_netted_batches
+                    >= (self
+                        .staking_pool
+                        .deposit_and_stake
+                        .max(self.staking_pool.unstake)
+                        + self.staking_pool.get_account
+                        + self.callbacks.on_netted_batches_settled
+                        + (TGAS * 5)),
+                "callbacks.on_run_netted_batches must be >= \
+            max(staking_pool.deposit_and_stake, staking_pool.unstake) + staking_pool.get_account \
+            + callbacks.on_netted_batches_settled + 5 TGas"
+            );
         }
     }
 }
@@ -165,6 +560,7 @@ impl Default for GasConfig {
             callbacks: Default::default(),
             function_call_promise: TGAS * 5,
             function_call_promise_data_dependency: TGAS * 10,
+            ft_batch_transfer_receiver: TGAS * 2,
         }
     }
 }
@@ -289,8 +685,21 @@ pub struct CallBacksGasConfig {
 
     /// used by FungibleToken transfer call workflow
     resolve_transfer_gas: Gas,
+    /// used by VaultFungibleToken transfer with vault workflow
+    resolve_vault_gas: Gas,
 
     on_refresh_stake_token_value: Gas,
+
+    /// used by staking pool migration workflow
+    on_staking_pool_migration_unstaked: Gas,
+    /// gas is used to deposit and stake the withdrawn NEAR with the staking pool being migrated to
+    on_staking_pool_migration_withdrawn: Gas,
+    on_staking_pool_migration_staked: Gas,
+
+    /// used by netted batches workflow
+    /// - gas is used to deposit and stake, or unstake, the net NEAR amount with the staking pool
+    on_run_netted_batches: Gas,
+    on_netted_batches_settled: Gas,
 }
 
 impl CallBacksGasConfig {
@@ -356,6 +765,53 @@ impl CallBacksGasConfig {
             }
             self.resolve_transfer_gas = gas;
         }
+        if let Some(gas) = config.resolve_vault_gas {
+            let gas = gas.into();
+            if validate {
+                assert_gas_range(gas, 5, 20, "callbacks::resolve_vault_gas");
+            }
+            self.resolve_vault_gas = gas;
+        }
+        if let Some(gas) = config.on_staking_pool_migration_unstaked {
+            let gas = gas.into();
+            if validate {
+                assert_gas_range(gas, 5, 10, "callbacks::on_staking_pool_migration_unstaked");
+            }
+            self.on_staking_pool_migration_unstaked = gas;
+        }
+        if let Some(gas) = config.on_staking_pool_migration_withdrawn {
+            let gas = gas.into();
+            if validate {
+                assert_gas_range(
+                    gas,
+                    60,
+                    100,
+                    "callbacks::on_staking_pool_migration_withdrawn",
+                );
+            }
+            self.on_staking_pool_migration_withdrawn = gas;
+        }
+        if let Some(gas) = config.on_staking_pool_migration_staked {
+            let gas = gas.into();
+            if validate {
+                assert_gas_range(gas, 5, 10, "callbacks::on_staking_pool_migration_staked");
+            }
+            self.on_staking_pool_migration_staked = gas;
+        }
+        if let Some(gas) = config.on_run_netted_batches {
+            let gas = gas.into();
+            if validate {
+                assert_gas_range(gas, 70, 150, "callbacks::on_run_netted_batches");
+            }
+            self.on_run_netted_batches = gas;
+        }
+        if let Some(gas) = config.on_netted_batches_settled {
+            let gas = gas.into();
+            if validate {
+                assert_gas_range(gas, 10, 30, "callbacks::on_netted_batches_settled");
+            }
+            self.on_netted_batches_settled = gas;
+        }
     }
 
     pub fn on_deposit_and_stake(&self) -> Gas {
@@ -390,9 +846,33 @@ impl CallBacksGasConfig {
         self.resolve_transfer_gas
     }
 
+    pub fn resolve_vault_gas(&self) -> Gas {
+        self.resolve_vault_gas
+    }
+
     pub fn on_refresh_stake_token_value(&self) -> Gas {
         self.on_refresh_stake_token_value
     }
+
+    pub fn on_staking_pool_migration_unstaked(&self) -> Gas {
+        self.on_staking_pool_migration_unstaked
+    }
+
+    pub fn on_staking_pool_migration_withdrawn(&self) -> Gas {
+        self.on_staking_pool_migration_withdrawn
+    }
+
+    pub fn on_staking_pool_migration_staked(&self) -> Gas {
+        self.on_staking_pool_migration_staked
+    }
+
+    pub fn on_run_netted_batches(&self) -> Gas {
+        self.on_run_netted_batches
+    }
+
+    pub fn on_netted_batches_settled(&self) -> Gas {
+        self.on_netted_batches_settled
+    }
 }
 
 impl Default for CallBacksGasConfig {
@@ -409,8 +889,124 @@ impl Default for CallBacksGasConfig {
             on_redeeming_stake_pending_withdrawal: TGAS * 85,
             on_redeeming_stake_post_withdrawal: TGAS * 5,
             resolve_transfer_gas: TGAS * 10,
+            resolve_vault_gas: TGAS * 10,
 
             on_refresh_stake_token_value: TGAS * 15,
+
+            on_staking_pool_migration_unstaked: TGAS * 5,
+            on_staking_pool_migration_withdrawn: TGAS * 70,
+            on_staking_pool_migration_staked: TGAS * 5,
+
+            on_run_netted_batches: TGAS * 85,
+            on_netted_batches_settled: TGAS * 20,
+        }
+    }
+}
+
+/// [Config] layout that was deployed before the contract state was versioned
+/// - only used to migrate the contract state - see [Contract::migrate](crate::Contract::migrate)
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct ConfigV0 {
+    storage_cost_per_byte: YoctoNear,
+    gas_config: GasConfigV0,
+    contract_owner_earnings_percentage: u8,
+}
+
+/// the settings that were not part of the original config are set to their defaults
+impl From<ConfigV0> for Config {
+    fn from(config: ConfigV0) -> Self {
+        Self {
+            storage_cost_per_byte: config.storage_cost_per_byte,
+            gas_config: config.gas_config.into(),
+            contract_owner_earnings_percentage: config.contract_owner_earnings_percentage,
+            ..Config::default()
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct GasConfigV0 {
+    staking_pool: StakingPoolGasConfig,
+    callbacks: CallBacksGasConfigV0,
+
+    function_call_promise: Gas,
+    function_call_promise_data_dependency: Gas,
+}
+
+impl From<GasConfigV0> for GasConfig {
+    fn from(config: GasConfigV0) -> Self {
+        Self {
+            staking_pool: config.staking_pool,
+            callbacks: config.callbacks.into(),
+            function_call_promise: config.function_call_promise,
+            function_call_promise_data_dependency: config.function_call_promise_data_dependency,
+            ..GasConfig::default()
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct CallBacksGasConfigV0 {
+    on_run_stake_batch: Gas,
+    on_deposit_and_stake: Gas,
+
+    on_unstake: Gas,
+    unlock: Gas,
+
+    on_run_redeem_stake_batch: Gas,
+    on_redeeming_stake_pending_withdrawal: Gas,
+    on_redeeming_stake_post_withdrawal: Gas,
+
+    resolve_transfer_gas: Gas,
+
+    on_refresh_stake_token_value: Gas,
+}
+
+impl From<CallBacksGasConfigV0> for CallBacksGasConfig {
+    fn from(config: CallBacksGasConfigV0) -> Self {
+        Self {
+            on_run_stake_batch: config.on_run_stake_batch,
+            on_deposit_and_stake: config.on_deposit_and_stake,
+            on_unstake: config.on_unstake,
+            unlock: config.unlock,
+            on_run_redeem_stake_batch: config.on_run_redeem_stake_batch,
+            on_redeeming_stake_pending_withdrawal: config.on_redeeming_stake_pending_withdrawal,
+            on_redeeming_stake_post_withdrawal: config.on_redeeming_stake_post_withdrawal,
+            resolve_transfer_gas: config.resolve_transfer_gas,
+            on_refresh_stake_token_value: config.on_refresh_stake_token_value,
+            ..CallBacksGasConfig::default()
+        }
+    }
+}
+
+#[cfg(test)]
+impl Default for ConfigV0 {
+    fn default() -> Self {
+        let config = Config::default();
+        let callbacks = config.gas_config.callbacks;
+        Self {
+            storage_cost_per_byte: config.storage_cost_per_byte,
+            gas_config: GasConfigV0 {
+                staking_pool: config.gas_config.staking_pool,
+                callbacks: CallBacksGasConfigV0 {
+                    on_run_stake_batch: callbacks.on_run_stake_batch,
+                    on_deposit_and_stake: callbacks.on_deposit_and_stake,
+                    on_unstake: callbacks.on_unstake,
+                    unlock: callbacks.unlock,
+                    on_run_redeem_stake_batch: callbacks.on_run_redeem_stake_batch,
+                    on_redeeming_stake_pending_withdrawal: callbacks
+                        .on_redeeming_stake_pending_withdrawal,
+                    on_redeeming_stake_post_withdrawal: callbacks
+                        .on_redeeming_stake_post_withdrawal,
+                    resolve_transfer_gas: callbacks.resolve_transfer_gas,
+                    on_refresh_stake_token_value: callbacks.on_refresh_stake_token_value,
+                },
+                function_call_promise: config.gas_config.function_call_promise,
+                function_call_promise_data_dependency: config
+                    .gas_config
+                    .function_call_promise_data_dependency,
+            },
+            contract_owner_earnings_percentage: config.contract_owner_earnings_percentage,
         }
     }
 }
@@ -419,6 +1015,8 @@ impl Default for CallBacksGasConfig {
 mod test {
 
     use super::*;
+    use near_sdk::json_types::ValidAccountId;
+    use std::convert::TryFrom;
 
     #[test]
     fn callbacks_gas_config_merge_success() {
@@ -433,7 +1031,13 @@ mod test {
                 on_redeeming_stake_pending_withdrawal: Some((TGAS * 73).into()),
                 on_redeeming_stake_post_withdrawal: Some((TGAS * 9).into()),
                 resolve_transfer_gas: Some((TGAS * 10).into()),
+                resolve_vault_gas: Some((TGAS * 11).into()),
                 refresh_stake_token_value: Some((TGAS * 15).into()),
+                on_staking_pool_migration_unstaked: Some((TGAS * 6).into()),
+                on_staking_pool_migration_withdrawn: Some((TGAS * 75).into()),
+                on_staking_pool_migration_staked: Some((TGAS * 7).into()),
+                on_run_netted_batches: Some((TGAS * 80).into()),
+                on_netted_batches_settled: Some((TGAS * 15).into()),
             },
             true,
         );
@@ -444,6 +1048,155 @@ mod test {
         assert_eq!(config.on_run_redeem_stake_batch, TGAS * 72);
         assert_eq!(config.on_redeeming_stake_pending_withdrawal, TGAS * 73);
         assert_eq!(config.on_redeeming_stake_post_withdrawal, TGAS * 9);
+        assert_eq!(config.resolve_vault_gas, TGAS * 11);
+        assert_eq!(config.on_staking_pool_migration_unstaked, TGAS * 6);
+        assert_eq!(config.on_staking_pool_migration_withdrawn, TGAS * 75);
+        assert_eq!(config.on_staking_pool_migration_staked, TGAS * 7);
+        assert_eq!(config.on_run_netted_batches, TGAS * 80);
+        assert_eq!(config.on_netted_batches_settled, TGAS * 15);
+    }
+
+    #[test]
+    fn keeper_reward_config_merge_success() {
+        let mut config = KeeperRewardConfig::default();
+        config.merge(
+            interface::KeeperRewardConfig {
+                amount: Some((YOCTO / 10).into()),
+                payout: Some(KeeperRewardPayout::Stake),
+                max_rewards_per_epoch: None,
+            },
+            true,
+        );
+        assert_eq!(config.amount(), (YOCTO / 10).into());
+        assert_eq!(config.payout(), KeeperRewardPayout::Stake);
+        assert_eq!(config.max_rewards_per_epoch(), 10);
+        assert_eq!(config.max_rewards_per_epoch_amount(), YOCTO.into());
+    }
+
+    #[test]
+    #[should_panic(expected = "keeper_reward::max_rewards_per_epoch must be > 0")]
+    fn keeper_reward_config_merge_zero_max_rewards_per_epoch() {
+        let mut config = KeeperRewardConfig::default();
+        config.merge(
+            interface::KeeperRewardConfig {
+                amount: None,
+                payout: None,
+                max_rewards_per_epoch: Some(0),
+            },
+            true,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "performance_fee_basis_points must be <= 2000")]
+    fn config_merge_performance_fee_too_high() {
+        let mut config = Config::default();
+        config.merge(interface::Config {
+            storage_cost_per_byte: None,
+            gas_config: None,
+            contract_owner_earnings_percentage: None,
+            keeper_reward: None,
+            instant_redeem_fee_basis_points: None,
+            performance_fee_basis_points: Some(2001),
+            insurance_buffer_earnings_percentage: None,
+            fee_schedule: None,
+            receipt_expiry_epochs: None,
+        });
+    }
+
+    #[test]
+    fn config_merge_performance_fee() {
+        let mut config = Config::default();
+        assert_eq!(config.performance_fee((100 * YOCTO).into()), 0.into());
+        config.merge(interface::Config {
+            storage_cost_per_byte: None,
+            gas_config: None,
+            contract_owner_earnings_percentage: None,
+            keeper_reward: None,
+            instant_redeem_fee_basis_points: None,
+            performance_fee_basis_points: Some(1000),
+            insurance_buffer_earnings_percentage: None,
+            fee_schedule: None,
+            receipt_expiry_epochs: None,
+        });
+        assert_eq!(config.performance_fee_basis_points(), 1000);
+        assert_eq!(
+            config.performance_fee((100 * YOCTO).into()),
+            (10 * YOCTO).into()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "insurance_buffer_earnings_percentage must be <= 100")]
+    fn config_merge_insurance_buffer_earnings_percentage_too_high() {
+        let mut config = Config::default();
+        config.merge(interface::Config {
+            storage_cost_per_byte: None,
+            gas_config: None,
+            contract_owner_earnings_percentage: None,
+            keeper_reward: None,
+            instant_redeem_fee_basis_points: None,
+            performance_fee_basis_points: None,
+            insurance_buffer_earnings_percentage: Some(101),
+            fee_schedule: None,
+            receipt_expiry_epochs: None,
+        });
+    }
+
+    #[test]
+    fn fee_schedule_merge() {
+        let mut fee_schedule = FeeSchedule::default();
+        assert_eq!(
+            fee_schedule
+                .redeem()
+                .fee("alfio.near", (100 * YOCTO).into()),
+            0.into()
+        );
+        fee_schedule.merge(
+            interface::FeeSchedule {
+                deposit: None,
+                redeem: Some(interface::Fee {
+                    flat: Some((YOCTO / 10).into()),
+                    basis_points: Some(50),
+                    exempt_account_ids: Some(vec![ValidAccountId::try_from("bob.near").unwrap()]),
+                }),
+                withdraw: None,
+                ft_transfer: None,
+            },
+            true,
+        );
+        let fee = fee_schedule.fee(FeeCategory::Redeem);
+        assert_eq!(
+            fee.fee("alfio.near", (100 * YOCTO).into()),
+            (YOCTO / 10 + YOCTO / 2).into()
+        );
+        assert!(fee.is_exempt("bob.near"));
+        assert_eq!(fee.fee("bob.near", (100 * YOCTO).into()), 0.into());
+        assert_eq!(
+            fee_schedule
+                .deposit()
+                .fee("alfio.near", (100 * YOCTO).into()),
+            0.into()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "fee_schedule::withdraw::basis_points must be <= 1000")]
+    fn fee_schedule_merge_basis_points_too_high() {
+        let mut fee_schedule = FeeSchedule::default();
+        fee_schedule.merge(
+            interface::FeeSchedule {
+                deposit: None,
+                redeem: None,
+                withdraw: Some(interface::Fee {
+                    flat: None,
+                    basis_points: Some(1001),
+                    exempt_account_ids: None,
+                }),
+                ft_transfer: None,
+            },
+            true,
+        );
     }
 
     #[test]
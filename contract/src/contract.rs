@@ -1,14 +1,29 @@
 pub(crate) mod account_management;
 pub(crate) mod account_storage;
+pub(crate) mod allowances;
 pub(crate) mod contract_owner;
+pub(crate) mod fees;
 pub(crate) mod financials;
 pub(crate) mod fungible_token;
+pub(crate) mod keeper;
+pub(crate) mod limit_orders;
+pub(crate) mod liquidity_provider;
 pub(crate) mod metadata;
+pub(crate) mod migration;
+pub(crate) mod netted_batches_workflow_callbacks;
 pub(crate) mod operator;
+pub(crate) mod pending_batches;
+pub(crate) mod quotes;
+pub(crate) mod receipts;
+pub(crate) mod redeem_queue;
 pub(crate) mod redeeming_workflow_callbacks;
+pub(crate) mod staking_losses;
 pub(crate) mod staking_pool;
+pub(crate) mod staking_pool_migration_callbacks;
 pub(crate) mod staking_service;
 pub(crate) mod staking_workflow_callbacks;
+pub(crate) mod unstaked_cohorts;
+pub(crate) mod vault_fungible_token;
 
 pub use staking_service::*;
 
@@ -67,6 +82,10 @@ impl Contract {
     pub fn promise_result(&self, result_index: u64) -> PromiseResult {
         env::promise_result(result_index)
     }
+
+    pub fn promise_results_count(&self) -> u64 {
+        env::promise_results_count()
+    }
 }
 
 /// in order to make it easier to unit test Promise func callbacks, we need to abstract away the near env
@@ -87,6 +106,10 @@ impl Contract {
         self.env.promise_result(result_index)
     }
 
+    pub fn promise_results_count(&self) -> u64 {
+        self.env.promise_results_count()
+    }
+
     pub fn set_env(&mut self, env: near_env::Env) {
         self.env = env;
     }
@@ -1,6 +1,11 @@
 //required in order for near_bindgen macro to work outside of lib.rs
 use crate::domain::RegisteredAccount;
-use crate::errors::account_management::ACCOUNT_NOT_REGISTERED;
+use crate::errors::account_management::{
+    ACCOUNT_NOT_REGISTERED, UNREGISTER_REQUIRES_NO_FUNDS_IN_BATCHES,
+};
+use crate::errors::allowances::UNREGISTER_REQUIRES_NO_ALLOWANCES;
+use crate::errors::liquidity_provider::UNREGISTER_REQUIRES_NO_LIQUIDITY_PROVIDER_SHARES;
+use crate::errors::vault::UNREGISTER_REQUIRES_NO_VAULTS;
 use crate::*;
 use crate::{
     core::Hash,
@@ -8,14 +13,21 @@ use crate::{
     errors::account_management::{
         ACCOUNT_ALREADY_REGISTERED, INSUFFICIENT_STORAGE_FEE, UNREGISTER_REQUIRES_ZERO_BALANCES,
     },
-    interface::{self, AccountManagement, StakeAccount, StakingService},
+    interface::{
+        self, fungible_token::events::FtBurn, AccountManagement, StakeAccount, StakingService,
+    },
+    near::log,
 };
 use near_sdk::{
     env,
     json_types::{ValidAccountId, U128},
-    near_bindgen, Promise,
+    near_bindgen, AccountId, Promise,
 };
 
+/// max number of accounts that are returned per page by [AccountManagement::accounts] and
+/// [AccountManagement::ft_holders]
+pub const MAX_ACCOUNTS_PAGE_LIMIT: u64 = 100;
+
 #[near_bindgen]
 impl AccountManagement for Contract {
     /// ## Logic
@@ -34,13 +46,7 @@ impl AccountManagement for Contract {
             INSUFFICIENT_STORAGE_FEE,
         );
 
-        let account_storage_fee = self.account_storage_fee().into();
-        self.total_account_storage_escrow += account_storage_fee;
-        let account = Account::new(account_storage_fee);
-        assert!(
-            self.save_account(&Hash::from(&env::predecessor_account_id()), &account),
-            ACCOUNT_ALREADY_REGISTERED
-        );
+        let account_storage_fee = self.register(&env::predecessor_account_id());
 
         // refund over payment of storage fees
         let refund = env::attached_deposit() - account_storage_fee.value();
@@ -50,18 +56,10 @@ impl AccountManagement for Contract {
     }
 
     fn unregister_account(&mut self) {
-        let account_id = env::predecessor_account_id();
-        let account_id_hash = Hash::from(&env::predecessor_account_id());
-
-        match self.delete_account(&account_id_hash) {
-            None => panic!(ACCOUNT_NOT_REGISTERED),
-            Some(account) => {
-                assert!(!account.has_funds(), UNREGISTER_REQUIRES_ZERO_BALANCES);
-                self.total_account_storage_escrow -= account.storage_escrow.amount();
-                // refund the escrowed storage fee
-                Promise::new(account_id).transfer(account.storage_escrow.amount().value());
-            }
-        };
+        assert!(
+            self.unregister(&env::predecessor_account_id(), false),
+            ACCOUNT_NOT_REGISTERED
+        );
     }
 
     /// returns the required account storage fee that needs to be attached to the account registration
@@ -84,71 +82,214 @@ impl AccountManagement for Contract {
 
     fn lookup_account(&self, account_id: ValidAccountId) -> Option<StakeAccount> {
         self.accounts
-            .get(&Hash::from(account_id))
-            .map(|account| self.apply_receipt_funds_for_view(&account))
-            .map(|account| {
-                let redeem_stake_batch = account.redeem_stake_batch.map(|batch| {
-                    interface::RedeemStakeBatch::from(
-                        batch,
-                        self.redeem_stake_batch_receipt(batch.id().into()),
-                    )
-                });
+            .get(&Hash::from(account_id.as_ref()))
+            .map(|account| self.stake_account(account_id.as_ref(), &account))
+    }
 
-                let next_redeem_stake_batch = account.next_redeem_stake_batch.map(|batch| {
-                    interface::RedeemStakeBatch::from(
+    fn accounts(&self, from_index: u64, limit: u64) -> Vec<(AccountId, StakeAccount)> {
+        self.stake_accounts_page(&self.account_ids, from_index, limit)
+    }
+
+    fn ft_holders(&self, from_index: u64, limit: u64) -> Vec<(AccountId, StakeAccount)> {
+        self.stake_accounts_page(&self.ft_holder_ids, from_index, limit)
+    }
+}
+
+impl Contract {
+    /// returns the account view with unclaimed receipts applied to the account balances
+    fn stake_account(&self, account_id: &str, account: &Account) -> StakeAccount {
+        // pending batches that are claimable are applied to the account balances
+        let pending_batches = self
+            .pending_batches(account_id, account)
+            .into_iter()
+            .filter(|batch| !self.is_pending_batch_claimable(batch))
+            .map(|batch| match batch {
+                domain::PendingBatch::Stake(batch) => interface::PendingBatch::Stake(batch.into()),
+                domain::PendingBatch::Redeem(batch) => {
+                    interface::PendingBatch::Redeem(interface::RedeemStakeBatch::from(
                         batch,
                         self.redeem_stake_batch_receipt(batch.id().into()),
-                    )
-                });
+                    ))
+                }
+            })
+            .collect();
+        let account = self.apply_receipt_funds_for_view(account_id, account);
+        let redeem_stake_batch = account.redeem_stake_batch.map(|batch| {
+            interface::RedeemStakeBatch::from(
+                batch,
+                self.redeem_stake_batch_receipt(batch.id().into()),
+            )
+        });
+
+        let next_redeem_stake_batch = account.next_redeem_stake_batch.map(|batch| {
+            interface::RedeemStakeBatch::from(
+                batch,
+                self.redeem_stake_batch_receipt(batch.id().into()),
+            )
+        });
+
+        let contract_near_liquidity = if self.near_liquidity_pool.value() == 0 {
+            None
+        } else {
+            let mut total_unstaked_near = YoctoNear(0);
+
+            let mut update_total_unstaked_near = |batch: &interface::RedeemStakeBatch| {
+                if let Some(receipt) = batch.receipt.as_ref() {
+                    let stake_token_value: domain::StakeTokenValue =
+                        receipt.stake_token_value.clone().into();
+                    total_unstaked_near +=
+                        stake_token_value.stake_to_near(receipt.redeemed_stake.0 .0.into());
+                }
+            };
+
+            if let Some(batch) = redeem_stake_batch.as_ref() {
+                update_total_unstaked_near(batch);
+            }
 
-                let contract_near_liquidity = if self.near_liquidity_pool.value() == 0 {
-                    None
+            if let Some(batch) = next_redeem_stake_batch.as_ref() {
+                update_total_unstaked_near(batch);
+            }
+
+            if total_unstaked_near.value() > 0 {
+                if self.near_liquidity_pool.value() >= total_unstaked_near.value() {
+                    Some(total_unstaked_near.into())
                 } else {
-                    let mut total_unstaked_near = YoctoNear(0);
-
-                    let mut update_total_unstaked_near = |batch: &interface::RedeemStakeBatch| {
-                        if let Some(receipt) = batch.receipt.as_ref() {
-                            let stake_token_value: domain::StakeTokenValue =
-                                receipt.stake_token_value.clone().into();
-                            total_unstaked_near +=
-                                stake_token_value.stake_to_near(receipt.redeemed_stake.0 .0.into());
-                        }
-                    };
-
-                    if let Some(batch) = redeem_stake_batch.as_ref() {
-                        update_total_unstaked_near(batch);
-                    }
-
-                    if let Some(batch) = next_redeem_stake_batch.as_ref() {
-                        update_total_unstaked_near(batch);
-                    }
-
-                    if total_unstaked_near.value() > 0 {
-                        if self.near_liquidity_pool.value() >= total_unstaked_near.value() {
-                            Some(total_unstaked_near.into())
-                        } else {
-                            Some(self.near_liquidity_pool.into())
-                        }
-                    } else {
-                        None
-                    }
-                };
-
-                StakeAccount {
-                    storage_escrow: account.storage_escrow.into(),
-                    near: account.near.map(Into::into),
-                    stake: account.stake.map(Into::into),
-                    stake_batch: account.stake_batch.map(Into::into),
-                    next_stake_batch: account.next_stake_batch.map(Into::into),
-                    redeem_stake_batch,
-                    next_redeem_stake_batch,
-                    contract_near_liquidity,
+                    Some(self.near_liquidity_pool.into())
                 }
+            } else {
+                None
+            }
+        };
+
+        StakeAccount {
+            storage_escrow: account.storage_escrow.into(),
+            near: account.near.map(Into::into),
+            stake: account.stake.map(Into::into),
+            stake_batch: account.stake_batch.map(Into::into),
+            next_stake_batch: account.next_stake_batch.map(Into::into),
+            redeem_stake_batch,
+            next_redeem_stake_batch,
+            pending_batches,
+            contract_near_liquidity,
+        }
+    }
+
+    /// returns the page of accounts from the specified account ID index
+    /// - the page size is capped at [MAX_ACCOUNTS_PAGE_LIMIT]
+    fn stake_accounts_page(
+        &self,
+        account_ids: &UnorderedSet<AccountId>,
+        from_index: u64,
+        limit: u64,
+    ) -> Vec<(AccountId, StakeAccount)> {
+        let account_ids = account_ids.as_vector();
+        let to_index = account_ids
+            .len()
+            .min(from_index.saturating_add(limit.min(MAX_ACCOUNTS_PAGE_LIMIT)));
+        (from_index..to_index)
+            .filter_map(|index| account_ids.get(index))
+            .filter_map(|account_id| {
+                self.accounts.get(&Hash::from(&account_id)).map(|account| {
+                    let stake_account = self.stake_account(&account_id, &account);
+                    (account_id, stake_account)
+                })
             })
+            .collect()
     }
 }
 
 impl Contract {
+    /// registers the account and escrows the account storage fee
+    /// - returns the account storage fee that was escrowed, which the caller is responsible for
+    ///   collecting
+    ///
+    /// ## Panics
+    /// if the account is already registered
+    pub(crate) fn register(&mut self, account_id: &str) -> YoctoNear {
+        let account_storage_fee: YoctoNear = self.account_storage_fee().into();
+        self.total_account_storage_escrow += account_storage_fee;
+        let account = Account::new(account_storage_fee);
+        assert!(
+            self.save_account(&Hash::from(account_id), &account),
+            ACCOUNT_ALREADY_REGISTERED
+        );
+        self.account_ids.insert(&account_id.to_string());
+        account_storage_fee
+    }
+
+    /// Unregisters the account and refunds the account storage escrow balance plus the attached
+    /// deposit. If `force` is true, then the account's STAKE balance is burned and its available NEAR
+    /// balance is refunded.
+    ///
+    /// Returns false if the account is not registered - the attached deposit is refunded.
+    ///
+    /// ## Panics
+    /// - if not forced and the account has funds
+    /// - if the account has funds in stake or redeem batches
+    /// - if the account has allowances
+    /// - if the account has open vaults
+    /// - if the account holds liquidity provider shares
+    pub(crate) fn unregister(&mut self, account_id: &str, force: bool) -> bool {
+        let mut account = match self.lookup_registered_account(account_id) {
+            None => {
+                if env::attached_deposit() > 0 {
+                    Promise::new(account_id.to_string()).transfer(env::attached_deposit());
+                }
+                return false;
+            }
+            Some(account) => account,
+        };
+        assert_eq!(
+            account.allowances_len, 0,
+            "{}",
+            UNREGISTER_REQUIRES_NO_ALLOWANCES
+        );
+        assert_eq!(account.vaults_len, 0, "{}", UNREGISTER_REQUIRES_NO_VAULTS);
+        // the shares are not redeemed on behalf of the account because liquidity can only be
+        // removed subject to the pool utilization
+        assert_eq!(
+            account.liquidity_provider_shares, 0,
+            "{}",
+            UNREGISTER_REQUIRES_NO_LIQUIDITY_PROVIDER_SHARES
+        );
+
+        if force {
+            self.claim_receipt_funds_and_pay_redeem_beneficiaries(&mut account);
+            assert!(
+                !account.has_funds_in_batches(),
+                UNREGISTER_REQUIRES_NO_FUNDS_IN_BATCHES
+            );
+            if let Some(balance) = account.stake {
+                let stake = balance.amount();
+                account.apply_stake_debit(stake);
+                // NOTE: this has the effect of transferring the burned value to the STAKE token,
+                // i.e., STAKE token value will increase when STAKE is burned
+                self.total_stake.debit(stake);
+                self.update_stake_token_value(self.stake_token_value.total_staked_near_balance());
+                log(FtBurn {
+                    owner_id: account_id,
+                    amount: stake.into(),
+                    memo: Some("storage_unregister"),
+                });
+            }
+            if let Some(balance) = account.near {
+                self.pay_out_near_funds(&mut account, balance.amount(), account_id);
+            }
+        } else {
+            assert!(!account.has_funds(), UNREGISTER_REQUIRES_ZERO_BALANCES);
+        }
+
+        self.delete_account(&account.id);
+        self.account_ids.remove(&account.account_id);
+        self.ft_holder_ids.remove(&account.account_id);
+        let storage_escrow = account.storage_escrow.amount();
+        self.total_account_storage_escrow -= storage_escrow;
+        // refund the escrowed storage fee
+        Promise::new(account_id.to_string())
+            .transfer(storage_escrow.value() + env::attached_deposit());
+        true
+    }
+
     /// ## Panics
     /// if account is not registered
     pub(crate) fn registered_account(&self, account_id: &str) -> RegisteredAccount {
@@ -157,6 +298,7 @@ impl Contract {
             Some(account) => RegisteredAccount {
                 account,
                 id: account_id_hash,
+                account_id: account_id.to_string(),
             },
             None => panic!("{}: {}", ACCOUNT_NOT_REGISTERED, account_id),
         }
@@ -169,6 +311,7 @@ impl Contract {
             .map(|account| RegisteredAccount {
                 account,
                 id: account_id_hash,
+                account_id: account_id.to_string(),
             })
     }
 
@@ -188,6 +331,13 @@ impl Contract {
 
     pub(crate) fn save_registered_account(&mut self, account: &RegisteredAccount) {
         self.save_account(&account.id, &account.account);
+        // accounts that were registered before the contract state was migrated are backfilled
+        self.account_ids.insert(&account.account_id);
+        if account.stake_balance().value() > 0 {
+            self.ft_holder_ids.insert(&account.account_id);
+        } else {
+            self.ft_holder_ids.remove(&account.account_id);
+        }
     }
 
     /// returns the account that was deleted, or None if no account exists for specified account ID
@@ -254,7 +404,7 @@ mod test_register_account {
 
         let account_storage_usage = env::storage_usage() - storage_before_registering_account;
         assert_eq!(
-            account_storage_usage, 119,
+            account_storage_usage, 261,
             "account storage usage changed !!! If the change is expected, then update the assert"
         );
 
@@ -602,6 +752,118 @@ mod test_lookup_account {
     }
 }
 
+#[cfg(test)]
+mod test_accounts {
+    use super::*;
+    use crate::interface::AccountManagement;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn accounts_paging() {
+        let mut test_ctx = TestContext::with_registered_account();
+        test_ctx.register_account("alice.near");
+        test_ctx.register_account("bob.near");
+
+        let accounts = test_ctx.accounts(0, 10);
+        let account_ids: Vec<&str> = accounts
+            .iter()
+            .map(|(account_id, _)| account_id.as_str())
+            .collect();
+        assert_eq!(
+            account_ids,
+            vec![test_ctx.account_id, "alice.near", "bob.near"]
+        );
+
+        let accounts = test_ctx.accounts(1, 1);
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].0, "alice.near");
+
+        assert!(test_ctx.accounts(3, 10).is_empty());
+        assert_eq!(test_ctx.accounts(2, u64::MAX).len(), 1);
+    }
+
+    #[test]
+    fn unregister_account_removes_account_from_index() {
+        let mut test_ctx = TestContext::with_registered_account();
+        test_ctx.register_account("alice.near");
+        test_ctx.register_account("bob.near");
+
+        let context = test_ctx.set_predecessor_account_id("alice.near");
+        testing_env!(context);
+        test_ctx.unregister_account();
+
+        let accounts = test_ctx.accounts(0, 10);
+        let account_ids: Vec<&str> = accounts
+            .iter()
+            .map(|(account_id, _)| account_id.as_str())
+            .collect();
+        assert_eq!(account_ids, vec![test_ctx.account_id, "bob.near"]);
+    }
+
+    #[test]
+    fn ft_holders() {
+        let mut test_ctx = TestContext::with_registered_account();
+        test_ctx.register_account("alice.near");
+        test_ctx.register_account("bob.near");
+
+        let mut account = test_ctx.registered_account("bob.near");
+        account.apply_stake_credit(100.into());
+        test_ctx.save_registered_account(&account);
+
+        let holders = test_ctx.ft_holders(0, 10);
+        assert_eq!(holders.len(), 1);
+        let (account_id, account) = &holders[0];
+        assert_eq!(account_id, "bob.near");
+        assert_eq!(account.stake.as_ref().unwrap().amount.value(), 100);
+
+        // holders are removed from the index once their STAKE balance is zero
+        let mut account = test_ctx.registered_account("bob.near");
+        account.apply_stake_debit(100.into());
+        test_ctx.save_registered_account(&account);
+        assert!(test_ctx.ft_holders(0, 10).is_empty());
+    }
+
+    /// holders are paged over the holder index, i.e., accounts without STAKE do not count towards
+    /// the page
+    #[test]
+    fn ft_holders_paging() {
+        let mut test_ctx = TestContext::with_registered_account();
+        for account_id in &["alice.near", "bob.near", "carol.near"] {
+            test_ctx.register_account(account_id);
+        }
+        for account_id in &["alice.near", "carol.near"] {
+            let mut account = test_ctx.registered_account(account_id);
+            account.apply_stake_credit(100.into());
+            test_ctx.save_registered_account(&account);
+        }
+
+        let holders = test_ctx.ft_holders(0, 2);
+        let holder_ids: Vec<&str> = holders
+            .iter()
+            .map(|(account_id, _)| account_id.as_str())
+            .collect();
+        assert_eq!(holder_ids, vec!["alice.near", "carol.near"]);
+
+        let holders = test_ctx.ft_holders(1, 1);
+        assert_eq!(holders.len(), 1);
+        assert_eq!(holders[0].0, "carol.near");
+    }
+
+    #[test]
+    fn accounts_page_limit_is_capped() {
+        let mut test_ctx = TestContext::with_registered_account();
+        for i in 0..MAX_ACCOUNTS_PAGE_LIMIT {
+            test_ctx.register_account(&format!("account-{}.near", i));
+        }
+
+        assert_eq!(
+            test_ctx.accounts(0, u64::MAX).len() as u64,
+            MAX_ACCOUNTS_PAGE_LIMIT
+        );
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
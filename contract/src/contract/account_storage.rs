@@ -1,7 +1,9 @@
 use crate::domain::RegisteredAccount;
 use crate::errors::account_management::INSUFFICIENT_STORAGE_FEE;
 use crate::errors::asserts::ATTACHED_DEPOSIT_IS_REQUIRED;
-use crate::interface::{AccountManagement, AccountStorage, AccountStorageBalance, YoctoNear};
+use crate::interface::{
+    AccountManagement, AccountStorage, StorageBalance, StorageBalanceBounds, YoctoNear,
+};
 use crate::near::assert_yocto_near_attached;
 use crate::*;
 use near_sdk::{json_types::ValidAccountId, near_bindgen, Promise};
@@ -9,35 +11,52 @@ use near_sdk::{json_types::ValidAccountId, near_bindgen, Promise};
 #[near_bindgen]
 impl AccountStorage for Contract {
     /// To be compliant with the expected behavior for the Account Storage Standard API (NEP-145):
-    /// - if overpayment is attached, then it is simply stored in the account storage escrow balance
-    ///
-    /// NOTE: We never want the function to panic.
+    /// - if overpayment is attached, then it is simply stored in the account storage escrow balance,
+    ///   unless `registration_only` is true, in which case the overpayment is refunded
     #[payable]
-    fn storage_deposit(&mut self, account_id: Option<ValidAccountId>) -> AccountStorageBalance {
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<ValidAccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
         assert!(env::attached_deposit() > 0, ATTACHED_DEPOSIT_IS_REQUIRED);
 
         let account_id = account_id.map_or_else(
             || env::predecessor_account_id(),
             |account_id| account_id.as_ref().to_string(),
         );
+        let registration_only = registration_only.unwrap_or(false);
         match self.lookup_registered_account(&account_id) {
-            None => self._register_account(&account_id),
+            None if registration_only => {
+                let account_storage_fee: domain::YoctoNear = self.account_storage_fee().into();
+                self._register_account(&account_id, account_storage_fee);
+                // refund over payment of storage fees
+                let refund = env::attached_deposit() - account_storage_fee.value();
+                if refund > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(refund);
+                }
+            }
+            None => self._register_account(&account_id, env::attached_deposit().into()),
+            // the account is already registered
+            Some(_) if registration_only => {
+                Promise::new(env::predecessor_account_id()).transfer(env::attached_deposit());
+            }
             // deposit funds into account storage escrow
             Some(mut account) => {
                 account
                     .storage_escrow
                     .credit(env::attached_deposit().into());
                 self.save_registered_account(&account);
+                // track total account storage escrow balance at contract level
+                self.total_account_storage_escrow += env::attached_deposit().into();
             }
         }
-        // track total account storage escrow balance at contract level
-        self.total_account_storage_escrow += env::attached_deposit().into();
 
-        self._storage_balance_of(&account_id)
+        self.account_storage_balance(&self.registered_account(&account_id))
     }
 
     #[payable]
-    fn storage_withdraw(&mut self, amount: Option<YoctoNear>) -> AccountStorageBalance {
+    fn storage_withdraw(&mut self, amount: Option<YoctoNear>) -> StorageBalance {
         assert_yocto_near_attached();
         if let Some(amount) = amount.as_ref() {
             assert!(
@@ -67,42 +86,69 @@ impl AccountStorage for Contract {
         account_storage_balance
     }
 
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_yocto_near_attached();
+        self.unregister(&env::predecessor_account_id(), force.unwrap_or(false))
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: self.account_storage_fee(),
+            max: None,
+        }
+    }
+
     fn storage_minimum_balance(&self) -> YoctoNear {
         self.account_storage_fee()
     }
 
-    fn storage_balance_of(&self, account_id: ValidAccountId) -> AccountStorageBalance {
-        self._storage_balance_of(account_id.as_ref())
+    fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance> {
+        self.lookup_registered_account(account_id.as_ref())
+            .map(|account| self.account_storage_balance(&account))
     }
 }
 
 impl Contract {
-    fn _register_account(&mut self, account_id: &str) {
+    /// registers the account with the specified storage escrow balance, which must cover the
+    /// account storage fee
+    /// - the storage escrow balance is paid for by the attached deposit
+    fn _register_account(&mut self, account_id: &str, storage_escrow: domain::YoctoNear) {
         assert!(
             env::attached_deposit() >= self.account_storage_fee().value(),
             INSUFFICIENT_STORAGE_FEE,
         );
-        let account = Account::new(env::attached_deposit().into());
+        let account = Account::new(storage_escrow);
         self.save_registered_account(&RegisteredAccount {
             account,
             id: Hash::from(account_id),
+            account_id: account_id.to_string(),
         });
+        self.account_ids.insert(&account_id.to_string());
+        // track total account storage escrow balance at contract level
+        self.total_account_storage_escrow += storage_escrow;
     }
 
-    /// accounts for changes in storage storage fees, i.e., if storage prices are lowered, then this
-    /// will be reflected in the available balance.
-    fn _storage_balance_of(&self, account_id: &str) -> AccountStorageBalance {
-        match self.lookup_registered_account(account_id) {
-            None => AccountStorageBalance::default(),
-            Some(account) => self.account_storage_balance(&account),
-        }
+    /// the account pays for its own storage plus the storage for each allowance that it has approved,
+    /// each pending batch that it holds, each open vault that it has transferred STAKE into, and each
+    /// redeem beneficiary that it has recorded
+    pub(crate) fn registered_account_storage_fee(&self, account: &Account) -> domain::YoctoNear {
+        let storage_usage = self.account_storage_usage.value()
+            + account.allowances_len as u64 * self.allowance_storage_usage.value()
+            + account.pending_batches_len as u64 * self.pending_batch_storage_usage.value()
+            + account.vaults_len as u64 * self.vault_storage_usage.value()
+            + account.redeem_beneficiaries_len as u64
+                * self.redeem_beneficiary_storage_usage.value();
+        (storage_usage as u128 * self.config.storage_cost_per_byte().value()).into()
     }
 
-    fn account_storage_balance(&self, account: &RegisteredAccount) -> AccountStorageBalance {
-        AccountStorageBalance {
+    /// accounts for changes in storage storage fees, i.e., if storage prices are lowered, then this
+    /// will be reflected in the available balance.
+    pub(crate) fn account_storage_balance(&self, account: &RegisteredAccount) -> StorageBalance {
+        StorageBalance {
             total: account.storage_escrow.amount().into(),
             available: {
-                let account_storage_fee = self.account_storage_fee().value();
+                let account_storage_fee = self.registered_account_storage_fee(account).value();
                 let storage_escrow_amount = account.storage_escrow.amount().value();
                 if account_storage_fee > storage_escrow_amount {
                     0.into()
@@ -132,7 +178,7 @@ mod test_storage_deposit {
         testing_env!(context);
 
         // Act
-        let balance = test_context.storage_deposit(Some(to_valid_account_id(&account_id)));
+        let balance = test_context.storage_deposit(Some(to_valid_account_id(&account_id)), None);
 
         // Assert
         assert_eq!(
@@ -148,9 +194,12 @@ mod test_storage_deposit {
             test_context.account_registered(to_valid_account_id(&account_id)),
             "the initial deposit should have registered the account"
         );
+        assert_eq!(test_context.accounts(0, 10)[0].0, account_id);
         assert_eq!(
             balance,
-            test_context.storage_balance_of(to_valid_account_id(&account_id))
+            test_context
+                .storage_balance_of(to_valid_account_id(&account_id))
+                .unwrap()
         );
     }
 
@@ -165,7 +214,7 @@ mod test_storage_deposit {
         testing_env!(context.clone());
 
         // Act
-        let balance = test_context.storage_deposit(Some(to_valid_account_id(&account_id)));
+        let balance = test_context.storage_deposit(Some(to_valid_account_id(&account_id)), None);
 
         // Assert
         assert_eq!(balance.total.value(), context.attached_deposit);
@@ -183,7 +232,9 @@ mod test_storage_deposit {
         );
         assert_eq!(
             balance,
-            test_context.storage_balance_of(to_valid_account_id(&account_id))
+            test_context
+                .storage_balance_of(to_valid_account_id(&account_id))
+                .unwrap()
         );
     }
 
@@ -195,7 +246,7 @@ mod test_storage_deposit {
         let account_id = test_context.account_id.to_string();
 
         // Act
-        test_context.storage_deposit(Some(to_valid_account_id(&account_id)));
+        test_context.storage_deposit(Some(to_valid_account_id(&account_id)), None);
     }
 
     #[test]
@@ -210,7 +261,7 @@ mod test_storage_deposit {
         testing_env!(context.clone());
 
         // Act
-        test_context.storage_deposit(Some(to_valid_account_id(&account_id)));
+        test_context.storage_deposit(Some(to_valid_account_id(&account_id)), None);
     }
 
     //
@@ -226,7 +277,7 @@ mod test_storage_deposit {
         testing_env!(context);
 
         // Act
-        let balance = test_context.storage_deposit(None);
+        let balance = test_context.storage_deposit(None, None);
 
         // Assert
         assert_eq!(
@@ -244,7 +295,9 @@ mod test_storage_deposit {
         );
         assert_eq!(
             balance,
-            test_context.storage_balance_of(to_valid_account_id(&account_id))
+            test_context
+                .storage_balance_of(to_valid_account_id(&account_id))
+                .unwrap()
         );
     }
 
@@ -259,7 +312,7 @@ mod test_storage_deposit {
         testing_env!(context.clone());
 
         // Act
-        let balance = test_context.storage_deposit(None);
+        let balance = test_context.storage_deposit(None, None);
 
         // Assert
         assert_eq!(balance.total.value(), context.attached_deposit);
@@ -277,7 +330,9 @@ mod test_storage_deposit {
         );
         assert_eq!(
             balance,
-            test_context.storage_balance_of(to_valid_account_id(&account_id))
+            test_context
+                .storage_balance_of(to_valid_account_id(&account_id))
+                .unwrap()
         );
     }
 
@@ -288,7 +343,7 @@ mod test_storage_deposit {
         let mut test_context = TestContext::new();
 
         // Act
-        test_context.storage_deposit(None);
+        test_context.storage_deposit(None, None);
     }
 
     #[test]
@@ -302,7 +357,7 @@ mod test_storage_deposit {
         testing_env!(context.clone());
 
         // Act
-        test_context.storage_deposit(None);
+        test_context.storage_deposit(None, None);
     }
 
     //
@@ -314,7 +369,7 @@ mod test_storage_deposit {
         let mut test_context = TestContext::with_registered_account();
         let account_id = test_context.account_id.to_string();
 
-        test_context.storage_deposit(Some(to_valid_account_id(&account_id)));
+        test_context.storage_deposit(Some(to_valid_account_id(&account_id)), None);
     }
 
     #[test]
@@ -328,7 +383,7 @@ mod test_storage_deposit {
         testing_env!(context.clone());
 
         // Act
-        let balance = test_context.storage_deposit(Some(to_valid_account_id(&account_id)));
+        let balance = test_context.storage_deposit(Some(to_valid_account_id(&account_id)), None);
 
         // Assert
         assert_eq!(balance.available.value(), context.attached_deposit);
@@ -338,7 +393,9 @@ mod test_storage_deposit {
         );
         assert_eq!(
             balance,
-            test_context.storage_balance_of(to_valid_account_id(&account_id))
+            test_context
+                .storage_balance_of(to_valid_account_id(&account_id))
+                .unwrap()
         );
     }
 
@@ -350,7 +407,7 @@ mod test_storage_deposit {
         // Arrange
         let mut test_context = TestContext::with_registered_account();
 
-        test_context.storage_deposit(None);
+        test_context.storage_deposit(None, None);
     }
 
     #[test]
@@ -364,7 +421,7 @@ mod test_storage_deposit {
         testing_env!(context.clone());
 
         // Act
-        let balance = test_context.storage_deposit(None);
+        let balance = test_context.storage_deposit(None, None);
 
         // Assert
         assert_eq!(balance.available.value(), context.attached_deposit);
@@ -374,9 +431,69 @@ mod test_storage_deposit {
         );
         assert_eq!(
             balance,
-            test_context.storage_balance_of(to_valid_account_id(&account_id))
+            test_context
+                .storage_balance_of(to_valid_account_id(&account_id))
+                .unwrap()
         );
     }
+
+    #[test]
+    fn registration_only_refunds_overpayment() {
+        // Arrange
+        let mut test_context = TestContext::new();
+        let account_id = test_context.account_id.to_string();
+        let account_storage_fee = test_context.storage_minimum_balance().value();
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = account_storage_fee * 3;
+        testing_env!(context);
+
+        // Act
+        let balance = test_context.storage_deposit(None, Some(true));
+
+        // Assert
+        assert_eq!(balance.total.value(), account_storage_fee);
+        assert_eq!(balance.available.value(), 0);
+        assert_eq!(
+            test_context.total_account_storage_escrow.value(),
+            account_storage_fee
+        );
+        assert!(test_context.account_registered(to_valid_account_id(&account_id)));
+
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, account_id);
+        match &receipts[0].actions[0] {
+            Action::Transfer { deposit } => assert_eq!(*deposit, account_storage_fee * 2),
+            _ => panic!("expected overpayment to be refunded"),
+        }
+    }
+
+    #[test]
+    fn registration_only_with_registered_account_refunds_deposit() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let account_id = test_context.account_id.to_string();
+        let balance_before_deposit = test_context
+            .storage_balance_of(to_valid_account_id(&account_id))
+            .unwrap();
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = 100;
+        testing_env!(context);
+
+        // Act
+        let balance = test_context.storage_deposit(None, Some(true));
+
+        // Assert
+        assert_eq!(balance, balance_before_deposit);
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 1);
+        match &receipts[0].actions[0] {
+            Action::Transfer { deposit } => assert_eq!(*deposit, 100),
+            _ => panic!("expected deposit to be refunded"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -405,7 +522,7 @@ mod test_storage_withdraw {
         context.attached_deposit = YOCTO;
         testing_env!(context);
 
-        test_context.storage_deposit(None);
+        test_context.storage_deposit(None, None);
 
         let mut context = test_context.context.clone();
         context.attached_deposit = 1;
@@ -488,7 +605,7 @@ mod test_storage_withdraw {
         context.attached_deposit = 300;
         testing_env!(context.clone());
 
-        test_context.storage_deposit(None);
+        test_context.storage_deposit(None, None);
 
         let mut context = test_context.context.clone();
         context.attached_deposit = 1;
@@ -531,7 +648,7 @@ mod test_storage_withdraw {
         context.attached_deposit = 100;
         testing_env!(context.clone());
 
-        test_context.storage_deposit(None);
+        test_context.storage_deposit(None, None);
 
         let mut context = test_context.context.clone();
         context.attached_deposit = 1;
@@ -581,3 +698,175 @@ mod test_storage_minimum_balance {
         );
     }
 }
+
+#[cfg(test)]
+mod test_storage_balance_bounds {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn min_is_account_storage_fee_with_no_max() {
+        let test_context = TestContext::new();
+
+        let bounds = test_context.storage_balance_bounds();
+        assert_eq!(bounds.min, test_context.account_storage_fee());
+        assert!(bounds.max.is_none());
+    }
+}
+
+#[cfg(test)]
+mod test_storage_unregister {
+    use super::*;
+    use crate::domain::{BlockTimeHeight, StakeTokenValue, TimestampedStakeBalance, YoctoStake};
+    use crate::interface::{LiquidityProvider, StakingService};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
+
+    #[test]
+    fn account_with_no_funds() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let account_id = test_context.account_id;
+        let account_storage_fee = test_context.account_storage_fee().value();
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = 1;
+        testing_env!(context);
+
+        // Act
+        assert!(test_context.storage_unregister(None));
+
+        // Assert
+        assert!(!test_context.account_registered(to_valid_account_id(account_id)));
+        assert!(test_context.accounts(0, 10).is_empty());
+        assert_eq!(test_context.total_account_storage_escrow.value(), 0);
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 1);
+        match &receipts[0].actions[0] {
+            Action::Transfer { deposit } => assert_eq!(*deposit, account_storage_fee + 1),
+            _ => panic!("expected account storage escrow to be refunded"),
+        }
+    }
+
+    #[test]
+    fn account_not_registered() {
+        let mut test_context = TestContext::new();
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = 1;
+        testing_env!(context);
+
+        assert!(!test_context.storage_unregister(None));
+
+        // the attached yoctoNEAR is refunded
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, test_context.account_id);
+        match &receipts[0].actions[0] {
+            Action::Transfer { deposit } => assert_eq!(*deposit, 1),
+            _ => panic!("expected the attached deposit to be refunded"),
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "all funds must be withdrawn from the account in order to unregister"
+    )]
+    fn account_with_stake_not_forced() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut account = test_context.registered_account(test_context.account_id);
+        account.apply_stake_credit(YOCTO.into());
+        test_context.save_registered_account(&account);
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = 1;
+        testing_env!(context);
+
+        test_context.storage_unregister(Some(false));
+    }
+
+    #[test]
+    fn forced_with_stake_and_near() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let account_id = test_context.account_id;
+        test_context.total_stake = TimestampedStakeBalance::new((10 * YOCTO).into());
+        test_context.stake_token_value = StakeTokenValue::new(
+            BlockTimeHeight::from_env(),
+            (11 * YOCTO).into(),
+            (10 * YOCTO).into(),
+        );
+        test_context.total_near.credit((2 * YOCTO).into());
+        let mut account = test_context.registered_account(account_id);
+        account.apply_stake_credit(YOCTO.into());
+        account.apply_near_credit((2 * YOCTO).into());
+        test_context.save_registered_account(&account);
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = 1;
+        testing_env!(context);
+
+        // Act
+        assert!(test_context.storage_unregister(Some(true)));
+
+        // Assert
+        assert!(!test_context.account_registered(to_valid_account_id(account_id)));
+        assert_eq!(test_context.total_stake.amount(), YoctoStake(9 * YOCTO));
+        assert_eq!(
+            test_context.stake_token_value.total_stake_supply(),
+            YoctoStake(9 * YOCTO)
+        );
+        assert_eq!(
+            test_context
+                .stake_token_value
+                .total_staked_near_balance()
+                .value(),
+            11 * YOCTO
+        );
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains("ft_burn") && log.contains("storage_unregister")));
+
+        // NEAR balance and storage escrow are refunded
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        match &receipts[0].actions[0] {
+            Action::Transfer { deposit } => assert_eq!(*deposit, 2 * YOCTO),
+            _ => panic!("expected NEAR balance to be refunded"),
+        }
+        assert_eq!(test_context.total_near.amount().value(), 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "all funds must be withdrawn or claimed from stake and redeem batches in order to unregister"
+    )]
+    fn forced_with_funds_in_stake_batch() {
+        let mut test_context = TestContext::with_registered_account();
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        test_context.deposit();
+
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_context.storage_unregister(Some(true));
+    }
+
+    #[test]
+    #[should_panic(expected = "all liquidity must be removed in order to unregister")]
+    fn forced_with_liquidity_provider_shares() {
+        let mut test_context = TestContext::with_registered_account();
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context.clone());
+        test_context.add_liquidity();
+
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_context.storage_unregister(Some(true));
+    }
+}
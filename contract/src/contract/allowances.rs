@@ -0,0 +1,502 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::*;
+use crate::{
+    config::FeeCategory,
+    contract::fungible_token::assert_token_amount_not_zero,
+    domain::{self, RegisteredAccount, YoctoStake},
+    errors::{
+        allowances::{
+            ALLOWANCE_EXPIRED, ALLOWANCE_NOT_FOUND, EXPIRATION_MUST_BE_IN_FUTURE,
+            INSUFFICIENT_STORAGE_ESCROW, SELF_APPROVAL,
+        },
+        asserts::ATTACHED_DEPOSIT_IS_REQUIRED,
+    },
+    interface::{
+        allowances::events::AllowanceUpdated, fungible_token::events::FtTransfer, Allowance,
+        BlockTimestamp, FungibleTokenAllowances, Memo, TokenAmount,
+    },
+    near::{self, assert_yocto_near_attached},
+};
+use near_sdk::{env, json_types::ValidAccountId, near_bindgen};
+
+#[near_bindgen]
+impl FungibleTokenAllowances for Contract {
+    #[payable]
+    fn ft_approve(
+        &mut self,
+        spender_id: ValidAccountId,
+        amount: TokenAmount,
+        expiration: Option<BlockTimestamp>,
+    ) -> Allowance {
+        assert!(env::attached_deposit() > 0, ATTACHED_DEPOSIT_IS_REQUIRED);
+        assert_token_amount_not_zero(&amount);
+        let expiration = expiration.map(domain::BlockTimestamp::from);
+        if let Some(expiration) = expiration {
+            assert!(
+                expiration.value() > env::block_timestamp(),
+                EXPIRATION_MUST_BE_IN_FUTURE
+            );
+        }
+
+        let mut owner = self.predecessor_registered_account();
+        assert_ne!(&owner.account_id, spender_id.as_ref(), "{}", SELF_APPROVAL);
+        self.deposit_into_storage_escrow(&mut owner);
+
+        let allowance = domain::Allowance::new(amount.value().into(), expiration);
+        self.save_allowance(&mut owner, spender_id.as_ref(), allowance);
+        self.save_registered_account(&owner);
+        allowance.into()
+    }
+
+    #[payable]
+    fn ft_increase_allowance(
+        &mut self,
+        spender_id: ValidAccountId,
+        amount: TokenAmount,
+    ) -> Allowance {
+        assert!(env::attached_deposit() > 0, ATTACHED_DEPOSIT_IS_REQUIRED);
+        assert_token_amount_not_zero(&amount);
+
+        let mut owner = self.predecessor_registered_account();
+        assert_ne!(&owner.account_id, spender_id.as_ref(), "{}", SELF_APPROVAL);
+        self.deposit_into_storage_escrow(&mut owner);
+
+        let mut allowance = self
+            .allowances
+            .get(&Self::allowance_key(&owner.account_id, spender_id.as_ref()))
+            .unwrap_or_else(|| domain::Allowance::new(0.into(), None));
+        assert!(!allowance.expired(), ALLOWANCE_EXPIRED);
+        allowance.increase(amount.value().into());
+        self.save_allowance(&mut owner, spender_id.as_ref(), allowance);
+        self.save_registered_account(&owner);
+        allowance.into()
+    }
+
+    #[payable]
+    fn ft_decrease_allowance(
+        &mut self,
+        spender_id: ValidAccountId,
+        amount: TokenAmount,
+    ) -> Option<Allowance> {
+        assert_yocto_near_attached();
+
+        let mut owner = self.predecessor_registered_account();
+        let mut allowance = self
+            .allowances
+            .get(&Self::allowance_key(&owner.account_id, spender_id.as_ref()))
+            .expect(ALLOWANCE_NOT_FOUND);
+        allowance.decrease(amount.value().into());
+        let allowance = self.save_allowance(&mut owner, spender_id.as_ref(), allowance);
+        // apply the 1 yoctoNEAR that was attached to the owner account's NEAR balance
+        owner.apply_near_credit(1.into());
+        self.save_registered_account(&owner);
+        allowance.map(Into::into)
+    }
+
+    #[payable]
+    fn ft_revoke_allowance(&mut self, spender_id: ValidAccountId) {
+        assert_yocto_near_attached();
+
+        let mut owner = self.predecessor_registered_account();
+        // the allowance update is only logged if an allowance was actually revoked
+        if self
+            .allowances
+            .get(&Self::allowance_key(&owner.account_id, spender_id.as_ref()))
+            .is_some()
+        {
+            self.save_allowance(
+                &mut owner,
+                spender_id.as_ref(),
+                domain::Allowance::new(0.into(), None),
+            );
+        }
+        // apply the 1 yoctoNEAR that was attached to the owner account's NEAR balance
+        owner.apply_near_credit(1.into());
+        self.save_registered_account(&owner);
+    }
+
+    #[payable]
+    fn ft_transfer_from(
+        &mut self,
+        owner_id: ValidAccountId,
+        receiver_id: ValidAccountId,
+        amount: TokenAmount,
+        memo: Option<Memo>,
+    ) {
+        assert_yocto_near_attached();
+        assert_token_amount_not_zero(&amount);
+
+        let stake_amount: YoctoStake = amount.value().into();
+        let spender_id = env::predecessor_account_id();
+
+        let mut owner = self.registered_account(owner_id.as_ref());
+        let mut allowance = self
+            .allowances
+            .get(&Self::allowance_key(owner_id.as_ref(), &spender_id))
+            .expect(ALLOWANCE_NOT_FOUND);
+        assert!(!allowance.expired(), ALLOWANCE_EXPIRED);
+        // the owner is charged the fee on top of the transfer amount - the fee is drawn from the
+        // spender's allowance, i.e., the spender can never spend more than the owner approved
+        let fee = self.stake_fee(FeeCategory::FtTransfer, &owner.account_id, stake_amount);
+        allowance.decrease(stake_amount + fee);
+        self.save_allowance(&mut owner, &spender_id, allowance);
+
+        self.claim_receipt_funds(&mut owner);
+        owner.apply_stake_debit(stake_amount + fee);
+        // apply the 1 yoctoNEAR that was attached to the owner account's NEAR balance
+        owner.apply_near_credit(1.into());
+        self.save_registered_account(&owner);
+
+        let mut receiver = self.registered_account(receiver_id.as_ref());
+        receiver.apply_stake_credit(stake_amount);
+        self.save_registered_account(&receiver);
+
+        near::log(FtTransfer {
+            old_owner_id: owner_id.as_ref(),
+            new_owner_id: receiver_id.as_ref(),
+            amount: amount.value().into(),
+            memo: memo.as_deref(),
+        });
+        self.collect_ft_transfer_fee(owner_id.as_ref(), fee);
+    }
+
+    fn ft_allowance(
+        &self,
+        owner_id: ValidAccountId,
+        spender_id: ValidAccountId,
+    ) -> Option<Allowance> {
+        self.allowances
+            .get(&Self::allowance_key(owner_id.as_ref(), spender_id.as_ref()))
+            .map(Into::into)
+    }
+}
+
+impl Contract {
+    /// account IDs cannot contain ':', which makes the (owner, spender) key unambiguous
+    pub(crate) fn allowance_key(owner_id: &str, spender_id: &str) -> Hash {
+        Hash::from(format!("{}:{}", owner_id, spender_id).as_str())
+    }
+
+    /// saves the allowance, or deletes it if the allowance amount is zero
+    /// - the owner account pays for the allowance storage - when a new allowance is created the
+    ///   owner account storage escrow balance must be enough to cover the additional storage
+    ///
+    /// NOTE: the owner account is not saved
+    fn save_allowance(
+        &mut self,
+        owner: &mut RegisteredAccount,
+        spender_id: &str,
+        allowance: domain::Allowance,
+    ) -> Option<domain::Allowance> {
+        let key = Self::allowance_key(&owner.account_id, spender_id);
+        let allowance = if allowance.amount().value() == 0 {
+            if self.allowances.remove(&key).is_some() {
+                owner.allowances_len -= 1;
+            }
+            None
+        } else {
+            if self.allowances.insert(&key, &allowance).is_none() {
+                owner.allowances_len += 1;
+                assert!(
+                    owner.storage_escrow.amount() >= self.registered_account_storage_fee(owner),
+                    INSUFFICIENT_STORAGE_ESCROW
+                );
+            }
+            Some(allowance)
+        };
+
+        near::log(AllowanceUpdated {
+            owner_id: &owner.account_id,
+            spender_id,
+            amount: allowance
+                .map_or(0, |allowance| allowance.amount().value())
+                .into(),
+            expiration: allowance
+                .and_then(|allowance| allowance.expiration())
+                .map(|expiration| expiration.value().into()),
+        });
+        allowance
+    }
+
+    fn deposit_into_storage_escrow(&mut self, account: &mut RegisteredAccount) {
+        let deposit: domain::YoctoNear = env::attached_deposit().into();
+        account.storage_escrow.credit(deposit);
+        // track total account storage escrow balance at contract level
+        self.total_account_storage_escrow += deposit;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{AccountManagement, AccountStorage, FungibleToken};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
+
+    const SPENDER_ID: &str = "spender.near";
+    const RECEIVER_ID: &str = "receiver.near";
+
+    fn allowance_storage_fee(test_ctx: &TestContext) -> u128 {
+        test_ctx.allowance_storage_usage.value() as u128
+            * test_ctx.config.storage_cost_per_byte().value()
+    }
+
+    /// credits the test account with 100 STAKE and approves the spender for 10 STAKE
+    fn approve_spender(test_ctx: &mut TestContext) {
+        let owner_id = test_ctx.account_id;
+        let mut owner = test_ctx.registered_account(owner_id);
+        let total_supply = YoctoStake(100 * YOCTO);
+        owner.apply_stake_credit(total_supply);
+        test_ctx.total_stake.credit(total_supply);
+        test_ctx.save_registered_account(&owner);
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = allowance_storage_fee(test_ctx);
+        testing_env!(context);
+        test_ctx.ft_approve(to_valid_account_id(SPENDER_ID), (10 * YOCTO).into(), None);
+    }
+
+    #[test]
+    fn approve_then_transfer_from() {
+        // Arrange
+        let mut test_ctx = TestContext::with_registered_account();
+        let owner_id = test_ctx.account_id;
+        test_ctx.register_account(RECEIVER_ID);
+        approve_spender(&mut test_ctx);
+
+        // Assert - the owner account pays for the allowance storage
+        let owner = test_ctx.registered_account(owner_id);
+        assert_eq!(owner.allowances_len, 1);
+        assert_eq!(
+            test_ctx
+                .storage_balance_of(to_valid_account_id(owner_id))
+                .unwrap()
+                .available
+                .value(),
+            0
+        );
+        assert_eq!(
+            test_ctx
+                .ft_allowance(
+                    to_valid_account_id(owner_id),
+                    to_valid_account_id(SPENDER_ID)
+                )
+                .unwrap()
+                .amount,
+            (10 * YOCTO).into()
+        );
+
+        // Act - spender transfers part of the allowance
+        let mut context = test_ctx.set_predecessor_account_id(SPENDER_ID);
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        test_ctx.ft_transfer_from(
+            to_valid_account_id(owner_id),
+            to_valid_account_id(RECEIVER_ID),
+            (4 * YOCTO).into(),
+            None,
+        );
+
+        // Assert
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id(owner_id))
+                .value(),
+            96 * YOCTO
+        );
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id(RECEIVER_ID))
+                .value(),
+            4 * YOCTO
+        );
+        assert_eq!(
+            test_ctx
+                .ft_allowance(
+                    to_valid_account_id(owner_id),
+                    to_valid_account_id(SPENDER_ID)
+                )
+                .unwrap()
+                .amount,
+            (6 * YOCTO).into()
+        );
+
+        // Act - spender transfers the rest of the allowance
+        test_ctx.ft_transfer_from(
+            to_valid_account_id(owner_id),
+            to_valid_account_id(RECEIVER_ID),
+            (6 * YOCTO).into(),
+            None,
+        );
+
+        // Assert - the spent allowance is deleted and its storage is freed
+        assert!(test_ctx
+            .ft_allowance(
+                to_valid_account_id(owner_id),
+                to_valid_account_id(SPENDER_ID)
+            )
+            .is_none());
+        assert_eq!(test_ctx.registered_account(owner_id).allowances_len, 0);
+        assert_eq!(
+            test_ctx
+                .storage_balance_of(to_valid_account_id(owner_id))
+                .unwrap()
+                .available
+                .value(),
+            allowance_storage_fee(&test_ctx)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "allowance is insufficient to fulfill request")]
+    fn transfer_from_more_than_allowance() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let owner_id = test_ctx.account_id;
+        test_ctx.register_account(RECEIVER_ID);
+        approve_spender(&mut test_ctx);
+
+        let mut context = test_ctx.set_predecessor_account_id(SPENDER_ID);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.ft_transfer_from(
+            to_valid_account_id(owner_id),
+            to_valid_account_id(RECEIVER_ID),
+            (11 * YOCTO).into(),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "allowance does not exist")]
+    fn transfer_from_without_allowance() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let owner_id = test_ctx.account_id;
+        test_ctx.register_account(RECEIVER_ID);
+
+        let mut context = test_ctx.set_predecessor_account_id(SPENDER_ID);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.ft_transfer_from(
+            to_valid_account_id(owner_id),
+            to_valid_account_id(RECEIVER_ID),
+            YOCTO.into(),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "allowance has expired")]
+    fn transfer_from_with_expired_allowance() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let owner_id = test_ctx.account_id;
+        test_ctx.register_account(RECEIVER_ID);
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = allowance_storage_fee(&test_ctx);
+        context.block_timestamp = 100;
+        testing_env!(context.clone());
+        test_ctx.ft_approve(
+            to_valid_account_id(SPENDER_ID),
+            YOCTO.into(),
+            Some(BlockTimestamp(200.into())),
+        );
+
+        context.predecessor_account_id = SPENDER_ID.to_string();
+        context.attached_deposit = 1;
+        context.block_timestamp = 200;
+        testing_env!(context);
+        test_ctx.ft_transfer_from(
+            to_valid_account_id(owner_id),
+            to_valid_account_id(RECEIVER_ID),
+            YOCTO.into(),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "account storage balance is insufficient to pay for allowance storage - attach more deposit"
+    )]
+    fn approve_with_insufficient_storage_deposit() {
+        let mut test_ctx = TestContext::with_registered_account();
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = allowance_storage_fee(&test_ctx) - 1;
+        testing_env!(context);
+        test_ctx.ft_approve(to_valid_account_id(SPENDER_ID), YOCTO.into(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "account cannot approve itself as a spender")]
+    fn approve_self() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let owner_id = test_ctx.account_id;
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = allowance_storage_fee(&test_ctx);
+        testing_env!(context);
+        test_ctx.ft_approve(to_valid_account_id(owner_id), YOCTO.into(), None);
+    }
+
+    #[test]
+    fn increase_decrease_and_revoke_allowance() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let owner_id = test_ctx.account_id;
+        approve_spender(&mut test_ctx);
+
+        // increasing an existing allowance requires no additional storage
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = 1;
+        testing_env!(context.clone());
+        let allowance =
+            test_ctx.ft_increase_allowance(to_valid_account_id(SPENDER_ID), (5 * YOCTO).into());
+        assert_eq!(allowance.amount, (15 * YOCTO).into());
+
+        let allowance = test_ctx
+            .ft_decrease_allowance(to_valid_account_id(SPENDER_ID), (5 * YOCTO).into())
+            .unwrap();
+        assert_eq!(allowance.amount, (10 * YOCTO).into());
+
+        test_ctx.ft_revoke_allowance(to_valid_account_id(SPENDER_ID));
+        assert!(test_ctx
+            .ft_allowance(
+                to_valid_account_id(owner_id),
+                to_valid_account_id(SPENDER_ID)
+            )
+            .is_none());
+        assert_eq!(test_ctx.registered_account(owner_id).allowances_len, 0);
+    }
+
+    #[test]
+    fn revoke_allowance_that_does_not_exist() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let owner_id = test_ctx.account_id;
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.ft_revoke_allowance(to_valid_account_id(SPENDER_ID));
+        assert!(get_logs().is_empty());
+        let owner = test_ctx.registered_account(owner_id);
+        assert_eq!(owner.allowances_len, 0);
+        assert_eq!(owner.near.unwrap().amount().value(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "all allowances must be revoked in order to unregister")]
+    fn unregister_with_allowances() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let owner_id = test_ctx.account_id;
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = allowance_storage_fee(&test_ctx);
+        testing_env!(context.clone());
+        test_ctx.ft_approve(to_valid_account_id(SPENDER_ID), YOCTO.into(), None);
+
+        context.predecessor_account_id = owner_id.to_string();
+        context.attached_deposit = 0;
+        testing_env!(context);
+        test_ctx.unregister_account();
+    }
+}
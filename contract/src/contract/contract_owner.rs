@@ -42,6 +42,24 @@ impl ContractOwner for Contract {
         self.operator_id = account_id.into();
     }
 
+    fn treasury_id(&self) -> AccountId {
+        self.treasury_id
+            .clone()
+            .unwrap_or_else(|| self.owner_id.clone())
+    }
+
+    fn set_treasury_id(&mut self, account_id: Option<ValidAccountId>) {
+        self.assert_predecessor_is_owner();
+        if let Some(account_id) = account_id.as_ref() {
+            assert!(
+                self.account_registered(account_id.clone()),
+                TRANSFER_TO_NON_REGISTERED_ACCOUNT,
+            );
+        }
+
+        self.treasury_id = account_id.map(Into::into);
+    }
+
     fn stake_all_owner_balance(&mut self) -> YoctoNear {
         self.assert_predecessor_is_owner();
         let mut account = self.registered_account(&self.owner_id);
@@ -124,6 +142,23 @@ mod test {
         assert_eq!(contract.operator_id, ctx.account_id);
     }
 
+    #[test]
+    fn set_treasury_id() {
+        let mut ctx = TestContext::with_registered_account();
+        let mut context = ctx.context.clone();
+        let contract = &mut ctx.contract;
+        assert_eq!(contract.treasury_id(), contract.owner_id);
+
+        context.predecessor_account_id = contract.owner_id.clone();
+        testing_env!(context.clone());
+
+        contract.set_treasury_id(Some(ValidAccountId::try_from(ctx.account_id).unwrap()));
+        assert_eq!(contract.treasury_id(), ctx.account_id);
+
+        contract.set_treasury_id(None);
+        assert_eq!(contract.treasury_id(), contract.owner_id);
+    }
+
     #[test]
     #[should_panic(expected = "contract call is only allowed by the contract owner")]
     fn set_operator_id_invoked_by_non_owner() {
@@ -0,0 +1,535 @@
+//! Fees are charged per the [FeeSchedule](crate::config::FeeSchedule):
+//! - NEAR fees are added to the collected earnings, which are split between the contract owner and
+//!   the user accounts when earnings are distributed
+//! - STAKE fees are held by the contract until earnings are distributed, at which point they are split
+//!   between the contract owner and the STAKE holders - the NEAR liquidity pool is not drawn on
+//!   because it backs the pending withdrawal
+use crate::*;
+use crate::{
+    config::FeeCategory,
+    errors::staking_service::AMOUNT_DOES_NOT_COVER_FEE,
+    interface::{
+        fungible_token::events::{FtBurn, FtTransfer},
+        staking_service::events::{FeeCharged, StakeFeesDistributed},
+    },
+    near::log,
+};
+
+impl Contract {
+    /// returns the fee that the account is charged for the NEAR amount
+    pub(crate) fn near_fee(
+        &self,
+        category: FeeCategory,
+        account_id: &str,
+        amount: domain::YoctoNear,
+    ) -> domain::YoctoNear {
+        self.config
+            .fee_schedule()
+            .fee(category)
+            .fee(account_id, amount)
+    }
+
+    /// returns the fee that the account is charged for the STAKE amount
+    /// - the fee is computed on the NEAR value of the STAKE using the cached STAKE token value
+    pub(crate) fn stake_fee(
+        &self,
+        category: FeeCategory,
+        account_id: &str,
+        amount: domain::YoctoStake,
+    ) -> domain::YoctoStake {
+        let fee = self.near_fee(
+            category,
+            account_id,
+            self.stake_token_value.stake_to_near(amount),
+        );
+        if fee.value() == 0 {
+            return 0.into();
+        }
+        self.stake_token_value.near_to_stake(fee)
+    }
+
+    /// charges the fee on the NEAR amount, which is added to the collected earnings
+    ///
+    /// Returns the amount after the fee is deducted.
+    ///
+    /// ## Panics
+    /// if the amount does not cover the fee
+    pub(crate) fn charge_near_fee(
+        &mut self,
+        category: FeeCategory,
+        account_id: &str,
+        amount: domain::YoctoNear,
+    ) -> domain::YoctoNear {
+        let fee = self.near_fee(category, account_id, amount);
+        if fee.value() == 0 {
+            return amount;
+        }
+        assert!(amount > fee, AMOUNT_DOES_NOT_COVER_FEE);
+
+        self.collected_earnings += fee;
+        self.collected_fees.record(category, fee);
+        log(FeeCharged {
+            account_id,
+            category,
+            fee: fee.into(),
+            fee_near_value: fee.into(),
+        });
+        amount - fee
+    }
+
+    /// charges the fee on the STAKE amount, which is held by the contract until it is distributed
+    /// - the STAKE amount must already have been debited from the account
+    ///
+    /// Returns the amount after the fee is deducted.
+    ///
+    /// ## Panics
+    /// if the amount does not cover the fee
+    pub(crate) fn charge_stake_fee(
+        &mut self,
+        category: FeeCategory,
+        account_id: &str,
+        amount: domain::YoctoStake,
+    ) -> domain::YoctoStake {
+        let fee = self.stake_fee(category, account_id, amount);
+        if fee.value() == 0 {
+            return amount;
+        }
+        assert!(amount > fee, AMOUNT_DOES_NOT_COVER_FEE);
+        self.collect_stake_fee(category, account_id, fee);
+        amount - fee
+    }
+
+    /// the STAKE fee must already have been debited from the account
+    pub(crate) fn collect_stake_fee(
+        &mut self,
+        category: FeeCategory,
+        account_id: &str,
+        fee: domain::YoctoStake,
+    ) {
+        if fee.value() == 0 {
+            return;
+        }
+        let fee_near_value = self.stake_token_value.stake_to_near(fee);
+        self.collected_fees.credit_stake(fee);
+        self.collected_fees.record(category, fee_near_value);
+        log(FeeCharged {
+            account_id,
+            category,
+            fee: fee.into(),
+            fee_near_value: fee_near_value.into(),
+        });
+    }
+
+    /// distributes the STAKE fees per the contract owner earnings percentage, same as the collected
+    /// earnings are split
+    /// - the contract owner's share is kept as treasury STAKE, i.e., it is transferred to the
+    ///   contract owner account
+    /// - the rest is burned, which distributes its value to the STAKE holders through the STAKE token
+    ///   value because the NEAR that backs it stays staked
+    /// - the STAKE fees are held until the contract owner account is registered
+    pub(crate) fn distribute_stake_fees(&mut self) {
+        let stake_fees = self.collected_fees.stake();
+        if stake_fees.value() == 0 {
+            return;
+        }
+        let mut owner = match self.lookup_registered_account(&self.owner_id) {
+            Some(owner) => owner,
+            None => return,
+        };
+
+        let contract_owner_stake: domain::YoctoStake = self
+            .contract_owner_share(stake_fees.value().into())
+            .value()
+            .into();
+        let burned_stake = stake_fees - contract_owner_stake;
+        self.collected_fees.debit_stake(stake_fees);
+
+        if contract_owner_stake.value() > 0 {
+            owner.apply_stake_credit(contract_owner_stake);
+            self.save_registered_account(&owner);
+            log(FtTransfer {
+                old_owner_id: &env::current_account_id(),
+                new_owner_id: &owner.account_id,
+                amount: contract_owner_stake.into(),
+                memo: Some("fees"),
+            });
+        }
+        if burned_stake.value() > 0 {
+            self.total_stake.debit(burned_stake);
+            self.update_stake_token_value(self.stake_token_value.total_staked_near_balance());
+            log(FtBurn {
+                owner_id: &env::current_account_id(),
+                amount: burned_stake.into(),
+                memo: Some("fees"),
+            });
+        }
+        log(StakeFeesDistributed {
+            contract_owner_stake: contract_owner_stake.into(),
+            burned_stake: burned_stake.into(),
+        });
+    }
+
+    pub(crate) fn fee_quote(
+        &self,
+        category: FeeCategory,
+        account_id: &str,
+        amount: u128,
+    ) -> interface::FeeQuote {
+        let (fee, fee_near_value) = match category {
+            FeeCategory::Deposit | FeeCategory::Withdraw => {
+                let fee = self.near_fee(category, account_id, amount.into());
+                (fee.value(), fee)
+            }
+            FeeCategory::Redeem | FeeCategory::FtTransfer => {
+                let fee = self.stake_fee(category, account_id, amount.into());
+                (fee.value(), self.stake_token_value.stake_to_near(fee))
+            }
+        };
+        let amount_with_fee = match category {
+            FeeCategory::FtTransfer => amount + fee,
+            _ => amount.saturating_sub(fee),
+        };
+        interface::FeeQuote {
+            category,
+            amount: amount.into(),
+            fee: fee.into(),
+            fee_near_value: fee_near_value.into(),
+            amount_with_fee: amount_with_fee.into(),
+            exempt: self
+                .config
+                .fee_schedule()
+                .fee(category)
+                .is_exempt(account_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        interface::{
+            FungibleToken, FungibleTokenAllowances, FungibleTokenBatchTransfer, StakingService,
+            VaultFungibleToken,
+        },
+        near::YOCTO,
+        test_utils::*,
+    };
+    use near_sdk::{json_types::ValidAccountId, testing_env, MockedBlockchain};
+    use std::convert::TryFrom;
+
+    fn set_fee(contract: &mut Contract, category: FeeCategory, fee: interface::Fee) {
+        let mut fee_schedule = interface::FeeSchedule {
+            deposit: None,
+            redeem: None,
+            withdraw: None,
+            ft_transfer: None,
+        };
+        match category {
+            FeeCategory::Deposit => fee_schedule.deposit = Some(fee),
+            FeeCategory::Redeem => fee_schedule.redeem = Some(fee),
+            FeeCategory::Withdraw => fee_schedule.withdraw = Some(fee),
+            FeeCategory::FtTransfer => fee_schedule.ft_transfer = Some(fee),
+        }
+        contract.config.merge(interface::Config {
+            storage_cost_per_byte: None,
+            gas_config: None,
+            contract_owner_earnings_percentage: None,
+            keeper_reward: None,
+            instant_redeem_fee_basis_points: None,
+            performance_fee_basis_points: None,
+            insurance_buffer_earnings_percentage: None,
+            fee_schedule: Some(fee_schedule),
+            receipt_expiry_epochs: None,
+        });
+    }
+
+    #[test]
+    fn deposit_fee_is_added_to_collected_earnings() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+        set_fee(
+            contract,
+            FeeCategory::Deposit,
+            interface::Fee {
+                flat: Some((YOCTO / 100).into()),
+                basis_points: Some(100),
+                exempt_account_ids: None,
+            },
+        );
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context);
+        contract.deposit();
+
+        let fee = YOCTO / 100 + YOCTO / 10;
+        let account = contract.registered_account(test_context.account_id);
+        assert_eq!(
+            account.stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO - fee).into()
+        );
+        assert_eq!(contract.collected_earnings, fee.into());
+        assert_eq!(
+            contract.collected_fees.total(FeeCategory::Deposit),
+            fee.into()
+        );
+    }
+
+    #[test]
+    fn exempt_account_is_not_charged_withdraw_fee() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        set_fee(
+            contract,
+            FeeCategory::Withdraw,
+            interface::Fee {
+                flat: Some((YOCTO / 100).into()),
+                basis_points: None,
+                exempt_account_ids: Some(vec![
+                    ValidAccountId::try_from(test_context.account_id).unwrap()
+                ]),
+            },
+        );
+        let mut account = contract.registered_account(test_context.account_id);
+        account.apply_near_credit((10 * YOCTO).into());
+        contract.save_registered_account(&account);
+        contract.total_near.credit((10 * YOCTO).into());
+
+        assert_eq!(contract.withdraw_all(), (10 * YOCTO).into());
+        assert_eq!(contract.collected_earnings, 0.into());
+    }
+
+    #[test]
+    fn redeem_fee_is_distributed_as_stake() {
+        let mut test_context = TestContext::with_registered_account();
+        set_fee(
+            &mut test_context,
+            FeeCategory::Redeem,
+            interface::Fee {
+                flat: None,
+                basis_points: Some(100),
+                exempt_account_ids: None,
+            },
+        );
+        let mut account = test_context.registered_account(test_context.account_id);
+        account.apply_stake_credit((100 * YOCTO).into());
+        test_context.save_registered_account(&account);
+        test_context.total_stake.credit((100 * YOCTO).into());
+        test_context.update_stake_token_value((100 * YOCTO).into());
+
+        let quote = test_context.quote_fee(
+            FeeCategory::Redeem,
+            ValidAccountId::try_from(test_context.account_id).unwrap(),
+            (10 * YOCTO).into(),
+        );
+        assert_eq!(quote.fee.0, YOCTO / 10);
+        assert_eq!(quote.amount_with_fee.0, 10 * YOCTO - YOCTO / 10);
+
+        // the fee is deducted from the STAKE that is redeemed and held by the contract
+        test_context.redeem((10 * YOCTO).into());
+        assert_eq!(
+            test_context.redeem_stake_batch.unwrap().balance().amount(),
+            quote.amount_with_fee.0.into()
+        );
+        assert_eq!(test_context.collected_fees.stake(), (YOCTO / 10).into());
+        assert_eq!(
+            test_context
+                .ft_balance_of(ValidAccountId::try_from(test_context.account_id).unwrap())
+                .value(),
+            90 * YOCTO
+        );
+
+        // the STAKE fees are held until the contract owner account is registered
+        test_context.distribute_stake_fees();
+        assert_eq!(test_context.collected_fees.stake(), (YOCTO / 10).into());
+
+        // the STAKE fees are distributed without drawing on the NEAR liquidity pool
+        test_context.near_liquidity_pool = YOCTO.into();
+        let owner_id = test_context.owner_id.clone();
+        test_context.register_account(&owner_id);
+        let contract_owner_stake =
+            YOCTO / 10 / 100 * test_context.config.contract_owner_earnings_percentage() as u128;
+        let burned_stake = YOCTO / 10 - contract_owner_stake;
+        test_context.distribute_stake_fees();
+        assert_eq!(test_context.collected_fees.stake(), 0.into());
+        assert_eq!(test_context.collected_earnings, 0.into());
+        assert_eq!(test_context.near_liquidity_pool, YOCTO.into());
+        assert_eq!(
+            test_context
+                .ft_balance_of(ValidAccountId::try_from(owner_id.as_str()).unwrap())
+                .value(),
+            contract_owner_stake
+        );
+        assert_eq!(
+            test_context.total_stake.amount(),
+            (100 * YOCTO - burned_stake).into()
+        );
+        // the burned STAKE fees are distributed to the STAKE holders
+        assert_eq!(
+            test_context.stake_token_value.total_staked_near_balance(),
+            (100 * YOCTO).into()
+        );
+    }
+
+    /// credits the test account with 100 STAKE and sets a 1% ft_transfer fee
+    fn with_ft_transfer_fee<'a>() -> TestContext<'a> {
+        let mut test_context = TestContext::with_registered_account();
+        let account_id = test_context.account_id;
+        set_fee(
+            &mut test_context,
+            FeeCategory::FtTransfer,
+            interface::Fee {
+                flat: None,
+                basis_points: Some(100),
+                exempt_account_ids: None,
+            },
+        );
+        let mut account = test_context.registered_account(account_id);
+        account.apply_stake_credit((100 * YOCTO).into());
+        test_context.save_registered_account(&account);
+        test_context.total_stake.credit((100 * YOCTO).into());
+        test_context.update_stake_token_value((100 * YOCTO).into());
+        test_context
+    }
+
+    #[test]
+    fn ft_transfer_from_is_charged_ft_transfer_fee() {
+        const SPENDER_ID: &str = "spender.near";
+        const RECEIVER_ID: &str = "receiver.near";
+
+        let mut test_context = with_ft_transfer_fee();
+        let owner_id = test_context.account_id;
+        test_context.register_account(RECEIVER_ID);
+
+        let mut context = test_context.context.clone();
+        context.attached_deposit = test_context.allowance_storage_usage.value() as u128
+            * test_context.config.storage_cost_per_byte().value();
+        testing_env!(context);
+        test_context.ft_approve(to_valid_account_id(SPENDER_ID), (10 * YOCTO).into(), None);
+
+        let mut context = test_context.set_predecessor_account_id(SPENDER_ID);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_context.ft_transfer_from(
+            to_valid_account_id(owner_id),
+            to_valid_account_id(RECEIVER_ID),
+            (4 * YOCTO).into(),
+            None,
+        );
+
+        // the owner is charged the fee on top of the transfer amount
+        let fee = 4 * YOCTO / 100;
+        assert_eq!(
+            test_context
+                .ft_balance_of(to_valid_account_id(owner_id))
+                .value(),
+            96 * YOCTO - fee
+        );
+        assert_eq!(
+            test_context
+                .ft_balance_of(to_valid_account_id(RECEIVER_ID))
+                .value(),
+            4 * YOCTO
+        );
+        // the fee is drawn from the allowance
+        assert_eq!(
+            test_context
+                .ft_allowance(
+                    to_valid_account_id(owner_id),
+                    to_valid_account_id(SPENDER_ID)
+                )
+                .unwrap()
+                .amount,
+            (6 * YOCTO - fee).into()
+        );
+        assert_eq!(test_context.collected_fees.stake(), fee.into());
+        assert_eq!(
+            test_context.collected_fees.total(FeeCategory::FtTransfer),
+            fee.into()
+        );
+    }
+
+    #[test]
+    fn ft_batch_transfer_is_charged_ft_transfer_fee_per_transfer() {
+        const ALICE: &str = "alice.near";
+        const BOB: &str = "bob.near";
+
+        let mut test_context = with_ft_transfer_fee();
+        let sender_id = test_context.account_id;
+        set_fee(
+            &mut test_context,
+            FeeCategory::FtTransfer,
+            interface::Fee {
+                flat: Some((YOCTO / 100).into()),
+                basis_points: None,
+                exempt_account_ids: None,
+            },
+        );
+        test_context.register_account(ALICE);
+        test_context.register_account(BOB);
+
+        let mut context = test_context.set_predecessor_account_id(sender_id);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_context.ft_batch_transfer(vec![
+            (to_valid_account_id(ALICE), YOCTO.into(), None),
+            (to_valid_account_id(BOB), YOCTO.into(), None),
+        ]);
+
+        // the flat fee is charged for each transfer in the batch
+        let fee = 2 * (YOCTO / 100);
+        assert_eq!(
+            test_context
+                .ft_balance_of(to_valid_account_id(sender_id))
+                .value(),
+            98 * YOCTO - fee
+        );
+        assert_eq!(test_context.collected_fees.stake(), fee.into());
+        assert_eq!(
+            test_context.collected_fees.total(FeeCategory::FtTransfer),
+            fee.into()
+        );
+    }
+
+    #[test]
+    fn transfer_with_vault_is_charged_ft_transfer_fee() {
+        const RECEIVER_ID: &str = "receiver.near";
+
+        let mut test_context = with_ft_transfer_fee();
+        let sender_id = test_context.account_id;
+        test_context.register_account(RECEIVER_ID);
+        // cover the vault storage
+        let vault_storage_fee = test_context.vault_storage_usage.value() as u128
+            * test_context.config.storage_cost_per_byte().value();
+        let mut sender = test_context.registered_account(sender_id);
+        sender.storage_escrow.credit(vault_storage_fee.into());
+        test_context.save_registered_account(&sender);
+        test_context.total_account_storage_escrow += domain::YoctoNear(vault_storage_fee);
+
+        let mut context = test_context.set_predecessor_account_id(sender_id);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_context.transfer_with_vault(
+            to_valid_account_id(RECEIVER_ID),
+            (10 * YOCTO).into(),
+            "pay".into(),
+        );
+
+        // the sender is charged the fee on top of the amount that is locked in the vault
+        let fee = 10 * YOCTO / 100;
+        assert_eq!(
+            test_context
+                .ft_balance_of(to_valid_account_id(sender_id))
+                .value(),
+            90 * YOCTO - fee
+        );
+        let vault = test_context.vaults.get(&domain::VaultId(1)).unwrap();
+        assert_eq!(vault.balance(), (10 * YOCTO).into());
+        assert_eq!(test_context.collected_fees.stake(), fee.into());
+        assert_eq!(
+            test_context.collected_fees.total(FeeCategory::FtTransfer),
+            fee.into()
+        );
+    }
+}
@@ -20,6 +20,7 @@ impl ContractFinancials for Contract {
             customer_batched_stake_deposits: self.customer_batched_stake_deposits().into(),
             total_available_unstaked_near: self.total_near.amount().into(),
             near_liquidity_pool: self.near_liquidity_pool.into(),
+            instant_redeem_fees: self.instant_redeem_fees.into(),
             total_account_storage_escrow: self.total_account_storage_escrow.into(),
 
             contract_owner_storage_usage_cost: self.contract_owner_storage_usage_cost().into(),
@@ -30,6 +31,10 @@ impl ContractFinancials for Contract {
             contract_owner_earnings: self.contract_owner_earnings().into(),
             user_accounts_earnings: self.user_accounts_earnings().into(),
             collected_earnings: self.collected_earnings.into(),
+            collected_fees: self.collected_fees.into(),
+            keeper_rewards_pool: self.keeper_rewards.balance().into(),
+            insurance_buffer: self.insurance_buffer.into(),
+            liquidity_provider_pool: self.liquidity_provider_pool.near().into(),
 
             contract_required_operational_balance: CONTRACT_MIN_OPERATIONAL_BALANCE.into(),
 
@@ -40,7 +45,13 @@ impl ContractFinancials for Contract {
 
     #[payable]
     fn deposit_earnings(&mut self) -> interface::YoctoNear {
-        *self.collected_earnings += env::account_balance();
+        *self.collected_earnings += env::attached_deposit();
+        // while unstaked NEAR is pending withdrawal, earnings are distributed right away to add
+        // liquidity for the redeem queue
+        if self.get_pending_withdrawal().is_some() && !self.stake_batch_locked() {
+            self.distribute_earnings();
+            self.allocate_redeem_queue_liquidity();
+        }
         self.collected_earnings.into()
     }
 }
@@ -68,7 +79,9 @@ impl Contract {
         (self.customer_batched_stake_deposits().value()
             + self.total_near.amount().value()
             + self.near_liquidity_pool.value()
-            + self.total_account_storage_escrow.value())
+            + self.instant_redeem_fees.value()
+            + self.total_account_storage_escrow.value()
+            + self.staking_pool_migration_withdrawn_balance().value())
         .into()
     }
 
@@ -78,6 +91,9 @@ impl Contract {
             .saturating_sub(self.contract_owner_balance.value())
             .saturating_sub(self.total_user_accounts_balance().value())
             .saturating_sub(self.collected_earnings.value())
+            .saturating_sub(self.keeper_rewards.balance().value())
+            .saturating_sub(self.insurance_buffer.balance().value())
+            .saturating_sub(self.liquidity_provider_pool.near().value())
             .into()
     }
 
@@ -91,7 +107,7 @@ impl Contract {
         self.contract_owner_share(self.total_earnings())
     }
 
-    fn contract_owner_share(&self, amount: YoctoNear) -> YoctoNear {
+    pub(crate) fn contract_owner_share(&self, amount: YoctoNear) -> YoctoNear {
         let contract_owner_earnings_percentage =
             self.config.contract_owner_earnings_percentage() as u128;
         (amount.value() / 100 * contract_owner_earnings_percentage).into()
@@ -116,9 +132,17 @@ impl Contract {
         }
     }
 
+    /// keeper rewards and the insurance buffer funding are set aside first, and then the rest of the
+    /// earnings are distributed
+    /// - instant redeem fees are distributed to the user accounts
+    /// - STAKE fees are distributed as STAKE - see [distribute_stake_fees](Contract::distribute_stake_fees)
     pub fn distribute_earnings(&mut self) {
+        self.distribute_stake_fees();
+        let keeper_rewards = self.fund_keeper_rewards();
+        let insurance_buffer = self.fund_insurance_buffer();
         let contract_owner_earnings = self.contract_owner_earnings();
-        let user_accounts_earnings = self.user_accounts_earnings();
+        let user_accounts_earnings = self.user_accounts_earnings() + self.instant_redeem_fees;
+        self.instant_redeem_fees = 0.into();
 
         self.contract_owner_balance = self
             .contract_owner_balance
@@ -135,8 +159,10 @@ impl Contract {
         self.collected_earnings = 0.into();
 
         log(EarningsDistribution {
-            contract_owner_earnings: contract_owner_earnings.into(),
-            user_accounts_earnings: user_accounts_earnings.into(),
+            contract_owner_earnings: contract_owner_earnings.value().into(),
+            user_accounts_earnings: user_accounts_earnings.value().into(),
+            keeper_rewards: keeper_rewards.value().into(),
+            insurance_buffer: insurance_buffer.value().into(),
         })
     }
 }
@@ -1,14 +1,22 @@
 use crate::near::assert_yocto_near_attached;
 use crate::*;
 use crate::{
+    config::FeeCategory,
     core::Hash,
     domain::YoctoStake,
-    interface::{FungibleToken, Memo, ResolveTransferCall, TokenAmount, TransferCallMessage},
-    near::NO_DEPOSIT,
+    errors::fungible_token::{
+        BATCH_TRANSFER_EXCEEDS_GAS_LIMIT, BATCH_TRANSFER_IS_EMPTY,
+        INVALID_CONTRACT_TRANSFER_CALL_MESSAGE, SENDER_CANNOT_BE_RECEIVER,
+    },
+    interface::{
+        fungible_token::events::{FtBurn, FtTransfer},
+        ContractTransferCallMessage, FungibleToken, FungibleTokenBatchTransfer, Memo,
+        ResolveTransferCall, TokenAmount, TransferCallMessage,
+    },
+    near::{self, NO_DEPOSIT},
 };
 use near_sdk::{
-    env, ext_contract, json_types::ValidAccountId, log, near_bindgen, serde_json, Promise,
-    PromiseResult,
+    env, ext_contract, json_types::ValidAccountId, log, near_bindgen, serde_json, PromiseResult,
 };
 #[allow(unused_imports)]
 use near_sdk::{AccountId, PromiseOrValue};
@@ -20,7 +28,7 @@ impl FungibleToken for Contract {
         &mut self,
         receiver_id: ValidAccountId,
         amount: TokenAmount,
-        _memo: Option<Memo>,
+        memo: Option<Memo>,
     ) {
         assert_yocto_near_attached();
         assert_token_amount_not_zero(&amount);
@@ -29,7 +37,8 @@ impl FungibleToken for Contract {
 
         let mut sender = self.predecessor_registered_account();
         self.claim_receipt_funds(&mut sender);
-        sender.apply_stake_debit(stake_amount);
+        let fee = self.stake_fee(FeeCategory::FtTransfer, &sender.account_id, stake_amount);
+        sender.apply_stake_debit(stake_amount + fee);
         // apply the 1 yoctoNEAR that was attached to the sender account's NEAR balance
         sender.apply_near_credit(1.into());
 
@@ -38,6 +47,14 @@ impl FungibleToken for Contract {
 
         self.save_registered_account(&sender);
         self.save_registered_account(&receiver);
+
+        near::log(FtTransfer {
+            old_owner_id: &sender.account_id,
+            new_owner_id: &receiver.account_id,
+            amount: amount.value().into(),
+            memo: memo.as_deref(),
+        });
+        self.collect_ft_transfer_fee(&sender.account_id, fee);
     }
 
     #[payable]
@@ -46,9 +63,13 @@ impl FungibleToken for Contract {
         receiver_id: ValidAccountId,
         amount: TokenAmount,
         msg: TransferCallMessage,
-        _memo: Option<Memo>,
-    ) -> Promise {
-        self.ft_transfer(receiver_id.clone(), amount.clone(), _memo);
+        memo: Option<Memo>,
+    ) -> PromiseOrValue<TokenAmount> {
+        if receiver_id.as_ref() == &env::current_account_id() {
+            return PromiseOrValue::Value(self.handle_contract_transfer_call(amount, msg));
+        }
+
+        self.ft_transfer(receiver_id.clone(), amount.clone(), memo);
 
         ext_transfer_receiver::ft_on_transfer(
             env::predecessor_account_id(),
@@ -66,6 +87,7 @@ impl FungibleToken for Contract {
             NO_DEPOSIT.value(),
             self.resolve_transfer_gas(),
         ))
+        .into()
     }
 
     fn ft_total_supply(&self) -> TokenAmount {
@@ -74,9 +96,9 @@ impl FungibleToken for Contract {
 
     fn ft_balance_of(&self, account_id: ValidAccountId) -> TokenAmount {
         self.accounts
-            .get(&Hash::from(account_id))
+            .get(&Hash::from(account_id.as_ref()))
             .map_or_else(TokenAmount::default, |account| {
-                let account = self.apply_receipt_funds_for_view(&account);
+                let account = self.apply_receipt_funds_for_view(account_id.as_ref(), &account);
                 account.stake.map_or_else(TokenAmount::default, |balance| {
                     balance.amount().value().into()
                 })
@@ -84,7 +106,122 @@ impl FungibleToken for Contract {
     }
 }
 
+#[near_bindgen]
+impl FungibleTokenBatchTransfer for Contract {
+    #[payable]
+    fn ft_batch_transfer(&mut self, transfers: Vec<(ValidAccountId, TokenAmount, Option<Memo>)>) {
+        assert_yocto_near_attached();
+        assert!(!transfers.is_empty(), BATCH_TRANSFER_IS_EMPTY);
+        assert!(
+            transfers.len() as u64 <= self.ft_batch_transfer_max_receivers(),
+            BATCH_TRANSFER_EXCEEDS_GAS_LIMIT
+        );
+
+        let mut sender = self.predecessor_registered_account();
+        let mut total_amount = YoctoStake(0);
+        for (receiver_id, amount, _memo) in transfers.iter() {
+            assert_token_amount_not_zero(amount);
+            assert_ne!(
+                &sender.account_id,
+                receiver_id.as_ref(),
+                "{}",
+                SENDER_CANNOT_BE_RECEIVER
+            );
+            total_amount += YoctoStake::from(amount.value());
+        }
+
+        self.claim_receipt_funds(&mut sender);
+        // the fee is charged per transfer, i.e., batching transfers does not avoid the flat fee
+        let fee = transfers
+            .iter()
+            .fold(YoctoStake(0), |fee, (_receiver_id, amount, _memo)| {
+                fee + self.stake_fee(
+                    FeeCategory::FtTransfer,
+                    &sender.account_id,
+                    amount.value().into(),
+                )
+            });
+        sender.apply_stake_debit(total_amount + fee);
+        // apply the 1 yoctoNEAR that was attached to the sender account's NEAR balance
+        sender.apply_near_credit(1.into());
+        self.save_registered_account(&sender);
+
+        for (receiver_id, amount, _memo) in transfers.iter() {
+            let mut receiver = self.registered_account(receiver_id.as_ref());
+            receiver.apply_stake_credit(amount.value().into());
+            self.save_registered_account(&receiver);
+        }
+
+        let events: Vec<FtTransfer> = transfers
+            .iter()
+            .map(|(receiver_id, amount, memo)| FtTransfer {
+                old_owner_id: &sender.account_id,
+                new_owner_id: receiver_id.as_ref(),
+                amount: amount.value().into(),
+                memo: memo.as_deref(),
+            })
+            .collect();
+        near::log_events(&events);
+        self.collect_ft_transfer_fee(&sender.account_id, fee);
+    }
+}
+
 impl Contract {
+    /// the fee is debited from the sender on top of the transfer amount and is held by the contract
+    /// until it is distributed
+    pub(crate) fn collect_ft_transfer_fee(&mut self, sender_id: &str, fee: YoctoStake) {
+        if fee.value() == 0 {
+            return;
+        }
+        near::log(FtTransfer {
+            old_owner_id: sender_id,
+            new_owner_id: &env::current_account_id(),
+            amount: fee.into(),
+            memo: Some("ft_transfer fee"),
+        });
+        self.collect_stake_fee(FeeCategory::FtTransfer, sender_id, fee);
+    }
+
+    /// handles [FungibleToken::ft_transfer_call] when the receiver is the STAKE contract itself
+    ///
+    /// Returns the amount used, which is always the full transfer amount.
+    fn handle_contract_transfer_call(
+        &mut self,
+        amount: TokenAmount,
+        msg: TransferCallMessage,
+    ) -> TokenAmount {
+        assert_yocto_near_attached();
+        assert_token_amount_not_zero(&amount);
+
+        let msg: ContractTransferCallMessage =
+            serde_json::from_str(&msg).expect(INVALID_CONTRACT_TRANSFER_CALL_MESSAGE);
+        match msg {
+            ContractTransferCallMessage::Redeem { beneficiary } => {
+                let mut sender = self.predecessor_registered_account();
+                // apply the 1 yoctoNEAR that was attached to the sender account's NEAR balance
+                sender.apply_near_credit(1.into());
+                let beneficiary_id = beneficiary
+                    .map_or_else(|| sender.account_id.clone(), |id| id.as_ref().to_string());
+                self.redeem_stake_for_beneficiary(
+                    &mut sender,
+                    amount.value().into(),
+                    &beneficiary_id,
+                );
+            }
+        }
+        amount
+    }
+
+    /// the max number of receivers that a batch transfer can credit with the remaining prepaid gas
+    fn ft_batch_transfer_max_receivers(&self) -> u64 {
+        (env::prepaid_gas() - env::used_gas())
+            / self
+                .config
+                .gas_config()
+                .ft_batch_transfer_receiver()
+                .value()
+    }
+
     fn resolve_transfer_gas(&self) -> u64 {
         self.config
             .gas_config()
@@ -168,12 +305,23 @@ impl ResolveTransferCall for Contract {
                                 sender.apply_stake_credit(refund_amount);
                                 self.save_registered_account(&sender);
                                 log!("sender refunded: {}", refund_amount.value());
+                                near::log(FtTransfer {
+                                    old_owner_id: receiver_id.as_ref(),
+                                    new_owner_id: sender_id.as_ref(),
+                                    amount: refund_amount.into(),
+                                    memo: Some("refund"),
+                                });
                             }
                             None => {
                                 log!("ERR: sender account is not registered - refund amount will be burned: {}", refund_amount);
                                 // NOTE: this has the effect of transferring the burned value to the STAKE token,
                                 // i.e., STAKE token value will increase when STAKE is burned
                                 self.total_stake.debit(refund_amount);
+                                near::log(FtBurn {
+                                    owner_id: receiver_id.as_ref(),
+                                    amount: refund_amount.into(),
+                                    memo: Some("refund - sender account is not registered"),
+                                });
                             }
                         }
                         refund_amount.value().into()
@@ -196,7 +344,7 @@ impl ResolveTransferCall for Contract {
     }
 }
 
-fn assert_token_amount_not_zero(amount: &TokenAmount) {
+pub(crate) fn assert_token_amount_not_zero(amount: &TokenAmount) {
     assert!(amount.value() > 0, "amount must not be zero")
 }
 
@@ -335,6 +483,7 @@ mod test_transfer {
             context.predecessor_account_id = env::current_account_id();
             testing_env!(context);
             test_ctx.on_deposit_and_stake(
+                TEST_STAKING_POOL_ID.to_string(),
                 None,
                 StakingPoolAccount {
                     account_id: env::current_account_id(),
@@ -548,6 +697,149 @@ mod test_transfer {
     }
 }
 
+#[cfg(test)]
+mod test_batch_transfer {
+    use super::*;
+    use crate::domain::TGAS;
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    /// registers the receiver accounts and credits the sender account with 100 STAKE
+    fn with_stake<'a>(receivers: &[&str]) -> TestContext<'a> {
+        let mut test_ctx = TestContext::with_registered_account();
+        for receiver_id in receivers {
+            test_ctx.register_account(receiver_id);
+        }
+
+        let mut sender = test_ctx.registered_account(test_ctx.account_id);
+        let total_supply = YoctoStake(100 * YOCTO);
+        sender.apply_stake_credit(total_supply);
+        test_ctx.total_stake.credit(total_supply);
+        test_ctx.save_registered_account(&sender);
+        test_ctx
+    }
+
+    #[test]
+    fn batch_transfer_ok() {
+        // Arrange
+        let mut test_ctx = with_stake(&["alfio.near", "bob.near"]);
+        let sender_id = test_ctx.account_id;
+
+        // Act
+        let mut context = test_ctx.set_predecessor_account_id(sender_id);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.ft_batch_transfer(vec![
+            (
+                to_valid_account_id("alfio.near"),
+                (10 * YOCTO).into(),
+                Some("payroll".into()),
+            ),
+            (to_valid_account_id("bob.near"), (20 * YOCTO).into(), None),
+        ]);
+
+        // Assert
+        assert_eq!(test_ctx.ft_total_supply().value(), 100 * YOCTO);
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id(sender_id))
+                .value(),
+            70 * YOCTO
+        );
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id("alfio.near"))
+                .value(),
+            10 * YOCTO
+        );
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id("bob.near"))
+                .value(),
+            20 * YOCTO
+        );
+        let sender = test_ctx.registered_account(sender_id);
+        assert_eq!(sender.near.unwrap().amount().value(), 1,
+                   "expected the attached 1 yoctoNEAR for the transfer to be credited to the account's NEAR balance");
+
+        let logs = get_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(
+            &logs[0],
+            &format!(
+                r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{{"old_owner_id":"{}","new_owner_id":"alfio.near","amount":"{}","memo":"payroll"}},{{"old_owner_id":"{}","new_owner_id":"bob.near","amount":"{}"}}]}}"#,
+                sender_id,
+                10 * YOCTO,
+                sender_id,
+                20 * YOCTO
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "account is not registered: bob.near")]
+    fn receiver_not_registered() {
+        let mut test_ctx = with_stake(&["alfio.near"]);
+        let sender_id = test_ctx.account_id;
+
+        let mut context = test_ctx.set_predecessor_account_id(sender_id);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.ft_batch_transfer(vec![
+            (to_valid_account_id("alfio.near"), YOCTO.into(), None),
+            (to_valid_account_id("bob.near"), YOCTO.into(), None),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "sender cannot transfer to itself")]
+    fn sender_is_receiver() {
+        let mut test_ctx = with_stake(&["alfio.near"]);
+        let sender_id = test_ctx.account_id;
+
+        let mut context = test_ctx.set_predecessor_account_id(sender_id);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.ft_batch_transfer(vec![
+            (to_valid_account_id("alfio.near"), YOCTO.into(), None),
+            (to_valid_account_id(sender_id), YOCTO.into(), None),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "batch transfer must have at least 1 transfer")]
+    fn empty_batch() {
+        let mut test_ctx = with_stake(&[]);
+        let sender_id = test_ctx.account_id;
+
+        let mut context = test_ctx.set_predecessor_account_id(sender_id);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.ft_batch_transfer(vec![]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "batch transfer has too many transfers for the prepaid gas - attach more gas or split up the batch"
+    )]
+    fn batch_exceeds_gas_limit() {
+        let mut test_ctx = with_stake(&["alfio.near", "bob.near"]);
+        let sender_id = test_ctx.account_id;
+
+        let mut context = test_ctx.set_predecessor_account_id(sender_id);
+        context.attached_deposit = 1;
+        // enough gas for 1 receiver
+        context.prepaid_gas = (TGAS * 3).value();
+        testing_env!(context);
+        test_ctx.ft_batch_transfer(vec![
+            (to_valid_account_id("alfio.near"), YOCTO.into(), None),
+            (to_valid_account_id("bob.near"), YOCTO.into(), None),
+        ]);
+    }
+}
+
 #[cfg(test)]
 mod test_transfer_call {
     use super::*;
@@ -717,6 +1009,7 @@ mod test_transfer_call {
             context.predecessor_account_id = env::current_account_id();
             testing_env!(context);
             test_ctx.on_deposit_and_stake(
+                TEST_STAKING_POOL_ID.to_string(),
                 None,
                 StakingPoolAccount {
                     account_id: env::current_account_id(),
@@ -949,6 +1242,278 @@ mod test_transfer_call {
     }
 }
 
+#[cfg(test)]
+mod test_transfer_call_to_contract {
+    use super::*;
+    use crate::domain::{StakeTokenValue, YoctoNear};
+    use crate::interface::{AccountStorage, StakingService};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::{test_utils::get_logs, testing_env, MockedBlockchain};
+
+    fn redeem_beneficiary_storage_fee(test_ctx: &TestContext) -> YoctoNear {
+        (test_ctx.redeem_beneficiary_storage_usage.value() as u128
+            * test_ctx.config.storage_cost_per_byte().value())
+        .into()
+    }
+
+    /// credits the sender account with 100 STAKE
+    /// - the sender account storage escrow covers the storage for 1 redeem beneficiary
+    fn with_stake<'a>() -> TestContext<'a> {
+        let mut test_ctx = TestContext::with_registered_account();
+        let sender_id = test_ctx.account_id;
+        let mut sender = test_ctx.registered_account(sender_id);
+        let redeem_beneficiary_storage_fee = redeem_beneficiary_storage_fee(&test_ctx);
+        sender.storage_escrow.credit(redeem_beneficiary_storage_fee);
+        test_ctx.total_account_storage_escrow += redeem_beneficiary_storage_fee;
+        let total_supply = YoctoStake(100 * YOCTO);
+        sender.apply_stake_credit(total_supply);
+        test_ctx.total_stake.credit(total_supply);
+        test_ctx.save_registered_account(&sender);
+
+        let mut context = test_ctx.context.clone();
+        context.predecessor_account_id = sender_id.to_string();
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx
+    }
+
+    fn redeem_msg(beneficiary: &str) -> TransferCallMessage {
+        TransferCallMessage(format!(
+            r#"{{"redeem":{{"beneficiary":"{}"}}}}"#,
+            beneficiary
+        ))
+    }
+
+    #[test]
+    fn redeem_for_beneficiary() {
+        // Arrange
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        let contract_id = test_ctx.context.current_account_id.clone();
+        let beneficiary_id = "bob.near";
+
+        // Act
+        let redeem_amount = 10 * YOCTO;
+        match test_ctx.ft_transfer_call(
+            to_valid_account_id(&contract_id),
+            redeem_amount.into(),
+            redeem_msg(beneficiary_id),
+            None,
+        ) {
+            PromiseOrValue::Value(amount) => assert_eq!(amount.value(), redeem_amount),
+            _ => panic!("expected the transfer call to the contract to return the amount used"),
+        }
+
+        // Assert
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id(sender_id))
+                .value(),
+            90 * YOCTO
+        );
+        let batch = test_ctx.redeem_stake_batch.unwrap();
+        assert_eq!(batch.balance().amount().value(), redeem_amount);
+        let sender = test_ctx.registered_account(sender_id);
+        assert_eq!(sender.near.unwrap().amount().value(), 1,
+                   "expected the attached 1 yoctoNEAR for the transfer to be credited to the account's NEAR balance");
+        let key = Contract::redeem_beneficiary_key(sender_id, batch.id());
+        let beneficiary = test_ctx.redeem_beneficiaries.get(&key).unwrap();
+        assert_eq!(beneficiary.account_id(), beneficiary_id);
+        assert_eq!(beneficiary.stake().value(), redeem_amount);
+        // the sender pays for the beneficiary storage
+        assert_eq!(sender.redeem_beneficiaries_len, 1);
+        assert_eq!(
+            test_ctx
+                .storage_balance_of(to_valid_account_id(sender_id))
+                .unwrap()
+                .available
+                .value(),
+            0
+        );
+
+        // Arrange - simulate the redeem stake batch being unstaked
+        test_ctx.redeem_stake_batch_receipts.insert(
+            &batch.id(),
+            &batch.create_receipt(StakeTokenValue::default()),
+        );
+        test_ctx.total_near.credit(YoctoNear(redeem_amount));
+        test_ctx.redeem_stake_batch = None;
+
+        // Act - the beneficiary claims the receipts on behalf of the sender
+        let mut context = test_ctx.context.clone();
+        context.predecessor_account_id = beneficiary_id.to_string();
+        testing_env!(context);
+        test_ctx.claim_receipts_for(to_valid_account_id(sender_id));
+
+        // Assert
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 1);
+        let receipt = &receipts[0];
+        assert_eq!(receipt.receiver_id, beneficiary_id);
+        match &receipt.actions[0] {
+            Action::Transfer { deposit } => assert_eq!(*deposit, redeem_amount),
+            _ => panic!("expected NEAR transfer to the beneficiary"),
+        }
+        assert!(test_ctx.redeem_beneficiaries.get(&key).is_none());
+        let sender = test_ctx.registered_account(sender_id);
+        assert_eq!(sender.near.unwrap().amount().value(), 1);
+        assert!(sender.redeem_stake_batch.is_none());
+        assert!(get_logs()
+            .iter()
+            .any(|log| log.contains("redeem_beneficiary_paid")));
+        // the beneficiary storage is released back to the sender
+        assert_eq!(sender.redeem_beneficiaries_len, 0);
+        assert_eq!(
+            test_ctx
+                .storage_balance_of(to_valid_account_id(sender_id))
+                .unwrap()
+                .available
+                .value(),
+            redeem_beneficiary_storage_fee(&test_ctx).value()
+        );
+    }
+
+    /// receipts are claimed implicitly by any account action, but beneficiaries are only paid out
+    /// when the receipts are claimed explicitly
+    #[test]
+    fn redeem_for_beneficiary_is_not_paid_out_by_implicit_claim() {
+        // Arrange
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        let contract_id = test_ctx.context.current_account_id.clone();
+        let beneficiary_id = "bob.near";
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(&contract_id),
+            (10 * YOCTO).into(),
+            redeem_msg(beneficiary_id),
+            None,
+        );
+        let batch = test_ctx.redeem_stake_batch.unwrap();
+        test_ctx.redeem_stake_batch_receipts.insert(
+            &batch.id(),
+            &batch.create_receipt(StakeTokenValue::default()),
+        );
+        test_ctx.total_near.credit(YoctoNear(10 * YOCTO));
+        test_ctx.redeem_stake_batch = None;
+
+        // Act - receipts are claimed implicitly
+        testing_env!(test_ctx.context.clone());
+        let mut sender = test_ctx.registered_account(sender_id);
+        test_ctx.claim_receipt_funds(&mut sender);
+
+        // Assert
+        assert!(deserialize_receipts().is_empty());
+        let sender = test_ctx.registered_account(sender_id);
+        assert_eq!(sender.redeem_stake_batch.unwrap().id(), batch.id());
+        assert_eq!(sender.near.unwrap().amount().value(), 1);
+        assert!(test_ctx
+            .redeem_beneficiaries
+            .get(&Contract::redeem_beneficiary_key(sender_id, batch.id()))
+            .is_some());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "receipts can only be claimed by the account or by a beneficiary that the account redeemed STAKE for"
+    )]
+    fn claim_receipts_for_by_unrelated_account() {
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        let contract_id = test_ctx.context.current_account_id.clone();
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(&contract_id),
+            (10 * YOCTO).into(),
+            redeem_msg("bob.near"),
+            None,
+        );
+
+        let mut context = test_ctx.context.clone();
+        context.predecessor_account_id = "alfio.near".to_string();
+        testing_env!(context);
+        test_ctx.claim_receipts_for(to_valid_account_id(sender_id));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "account storage balance is insufficient to pay for redeem beneficiary storage"
+    )]
+    fn redeem_for_beneficiary_with_insufficient_storage_escrow() {
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        let contract_id = test_ctx.context.current_account_id.clone();
+        let mut sender = test_ctx.registered_account(sender_id);
+        sender
+            .storage_escrow
+            .debit(redeem_beneficiary_storage_fee(&test_ctx));
+        test_ctx.save_registered_account(&sender);
+
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(&contract_id),
+            (10 * YOCTO).into(),
+            redeem_msg("bob.near"),
+            None,
+        );
+    }
+
+    #[test]
+    fn redeem_without_beneficiary() {
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        let contract_id = test_ctx.context.current_account_id.clone();
+
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(&contract_id),
+            (10 * YOCTO).into(),
+            r#"{"redeem":{}}"#.into(),
+            None,
+        );
+
+        let batch = test_ctx.redeem_stake_batch.unwrap();
+        let beneficiary = test_ctx
+            .redeem_beneficiaries
+            .get(&Contract::redeem_beneficiary_key(sender_id, batch.id()))
+            .unwrap();
+        assert_eq!(beneficiary.account_id(), sender_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid msg for transfer call to the STAKE contract")]
+    fn invalid_msg() {
+        let mut test_ctx = with_stake();
+        let contract_id = test_ctx.context.current_account_id.clone();
+
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(&contract_id),
+            (10 * YOCTO).into(),
+            "pay".into(),
+            None,
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "redeem stake batch already has a different beneficiary for the account"
+    )]
+    fn beneficiary_conflict() {
+        let mut test_ctx = with_stake();
+        let contract_id = test_ctx.context.current_account_id.clone();
+
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(&contract_id),
+            (10 * YOCTO).into(),
+            redeem_msg("bob.near"),
+            None,
+        );
+        test_ctx.ft_transfer_call(
+            to_valid_account_id(&contract_id),
+            (10 * YOCTO).into(),
+            redeem_msg("alice.near"),
+            None,
+        );
+    }
+}
+
 #[cfg(test)]
 mod test_resolve_transfer_call {
     use super::*;
@@ -1217,9 +1782,16 @@ mod test_resolve_transfer_call {
 
         let logs = get_logs();
         println!("{:?}", logs);
-        assert_eq!(logs.len(), 2);
+        assert_eq!(logs.len(), 3);
         assert_eq!(&logs[0], &format!("unused amount: {}", YOCTO));
         assert_eq!(&logs[1], &format!("sender refunded: {}", YOCTO));
+        assert_eq!(
+            &logs[2],
+            &format!(
+                r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{{"old_owner_id":"{}","new_owner_id":"{}","amount":"{}","memo":"refund"}}]}}"#,
+                receiver_id, sender_id, YOCTO
+            )
+        );
     }
 
     #[test]
@@ -1268,7 +1840,7 @@ mod test_resolve_transfer_call {
 
         let logs = get_logs();
         println!("{:?}", logs);
-        assert_eq!(logs.len(), 2);
+        assert_eq!(logs.len(), 3);
         assert_eq!(&logs[0], &format!("unused amount: {}", YOCTO));
         assert_eq!(
             &logs[1],
@@ -1277,6 +1849,13 @@ mod test_resolve_transfer_call {
                 YOCTO
             )
         );
+        assert_eq!(
+            &logs[2],
+            &format!(
+                r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"ft_burn","data":[{{"owner_id":"{}","amount":"{}","memo":"refund - sender account is not registered"}}]}}"#,
+                receiver_id, YOCTO
+            )
+        );
     }
 
     #[test]
@@ -0,0 +1,276 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::*;
+use crate::{
+    config::KeeperRewardPayout,
+    interface::{
+        fungible_token::events::FtMint, keeper::events::KeeperRewarded, BatchId, Keeper,
+        ReceiptStorageReport, RunnableBatches,
+    },
+    near::log,
+};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+impl Keeper for Contract {
+    fn runnable_batches(&self) -> RunnableBatches {
+        let stake_batch = if self.can_run_batch() && !self.is_migrating_staking_pool() {
+            self.stake_batch.map(|batch| batch.id().into())
+        } else {
+            None
+        };
+        let redeem_stake_batch = if self.can_unstake() {
+            self.redeem_stake_batch.map(|batch| batch.id().into())
+        } else {
+            None
+        };
+
+        let unstaked_cohort = self
+            .withdrawable_unstaked_cohort()
+            .map(|cohort| cohort.batch_id().into());
+
+        RunnableBatches {
+            stake_batch,
+            redeem_stake_batch,
+            unstaked_cohort,
+            keeper_reward: self.keeper_reward_amount().into(),
+            keeper_reward_payout: self.config.keeper_reward().payout(),
+            remaining_epoch_rewards: self.remaining_epoch_keeper_rewards(),
+            keeper_rewards_pool: self.keeper_rewards.balance().into(),
+        }
+    }
+
+    fn settle_receipts(&mut self, batch_id: BatchId, accounts: Vec<ValidAccountId>) -> bool {
+        self.settle_expired_receipt(batch_id.into(), &accounts)
+    }
+
+    fn receipt_storage_report(&self, from_index: u64, limit: u64) -> ReceiptStorageReport {
+        ReceiptStorageReport {
+            receipt_expiry_epochs: self.config.receipt_expiry_epochs(),
+            stake_batch_receipts: self.receipt_backlog(
+                &self.stake_batch_receipt_ids,
+                self.stake_batch_receipt_storage_usage,
+                from_index,
+                limit,
+            ),
+            redeem_stake_batch_receipts: self.receipt_backlog(
+                &self.redeem_stake_batch_receipt_ids,
+                self.redeem_stake_batch_receipt_storage_usage,
+                from_index,
+                limit,
+            ),
+        }
+    }
+}
+
+impl Contract {
+    fn remaining_epoch_keeper_rewards(&self) -> u32 {
+        self.config
+            .keeper_reward()
+            .max_rewards_per_epoch()
+            .saturating_sub(self.keeper_rewards.epoch_rewards_count())
+    }
+
+    /// returns the reward that would be paid out to a keeper right now
+    fn keeper_reward_amount(&self) -> YoctoNear {
+        if self.remaining_epoch_keeper_rewards() == 0 {
+            return 0.into();
+        }
+        self.config
+            .keeper_reward()
+            .amount()
+            .min(self.keeper_rewards.balance())
+    }
+
+    /// tops up the keeper rewards pool out of the contract earnings to cover the max number of
+    /// keeper rewards that can be paid out per epoch
+    /// - contract gas earnings are drawn on first, and then collected earnings
+    ///
+    /// Returns the amount that was added to the keeper rewards pool
+    pub(crate) fn fund_keeper_rewards(&mut self) -> YoctoNear {
+        let funding = self
+            .config
+            .keeper_reward()
+            .max_rewards_per_epoch_amount()
+            .saturating_sub(self.keeper_rewards.balance().value())
+            .min(self.total_earnings().value());
+        if funding == 0 {
+            return 0.into();
+        }
+
+        let contract_earnings = self.contract_earnings().value();
+        if funding > contract_earnings {
+            self.collected_earnings -= (funding - contract_earnings).into();
+        }
+        self.keeper_rewards.fund(funding.into());
+        funding.into()
+    }
+
+    /// pays the keeper reward to the account that ran the batch that completed successfully
+    /// - if the keeper account is not registered, then no reward is paid out
+    pub(crate) fn pay_keeper_reward(&mut self, keeper_id: Option<AccountId>) {
+        let keeper_id = match keeper_id {
+            Some(keeper_id) => keeper_id,
+            None => return,
+        };
+        let amount = self.keeper_reward_amount();
+        if amount.value() == 0 {
+            return;
+        }
+        let mut account = match self.lookup_registered_account(&keeper_id) {
+            Some(account) => account,
+            None => return,
+        };
+
+        self.keeper_rewards.pay(amount);
+        let payout = self.config.keeper_reward().payout();
+        let stake = match payout {
+            KeeperRewardPayout::Near => {
+                account.apply_near_credit(amount);
+                self.total_near.credit(amount);
+                None
+            }
+            KeeperRewardPayout::Stake => {
+                let stake = self.stake_token_value.near_to_stake(amount);
+                account.apply_stake_credit(stake);
+                self.total_stake.credit(stake);
+                // the NEAR backing the minted STAKE will be staked when the next batch is run
+                self.near_liquidity_pool += amount;
+                log(FtMint {
+                    owner_id: &keeper_id,
+                    amount: stake.into(),
+                    memo: Some("keeper reward"),
+                });
+                Some(stake.into())
+            }
+        };
+        self.save_registered_account(&account);
+
+        log(KeeperRewarded {
+            keeper_id: &keeper_id,
+            payout,
+            amount: amount.into(),
+            stake,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        config::KeeperRewardConfig, interface::AccountManagement, near::YOCTO, test_utils::*,
+    };
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    fn enable_keeper_rewards(contract: &mut Contract, payout: KeeperRewardPayout) {
+        contract.config.merge(interface::Config {
+            storage_cost_per_byte: None,
+            gas_config: None,
+            contract_owner_earnings_percentage: None,
+            keeper_reward: Some(interface::KeeperRewardConfig {
+                amount: Some((YOCTO / 10).into()),
+                payout: Some(payout),
+                max_rewards_per_epoch: Some(2),
+            }),
+            instant_redeem_fee_basis_points: None,
+            performance_fee_basis_points: None,
+            insurance_buffer_earnings_percentage: None,
+            fee_schedule: None,
+            receipt_expiry_epochs: None,
+        });
+    }
+
+    #[test]
+    fn runnable_batches() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        let runnable_batches = contract.runnable_batches();
+        assert!(runnable_batches.stake_batch.is_none());
+        assert!(runnable_batches.redeem_stake_batch.is_none());
+        assert_eq!(runnable_batches.keeper_reward, 0.into());
+        assert_eq!(
+            runnable_batches.remaining_epoch_rewards,
+            KeeperRewardConfig::default().max_rewards_per_epoch()
+        );
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        let batch_id = contract.deposit();
+        let runnable_batches = contract.runnable_batches();
+        assert_eq!(runnable_batches.stake_batch, Some(batch_id.clone()));
+
+        // batches cannot be run while a batch is running
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        let runnable_batches = contract.runnable_batches();
+        assert!(runnable_batches.stake_batch.is_none());
+        assert!(runnable_batches.redeem_stake_batch.is_none());
+    }
+
+    #[test]
+    fn pay_keeper_reward_in_near() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+        testing_env!(context.clone());
+
+        enable_keeper_rewards(contract, KeeperRewardPayout::Near);
+        contract.collected_earnings = (10 * YOCTO).into();
+        contract.distribute_earnings();
+        // the pool is funded to cover the max number of rewards per epoch
+        assert_eq!(contract.keeper_rewards.balance(), (YOCTO / 5).into());
+        assert_eq!(
+            contract.runnable_batches().keeper_reward,
+            (YOCTO / 10).into()
+        );
+
+        contract.pay_keeper_reward(Some(test_ctx.account_id.to_string()));
+        let account = contract
+            .lookup_account(to_valid_account_id(test_ctx.account_id))
+            .unwrap();
+        assert_eq!(account.near.unwrap().amount, (YOCTO / 10).into());
+        assert_eq!(contract.total_near.amount(), (YOCTO / 10).into());
+        assert_eq!(contract.keeper_rewards.balance(), (YOCTO / 10).into());
+
+        // unregistered keepers are not rewarded
+        contract.pay_keeper_reward(Some("unregistered.near".to_string()));
+        assert_eq!(contract.keeper_rewards.balance(), (YOCTO / 10).into());
+
+        // the second reward hits the max number of rewards per epoch
+        contract.pay_keeper_reward(Some(test_ctx.account_id.to_string()));
+        assert_eq!(contract.keeper_rewards.epoch_rewards_count(), 2);
+        let runnable_batches = contract.runnable_batches();
+        assert_eq!(runnable_batches.remaining_epoch_rewards, 0);
+        assert_eq!(runnable_batches.keeper_reward, 0.into());
+
+        contract.pay_keeper_reward(Some(test_ctx.account_id.to_string()));
+        let account = contract
+            .lookup_account(to_valid_account_id(test_ctx.account_id))
+            .unwrap();
+        assert_eq!(account.near.unwrap().amount, (YOCTO / 5).into());
+    }
+
+    #[test]
+    fn pay_keeper_reward_in_stake() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+        testing_env!(context.clone());
+
+        enable_keeper_rewards(contract, KeeperRewardPayout::Stake);
+        contract.keeper_rewards.fund(YOCTO.into());
+
+        contract.pay_keeper_reward(Some(test_ctx.account_id.to_string()));
+        let stake = contract
+            .stake_token_value
+            .near_to_stake((YOCTO / 10).into());
+        let account = contract
+            .lookup_account(to_valid_account_id(test_ctx.account_id))
+            .unwrap();
+        assert_eq!(account.stake.unwrap().amount, stake.into());
+        assert_eq!(contract.total_stake.amount(), stake);
+        assert_eq!(contract.near_liquidity_pool, (YOCTO / 10).into());
+        assert_eq!(contract.keeper_rewards.total_paid(), (YOCTO / 10).into());
+    }
+}
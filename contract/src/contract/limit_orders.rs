@@ -0,0 +1,358 @@
+//! Deposits and redeem requests can be submitted with limits, i.e., a min amount out and a deadline
+//! epoch. The limits are checked when the batch is run, once the STAKE token value has been
+//! refreshed and before any NEAR is deposited into or unstaked from the staking pool. Orders whose
+//! limits are not met are returned to the account.
+use crate::*;
+use crate::{
+    domain::{LimitOrder, RegisteredAccount, MIN_LIMIT_ORDER_AMOUNT},
+    errors::{
+        illegal_state::{REDEEM_STAKE_BATCH_SHOULD_EXIST, STAKE_BATCH_SHOULD_EXIST},
+        staking_service::{
+            LIMIT_ORDER_BELOW_MIN_AMOUNT, LIMIT_ORDER_DEADLINE_PASSED,
+            MAX_LIMIT_ORDERS_PER_BATCH_EXCEEDED,
+        },
+    },
+    interface::{
+        fungible_token::events::FtTransfer,
+        staking_service::events::{DepositLimitOrderRejected, RedeemLimitOrderRejected},
+    },
+    near::log,
+};
+
+impl Contract {
+    pub(crate) fn submit_deposit_limit_order(
+        &mut self,
+        account: &RegisteredAccount,
+        batch_id: domain::BatchId,
+        amount: domain::YoctoNear,
+        min_stake_out: domain::YoctoStake,
+        deadline_epoch: domain::EpochHeight,
+    ) {
+        assert!(
+            amount.value() >= MIN_LIMIT_ORDER_AMOUNT,
+            LIMIT_ORDER_BELOW_MIN_AMOUNT
+        );
+        assert!(
+            self.deposit_limit_orders.push(
+                batch_id,
+                LimitOrder::new(
+                    &account.account_id,
+                    amount.value(),
+                    min_stake_out.value(),
+                    deadline_epoch
+                )
+            ),
+            MAX_LIMIT_ORDERS_PER_BATCH_EXCEEDED
+        );
+    }
+
+    pub(crate) fn submit_redeem_limit_order(
+        &mut self,
+        account: &RegisteredAccount,
+        batch_id: domain::BatchId,
+        amount: domain::YoctoStake,
+        min_near_out: domain::YoctoNear,
+        deadline_epoch: domain::EpochHeight,
+    ) {
+        assert!(
+            amount.value() >= MIN_LIMIT_ORDER_AMOUNT,
+            LIMIT_ORDER_BELOW_MIN_AMOUNT
+        );
+        assert!(
+            self.redeem_limit_orders.push(
+                batch_id,
+                LimitOrder::new(
+                    &account.account_id,
+                    amount.value(),
+                    min_near_out.value(),
+                    deadline_epoch
+                )
+            ),
+            MAX_LIMIT_ORDERS_PER_BATCH_EXCEEDED
+        );
+    }
+
+    pub(crate) fn assert_limit_order_deadline(deadline_epoch: domain::EpochHeight) {
+        assert!(
+            deadline_epoch.value() >= env::epoch_height(),
+            LIMIT_ORDER_DEADLINE_PASSED
+        );
+    }
+
+    /// checks the deposit limit orders for the stake batch against the current STAKE token value
+    /// - the NEAR for orders that are rejected is removed from the batch and credited to the
+    ///   account's NEAR balance
+    /// - the STAKE token value must be refreshed before this is called
+    ///
+    /// NOTE: if all NEAR is removed, then the stake batch is cleared
+    pub(crate) fn apply_deposit_limit_orders(&mut self) {
+        let batch_id = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST).id();
+        for order in self.deposit_limit_orders.take(batch_id) {
+            let stake_out = self.stake_token_value.near_to_stake(order.amount().into());
+            let reason = match order.rejection(stake_out.value()) {
+                Some(reason) => reason,
+                None => continue,
+            };
+            let mut account = match self.lookup_registered_account(order.account_id()) {
+                Some(account) => account,
+                None => continue,
+            };
+            let (mut account_batch, is_next_batch_slot) =
+                match (account.stake_batch, account.next_stake_batch) {
+                    (Some(batch), _) if batch.id() == batch_id => (batch, false),
+                    (_, Some(batch)) if batch.id() == batch_id => (batch, true),
+                    // the NEAR was withdrawn from the batch
+                    _ => continue,
+                };
+            let near = account_batch.balance().amount().min(order.amount().into());
+
+            // remove funds from contract level batch
+            {
+                let mut batch = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
+                if batch.remove(near).value() == 0 {
+                    self.stake_batch = None;
+                } else {
+                    self.stake_batch = Some(batch);
+                }
+            }
+
+            let account_batch = if account_batch.remove(near).value() == 0 {
+                None
+            } else {
+                Some(account_batch)
+            };
+            if is_next_batch_slot {
+                account.next_stake_batch = account_batch;
+            } else {
+                account.stake_batch = account_batch;
+            }
+            account.apply_near_credit(near);
+            self.total_near.credit(near);
+            self.save_registered_account(&account);
+
+            log(DepositLimitOrderRejected {
+                account_id: order.account_id(),
+                batch_id: batch_id.value().into(),
+                near: near.value().into(),
+                stake_out: stake_out.value().into(),
+                min_stake_out: order.min_amount_out().into(),
+                deadline_epoch: order.deadline_epoch().value().into(),
+                reason,
+            });
+        }
+    }
+
+    /// checks the redeem limit orders for the redeem stake batch against the current STAKE token value
+    /// - the STAKE for orders that are rejected is removed from the batch and credited back to the
+    ///   account's STAKE balance
+    /// - the STAKE token value must be refreshed before this is called
+    ///
+    /// NOTE: if all STAKE is removed, then the redeem stake batch is cleared
+    pub(crate) fn apply_redeem_limit_orders(&mut self) {
+        let batch_id = self
+            .redeem_stake_batch
+            .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST)
+            .id();
+        for order in self.redeem_limit_orders.take(batch_id) {
+            let near_out = self.stake_token_value.stake_to_near(order.amount().into());
+            let reason = match order.rejection(near_out.value()) {
+                Some(reason) => reason,
+                None => continue,
+            };
+            let mut account = match self.lookup_registered_account(order.account_id()) {
+                Some(account) => account,
+                None => continue,
+            };
+            // the account's next batch slot may still be holding the contract's current batch
+            let (mut account_batch, is_next_batch_slot) =
+                match (account.redeem_stake_batch, account.next_redeem_stake_batch) {
+                    (Some(batch), _) if batch.id() == batch_id => (batch, false),
+                    (_, Some(batch)) if batch.id() == batch_id => (batch, true),
+                    // the STAKE was removed from the batch
+                    _ => continue,
+                };
+            let stake = account_batch.balance().amount().min(order.amount().into());
+
+            // remove funds from contract level batch
+            {
+                let mut batch = self
+                    .redeem_stake_batch
+                    .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST);
+                if batch.remove(stake).value() == 0 {
+                    self.redeem_stake_batch = None;
+                } else {
+                    self.redeem_stake_batch = Some(batch);
+                }
+            }
+
+            let account_batch = if account_batch.remove(stake).value() == 0 {
+                None
+            } else {
+                Some(account_batch)
+            };
+            if is_next_batch_slot {
+                account.next_redeem_stake_batch = account_batch;
+            } else {
+                account.redeem_stake_batch = account_batch;
+            }
+            account.apply_stake_credit(stake);
+            self.save_registered_account(&account);
+            self.sync_redeem_beneficiary(&mut account, batch_id);
+
+            log(FtTransfer {
+                old_owner_id: &env::current_account_id(),
+                new_owner_id: order.account_id(),
+                amount: stake.into(),
+                memo: Some("redeem limit order rejected"),
+            });
+            log(RedeemLimitOrderRejected {
+                account_id: order.account_id(),
+                batch_id: batch_id.value().into(),
+                stake: stake.value().into(),
+                near_out: near_out.value().into(),
+                min_near_out: order.min_amount_out().into(),
+                deadline_epoch: order.deadline_epoch().value().into(),
+                reason,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{interface::StakingService, near::YOCTO, test_utils::*};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn deposit_limit_order_rejected_below_min_stake_out() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        let batch_id =
+            contract.deposit_with_limits((11 * YOCTO).into(), domain::EpochHeight(10).into());
+        // NEAR that is deposited without limits is not affected
+        contract.deposit();
+
+        let total_near = contract.total_near.amount();
+        contract.apply_deposit_limit_orders();
+
+        assert_eq!(
+            contract.stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+        let account = contract.registered_account(test_context.account_id);
+        assert_eq!(
+            account.stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+        assert_eq!(account.near.unwrap().amount(), (10 * YOCTO).into());
+        assert_eq!(
+            contract.total_near.amount(),
+            total_near + (10 * YOCTO).into()
+        );
+        assert!(!contract.deposit_limit_orders.contains(batch_id.into()));
+    }
+
+    #[test]
+    fn deposit_limit_order_filled_within_limits() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit_with_limits((10 * YOCTO).into(), domain::EpochHeight(10).into());
+        contract.apply_deposit_limit_orders();
+
+        assert_eq!(
+            contract.stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+        let account = contract.registered_account(test_context.account_id);
+        assert!(account.near.is_none());
+    }
+
+    #[test]
+    fn deposit_limit_order_rejected_after_deadline() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        context.epoch_height = 10;
+        testing_env!(context.clone());
+        contract.deposit_with_limits(0.into(), domain::EpochHeight(10).into());
+
+        context.epoch_height = 11;
+        testing_env!(context.clone());
+        contract.apply_deposit_limit_orders();
+
+        // all NEAR was returned, which means the batch is empty
+        assert!(contract.stake_batch.is_none());
+        let account = contract.registered_account(test_context.account_id);
+        assert!(account.stake_batch.is_none());
+        assert_eq!(account.near.unwrap().amount(), (10 * YOCTO).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "limit order deadline epoch has already passed")]
+    fn deposit_with_limits_deadline_passed() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        context.epoch_height = 10;
+        testing_env!(context);
+        contract.deposit_with_limits(0.into(), domain::EpochHeight(9).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "limit order amount is below the minimum")]
+    fn deposit_with_limits_below_min_amount() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        context.attached_deposit = YOCTO / 2;
+        testing_env!(context);
+        contract.deposit_with_limits(0.into(), domain::EpochHeight(10).into());
+    }
+
+    #[test]
+    fn redeem_limit_order_rejected_below_min_near_out() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        let mut account = contract.registered_account(test_context.account_id);
+        account.apply_stake_credit((20 * YOCTO).into());
+        contract.save_registered_account(&account);
+        contract.total_stake.credit((20 * YOCTO).into());
+
+        let batch_id = contract.redeem_with_limits(
+            (10 * YOCTO).into(),
+            (11 * YOCTO).into(),
+            domain::EpochHeight(10).into(),
+        );
+        contract.redeem((5 * YOCTO).into());
+        contract.apply_redeem_limit_orders();
+
+        assert_eq!(
+            contract.redeem_stake_batch.unwrap().balance().amount(),
+            (5 * YOCTO).into()
+        );
+        let account = contract.registered_account(test_context.account_id);
+        assert_eq!(
+            account.redeem_stake_batch.unwrap().balance().amount(),
+            (5 * YOCTO).into()
+        );
+        assert_eq!(account.stake.unwrap().amount(), (15 * YOCTO).into());
+        assert!(!contract.redeem_limit_orders.contains(batch_id.into()));
+        // the STAKE supply is unchanged because the STAKE was never burned
+        assert_eq!(contract.total_stake.amount(), (20 * YOCTO).into());
+    }
+}
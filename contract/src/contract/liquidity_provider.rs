@@ -0,0 +1,257 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::*;
+use crate::{
+    domain::RegisteredAccount,
+    errors::liquidity_provider::{
+        INSUFFICIENT_LIQUIDITY_PROVIDER_POOL_NEAR, INSUFFICIENT_SHARES, ZERO_LIQUIDITY_DEPOSIT,
+        ZERO_SHARES,
+    },
+    interface::{
+        liquidity_provider::events::{LiquidityAdded, LiquidityRemoved},
+        LiquidityProvider, LiquidityProviderPosition,
+    },
+    near::log,
+};
+use near_sdk::{
+    json_types::{ValidAccountId, U128},
+    near_bindgen, Promise,
+};
+
+#[near_bindgen]
+impl LiquidityProvider for Contract {
+    #[payable]
+    fn add_liquidity(&mut self) -> U128 {
+        let amount: YoctoNear = env::attached_deposit().into();
+        assert!(amount.value() > 0, ZERO_LIQUIDITY_DEPOSIT);
+        let mut account = self.predecessor_registered_account();
+
+        self.claim_liquidity_provider_pool_receipts();
+        let shares = self
+            .liquidity_provider_pool
+            .shares_for_deposit(amount, self.liquidity_provider_pool_value());
+        self.liquidity_provider_pool.issue_shares(shares);
+        self.liquidity_provider_pool.credit_near(amount);
+        account.liquidity_provider_shares += shares;
+        self.save_registered_account(&account);
+
+        log(LiquidityAdded {
+            account_id: &account.account_id,
+            near: amount.value().into(),
+            shares: shares.into(),
+        });
+        shares.into()
+    }
+
+    fn remove_liquidity(&mut self, shares: U128) -> interface::YoctoNear {
+        let shares = shares.0;
+        assert!(shares > 0, ZERO_SHARES);
+        let mut account = self.predecessor_registered_account();
+        assert!(
+            account.liquidity_provider_shares >= shares,
+            INSUFFICIENT_SHARES
+        );
+
+        self.claim_liquidity_provider_pool_receipts();
+        let amount = self
+            .liquidity_provider_pool
+            .shares_value(shares, self.liquidity_provider_pool_value());
+        assert!(
+            self.liquidity_provider_pool.near() >= amount,
+            INSUFFICIENT_LIQUIDITY_PROVIDER_POOL_NEAR
+        );
+        self.liquidity_provider_pool.retire_shares(shares);
+        self.liquidity_provider_pool.debit_near(amount);
+        account.liquidity_provider_shares -= shares;
+        self.save_registered_account(&account);
+
+        log(LiquidityRemoved {
+            account_id: &account.account_id,
+            near: amount.value().into(),
+            shares: shares.into(),
+        });
+        Promise::new(account.account_id.clone()).transfer(amount.value());
+        amount.into()
+    }
+
+    fn liquidity_provider_pool(&self) -> interface::LiquidityProviderPool {
+        let total_value = self.liquidity_provider_pool_value();
+        let near = self.liquidity_provider_pool.near();
+        let utilization_basis_points = if total_value.value() == 0 {
+            0
+        } else {
+            ((total_value.value() - near.value()) / (total_value.value() / 10_000).max(1))
+                .min(10_000) as u16
+        };
+        interface::LiquidityProviderPool {
+            near: near.into(),
+            redeeming_stake: self.liquidity_provider_pool_redeeming_stake().into(),
+            total_value: total_value.into(),
+            total_shares: self.liquidity_provider_pool.total_shares().into(),
+            utilization_basis_points,
+            total_fees_earned: self.liquidity_provider_pool.total_fees_earned().into(),
+        }
+    }
+
+    fn liquidity_provider_position(
+        &self,
+        account_id: ValidAccountId,
+    ) -> Option<LiquidityProviderPosition> {
+        self.lookup_registered_account(account_id.as_ref())
+            .filter(|account: &RegisteredAccount| account.liquidity_provider_shares > 0)
+            .map(|account| LiquidityProviderPosition {
+                shares: account.liquidity_provider_shares.into(),
+                near_value: self
+                    .liquidity_provider_pool
+                    .shares_value(
+                        account.liquidity_provider_shares,
+                        self.liquidity_provider_pool_value(),
+                    )
+                    .into(),
+            })
+    }
+}
+
+impl Contract {
+    /// claims the NEAR for the STAKE that the pool redeemed and moves it into the pool
+    /// - the claimed NEAR is moved out of the user accounts' total NEAR balance
+    pub(crate) fn claim_liquidity_provider_pool_receipts(&mut self) {
+        let mut account = self.liquidity_provider_pool.account();
+        self.claim_redeem_stake_batch_receipts(&env::current_account_id(), &mut account, &[]);
+        if let Some(near) = account.near.take() {
+            self.total_near.debit(near.amount());
+            self.liquidity_provider_pool.credit_near(near.amount());
+        }
+        self.liquidity_provider_pool.set_account(account);
+    }
+
+    fn liquidity_provider_pool_redeeming_stake(&self) -> domain::YoctoStake {
+        let account = self.liquidity_provider_pool.account();
+        self.account_redeem_stake_batches(&env::current_account_id(), &account)
+            .iter()
+            .map(|batch| batch.balance().amount().value())
+            .sum::<u128>()
+            .into()
+    }
+
+    /// pool NEAR balance plus the NEAR value of the STAKE that the pool is redeeming
+    /// - STAKE is valued at the batch receipt STAKE token value once the batch has been unstaked,
+    ///   and at the current STAKE token value otherwise
+    pub(crate) fn liquidity_provider_pool_value(&self) -> YoctoNear {
+        let account = self.liquidity_provider_pool.account();
+        let redeeming_stake_value = self
+            .account_redeem_stake_batches(&env::current_account_id(), &account)
+            .iter()
+            .map(|batch| {
+                let stake_token_value = self
+                    .redeem_stake_batch_receipts
+                    .get(&batch.id())
+                    .map_or(self.stake_token_value, |receipt| {
+                        receipt.stake_token_value()
+                    });
+                stake_token_value
+                    .stake_to_near(batch.balance().amount())
+                    .value()
+            })
+            .sum::<u128>();
+        (self.liquidity_provider_pool.near().value()
+            + account.near.map_or(0, |near| near.amount().value())
+            + redeeming_stake_value)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{interface::StakingService, near::YOCTO, test_utils::*};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn add_and_remove_liquidity() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        let shares = contract.add_liquidity();
+        assert_eq!(shares.0, 10 * YOCTO);
+        let pool = contract.liquidity_provider_pool();
+        assert_eq!(pool.near, (10 * YOCTO).into());
+        assert_eq!(pool.total_shares.0, 10 * YOCTO);
+        assert_eq!(pool.utilization_basis_points, 0);
+        // LP funds are not mixed into user account balances
+        assert_eq!(contract.total_user_accounts_balance(), 0.into());
+
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        let near = contract.remove_liquidity((4 * YOCTO).into());
+        assert_eq!(near, (4 * YOCTO).into());
+        let position = contract
+            .liquidity_provider_position(to_valid_account_id(test_ctx.account_id))
+            .unwrap();
+        assert_eq!(position.shares.0, 6 * YOCTO);
+        assert_eq!(position.near_value, (6 * YOCTO).into());
+    }
+
+    #[test]
+    fn instant_redeem_funded_by_liquidity_provider_pool() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        contract.add_liquidity();
+
+        let mut account = contract.registered_account(test_ctx.account_id);
+        account.apply_stake_credit((5 * YOCTO).into());
+        contract.save_registered_account(&account);
+        contract.total_stake.credit((5 * YOCTO).into());
+
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        let fee = contract.config.instant_redeem_fee((5 * YOCTO).into());
+        let near_out = contract.instant_redeem((5 * YOCTO).into(), 0.into());
+        assert_eq!(near_out, (5 * YOCTO - fee.value()).into());
+
+        // the pool took over the STAKE and submitted it for redemption
+        assert_eq!(contract.total_stake.amount(), (5 * YOCTO).into());
+        assert_eq!(
+            contract.redeem_stake_batch.unwrap().balance().amount(),
+            (5 * YOCTO).into()
+        );
+        // the fee is earned by the pool
+        let pool = contract.liquidity_provider_pool();
+        assert_eq!(pool.near, (5 * YOCTO + fee.value()).into());
+        assert_eq!(pool.redeeming_stake, (5 * YOCTO).into());
+        assert_eq!(pool.total_value, (10 * YOCTO + fee.value()).into());
+        assert_eq!(pool.total_fees_earned, fee.into());
+        assert!(pool.utilization_basis_points > 0);
+        assert_eq!(contract.instant_redeem_fees, 0.into());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "liquidity provider pool NEAR balance is insufficient while STAKE is being redeemed"
+    )]
+    fn remove_liquidity_is_limited_by_utilization() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        let shares = contract.add_liquidity();
+
+        let mut account = contract.registered_account(test_ctx.account_id);
+        account.apply_stake_credit((5 * YOCTO).into());
+        contract.save_registered_account(&account);
+        contract.total_stake.credit((5 * YOCTO).into());
+
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        contract.instant_redeem((5 * YOCTO).into(), 0.into());
+        contract.remove_liquidity(shares);
+    }
+}
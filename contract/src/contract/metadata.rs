@@ -1,4 +1,8 @@
-use crate::interface::metadata::MetaData;
+use crate::interface::{
+    metadata::{FungibleTokenMetadataProvider, MetaData},
+    FungibleTokenMetadata,
+};
+//required in order for near_bindgen macro to work outside of lib.rs
 use crate::*;
 use near_sdk::{
     near_bindgen,
@@ -8,6 +12,13 @@ use near_sdk::{
 
 const METADATA_FT: &str = "http://near.org/contract/metadata/fungible-token";
 
+#[near_bindgen]
+impl FungibleTokenMetadataProvider for Contract {
+    fn ft_metadata(&self) -> FungibleTokenMetadata {
+        self.ft_metadata.clone().into()
+    }
+}
+
 #[near_bindgen]
 impl MetaData for Contract {
     fn metadata(uri: String) -> Option<Value> {
@@ -42,3 +53,85 @@ pub struct TokenMetadata {
     granularity: u8,
     decimals: u8,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::Operator;
+    use crate::test_utils::*;
+    use near_sdk::{json_types::Base64VecU8, testing_env, MockedBlockchain};
+
+    #[test]
+    fn default_ft_metadata() {
+        let test_ctx = TestContext::new();
+
+        let metadata = test_ctx.ft_metadata();
+        assert_eq!(metadata.spec, "ft-1.0.0");
+        assert_eq!(metadata.name, "OysterPack STAKE Token");
+        assert_eq!(metadata.symbol, "STAKE");
+        assert_eq!(metadata.decimals, 24);
+        assert!(metadata.icon.is_none());
+        assert!(metadata.reference.is_none());
+        assert!(metadata.reference_hash.is_none());
+
+        assert_eq!(Contract::metadata_uris(), vec![METADATA_FT.to_string()]);
+    }
+
+    #[test]
+    fn legacy_metadata_shape_is_retained() {
+        let _test_ctx = TestContext::new();
+
+        let metadata = Contract::metadata(METADATA_FT.to_string()).unwrap();
+        assert_eq!(metadata["name"], "OysterPack STAKE Token");
+        assert_eq!(metadata["symbol"], "STAKE");
+        assert_eq!(
+            metadata["ref_url"],
+            "https://github.com/oysterpack/oysterpack-near-stake-token"
+        );
+        assert_eq!(metadata["granularity"], 1);
+        assert_eq!(metadata["decimals"], 24);
+        assert!(Contract::metadata("http://near.org/unknown".to_string()).is_none());
+    }
+
+    #[test]
+    fn update_ft_metadata_invoked_by_operator() {
+        let mut test_ctx = TestContext::new();
+        let mut context = test_ctx.context.clone();
+        context.predecessor_account_id = TEST_OPERATOR_ID.to_string();
+        testing_env!(context);
+
+        let initial_storage_usage = test_ctx.contract_initial_storage_usage;
+
+        let mut metadata = test_ctx.ft_metadata();
+        metadata.icon = Some("data:image/svg+xml,<svg></svg>".to_string());
+        metadata.reference = Some("https://oysterpack.com/stake.json".to_string());
+        metadata.reference_hash = Some(Base64VecU8(vec![1; 32]));
+        let updated_metadata = test_ctx.update_ft_metadata(metadata.clone());
+        assert_eq!(updated_metadata, metadata);
+        assert_eq!(test_ctx.ft_metadata(), metadata);
+
+        // the contract owner pays for the metadata storage
+        assert!(test_ctx.contract_initial_storage_usage > initial_storage_usage);
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by an operator account")]
+    fn update_ft_metadata_invoked_by_non_operator() {
+        let mut test_ctx = TestContext::new();
+        let metadata = test_ctx.ft_metadata();
+        test_ctx.update_ft_metadata(metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "`reference` and `reference_hash` must both be specified")]
+    fn update_ft_metadata_with_reference_but_no_hash() {
+        let mut test_ctx = TestContext::new();
+        let mut context = test_ctx.context.clone();
+        context.predecessor_account_id = TEST_OPERATOR_ID.to_string();
+        testing_env!(context);
+
+        let mut metadata = test_ctx.ft_metadata();
+        metadata.reference = Some("https://oysterpack.com/stake.json".to_string());
+        test_ctx.update_ft_metadata(metadata);
+    }
+}
@@ -0,0 +1,353 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::*;
+use crate::{
+    config::ConfigV0,
+    errors::{
+        asserts::PREDECESSOR_MUST_BE_SELF_OR_OWNER,
+        migration::{CONTRACT_STATE_NOT_FOUND, MIGRATION_BLOCKED_BY_BATCH_RUNNING},
+    },
+};
+use near_sdk::near_bindgen;
+
+/// [Contract] state layout that was deployed before the contract state was versioned, i.e., the
+/// contract delegated to a single staking pool
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(crate) struct ContractV0 {
+    owner_id: AccountId,
+    contract_owner_balance: YoctoNear,
+    contract_initial_storage_usage: StorageUsage,
+    collected_earnings: YoctoNear,
+    operator_id: AccountId,
+    config: ConfigV0,
+    config_change_block_height: BlockHeight,
+    account_storage_usage: StorageUsage,
+    total_account_storage_escrow: YoctoNear,
+    accounts: LookupMap<Hash, Account>,
+    accounts_len: u128,
+    total_near: TimestampedNearBalance,
+    total_stake: TimestampedStakeBalance,
+    near_liquidity_pool: YoctoNear,
+    stake_token_value: StakeTokenValue,
+    batch_id_sequence: BatchId,
+    stake_batch: Option<StakeBatch>,
+    next_stake_batch: Option<StakeBatch>,
+    redeem_stake_batch: Option<RedeemStakeBatch>,
+    next_redeem_stake_batch: Option<RedeemStakeBatch>,
+    stake_batch_receipts: LookupMap<BatchId, StakeBatchReceipt>,
+    redeem_stake_batch_receipts: LookupMap<BatchId, RedeemStakeBatchReceipt>,
+    staking_pool_id: AccountId,
+    stake_batch_lock: Option<StakeLock>,
+    redeem_stake_batch_lock: Option<RedeemLock>,
+}
+
+#[near_bindgen]
+impl Contract {
+    /// migrates the [ContractV0] state over to the current contract state layout
+    /// - must be called, via a function call action batched with the contract deployment, by the
+    ///   contract account or the contract owner
+    /// - the contract state is read as is, i.e., the init method does not require the contract state
+    ///   to not exist
+    /// - the staking pool that the contract was deployed with is registered with the default weight
+    /// - the fields and config settings that are new are set to their defaults, and storage usage is
+    ///   re-measured
+    /// - accounts and redeem stake batch receipts are stored as is - the fields that were appended
+    ///   to them since are read in with their default values
+    /// - accounts are added to the registered account IDs and STAKE holder IDs when they are next
+    ///   saved
+    /// - batch receipts that were created before the migration are not tracked in the receipt backlog
+    ///
+    /// ## Panics
+    /// - if there is no contract state
+    /// - if the stake batch is running or the redeem stake batch is being unstaked
+    #[init]
+    pub fn migrate() -> Self {
+        let prev: ContractV0 = env::state_read().expect(CONTRACT_STATE_NOT_FOUND);
+        let predecessor_account_id = env::predecessor_account_id();
+        assert!(
+            predecessor_account_id == env::current_account_id()
+                || predecessor_account_id == prev.owner_id,
+            "{}",
+            PREDECESSOR_MUST_BE_SELF_OR_OWNER
+        );
+        assert!(
+            prev.stake_batch_lock.is_none()
+                && prev.redeem_stake_batch_lock != Some(RedeemLock::Unstaking),
+            "{}",
+            MIGRATION_BLOCKED_BY_BATCH_RUNNING
+        );
+        let prev_state_len = prev.try_to_vec().unwrap().len() as u64;
+
+        let unstaking_pool_id = match prev.redeem_stake_batch_lock {
+            Some(RedeemLock::PendingWithdrawal) => Some(prev.staking_pool_id.clone()),
+            _ => None,
+        };
+
+        let mut contract = Self {
+            owner_id: prev.owner_id,
+            contract_owner_balance: prev.contract_owner_balance,
+            contract_initial_storage_usage: prev.contract_initial_storage_usage,
+            collected_earnings: prev.collected_earnings,
+
+            operator_id: prev.operator_id,
+            treasury_id: None,
+
+            config: prev.config.into(),
+            config_change_block_height: env::block_index().into(),
+
+            accounts: prev.accounts,
+            accounts_len: prev.accounts_len,
+            account_ids: UnorderedSet::new(ACCOUNT_IDS_KEY_PREFIX.to_vec()),
+            ft_holder_ids: UnorderedSet::new(FT_HOLDER_IDS_KEY_PREFIX.to_vec()),
+            allowances: LookupMap::new(ALLOWANCES_KEY_PREFIX.to_vec()),
+            allowance_storage_usage: Default::default(),
+            pending_batches: LookupMap::new(PENDING_BATCHES_KEY_PREFIX.to_vec()),
+            pending_batch_storage_usage: Default::default(),
+            vaults: LookupMap::new(VAULTS_KEY_PREFIX.to_vec()),
+            vault_storage_usage: Default::default(),
+            vault_id_sequence: VaultId::default(),
+            total_near: prev.total_near,
+            total_stake: prev.total_stake,
+            near_liquidity_pool: prev.near_liquidity_pool,
+            instant_redeem_fees: 0.into(),
+            collected_fees: CollectedFees::default(),
+            liquidity_provider_pool: LiquidityProviderPool::default(),
+            stake_token_value: prev.stake_token_value,
+            stake_token_value_history: StakeTokenValueHistory::new(
+                STAKE_TOKEN_VALUE_HISTORY_KEY_PREFIX.to_vec(),
+            ),
+            batch_id_sequence: prev.batch_id_sequence,
+            stake_batch: prev.stake_batch,
+            redeem_stake_batch: prev.redeem_stake_batch,
+            next_stake_batch: prev.next_stake_batch,
+            next_redeem_stake_batch: prev.next_redeem_stake_batch,
+            stake_batch_receipts: prev.stake_batch_receipts,
+            redeem_stake_batch_receipts: prev.redeem_stake_batch_receipts,
+            stake_batch_receipt_ids: UnorderedSet::new(STAKE_BATCH_RECEIPT_IDS_KEY_PREFIX.to_vec()),
+            redeem_stake_batch_receipt_ids: UnorderedSet::new(
+                REDEEM_STAKE_BATCH_RECEIPT_IDS_KEY_PREFIX.to_vec(),
+            ),
+            stake_batch_receipt_storage_usage: Default::default(),
+            redeem_stake_batch_receipt_storage_usage: Default::default(),
+            redeem_beneficiaries: LookupMap::new(REDEEM_BENEFICIARIES_KEY_PREFIX.to_vec()),
+            redeem_beneficiary_storage_usage: Default::default(),
+            redeem_queue: RedeemQueue::new(
+                REDEEM_QUEUE_KEY_PREFIX.to_vec(),
+                REDEEM_QUEUE_ACCOUNT_REQUESTS_KEY_PREFIX.to_vec(),
+            ),
+            deposit_limit_orders: LimitOrders::new(DEPOSIT_LIMIT_ORDERS_KEY_PREFIX.to_vec()),
+            redeem_limit_orders: LimitOrders::new(REDEEM_LIMIT_ORDERS_KEY_PREFIX.to_vec()),
+            account_storage_usage: prev.account_storage_usage,
+            total_account_storage_escrow: prev.total_account_storage_escrow,
+            staking_pools: vec![StakingPool::new(
+                &prev.staking_pool_id,
+                DEFAULT_STAKING_POOL_WEIGHT,
+            )],
+            unstaking_pool_id,
+            unstaked_cohorts: vec![],
+            staking_pool_migration: None,
+            ft_metadata: FungibleTokenMetadata::default(),
+            stake_batch_lock: None,
+            redeem_stake_batch_lock: prev.redeem_stake_batch_lock,
+            keeper_rewards: KeeperRewards::default(),
+            insurance_buffer: InsuranceBuffer::default(),
+
+            #[cfg(test)]
+            env: near_env::Env::default(),
+        };
+
+        // the contract owner pays for the contract state growth
+        contract.contract_initial_storage_usage = (contract.contract_initial_storage_usage.value()
+            + contract.try_to_vec().unwrap().len() as u64
+            - prev_state_len)
+            .into();
+
+        contract.measure_storage_usage();
+
+        #[cfg(test)]
+        {
+            crate::test_utils::set_env_with_success_promise_result(&mut contract);
+        }
+
+        contract
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        config::Config,
+        domain::{BlockTimeHeight, YoctoStake},
+        interface::AccountManagement,
+        near::YOCTO,
+        test_utils::*,
+    };
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    /// [Account] layout that was deployed before the contract state was versioned
+    #[derive(BorshSerialize)]
+    struct AccountV0 {
+        storage_escrow: TimestampedNearBalance,
+        near: Option<TimestampedNearBalance>,
+        stake: Option<TimestampedStakeBalance>,
+        stake_batch: Option<StakeBatch>,
+        next_stake_batch: Option<StakeBatch>,
+        redeem_stake_batch: Option<RedeemStakeBatch>,
+        next_redeem_stake_batch: Option<RedeemStakeBatch>,
+    }
+
+    /// [RedeemStakeBatchReceipt] layout that was deployed before the contract state was versioned
+    #[derive(BorshSerialize)]
+    struct RedeemStakeBatchReceiptV0 {
+        redeemed_stake: YoctoStake,
+        stake_token_value: StakeTokenValue,
+    }
+
+    const LEGACY_ACCOUNT_ID: &str = "alfio.near";
+
+    /// writes the contract state using the original layout
+    fn deploy_contract_v0(redeem_stake_batch_lock: Option<RedeemLock>) {
+        let stake_token_value = StakeTokenValue::new(
+            BlockTimeHeight::from_env(),
+            (110 * YOCTO).into(),
+            (100 * YOCTO).into(),
+        );
+
+        let mut accounts: LookupMap<Hash, AccountV0> = LookupMap::new(ACCOUNTS_KEY_PREFIX.to_vec());
+        accounts.insert(
+            &Hash::from(LEGACY_ACCOUNT_ID),
+            &AccountV0 {
+                storage_escrow: TimestampedNearBalance::new(YOCTO.into()),
+                near: None,
+                stake: Some(TimestampedStakeBalance::new((10 * YOCTO).into())),
+                stake_batch: None,
+                next_stake_batch: None,
+                redeem_stake_batch: Some(RedeemStakeBatch::new(BatchId(1), (5 * YOCTO).into())),
+                next_redeem_stake_batch: None,
+            },
+        );
+
+        let mut redeem_stake_batch_receipts: LookupMap<BatchId, RedeemStakeBatchReceiptV0> =
+            LookupMap::new(REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec());
+        redeem_stake_batch_receipts.insert(
+            &BatchId(1),
+            &RedeemStakeBatchReceiptV0 {
+                redeemed_stake: (5 * YOCTO).into(),
+                stake_token_value,
+            },
+        );
+
+        env::state_write(&ContractV0 {
+            owner_id: TEST_OWNER_ID.to_string(),
+            contract_owner_balance: (100 * YOCTO).into(),
+            contract_initial_storage_usage: 1000.into(),
+            collected_earnings: 0.into(),
+            operator_id: TEST_OPERATOR_ID.to_string(),
+            config: ConfigV0::default(),
+            config_change_block_height: env::block_index().into(),
+            account_storage_usage: 681.into(),
+            total_account_storage_escrow: YOCTO.into(),
+            accounts: LookupMap::new(ACCOUNTS_KEY_PREFIX.to_vec()),
+            accounts_len: 1,
+            total_near: TimestampedNearBalance::new(0.into()),
+            total_stake: TimestampedStakeBalance::new((100 * YOCTO).into()),
+            near_liquidity_pool: 0.into(),
+            stake_token_value,
+            batch_id_sequence: BatchId(1),
+            stake_batch: None,
+            next_stake_batch: None,
+            redeem_stake_batch: Some(RedeemStakeBatch::new(BatchId(1), (5 * YOCTO).into())),
+            next_redeem_stake_batch: None,
+            stake_batch_receipts: LookupMap::new(STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec()),
+            redeem_stake_batch_receipts: LookupMap::new(
+                REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX.to_vec(),
+            ),
+            staking_pool_id: TEST_STAKING_POOL_ID.to_string(),
+            stake_batch_lock: None,
+            redeem_stake_batch_lock,
+        });
+    }
+
+    #[test]
+    fn migrate_contract_v0_state() {
+        let mut context = new_context(TEST_OWNER_ID);
+        context.is_view = false;
+        testing_env!(context.clone());
+        deploy_contract_v0(Some(RedeemLock::PendingWithdrawal));
+
+        let mut contract = Contract::migrate();
+
+        assert_eq!(contract.owner_id, TEST_OWNER_ID);
+        assert_eq!(contract.operator_id, TEST_OPERATOR_ID);
+        assert_eq!(
+            contract.config.storage_cost_per_byte(),
+            Config::default().storage_cost_per_byte()
+        );
+        assert_eq!(contract.staking_pools.len(), 1);
+        assert_eq!(contract.staking_pools[0].account_id(), TEST_STAKING_POOL_ID);
+        assert_eq!(
+            contract.unstaking_pool_id.as_deref(),
+            Some(TEST_STAKING_POOL_ID)
+        );
+        assert_eq!(
+            contract.redeem_stake_batch_lock,
+            Some(RedeemLock::PendingWithdrawal)
+        );
+        assert_eq!(contract.total_stake.amount(), (100 * YOCTO).into());
+        assert_eq!(contract.accounts_len, 1);
+        assert_eq!(contract.account_storage_usage.value(), 1185);
+        assert!(contract.contract_initial_storage_usage.value() > 1000);
+
+        // legacy records are read in with the appended fields set to their defaults
+        let mut account = contract.registered_account(LEGACY_ACCOUNT_ID);
+        assert_eq!(account.stake_balance(), (10 * YOCTO).into());
+        assert_eq!(
+            account.redeem_stake_batch.unwrap().balance().amount(),
+            (5 * YOCTO).into()
+        );
+        assert_eq!(account.allowances_len, 0);
+        assert_eq!(account.pending_batches_len, 0);
+        assert_eq!(account.vaults_len, 0);
+        assert_eq!(account.redeem_beneficiaries_len, 0);
+        assert_eq!(account.liquidity_provider_shares, 0);
+        let receipt = contract
+            .redeem_stake_batch_receipts
+            .get(&BatchId(1))
+            .unwrap();
+        assert_eq!(receipt.redeemed_stake(), (5 * YOCTO).into());
+        assert_eq!(receipt.haircut(), 0.into());
+
+        // migrated accounts are written using the current layout
+        account.pending_batches_len = 1;
+        contract.save_registered_account(&account);
+        let account = contract.registered_account(LEGACY_ACCOUNT_ID);
+        assert_eq!(account.pending_batches_len, 1);
+        assert_eq!(account.stake_balance(), (10 * YOCTO).into());
+        assert_eq!(contract.accounts(0, 10).len(), 1);
+        assert_eq!(contract.ft_holders(0, 10).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "contract state cannot be migrated while a batch is running")]
+    fn migrate_contract_v0_state_while_unstaking() {
+        let mut context = new_context(TEST_OWNER_ID);
+        context.is_view = false;
+        testing_env!(context.clone());
+        deploy_contract_v0(Some(RedeemLock::Unstaking));
+
+        Contract::migrate();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "contract call is only allowed by the contract account or the contract owner"
+    )]
+    fn migrate_contract_v0_state_called_by_operator() {
+        let mut context = new_context(TEST_OPERATOR_ID);
+        context.is_view = false;
+        testing_env!(context.clone());
+        deploy_contract_v0(None);
+
+        Contract::migrate();
+    }
+}
@@ -0,0 +1,544 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::*;
+use crate::{
+    domain::{RedeemLock, YoctoNear},
+    errors::{
+        illegal_state::{
+            ILLEGAL_REDEEM_LOCK_STATE, REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST,
+            REDEEM_STAKE_BATCH_SHOULD_EXIST, STAKE_BATCH_SHOULD_EXIST,
+        },
+        staking_pool_failures::GET_ACCOUNT_FAILURE,
+    },
+    ext_netted_batches_workflow_callbacks,
+    interface::staking_service::events::{
+        BatchesNetted, RedeemStakeBatchCancelled, StakeBatchCancelled,
+    },
+    near::{log, NO_DEPOSIT},
+};
+use near_sdk::{env, near_bindgen, AccountId, Promise, PromiseOrValue};
+
+#[near_bindgen]
+impl Contract {
+    /// the STAKE token value is updated using the balances summed across all staking pools, then the
+    /// limit orders for both batches are checked against the updated STAKE token value, and then
+    /// the batches are netted against each other using the updated STAKE token value
+    /// - if all funds in either batch were returned to the accounts, then there is nothing left to
+    ///   net against and the workflow ends - the remaining batch is unlocked to be run separately
+    /// - if more NEAR is being staked, then the net NEAR amount is deposited and staked
+    /// - if more NEAR is being redeemed, then the net NEAR amount is unstaked
+    /// - if the batches fully offset each other, then the staking pool is not called and the
+    ///   batches are settled right away
+    ///
+    /// ## Panics
+    /// - if not called by self
+    /// - if either batch does not exist
+    /// - if the upstream promise to get the account from the staking pool failed
+    #[private]
+    pub fn on_run_netted_batches(
+        &mut self,
+        staking_pool_id: AccountId,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> PromiseOrValue<()> {
+        assert!(self.is_unstaking(), ILLEGAL_REDEEM_LOCK_STATE);
+        assert!(self.promise_result_succeeded(), GET_ACCOUNT_FAILURE);
+        self.update_staking_pool_balances(&staking_pool_id, &staking_pool_account);
+
+        // update the cached STAKE token value
+        let (staked_balance, unstaked_balance) = self.total_staking_pool_balances();
+        let staked_near_balance = self.staked_near_balance(staked_balance, unstaked_balance);
+        self.accrue_staking_rewards(staked_near_balance);
+        self.update_stake_token_value(staked_near_balance);
+
+        if !self.apply_netted_batches_limit_orders() {
+            return PromiseOrValue::Value(());
+        }
+
+        let (stake_amount, redeem_amount) = self.netted_batch_amounts();
+        if stake_amount > redeem_amount {
+            self.staking_pool_promise(&staking_pool_id)
+                .deposit_and_stake(stake_amount - redeem_amount)
+                .get_account()
+                .promise()
+                .then(self.invoke_on_netted_batches_settled(&staking_pool_id))
+                .into()
+        } else if redeem_amount > stake_amount {
+            let unstake_amount = redeem_amount - stake_amount;
+            let promise = if staking_pool_account.staked_balance.0 < unstake_amount.value() {
+                // see `on_run_redeem_stake_batch`
+                self.staking_pool_promise(&staking_pool_id).unstake_all()
+            } else {
+                self.staking_pool_promise(&staking_pool_id)
+                    .unstake(unstake_amount)
+            };
+            promise
+                .get_account()
+                .promise()
+                .then(self.invoke_on_netted_batches_settled(&staking_pool_id))
+                .into()
+        } else {
+            self.settle_netted_batches(&staking_pool_id, staked_balance, unstaked_balance);
+            PromiseOrValue::Value(())
+        }
+    }
+
+    /// ## Panics
+    /// - if not called by self
+    /// - if either batch does not exist
+    /// - if the upstream promise to deposit and stake, or unstake, the net NEAR amount failed
+    #[private]
+    pub fn on_netted_batches_settled(
+        &mut self,
+        staking_pool_id: AccountId,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) {
+        self.update_staking_pool_balances(&staking_pool_id, &staking_pool_account);
+        let (staked_balance, unstaked_balance) = self.total_staking_pool_balances();
+        self.settle_netted_batches(&staking_pool_id, staked_balance, unstaked_balance);
+    }
+}
+
+impl Contract {
+    /// applies the limit orders for both batches before they are netted - see
+    /// [apply_deposit_limit_orders](Contract::apply_deposit_limit_orders) and
+    /// [apply_redeem_limit_orders](Contract::apply_redeem_limit_orders)
+    /// - if either batch was cancelled because all of its funds were returned to the accounts, then
+    ///   both batch locks are released and the keepers are not paid
+    ///
+    /// Returns true if both batches are still present to be netted
+    fn apply_netted_batches_limit_orders(&mut self) -> bool {
+        let stake_batch = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
+        let redeem_stake_batch = self
+            .redeem_stake_batch
+            .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST);
+        if self.deposit_limit_orders.contains(stake_batch.id()) {
+            self.apply_deposit_limit_orders();
+        }
+        if self.redeem_limit_orders.contains(redeem_stake_batch.id()) {
+            self.apply_redeem_limit_orders();
+        }
+        if self.stake_batch.is_some() && self.redeem_stake_batch.is_some() {
+            return true;
+        }
+
+        if self.stake_batch.is_none() {
+            log(StakeBatchCancelled {
+                batch_id: stake_batch.id().value().into(),
+            });
+            self.pop_stake_batch();
+        }
+        if self.redeem_stake_batch.is_none() {
+            log(RedeemStakeBatchCancelled {
+                batch_id: redeem_stake_batch.id().value().into(),
+            });
+            self.pop_redeem_stake_batch();
+        }
+        self.stake_batch_lock = None;
+        self.redeem_stake_batch_lock = None;
+        self.keeper_rewards.take_stake_batch_keeper();
+        self.keeper_rewards.take_redeem_stake_batch_keeper();
+        false
+    }
+
+    /// returns the NEAR amounts that are netted against each other: (stake amount, redeem amount)
+    /// - any NEAR liquidity is staked along with the stake batch because there is no pending
+    ///   withdrawal when the batches are netted
+    fn netted_batch_amounts(&self) -> (YoctoNear, YoctoNear) {
+        let stake_batch = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
+        let redeem_stake_batch = self
+            .redeem_stake_batch
+            .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST);
+        let stake_amount = stake_batch.balance().amount() + self.near_liquidity_pool;
+        let redeem_amount = self
+            .stake_token_value
+            .stake_to_near(redeem_stake_batch.balance().amount());
+        (stake_amount, redeem_amount)
+    }
+
+    /// ## Workflow
+    /// 1. create [RedeemStakeBatchReceipt](crate::domain::RedeemStakeBatchReceipt) using the
+    ///    STAKE token value that the batches were netted at, which burns the redeemed STAKE
+    /// 2. if the NEAR being staked covers the redeemed STAKE NEAR value, then the NEAR is made
+    ///    available for withdrawal and the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+    ///    is popped
+    ///    - else the NEAR being staked is added as liquidity for the pending withdrawal
+    /// 3. mint STAKE for the [StakeBatch](crate::domain::StakeBatch) and update the STAKE token value
+    /// 4. create [StakeBatchReceipt](crate::domain::StakeBatchReceipt)
+    /// 5. pop the [StakeBatch](crate::domain::StakeBatch) and release the stake lock
+    /// 6. allocate NEAR liquidity to the redeem queue
+    /// 7. reward the keeper for each batch
+    fn settle_netted_batches(
+        &mut self,
+        staking_pool_id: &str,
+        staked_balance: YoctoNear,
+        unstaked_balance: YoctoNear,
+    ) {
+        let stake_batch = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
+        let redeem_stake_batch = self
+            .redeem_stake_batch
+            .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST);
+        let (stake_amount, _) = self.netted_batch_amounts();
+        self.near_liquidity_pool = 0.into();
+
+        self.create_redeem_stake_batch_receipt();
+        let redeem_amount = self
+            .redeem_stake_batch_receipts
+            .get(&redeem_stake_batch.id())
+            .expect(REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST)
+            .stake_near_value();
+        if stake_amount >= redeem_amount {
+            self.total_near.credit(redeem_amount);
+            self.redeem_stake_batch_lock = None;
+            self.pop_redeem_stake_batch();
+        } else {
+            self.near_liquidity_pool = stake_amount;
+            self.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+            self.unstaking_pool_id = Some(staking_pool_id.to_string());
+        }
+
+        self.mint_stake_and_update_stake_token_value(staked_balance, unstaked_balance, stake_batch);
+        self.create_stake_batch_receipt(stake_batch);
+        self.pop_stake_batch();
+        self.stake_batch_lock = None;
+        self.allocate_redeem_queue_liquidity();
+
+        log(BatchesNetted {
+            stake_batch_id: stake_batch.id().value().into(),
+            redeem_stake_batch_id: redeem_stake_batch.id().value().into(),
+            offset_near: stake_amount.min(redeem_amount).value().into(),
+            staked_near: stake_amount
+                .value()
+                .saturating_sub(redeem_amount.value())
+                .into(),
+            unstaked_near: redeem_amount
+                .value()
+                .saturating_sub(stake_amount.value())
+                .into(),
+        });
+
+        let keeper_id = self.keeper_rewards.take_stake_batch_keeper();
+        self.pay_keeper_reward(keeper_id);
+        let keeper_id = self.keeper_rewards.take_redeem_stake_batch_keeper();
+        self.pay_keeper_reward(keeper_id);
+    }
+}
+
+/// netted batches workflow callback invocations
+impl Contract {
+    pub(crate) fn invoke_on_run_netted_batches(&self, staking_pool_id: &str) -> Promise {
+        ext_netted_batches_workflow_callbacks::on_run_netted_batches(
+            staking_pool_id.to_string(),
+            &env::current_account_id(),
+            NO_DEPOSIT.into(),
+            self.config
+                .gas_config()
+                .callbacks()
+                .on_run_netted_batches()
+                .value(),
+        )
+    }
+
+    fn invoke_on_netted_batches_settled(&self, staking_pool_id: &str) -> Promise {
+        ext_netted_batches_workflow_callbacks::on_netted_batches_settled(
+            staking_pool_id.to_string(),
+            &env::current_account_id(),
+            NO_DEPOSIT.into(),
+            self.config
+                .gas_config()
+                .callbacks()
+                .on_netted_batches_settled()
+                .value(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        domain::{RedeemStakeBatch, StakeBatch, TimestampedStakeBalance},
+        interface::StakingService,
+        near::YOCTO,
+        test_utils::*,
+    };
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    /// sets up the stake batch and the redeem stake batch in the locked state as if
+    /// [run_netted_batches](crate::interface::StakingService::run_netted_batches) was invoked
+    /// - 1 STAKE is worth 1 NEAR
+    fn run_netted_batches(contract: &mut Contract, stake_near: u128, redeem_stake: u128) {
+        contract.total_stake = TimestampedStakeBalance::new((1000 * YOCTO).into());
+        contract.update_stake_token_value((1000 * YOCTO).into());
+
+        *contract.batch_id_sequence += 1;
+        contract.stake_batch = Some(StakeBatch::new(
+            contract.batch_id_sequence,
+            stake_near.into(),
+        ));
+        *contract.batch_id_sequence += 1;
+        contract.redeem_stake_batch = Some(RedeemStakeBatch::new(
+            contract.batch_id_sequence,
+            redeem_stake.into(),
+        ));
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+    }
+
+    fn staking_pool_account(staked_balance: u128, unstaked_balance: u128) -> StakingPoolAccount {
+        StakingPoolAccount {
+            account_id: env::current_account_id(),
+            unstaked_balance: unstaked_balance.into(),
+            staked_balance: staked_balance.into(),
+            can_withdraw: false,
+        }
+    }
+
+    /// Given more NEAR is being staked than redeemed
+    /// When the batches are netted
+    /// Then only the net NEAR amount is deposited and staked
+    /// And when the batches are settled
+    /// Then the redeemed STAKE NEAR value is available for withdrawal
+    /// And both batch receipts are created
+    #[test]
+    fn net_stake() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        set_env_with_success_promise_result(contract);
+
+        run_netted_batches(contract, 100 * YOCTO, 40 * YOCTO);
+        let stake_batch = contract.stake_batch.unwrap();
+        let redeem_stake_batch = contract.redeem_stake_batch.unwrap();
+        let total_near = contract.total_near.amount();
+
+        contract.on_run_netted_batches(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account(1000 * YOCTO, 0),
+        );
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        match &receipts[0].actions[0] {
+            Action::FunctionCall {
+                method_name,
+                deposit,
+                ..
+            } => {
+                assert_eq!(method_name, "deposit_and_stake");
+                assert_eq!(*deposit, 60 * YOCTO);
+            }
+            _ => panic!("expected deposit_and_stake function call"),
+        }
+        match &receipts[1].actions[0] {
+            Action::FunctionCall { method_name, .. } => {
+                assert_eq!(method_name, "on_netted_batches_settled")
+            }
+            _ => panic!("expected on_netted_batches_settled function call"),
+        }
+
+        contract.on_netted_batches_settled(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account(1060 * YOCTO, 0),
+        );
+        assert!(contract.stake_batch.is_none());
+        assert!(contract.redeem_stake_batch.is_none());
+        assert!(contract.stake_batch_lock.is_none());
+        assert!(contract.redeem_stake_batch_lock.is_none());
+        assert_eq!(
+            contract.total_near.amount(),
+            total_near + (40 * YOCTO).into()
+        );
+        assert_eq!(contract.total_stake.amount(), (1060 * YOCTO).into());
+        assert_eq!(
+            contract.stake_token_value.stake_to_near(YOCTO.into()),
+            YOCTO.into()
+        );
+        assert!(contract
+            .stake_batch_receipts
+            .get(&stake_batch.id())
+            .is_some());
+        assert!(contract
+            .redeem_stake_batch_receipts
+            .get(&redeem_stake_batch.id())
+            .is_some());
+    }
+
+    /// Given more NEAR is being redeemed than staked
+    /// When the batches are netted
+    /// Then only the net NEAR amount is unstaked
+    /// And when the batches are settled
+    /// Then the NEAR being staked is added as liquidity for the pending withdrawal
+    #[test]
+    fn net_unstake() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        set_env_with_success_promise_result(contract);
+
+        run_netted_batches(contract, 40 * YOCTO, 100 * YOCTO);
+
+        contract.on_run_netted_batches(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account(1000 * YOCTO, 0),
+        );
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        match &receipts[0].actions[0] {
+            Action::FunctionCall {
+                method_name, args, ..
+            } => {
+                assert_eq!(method_name, "unstake");
+                assert!(args.contains(&(60 * YOCTO).to_string()));
+            }
+            _ => panic!("expected unstake function call"),
+        }
+
+        contract.on_netted_batches_settled(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account(940 * YOCTO, 60 * YOCTO),
+        );
+        assert!(contract.stake_batch.is_none());
+        assert!(contract.stake_batch_lock.is_none());
+        assert_eq!(
+            contract.redeem_stake_batch_lock,
+            Some(RedeemLock::PendingWithdrawal)
+        );
+        assert_eq!(
+            contract.unstaking_pool_id,
+            Some(TEST_STAKING_POOL_ID.to_string())
+        );
+        assert_eq!(contract.near_liquidity_pool, (40 * YOCTO).into());
+        assert_eq!(
+            contract.pending_withdrawal().unwrap().redeemed_stake,
+            (100 * YOCTO).into()
+        );
+        assert_eq!(contract.total_stake.amount(), (940 * YOCTO).into());
+        assert_eq!(
+            contract.stake_token_value.stake_to_near(YOCTO.into()),
+            YOCTO.into()
+        );
+    }
+
+    /// Given the batches fully offset each other
+    /// When the batches are netted
+    /// Then the staking pool is not called
+    /// And the batches are settled right away
+    #[test]
+    fn fully_offset() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        set_env_with_success_promise_result(contract);
+
+        run_netted_batches(contract, 50 * YOCTO, 50 * YOCTO);
+        let total_near = contract.total_near.amount();
+
+        contract.on_run_netted_batches(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account(1000 * YOCTO, 0),
+        );
+        assert!(deserialize_receipts().is_empty());
+        assert!(contract.stake_batch.is_none());
+        assert!(contract.redeem_stake_batch.is_none());
+        assert!(!contract.stake_batch_locked());
+        assert!(contract.redeem_stake_batch_lock.is_none());
+        assert_eq!(
+            contract.total_near.amount(),
+            total_near + (50 * YOCTO).into()
+        );
+        assert_eq!(contract.total_stake.amount(), (1000 * YOCTO).into());
+    }
+
+    /// Given the stake batch has a deposit limit order whose limits are not met
+    /// When the batches are netted
+    /// Then the limit order is rejected before the batches are netted
+    /// And only the remaining NEAR is netted against the redeemed STAKE
+    #[test]
+    fn deposit_limit_order_rejected_before_netting() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        run_netted_batches(contract, 0, 100 * YOCTO);
+        contract.stake_batch = None;
+        contract.stake_batch_lock = None;
+        context.attached_deposit = 40 * YOCTO;
+        context.epoch_height = 10;
+        testing_env!(context.clone());
+        contract.deposit_with_limits((41 * YOCTO).into(), domain::EpochHeight(10).into());
+        context.attached_deposit = 20 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit();
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+
+        context.predecessor_account_id = context.current_account_id.clone();
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        set_env_with_success_promise_result(contract);
+        contract.on_run_netted_batches(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account(1000 * YOCTO, 0),
+        );
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        match &receipts[0].actions[0] {
+            Action::FunctionCall {
+                method_name, args, ..
+            } => {
+                assert_eq!(method_name, "unstake");
+                assert!(args.contains(&(80 * YOCTO).to_string()));
+            }
+            _ => panic!("expected unstake function call"),
+        }
+        assert_eq!(
+            contract.stake_batch.unwrap().balance().amount(),
+            (20 * YOCTO).into()
+        );
+        let account = contract.registered_account(test_context.account_id);
+        assert_eq!(account.near.unwrap().amount(), (40 * YOCTO).into());
+    }
+
+    /// Given all NEAR in the stake batch is returned by its limit orders
+    /// When the batches are netted
+    /// Then the stake batch is cancelled and the netting workflow ends
+    /// And both locks are released so that the redeem stake batch can be run separately
+    #[test]
+    fn stake_batch_emptied_by_limit_orders_ends_netting() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        run_netted_batches(contract, 0, 100 * YOCTO);
+        contract.stake_batch = None;
+        contract.stake_batch_lock = None;
+        context.attached_deposit = 40 * YOCTO;
+        context.epoch_height = 10;
+        testing_env!(context.clone());
+        contract.deposit_with_limits((41 * YOCTO).into(), domain::EpochHeight(10).into());
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        let redeem_stake_batch_id = contract.redeem_stake_batch.unwrap().id();
+
+        context.predecessor_account_id = context.current_account_id.clone();
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        set_env_with_success_promise_result(contract);
+        contract.on_run_netted_batches(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account(1000 * YOCTO, 0),
+        );
+        assert!(deserialize_receipts().is_empty());
+        assert!(contract.stake_batch.is_none());
+        assert!(contract.stake_batch_lock.is_none());
+        assert!(contract.redeem_stake_batch_lock.is_none());
+        assert_eq!(
+            contract.redeem_stake_batch.unwrap().id(),
+            redeem_stake_batch_id
+        );
+        let account = contract.registered_account(test_context.account_id);
+        assert_eq!(account.near.unwrap().amount(), (40 * YOCTO).into());
+    }
+}
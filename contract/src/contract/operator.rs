@@ -2,11 +2,23 @@
 use crate::interface::ContractFinancials;
 use crate::*;
 use crate::{
-    domain::RedeemLock,
+    domain::{
+        RedeemLock, StakingPool, StakingPoolMigration, StakingPoolMigrationStatus,
+        MAX_STAKING_POOLS,
+    },
+    errors::{
+        redeeming_stake_errors::UNSTAKED_FUNDS_NOT_AVAILABLE_FOR_WITHDRAWAL,
+        staking_errors::{BLOCKED_BY_BATCH_RUNNING, BLOCKED_BY_STAKE_TOKEN_VALUE_REFRESH},
+        staking_pools::{
+            MAX_STAKING_POOLS_EXCEEDED, NO_STAKING_POOL_MIGRATION, STAKING_POOL_ALREADY_REGISTERED,
+            STAKING_POOL_MIGRATION_IN_PROGRESS, STAKING_POOL_MIGRATION_RUNNING,
+            STAKING_POOL_MIGRATION_TO_SELF, STAKING_POOL_NOT_FOUND, ZERO_TOTAL_STAKING_POOL_WEIGHT,
+        },
+    },
     interface::{contract_state::ContractState, AccountManagement},
     interface::{Operator, StakingService},
 };
-use near_sdk::near_bindgen;
+use near_sdk::{json_types::ValidAccountId, near_bindgen, Promise};
 
 #[near_bindgen]
 impl Operator for Contract {
@@ -18,7 +30,9 @@ impl Operator for Contract {
         ContractState {
             block: domain::BlockTimeHeight::from_env().into(),
             config_change_block_height: self.config_change_block_height.into(),
-            staking_pool_id: self.staking_pool_id.clone(),
+            staking_pools: self.staking_pools(),
+            unstaking_pool_id: self.unstaking_pool_id.clone(),
+            staking_pool_migration: self.staking_pool_migration(),
             registered_accounts_count: self.total_registered_accounts().clone(),
             total_unstaked_near: self.total_near.into(),
             total_stake_supply: self.total_stake.into(),
@@ -40,6 +54,8 @@ impl Operator for Contract {
             }),
             stake_batch_lock: self.stake_batch_lock.map(Into::into),
             redeem_stake_batch_lock: self.redeem_stake_batch_lock,
+            next_redeem_stake_batch_blocked_by_pending_withdrawal: self
+                .is_next_redeem_stake_batch_blocked_by_pending_withdrawal(),
             balances: self.balances(),
             initial_storage_usage: self.contract_initial_storage_usage.into(),
             storage_usage_growth: (env::storage_usage()
@@ -49,27 +65,156 @@ impl Operator for Contract {
     }
 
     fn config(&self) -> interface::Config {
-        self.config.into()
+        self.config.clone().into()
     }
 
     fn reset_config_default(&mut self) -> interface::Config {
         self.assert_predecessor_is_operator();
-        self.config = Config::default();
-        self.config.into()
+        self.set_config(Config::default());
+        self.config.clone().into()
     }
 
     fn update_config(&mut self, config: interface::Config) -> interface::Config {
         self.assert_predecessor_is_operator();
-        self.config.merge(config);
+        let mut updated_config = self.config.clone();
+        updated_config.merge(config);
+        self.set_config(updated_config);
         self.config_change_block_height = env::block_index().into();
-        self.config.into()
+        self.config.clone().into()
     }
 
     fn force_update_config(&mut self, config: interface::Config) -> interface::Config {
         self.assert_predecessor_is_operator();
-        self.config.force_merge(config);
+        let mut updated_config = self.config.clone();
+        updated_config.force_merge(config);
+        self.set_config(updated_config);
         self.config_change_block_height = env::block_index().into();
-        self.config.into()
+        self.config.clone().into()
+    }
+
+    fn update_ft_metadata(
+        &mut self,
+        metadata: interface::FungibleTokenMetadata,
+    ) -> interface::FungibleTokenMetadata {
+        self.assert_predecessor_is_operator();
+        let metadata = domain::FungibleTokenMetadata::from(metadata);
+        metadata.assert_valid();
+
+        // metadata is stored in contract state - thus the contract owner is responsible to pay for
+        // the storage, which is tracked via the contract initial storage usage
+        let storage_usage =
+            |metadata: &domain::FungibleTokenMetadata| metadata.try_to_vec().unwrap().len() as u64;
+        let initial_storage_usage = self.contract_initial_storage_usage.value()
+            - storage_usage(&self.ft_metadata)
+            + storage_usage(&metadata);
+        self.contract_initial_storage_usage = initial_storage_usage.into();
+
+        self.ft_metadata = metadata;
+        self.ft_metadata.clone().into()
+    }
+
+    fn add_staking_pool(
+        &mut self,
+        staking_pool_id: ValidAccountId,
+        weight: u32,
+    ) -> Vec<interface::StakingPool> {
+        self.assert_predecessor_is_operator();
+        assert!(
+            self.staking_pools
+                .iter()
+                .all(|pool| pool.account_id() != staking_pool_id.as_ref()),
+            STAKING_POOL_ALREADY_REGISTERED
+        );
+        assert!(
+            self.staking_pools.len() < MAX_STAKING_POOLS,
+            MAX_STAKING_POOLS_EXCEEDED
+        );
+
+        self.staking_pools
+            .push(StakingPool::new(staking_pool_id.as_ref(), weight));
+        self.staking_pools()
+    }
+
+    fn set_staking_pool_weight(
+        &mut self,
+        staking_pool_id: ValidAccountId,
+        weight: u32,
+    ) -> Vec<interface::StakingPool> {
+        self.assert_predecessor_is_operator();
+        self.staking_pool_mut(staking_pool_id.as_ref())
+            .set_weight(weight);
+        assert!(
+            self.total_staking_pool_weight() > 0,
+            ZERO_TOTAL_STAKING_POOL_WEIGHT
+        );
+        self.staking_pools()
+    }
+
+    fn migrate_staking_pool(
+        &mut self,
+        from_staking_pool_id: ValidAccountId,
+        to_staking_pool_id: ValidAccountId,
+    ) -> Promise {
+        self.assert_predecessor_is_operator();
+        assert!(
+            self.staking_pool_migration.is_none(),
+            STAKING_POOL_MIGRATION_IN_PROGRESS
+        );
+        assert!(
+            self.stake_batch_lock.is_none()
+                && self.redeem_stake_batch_lock.is_none()
+                && self.unstaked_cohorts.is_empty(),
+            BLOCKED_BY_BATCH_RUNNING
+        );
+        assert!(
+            self.staking_pools
+                .iter()
+                .any(|pool| pool.account_id() == from_staking_pool_id.as_ref()),
+            STAKING_POOL_NOT_FOUND
+        );
+        assert_ne!(
+            from_staking_pool_id.as_ref(),
+            to_staking_pool_id.as_ref(),
+            "{}",
+            STAKING_POOL_MIGRATION_TO_SELF
+        );
+
+        self.start_staking_pool_migration(StakingPoolMigration::new(
+            from_staking_pool_id.as_ref(),
+            to_staking_pool_id.as_ref(),
+        ))
+    }
+
+    fn run_staking_pool_migration(&mut self) -> Promise {
+        self.assert_predecessor_is_operator();
+        let migration = self
+            .staking_pool_migration
+            .clone()
+            .expect(NO_STAKING_POOL_MIGRATION);
+        assert!(!migration.is_running(), STAKING_POOL_MIGRATION_RUNNING);
+        assert!(
+            self.stake_batch_lock.is_none(),
+            BLOCKED_BY_STAKE_TOKEN_VALUE_REFRESH
+        );
+
+        match migration.status() {
+            StakingPoolMigrationStatus::PendingWithdrawal => {
+                assert!(
+                    migration.unstaked_funds_available_for_withdrawal(),
+                    UNSTAKED_FUNDS_NOT_AVAILABLE_FOR_WITHDRAWAL
+                );
+                self.withdraw_migrated_near()
+            }
+            StakingPoolMigrationStatus::Withdrawn => self.stake_migrated_near(),
+            // running states are handled by above assert
+            _ => panic!(STAKING_POOL_MIGRATION_RUNNING),
+        }
+    }
+
+    fn staking_pool_migration(&self) -> Option<interface::StakingPoolMigration> {
+        self.staking_pool_migration
+            .as_ref()
+            .map(interface::StakingPoolMigration::from)
     }
 
     fn clear_stake_lock(&mut self) {
@@ -96,6 +241,19 @@ impl Operator for Contract {
     }
 }
 
+impl Contract {
+    /// config is stored in contract state, which means the contract owner is responsible to pay for
+    /// the storage, e.g., for the fee exemption allowlists - tracked via the contract initial storage usage
+    fn set_config(&mut self, config: Config) {
+        let storage_usage = |config: &Config| config.try_to_vec().unwrap().len() as u64;
+        let initial_storage_usage = self.contract_initial_storage_usage.value()
+            - storage_usage(&self.config)
+            + storage_usage(&config);
+        self.contract_initial_storage_usage = initial_storage_usage.into();
+        self.config = config;
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -176,5 +334,96 @@ mod test {
         testing_env!(context.clone());
         let state = contract.contract_state();
         println!("{}", serde_json::to_string_pretty(&state).unwrap());
+        assert_eq!(state.staking_pools.len(), 1);
+        assert_eq!(state.staking_pools[0].account_id, TEST_STAKING_POOL_ID);
+        assert!(state.unstaking_pool_id.is_none());
+        assert!(!state.next_redeem_stake_batch_blocked_by_pending_withdrawal);
+    }
+
+    #[test]
+    fn add_staking_pool() {
+        // Arrange
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context.clone());
+
+        // Act
+        let staking_pools =
+            contract.add_staking_pool(to_valid_account_id("staking-pool-2.near"), 50);
+
+        // Assert
+        assert_eq!(staking_pools.len(), 2);
+        assert_eq!(staking_pools[1].account_id, "staking-pool-2.near");
+        assert_eq!(staking_pools[1].weight, 50);
+        assert_eq!(staking_pools[1].staked_balance, 0.into());
+        assert_eq!(contract.staking_pools(), staking_pools);
+    }
+
+    #[test]
+    #[should_panic(expected = "staking pool is already registered with the contract")]
+    fn add_staking_pool_already_registered() {
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context.clone());
+        contract.add_staking_pool(to_valid_account_id(TEST_STAKING_POOL_ID), 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "contract call is only allowed by an operator account")]
+    fn add_staking_pool_access_denied() {
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+
+        contract.add_staking_pool(to_valid_account_id("staking-pool-2.near"), 50);
+    }
+
+    #[test]
+    fn set_staking_pool_weight() {
+        // Arrange
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context.clone());
+        contract.add_staking_pool(to_valid_account_id("staking-pool-2.near"), 50);
+
+        // Act
+        let staking_pools =
+            contract.set_staking_pool_weight(to_valid_account_id(TEST_STAKING_POOL_ID), 0);
+
+        // Assert
+        assert_eq!(staking_pools[0].weight, 0);
+        assert_eq!(staking_pools[1].weight, 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one staking pool must have a non-zero weight")]
+    fn set_staking_pool_weight_to_zero_for_all_staking_pools() {
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context.clone());
+        contract.set_staking_pool_weight(to_valid_account_id(TEST_STAKING_POOL_ID), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "staking pool is not registered with the contract")]
+    fn set_staking_pool_weight_for_unregistered_staking_pool() {
+        let mut context = TestContext::new();
+        let contract = &mut context.contract;
+        let mut context = context.context.clone();
+
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context.clone());
+        contract.set_staking_pool_weight(to_valid_account_id("staking-pool-2.near"), 50);
     }
 }
@@ -0,0 +1,267 @@
+//! An account's batch slots hold the account's funds in the contract's current and next batches. When
+//! a slot is needed while it is still holding an older batch that has not been claimed yet, e.g., the
+//! redeemed STAKE was moved into an unstaked cohort, then the older batch is moved into the account's
+//! [pending batches](crate::domain::PendingBatch). Pending batches are claimed along with the batch
+//! slots once their receipts can be claimed.
+//!
+//! The account pays for the pending batch storage out of its storage escrow, which is freed up once
+//! the pending batch is claimed.
+use crate::*;
+use crate::{
+    domain::{PendingBatch, RedeemStakeBatch},
+    errors::account_management::INSUFFICIENT_STORAGE_ESCROW_FOR_PENDING_BATCH,
+};
+
+impl Contract {
+    /// the pending batches are only looked up in storage if the account holds any
+    pub(crate) fn pending_batches(&self, account_id: &str, account: &Account) -> Vec<PendingBatch> {
+        if account.pending_batches_len == 0 {
+            return vec![];
+        }
+        self.pending_batches
+            .get(&Hash::from(account_id))
+            .unwrap_or_default()
+    }
+
+    /// saves the account's pending batches, or deletes them if there are none left
+    ///
+    /// NOTE: the account is not saved
+    pub(crate) fn save_pending_batches(
+        &mut self,
+        account_id: &str,
+        account: &mut Account,
+        batches: Vec<PendingBatch>,
+    ) {
+        let key = Hash::from(account_id);
+        if batches.is_empty() {
+            self.pending_batches.remove(&key);
+        } else {
+            self.pending_batches.insert(&key, &batches);
+        }
+        account.pending_batches_len = batches.len() as u32;
+    }
+
+    /// moves the batch out of the account's batch slots into the account's pending batches
+    /// - the account storage escrow balance must be enough to cover the additional storage - the
+    ///   liquidity provider pool is exempt because the contract pays for its storage
+    ///
+    /// NOTE: the account is not saved
+    pub(crate) fn push_pending_batch(
+        &mut self,
+        account_id: &str,
+        account: &mut Account,
+        batch: PendingBatch,
+    ) {
+        let mut batches = self.pending_batches(account_id, account);
+        batches.push(batch);
+        self.save_pending_batches(account_id, account, batches);
+        if account_id != env::current_account_id() {
+            assert!(
+                account.storage_escrow.amount() >= self.registered_account_storage_fee(account),
+                INSUFFICIENT_STORAGE_ESCROW_FOR_PENDING_BATCH
+            );
+        }
+    }
+
+    /// returns the account's redeem stake batches, i.e., both batch slots and pending batches
+    pub(crate) fn account_redeem_stake_batches(
+        &self,
+        account_id: &str,
+        account: &Account,
+    ) -> Vec<RedeemStakeBatch> {
+        account
+            .redeem_stake_batch
+            .iter()
+            .chain(account.next_redeem_stake_batch.iter())
+            .cloned()
+            .chain(
+                self.pending_batches(account_id, account)
+                    .into_iter()
+                    .filter_map(|batch| batch.redeem_stake_batch()),
+            )
+            .collect()
+    }
+
+    /// returns true if the pending batch receipt can be claimed
+    pub(crate) fn is_pending_batch_claimable(&self, batch: &PendingBatch) -> bool {
+        match batch {
+            PendingBatch::Stake(batch) => self.stake_batch_receipts.contains_key(&batch.id()),
+            PendingBatch::Redeem(batch) => self
+                .claimable_redeem_stake_batch_receipt(batch.id())
+                .is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        domain::{RedeemLock, StakeLock, UnstakedCohort},
+        interface::{AccountManagement, AccountStorage, StakingService},
+        near::YOCTO,
+        test_utils::*,
+    };
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    /// moves the account's redeem stake batch into an unstaked cohort, and then runs the next batch,
+    /// i.e., the account's next batch slot is holding the contract's current batch
+    fn redeem_into_unstaked_cohort(contract: &mut Contract, account_id: &str) -> domain::BatchId {
+        let mut account = contract.registered_account(account_id);
+        account.apply_stake_credit((30 * YOCTO).into());
+        // cover the pending batch storage
+        account.storage_escrow.credit(YOCTO.into());
+        contract.save_registered_account(&account);
+        contract.total_stake.credit((30 * YOCTO).into());
+
+        let cohort_batch_id = domain::BatchId::from(contract.redeem((10 * YOCTO).into()));
+        let batch = contract.redeem_stake_batch.unwrap();
+        contract.redeem_stake_batch_receipts.insert(
+            &batch.id(),
+            &batch.create_receipt(contract.stake_token_value),
+        );
+        contract.redeem_stake_batch_receipt_ids.insert(&batch.id());
+        contract.unstaked_cohorts.push(UnstakedCohort::new(
+            batch.id(),
+            TEST_STAKING_POOL_ID,
+            contract
+                .stake_token_value
+                .stake_to_near(batch.balance().amount()),
+        ));
+        contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        contract.redeem((10 * YOCTO).into());
+        contract.redeem_stake_batch = contract.next_redeem_stake_batch.take();
+        contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        cohort_batch_id
+    }
+
+    #[test]
+    fn redeem_while_account_batch_is_in_unstaked_cohort() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        let cohort_batch_id = redeem_into_unstaked_cohort(contract, test_context.account_id);
+        let current_batch_id = contract.redeem_stake_batch.unwrap().id();
+        let storage_balance = contract
+            .storage_balance_of(to_valid_account_id(test_context.account_id))
+            .unwrap();
+
+        // the next batch slot is needed for the contract's next batch
+        let batch_id = domain::BatchId::from(contract.redeem((10 * YOCTO).into()));
+        let account = contract.registered_account(test_context.account_id);
+        assert_eq!(account.redeem_stake_batch.unwrap().id(), current_batch_id);
+        assert_eq!(account.next_redeem_stake_batch.unwrap().id(), batch_id);
+        assert_eq!(account.pending_batches_len, 1);
+        let pending_batches = contract.pending_batches(test_context.account_id, &account);
+        assert_eq!(pending_batches[0].id(), cohort_batch_id);
+        assert_eq!(
+            contract
+                .account_redeem_stake_batches(test_context.account_id, &account)
+                .len(),
+            3
+        );
+
+        // the account pays for the pending batch storage
+        let pending_batch_storage_fee = contract.pending_batch_storage_usage.value() as u128
+            * contract.config.storage_cost_per_byte().value();
+        assert_eq!(
+            contract
+                .storage_balance_of(to_valid_account_id(test_context.account_id))
+                .unwrap()
+                .available
+                .0
+                 .0,
+            storage_balance.available.0 .0 - pending_batch_storage_fee
+        );
+
+        let stake_account = contract
+            .lookup_account(to_valid_account_id(test_context.account_id))
+            .unwrap();
+        assert_eq!(stake_account.pending_batches.len(), 1);
+    }
+
+    #[test]
+    fn claim_pending_redeem_stake_batch() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        let cohort_batch_id = redeem_into_unstaked_cohort(contract, test_context.account_id);
+        contract.redeem((10 * YOCTO).into());
+        contract.redeem_stake_batch_lock = None;
+
+        // the NEAR cannot be claimed until the cohort is withdrawn
+        let mut account = contract.registered_account(test_context.account_id);
+        contract.claim_receipt_funds(&mut account);
+        assert_eq!(account.pending_batches_len, 1);
+
+        contract.unstaked_cohorts.clear();
+
+        // the NEAR is applied to the account view before it is claimed
+        let stake_account = contract
+            .lookup_account(to_valid_account_id(test_context.account_id))
+            .unwrap();
+        assert_eq!(stake_account.near.unwrap().amount.0 .0, 10 * YOCTO);
+        assert!(stake_account.pending_batches.is_empty());
+
+        let mut account = contract.registered_account(test_context.account_id);
+        contract.claim_receipt_funds(&mut account);
+        assert_eq!(account.near.unwrap().amount(), (10 * YOCTO).into());
+        assert_eq!(account.pending_batches_len, 0);
+        assert!(contract
+            .pending_batches
+            .get(&Hash::from(test_context.account_id))
+            .is_none());
+        assert!(contract
+            .redeem_stake_batch_receipts
+            .get(&cohort_batch_id)
+            .is_none());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "account storage balance is insufficient to pay for pending batch storage"
+    )]
+    fn push_pending_batch_with_insufficient_storage_escrow() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        let mut account = contract.registered_account(test_context.account_id);
+        contract.push_pending_batch(
+            test_context.account_id,
+            &mut account.account,
+            PendingBatch::Stake(domain::StakeBatch::new(1.into(), YOCTO.into())),
+        );
+    }
+
+    #[test]
+    fn deposit_while_next_batch_slot_holds_contract_current_batch() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit();
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        let second_batch_id = contract.deposit();
+
+        // the first batch is staked, and the contract starts staking the second batch
+        let batch = contract.stake_batch.take().unwrap();
+        contract.create_stake_batch_receipt(batch);
+        contract.stake_batch = contract.next_stake_batch.take();
+
+        let third_batch_id = contract.deposit();
+        let account = contract.registered_account(test_context.account_id);
+        assert_eq!(account.stake_balance(), (10 * YOCTO).into());
+        assert_eq!(account.stake_batch.unwrap().id(), second_batch_id.into());
+        assert_eq!(
+            account.next_stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+        assert_eq!(
+            account.next_stake_batch.unwrap().id(),
+            third_batch_id.into()
+        );
+        assert_eq!(account.pending_batches_len, 0);
+    }
+}
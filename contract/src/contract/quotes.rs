@@ -0,0 +1,205 @@
+//! Quotes for deposits and redeem requests, which tell the client what to expect before the request
+//! is submitted.
+use crate::*;
+use crate::{
+    config::FeeCategory,
+    domain::RedeemLock,
+    interface::{QuoteBatch, RedeemQuote, StakeQuote},
+    near::UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK,
+};
+
+impl Contract {
+    pub(crate) fn stake_quote(&self, amount: domain::YoctoNear, account_id: &str) -> StakeQuote {
+        let fee = self.fee_quote(FeeCategory::Deposit, account_id, amount.value());
+        let (batch, batch_id) = if !self.stake_batch_locked() && !self.is_migrating_staking_pool() {
+            (QuoteBatch::Current, self.stake_batch)
+        } else {
+            (QuoteBatch::Next, self.next_stake_batch)
+        };
+        StakeQuote {
+            amount: amount.into(),
+            stake: self
+                .stake_token_value
+                .near_to_stake(fee.amount_with_fee.0.into())
+                .into(),
+            fee,
+            stake_token_value: self.stake_token_value.into(),
+            stake_token_value_age: self.stake_token_value_age().into(),
+            batch,
+            batch_id: batch_id.map(|batch| batch.id().into()),
+        }
+    }
+
+    pub(crate) fn redeem_quote(&self, amount: domain::YoctoStake, account_id: &str) -> RedeemQuote {
+        let fee = self.fee_quote(FeeCategory::Redeem, account_id, amount.value());
+        let (batch, batch_id) =
+            if self.redeem_stake_batch_lock.is_none() && !self.is_migrating_staking_pool() {
+                (QuoteBatch::Current, self.redeem_stake_batch)
+            } else {
+                (QuoteBatch::Next, self.next_redeem_stake_batch)
+            };
+        RedeemQuote {
+            amount: amount.into(),
+            near: self
+                .stake_token_value
+                .stake_to_near(fee.amount_with_fee.0.into())
+                .into(),
+            fee,
+            stake_token_value: self.stake_token_value.into(),
+            stake_token_value_age: self.stake_token_value_age().into(),
+            batch,
+            batch_id: batch_id.map(|batch| batch.id().into()),
+            estimated_withdrawable_epoch: (self.estimated_unstake_epoch(batch)
+                + UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK)
+                .into(),
+            instant_redeem_liquidity: self
+                .liquidity_provider_pool
+                .near()
+                .max(self.available_instant_redeem_liquidity())
+                .into(),
+        }
+    }
+
+    fn stake_token_value_age(&self) -> domain::EpochHeight {
+        env::epoch_height()
+            .saturating_sub(
+                self.stake_token_value
+                    .block_time_height()
+                    .epoch_height()
+                    .value(),
+            )
+            .into()
+    }
+
+    /// estimates the epoch at which the redeem stake batch will be unstaked
+    /// - the current batch can be unstaked right away
+    /// - the next batch can be unstaked once the current batch is no longer blocking it, i.e., the
+    ///   batch that is pending withdrawal is either withdrawn, cleared by NEAR liquidity, or moved
+    ///   into an unstaked cohort
+    fn estimated_unstake_epoch(&self, batch: QuoteBatch) -> domain::EpochHeight {
+        let current_epoch = domain::EpochHeight(env::epoch_height());
+        if batch == QuoteBatch::Current {
+            return current_epoch;
+        }
+        match self.redeem_stake_batch_lock {
+            // the batch being unstaked will be pending withdrawal until its unstaked NEAR unlocks
+            Some(RedeemLock::Unstaking) if !self.has_staking_pool_available_to_unstake() => {
+                current_epoch + UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK
+            }
+            Some(RedeemLock::PendingWithdrawal) => match self.get_pending_withdrawal() {
+                Some(receipt)
+                    if !receipt.unstaked_funds_available_for_withdrawal()
+                        && self.near_liquidity_pool < receipt.stake_near_value()
+                        && !self.has_staking_pool_available_to_unstake() =>
+                {
+                    receipt.unstaked_near_withdrawal_availability()
+                }
+                _ => current_epoch,
+            },
+            // the staking pool migration is expected to complete within the epoch
+            _ => current_epoch,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        domain::{RedeemStakeBatch, StakeLock, StakingPool},
+        interface::StakingService,
+        near::YOCTO,
+        test_utils::*,
+    };
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn quote_stake() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        let quote = contract.quote_stake((10 * YOCTO).into(), None);
+        assert_eq!(quote.stake, (10 * YOCTO).into());
+        assert_eq!(quote.batch, QuoteBatch::Current);
+        assert!(quote.batch_id.is_none());
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        let batch_id = contract.deposit();
+
+        // the quote reports how stale the STAKE token value is
+        context.epoch_height += 3;
+        testing_env!(context.clone());
+        let quote = contract.quote_stake((10 * YOCTO).into(), None);
+        assert_eq!(quote.stake_token_value_age, domain::EpochHeight(3).into());
+        assert_eq!(quote.batch_id, Some(batch_id));
+
+        // while the stake batch is locked, deposits go into the next batch
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        let quote = contract.quote_stake((10 * YOCTO).into(), None);
+        assert_eq!(quote.batch, QuoteBatch::Next);
+        assert!(quote.batch_id.is_none());
+    }
+
+    #[test]
+    fn quote_redeem_withdrawable_epoch() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+        context.epoch_height = 2;
+        testing_env!(context.clone());
+
+        // the current batch can be unstaked right away
+        let quote = contract.quote_redeem((10 * YOCTO).into(), None);
+        assert_eq!(quote.near, (10 * YOCTO).into());
+        assert_eq!(quote.batch, QuoteBatch::Current);
+        assert_eq!(
+            quote.estimated_withdrawable_epoch,
+            domain::EpochHeight(6).into()
+        );
+
+        // the batch is pending withdrawal and there is no other staking pool to unstake from
+        contract.staking_pools[0].update_balances((100 * YOCTO).into(), (10 * YOCTO).into());
+        *contract.batch_id_sequence += 1;
+        let batch = RedeemStakeBatch::new(contract.batch_id_sequence, (10 * YOCTO).into());
+        contract.redeem_stake_batch = Some(batch);
+        contract.redeem_stake_batch_receipts.insert(
+            &batch.id(),
+            &batch.create_receipt(contract.stake_token_value),
+        );
+        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        contract.unstaking_pool_id = Some(TEST_STAKING_POOL_ID.to_string());
+        let quote = contract.quote_redeem((10 * YOCTO).into(), None);
+        assert_eq!(quote.batch, QuoteBatch::Next);
+        assert_eq!(
+            quote.estimated_withdrawable_epoch,
+            (contract
+                .get_pending_withdrawal()
+                .unwrap()
+                .unstaked_near_withdrawal_availability()
+                + UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK)
+                .into()
+        );
+
+        // the pending withdrawal can be cleared by NEAR liquidity
+        contract.near_liquidity_pool = (10 * YOCTO).into();
+        let quote = contract.quote_redeem((10 * YOCTO).into(), None);
+        assert_eq!(
+            quote.estimated_withdrawable_epoch,
+            domain::EpochHeight(6).into()
+        );
+
+        // the pending withdrawal can be moved into an unstaked cohort
+        contract.near_liquidity_pool = 0.into();
+        contract
+            .staking_pools
+            .push(StakingPool::new("staking-pool-2.near", 1));
+        contract.staking_pools[1].update_balances((100 * YOCTO).into(), 0.into());
+        let quote = contract.quote_redeem((10 * YOCTO).into(), None);
+        assert_eq!(
+            quote.estimated_withdrawable_epoch,
+            domain::EpochHeight(6).into()
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Batch receipts are held in contract storage until all funds have been claimed by the accounts,
+//! and the contract pays for the receipt storage. Receipts that have not been fully claimed within
+//! [receipt_expiry_epochs](crate::config::Config::receipt_expiry_epochs) can be settled on behalf of
+//! the accounts, which deletes the receipt and frees up its storage.
+use crate::*;
+use crate::{
+    domain::RedeemLock,
+    errors::receipts::{RECEIPT_NOT_CLAIMABLE, RECEIPT_NOT_EXPIRED, RECEIPT_NOT_FOUND},
+    interface::{keeper::events::ReceiptsSettled, ReceiptBacklog},
+    near::log,
+};
+
+impl Contract {
+    /// returns the epoch at which the batch receipt was created, or None if the receipt does not exist
+    pub(crate) fn receipt_epoch_height(
+        &self,
+        batch_id: domain::BatchId,
+    ) -> Option<domain::EpochHeight> {
+        self.stake_batch_receipts
+            .get(&batch_id)
+            .map(|receipt| receipt.stake_token_value())
+            .or_else(|| {
+                self.redeem_stake_batch_receipts
+                    .get(&batch_id)
+                    .map(|receipt| receipt.stake_token_value())
+            })
+            .map(|stake_token_value| stake_token_value.block_time_height().epoch_height())
+    }
+
+    fn is_receipt_expired(&self, receipt_epoch_height: domain::EpochHeight) -> bool {
+        env::epoch_height().saturating_sub(receipt_epoch_height.value())
+            >= self.config.receipt_expiry_epochs() as u64
+    }
+
+    /// NEAR cannot be claimed from a redeem stake batch receipt while its unstaked NEAR is pending
+    /// withdrawal from the staking pool
+    fn is_receipt_claimable(&self, batch_id: domain::BatchId) -> bool {
+        if !self.redeem_stake_batch_receipts.contains_key(&batch_id) {
+            return true;
+        }
+        let pending_withdrawal = self.redeem_stake_batch_lock
+            == Some(RedeemLock::PendingWithdrawal)
+            && self
+                .redeem_stake_batch
+                .map_or(false, |batch| batch.id() == batch_id);
+        !pending_withdrawal && !self.is_unstaked_cohort(batch_id)
+    }
+
+    /// claims the receipt funds on behalf of the accounts that have funds in the batch
+    ///
+    /// Returns true if all funds were claimed, i.e., the receipt was deleted
+    ///
+    /// ## Panics
+    /// - if the receipt does not exist
+    /// - if the receipt has not expired
+    /// - if the receipt cannot be claimed yet
+    pub(crate) fn settle_expired_receipt(
+        &mut self,
+        batch_id: domain::BatchId,
+        account_ids: &[ValidAccountId],
+    ) -> bool {
+        let receipt_epoch_height = self
+            .receipt_epoch_height(batch_id)
+            .expect(RECEIPT_NOT_FOUND);
+        assert!(
+            self.is_receipt_expired(receipt_epoch_height),
+            RECEIPT_NOT_EXPIRED
+        );
+        assert!(self.is_receipt_claimable(batch_id), RECEIPT_NOT_CLAIMABLE);
+
+        let mut settled_accounts = 0;
+        for account_id in account_ids {
+            let mut account = match self.lookup_registered_account(account_id.as_ref()) {
+                Some(account)
+                    if account.has_funds_in_batch(batch_id)
+                        || self
+                            .pending_batches(&account.account_id, &account)
+                            .iter()
+                            .any(|batch| batch.id() == batch_id) =>
+                {
+                    account
+                }
+                _ => continue,
+            };
+            self.claim_receipt_funds(&mut account);
+            settled_accounts += 1;
+        }
+
+        let receipt_deleted = self.receipt_epoch_height(batch_id).is_none();
+        log(ReceiptsSettled {
+            batch_id: batch_id.value().into(),
+            accounts: settled_accounts,
+            receipt_deleted,
+        });
+        receipt_deleted
+    }
+
+    /// only the page of receipt IDs is scanned for the oldest and expired receipts
+    /// - the storage usage is computed from the receipt count, i.e., the receipts are not looked up
+    pub(crate) fn receipt_backlog(
+        &self,
+        batch_ids: &UnorderedSet<domain::BatchId>,
+        receipt_storage_usage: domain::StorageUsage,
+        from_index: u64,
+        limit: u64,
+    ) -> ReceiptBacklog {
+        let batch_ids = batch_ids.as_vector();
+        let to_index = batch_ids.len().min(from_index.saturating_add(limit));
+        let mut oldest_receipt_epoch: Option<domain::EpochHeight> = None;
+        let mut expired = vec![];
+        for batch_id in (from_index..to_index).filter_map(|index| batch_ids.get(index)) {
+            let epoch_height = match self.receipt_epoch_height(batch_id) {
+                Some(epoch_height) => epoch_height,
+                None => continue,
+            };
+            if oldest_receipt_epoch.map_or(true, |oldest| epoch_height < oldest) {
+                oldest_receipt_epoch = Some(epoch_height);
+            }
+            if self.is_receipt_expired(epoch_height) {
+                expired.push(batch_id);
+            }
+        }
+        expired.sort();
+
+        ReceiptBacklog {
+            count: batch_ids.len() as u32,
+            storage_usage: domain::StorageUsage(batch_ids.len() * receipt_storage_usage.value())
+                .into(),
+            oldest_receipt_epoch: oldest_receipt_epoch.map(Into::into),
+            expired: expired.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        interface::{Keeper, StakingService},
+        near::YOCTO,
+        test_utils::*,
+    };
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn settle_expired_stake_batch_receipt() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        test_context.register_account("bob.near");
+        let contract = &mut test_context.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        let batch_id = domain::BatchId::from(contract.deposit());
+        let batch = contract.stake_batch.take().unwrap();
+        contract.create_stake_batch_receipt(batch);
+
+        let report = contract.receipt_storage_report(0, 10);
+        assert_eq!(report.stake_batch_receipts.count, 1);
+        assert_eq!(
+            report.stake_batch_receipts.oldest_receipt_epoch,
+            Some(domain::EpochHeight(0).into())
+        );
+        assert!(report.stake_batch_receipts.expired.is_empty());
+        // the contract pays for the receipt storage, including the receipt ID
+        assert_eq!(
+            report.stake_batch_receipts.storage_usage,
+            contract.stake_batch_receipt_storage_usage.into()
+        );
+        assert_eq!(report.redeem_stake_batch_receipts.count, 0);
+        assert_eq!(
+            report.redeem_stake_batch_receipts.storage_usage,
+            domain::StorageUsage(0).into()
+        );
+
+        context.epoch_height += contract.config.receipt_expiry_epochs() as u64;
+        testing_env!(context.clone());
+        let report = contract.receipt_storage_report(0, 10);
+        assert_eq!(report.stake_batch_receipts.expired, vec![batch_id.into()]);
+
+        // accounts that have no funds in the batch are skipped
+        assert!(contract.settle_receipts(
+            batch_id.into(),
+            vec![
+                to_valid_account_id("bob.near"),
+                to_valid_account_id(test_context.account_id)
+            ]
+        ));
+        assert!(contract.stake_batch_receipts.get(&batch_id).is_none());
+        let account = contract.registered_account(test_context.account_id);
+        assert!(account.stake_batch.is_none());
+        assert_eq!(account.stake_balance(), (10 * YOCTO).into());
+
+        let report = contract.receipt_storage_report(0, 10);
+        assert_eq!(report.stake_batch_receipts.count, 0);
+        assert!(report.stake_batch_receipts.oldest_receipt_epoch.is_none());
+    }
+
+    #[test]
+    fn receipt_storage_report_paging() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        let mut batch_ids = vec![];
+        for _ in 0..3 {
+            context.attached_deposit = 10 * YOCTO;
+            testing_env!(context.clone());
+            batch_ids.push(contract.deposit());
+            let batch = contract.stake_batch.take().unwrap();
+            contract.create_stake_batch_receipt(batch);
+        }
+        context.epoch_height += contract.config.receipt_expiry_epochs() as u64;
+        testing_env!(context.clone());
+
+        let report = contract.receipt_storage_report(0, 10);
+        assert_eq!(report.stake_batch_receipts.expired, batch_ids);
+
+        // the count and storage usage cover all receipts, regardless of the page
+        let report = contract.receipt_storage_report(1, 1);
+        assert_eq!(report.stake_batch_receipts.count, 3);
+        assert_eq!(
+            report.stake_batch_receipts.storage_usage,
+            domain::StorageUsage(3 * contract.stake_batch_receipt_storage_usage.value()).into()
+        );
+        assert_eq!(
+            report.stake_batch_receipts.expired,
+            vec![batch_ids[1].clone()]
+        );
+
+        let report = contract.receipt_storage_report(3, 10);
+        assert_eq!(report.stake_batch_receipts.count, 3);
+        assert!(report.stake_batch_receipts.expired.is_empty());
+        assert!(report.stake_batch_receipts.oldest_receipt_epoch.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "batch receipt has not expired yet")]
+    fn settle_receipts_before_expiry() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        let batch_id = contract.deposit();
+        let batch = contract.stake_batch.take().unwrap();
+        contract.create_stake_batch_receipt(batch);
+
+        context.epoch_height += contract.config.receipt_expiry_epochs() as u64 - 1;
+        testing_env!(context);
+        contract.settle_receipts(batch_id, vec![to_valid_account_id(test_context.account_id)]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "batch receipt cannot be claimed until the unstaked NEAR has been withdrawn"
+    )]
+    fn settle_receipts_pending_withdrawal() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        *contract.batch_id_sequence += 1;
+        let batch = domain::RedeemStakeBatch::new(contract.batch_id_sequence, (10 * YOCTO).into());
+        contract.redeem_stake_batch = Some(batch);
+        contract.redeem_stake_batch_receipts.insert(
+            &batch.id(),
+            &batch.create_receipt(contract.stake_token_value),
+        );
+        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+
+        context.epoch_height += contract.config.receipt_expiry_epochs() as u64;
+        testing_env!(context);
+        contract.settle_receipts(
+            batch.id().into(),
+            vec![to_valid_account_id(test_context.account_id)],
+        );
+    }
+}
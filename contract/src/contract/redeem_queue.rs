@@ -0,0 +1,299 @@
+//! NEAR liquidity is allocated to [RedeemRequest](crate::domain::RedeemRequest)s in FIFO order while
+//! the [RedeemStakeBatch](crate::domain::RedeemStakeBatch) is pending withdrawal.
+use crate::*;
+use crate::{
+    core::Hash,
+    domain::{RedeemLock, RedeemStakeBatch},
+    errors::illegal_state::REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST,
+    interface::staking_service::events::RedeemRequestFilled,
+    near::log,
+};
+
+/// the number of requests that are filled per allocation is bounded to keep gas usage in check
+/// - any liquidity that is left over is allocated on the next allocation
+pub const MAX_REDEEM_REQUEST_FILLS_PER_ALLOCATION: usize = 10;
+
+impl Contract {
+    /// allocates the NEAR liquidity pool to the requests at the head of the redeem queue
+    /// - requests are partially filled if the liquidity is insufficient to fully fill the request
+    /// - requests for batches that have completed are dropped from the queue because their funds
+    ///   can be claimed directly from the batch receipt
+    ///
+    /// NOTE: accounts are loaded from and saved to storage - thus this must not be called while an
+    /// account is being held in memory
+    pub(crate) fn allocate_redeem_queue_liquidity(&mut self) {
+        let mut fills = 0;
+        while let Some(mut request) = self.redeem_queue.peek() {
+            let batch_id = request.batch_id();
+            let is_active_batch = self
+                .redeem_stake_batch
+                .map_or(false, |batch| batch.id() == batch_id)
+                || self
+                    .next_redeem_stake_batch
+                    .map_or(false, |batch| batch.id() == batch_id);
+            if !is_active_batch {
+                self.redeem_queue.pop();
+                continue;
+            }
+            if fills == MAX_REDEEM_REQUEST_FILLS_PER_ALLOCATION
+                || self.near_liquidity_pool.value() == 0
+                || self.pending_withdrawal_batch_id() != Some(batch_id)
+            {
+                return;
+            }
+
+            let is_liquidity_provider_pool = request.account_id() == env::current_account_id();
+            let account_id_hash = Hash::from(request.account_id());
+            let mut account = if is_liquidity_provider_pool {
+                self.liquidity_provider_pool.account()
+            } else {
+                match self.accounts.get(&account_id_hash) {
+                    Some(account) => account,
+                    None => {
+                        self.redeem_queue.pop();
+                        continue;
+                    }
+                }
+            };
+            let (mut batch, is_next_batch) =
+                match (account.redeem_stake_batch, account.next_redeem_stake_batch) {
+                    (Some(batch), _) if batch.id() == batch_id => (batch, false),
+                    (_, Some(batch)) if batch.id() == batch_id => (batch, true),
+                    _ => {
+                        // the STAKE was removed from the batch
+                        self.redeem_queue.pop();
+                        continue;
+                    }
+                };
+
+            let receipt = self
+                .redeem_stake_batch_receipts
+                .get(&batch_id)
+                .expect(REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST);
+            let batch_balance = batch.balance().amount();
+            let near = self.claim_pending_withdrawal_from_liquidity(
+                &mut account,
+                &mut batch,
+                receipt,
+                request.stake(),
+            );
+            let filled_stake = batch_balance - batch.balance().amount();
+            if filled_stake.value() == 0 {
+                // liquidity is too low to fill any STAKE
+                return;
+            }
+            fills += 1;
+
+            let batch = Self::non_empty_redeem_stake_batch(batch);
+            if is_next_batch {
+                account.next_redeem_stake_batch = batch;
+            } else {
+                account.redeem_stake_batch = batch;
+            }
+            if is_liquidity_provider_pool {
+                // move the NEAR into the pool
+                if let Some(near) = account.near.take() {
+                    self.total_near.debit(near.amount());
+                    self.liquidity_provider_pool.credit_near(near.amount());
+                }
+                self.liquidity_provider_pool.set_account(account);
+            } else {
+                self.accounts.insert(&account_id_hash, &account);
+            }
+
+            request.fill(filled_stake, near);
+            log(RedeemRequestFilled {
+                account_id: request.account_id(),
+                batch_id: batch_id.value().into(),
+                stake: filled_stake.value().into(),
+                near: near.value().into(),
+                remaining_stake: request.stake().value().into(),
+            });
+            if request.is_filled() || batch.is_none() {
+                self.redeem_queue.pop();
+            } else {
+                self.redeem_queue.update_head(&request);
+            }
+        }
+    }
+
+    /// returns true if there are requests queued up that are waiting on liquidity for the batch,
+    /// or for an earlier batch
+    pub(crate) fn has_queued_redeem_requests(&self, batch_id: domain::BatchId) -> bool {
+        self.redeem_queue
+            .peek()
+            .map_or(false, |request| request.batch_id() <= batch_id)
+    }
+
+    fn pending_withdrawal_batch_id(&self) -> Option<domain::BatchId> {
+        match self.redeem_stake_batch_lock {
+            Some(RedeemLock::PendingWithdrawal) => self.redeem_stake_batch.map(|batch| batch.id()),
+            _ => None,
+        }
+    }
+
+    fn non_empty_redeem_stake_batch(batch: RedeemStakeBatch) -> Option<RedeemStakeBatch> {
+        if batch.balance().amount().value() == 0 {
+            None
+        } else {
+            Some(batch)
+        }
+    }
+
+    /// returns the account's position in the redeem queue, i.e., the number of requests ahead of the
+    /// account's first request
+    /// - the account's requests are looked up via the queue's account index, i.e., the queue is not
+    ///   scanned
+    pub(crate) fn redeem_queue_position_for(
+        &self,
+        account_id: &str,
+    ) -> Option<interface::RedeemQueuePosition> {
+        let requests = self.redeem_queue.account_requests(account_id);
+        let (position, first_request) = requests.first()?;
+        let queued_stake: u128 = requests
+            .iter()
+            .map(|(_, request)| request.stake().value())
+            .sum();
+        let filled_near: u128 = requests
+            .iter()
+            .map(|(_, request)| request.filled_near().value())
+            .sum();
+        Some(interface::RedeemQueuePosition {
+            position: *position as u32,
+            queue_length: self.redeem_queue.len() as u32,
+            batch_id: first_request.batch_id().into(),
+            requested_at: first_request.requested_at().into(),
+            queued_stake: queued_stake.into(),
+            filled_near: filled_near.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{interface::StakingService, near::YOCTO, test_utils::*};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    const ALICE: &str = "alice.near";
+
+    fn credit_stake(test_ctx: &mut TestContext, account_id: &str, amount: u128) {
+        let mut account = test_ctx.registered_account(account_id);
+        account.apply_stake_credit(amount.into());
+        test_ctx.save_registered_account(&account);
+        test_ctx.total_stake.credit(amount.into());
+    }
+
+    #[test]
+    fn liquidity_is_allocated_in_fifo_order() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let account_id = test_ctx.account_id;
+        test_ctx.register_account(ALICE);
+        credit_stake(&mut test_ctx, ALICE, 10 * YOCTO);
+        credit_stake(&mut test_ctx, account_id, 10 * YOCTO);
+
+        // Alice redeems first
+        let context = test_ctx.set_predecessor_account_id(ALICE);
+        testing_env!(context);
+        let batch_id = test_ctx.redeem((10 * YOCTO).into());
+        let context = test_ctx.set_predecessor_account_id(account_id);
+        testing_env!(context);
+        test_ctx.redeem((10 * YOCTO).into());
+
+        // Given the batch is pending withdrawal
+        let batch_id = domain::BatchId::from(batch_id);
+        let receipt =
+            domain::RedeemStakeBatchReceipt::new((20 * YOCTO).into(), test_ctx.stake_token_value);
+        test_ctx
+            .redeem_stake_batch_receipts
+            .insert(&batch_id, &receipt);
+        test_ctx.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        assert_eq!(
+            test_ctx
+                .redeem_queue_position(to_valid_account_id(account_id))
+                .unwrap()
+                .position,
+            1
+        );
+
+        // When liquidity arrives that covers Alice and half of the next request
+        test_ctx.near_liquidity_pool = (15 * YOCTO).into();
+        test_ctx.allocate_redeem_queue_liquidity();
+
+        // Then Alice is filled first
+        let alice = test_ctx.registered_account(ALICE);
+        assert_eq!(alice.near.unwrap().amount(), (10 * YOCTO).into());
+        assert!(alice.redeem_stake_batch.is_none());
+        assert!(test_ctx
+            .redeem_queue_position(to_valid_account_id(ALICE))
+            .is_none());
+        // And the next request is partially filled
+        let account = test_ctx.registered_account(account_id);
+        assert_eq!(account.near.unwrap().amount(), (5 * YOCTO).into());
+        let position = test_ctx
+            .redeem_queue_position(to_valid_account_id(account_id))
+            .unwrap();
+        assert_eq!(position.position, 0);
+        assert_eq!(position.queue_length, 1);
+        assert_eq!(position.queued_stake, (5 * YOCTO).into());
+        assert_eq!(position.filled_near, (5 * YOCTO).into());
+        assert_eq!(test_ctx.near_liquidity_pool, 0.into());
+
+        // accounts cannot jump the queue by claiming against liquidity directly
+        assert!(test_ctx.has_queued_redeem_requests(batch_id));
+
+        // When the rest of the liquidity arrives, then the pending withdrawal is closed out
+        test_ctx.near_liquidity_pool = (5 * YOCTO).into();
+        test_ctx.claim_receipts();
+        let account = test_ctx.registered_account(account_id);
+        assert_eq!(account.near.unwrap().amount(), (10 * YOCTO).into());
+        assert!(test_ctx.redeem_stake_batch_lock.is_none());
+        assert!(test_ctx.redeem_queue.is_empty());
+    }
+
+    #[test]
+    fn account_holds_at_most_one_request_per_batch() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let account_id = test_ctx.account_id;
+        test_ctx.register_account(ALICE);
+        credit_stake(&mut test_ctx, ALICE, 10 * YOCTO);
+        credit_stake(&mut test_ctx, account_id, 10 * YOCTO);
+
+        let context = test_ctx.set_predecessor_account_id(account_id);
+        testing_env!(context);
+        test_ctx.redeem((2 * YOCTO).into());
+        let context = test_ctx.set_predecessor_account_id(ALICE);
+        testing_env!(context);
+        test_ctx.redeem((10 * YOCTO).into());
+        let context = test_ctx.set_predecessor_account_id(account_id);
+        testing_env!(context);
+        test_ctx.redeem((3 * YOCTO).into());
+
+        assert_eq!(test_ctx.redeem_queue.len(), 2);
+        let position = test_ctx
+            .redeem_queue_position(to_valid_account_id(account_id))
+            .unwrap();
+        assert_eq!(position.position, 0);
+        assert_eq!(position.queue_length, 2);
+        assert_eq!(position.queued_stake, (5 * YOCTO).into());
+        let position = test_ctx
+            .redeem_queue_position(to_valid_account_id(ALICE))
+            .unwrap();
+        assert_eq!(position.position, 1);
+        assert_eq!(position.queued_stake, (10 * YOCTO).into());
+    }
+
+    #[test]
+    fn requests_for_completed_batches_are_dropped() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let account_id = test_ctx.account_id;
+        credit_stake(&mut test_ctx, account_id, 10 * YOCTO);
+        test_ctx.redeem((10 * YOCTO).into());
+        assert_eq!(test_ctx.redeem_queue.len(), 1);
+
+        // batch completed
+        test_ctx.redeem_stake_batch = None;
+        test_ctx.allocate_redeem_queue_liquidity();
+        assert!(test_ctx.redeem_queue.is_empty());
+    }
+}
@@ -1,10 +1,15 @@
 //required in order for near_bindgen macro to work outside of lib.rs
 use crate::errors::illegal_state::STAKE_BATCH_SHOULD_EXIST;
-use crate::interface::staking_service::events::Unstaked;
+use crate::interface::{
+    fungible_token::events::FtBurn,
+    staking_service::events::{
+        RedeemStakeBatchCancelled, Unstaked, UnstakedCohortAdded, UnstakedCohortWithdrawn,
+    },
+};
 use crate::near::log;
 use crate::*;
 use crate::{
-    domain::RedeemLock,
+    domain::{RedeemLock, UnstakedCohort},
     errors::{
         illegal_state::{
             ILLEGAL_REDEEM_LOCK_STATE, REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST,
@@ -17,15 +22,21 @@ use crate::{
     interface::BatchId,
     near::NO_DEPOSIT,
 };
-use near_sdk::{env, near_bindgen, Promise, PromiseOrValue};
+use near_sdk::{env, near_bindgen, serde_json, AccountId, Promise, PromiseOrValue, PromiseResult};
 
 #[near_bindgen]
 impl Contract {
+    /// the STAKE token value is updated using the balances summed across all staking pools, and then
+    /// the NEAR value of the batch is unstaked from the specified staking pool
+    /// - if the batch has limit orders, then they are checked against the updated STAKE token value
+    ///   before unstaking - see [apply_redeem_limit_orders](Contract::apply_redeem_limit_orders)
+    /// - if all STAKE is returned to the accounts, then the batch is cancelled and the workflow ends
     #[private]
     pub fn on_run_redeem_stake_batch(
         &mut self,
+        staking_pool_id: AccountId,
         #[callback] staking_pool_account: StakingPoolAccount,
-    ) -> Promise {
+    ) -> PromiseOrValue<()> {
         // this callback should only be invoked when we are unstaking, i.e., when the RedeemStakeBatch
         // is kicked off
         assert!(self.is_unstaking(), ILLEGAL_REDEEM_LOCK_STATE);
@@ -38,48 +49,163 @@ impl Contract {
             .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST);
 
         assert!(self.promise_result_succeeded(), GET_ACCOUNT_FAILURE);
+        self.update_staking_pool_balances(&staking_pool_id, &staking_pool_account);
 
         // update the cached STAKE token value
-        let staked_balance = self.staked_near_balance(
-            staking_pool_account.staked_balance.into(),
-            staking_pool_account.unstaked_balance.into(),
-        );
+        let (staked_balance, unstaked_balance) = self.total_staking_pool_balances();
+        let staked_balance = self.staked_near_balance(staked_balance, unstaked_balance);
+        self.accrue_staking_rewards(staked_balance);
         self.update_stake_token_value(staked_balance);
 
+        if self.redeem_limit_orders.contains(batch.id()) {
+            self.apply_redeem_limit_orders();
+            if self.redeem_stake_batch.is_none() {
+                // all STAKE in the batch was returned to the accounts
+                log(RedeemStakeBatchCancelled {
+                    batch_id: batch.id().value().into(),
+                });
+                self.pop_redeem_stake_batch();
+                self.redeem_stake_batch_lock = None;
+                self.keeper_rewards.take_redeem_stake_batch_keeper();
+                return PromiseOrValue::Value(());
+            }
+        }
+        let batch = self
+            .redeem_stake_batch
+            .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST);
+
         let unstake_amount = self
             .stake_token_value
             .stake_to_near(batch.balance().amount());
 
         if staking_pool_account.staked_balance.0 < unstake_amount.value() {
+            let overflow = self.redeem_stake_batch_unstake_overflow(
+                &staking_pool_id,
+                staking_pool_account.staked_balance.0.into(),
+                unstake_amount,
+            );
+            if !overflow.is_empty() {
+                // no single staking pool can cover the batch - the shortfall is unstaked from the
+                // other staking pools
+                // - each staking pool account is retrieved after unstaking in order to track the NEAR
+                //   that was actually unstaked
+                let staking_pool_ids = std::iter::once(staking_pool_id.clone())
+                    .chain(overflow.iter().map(|(id, _)| id.clone()))
+                    .collect();
+                return overflow
+                    .iter()
+                    .fold(
+                        self.staking_pool_promise(&staking_pool_id)
+                            .unstake_all()
+                            .get_account()
+                            .promise(),
+                        |promise, (overflow_staking_pool_id, amount)| {
+                            promise.and(
+                                self.staking_pool_promise(overflow_staking_pool_id)
+                                    .unstake(*amount)
+                                    .get_account()
+                                    .promise(),
+                            )
+                        },
+                    )
+                    .then(self.invoke_on_unstake_split(staking_pool_ids))
+                    .into();
+            }
             // when unstaking the remaining balance, there will probably be some NEAR that is already
             // unstaked because of the rounding issues when the staking pool issued shares
-            self.staking_pool_promise()
+            self.staking_pool_promise(&staking_pool_id)
                 .unstake_all()
                 .promise()
-                .then(self.invoke_on_unstake())
+                .then(self.invoke_on_unstake(&staking_pool_id))
+                .into()
         } else {
-            self.staking_pool_promise()
+            self.staking_pool_promise(&staking_pool_id)
                 .unstake(unstake_amount)
                 .promise()
-                .then(self.invoke_on_unstake())
+                .then(self.invoke_on_unstake(&staking_pool_id))
+                .into()
         }
     }
 
     #[private]
-    pub fn on_unstake(&mut self) {
+    pub fn on_unstake(&mut self, staking_pool_id: AccountId) {
         assert!(self.promise_result_succeeded(), UNSTAKE_FAILURE);
 
         self.create_redeem_stake_batch_receipt();
 
-        self.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal)
+        self.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        self.unstaking_pool_id = Some(staking_pool_id);
+
+        let keeper_id = self.keeper_rewards.take_redeem_stake_batch_keeper();
+        self.pay_keeper_reward(keeper_id);
+    }
+
+    /// the batch was unstaked across multiple staking pools because no single staking pool could
+    /// cover it - the NEAR that was unstaked from each staking pool is moved into an
+    /// [UnstakedCohort](crate::domain::UnstakedCohort), and then the batch is popped
+    /// - the promise results are the staking pool accounts that were retrieved after unstaking, in
+    ///   the same order as `staking_pool_ids` - each cohort is credited with the staking pool's
+    ///   `unstaked_balance`, which reflects what the staking pool actually unstaked
+    /// - the receipt's NEAR can be claimed once all of its cohorts have been withdrawn
+    #[private]
+    pub fn on_unstake_split(&mut self, staking_pool_ids: Vec<AccountId>) {
+        let staking_pool_accounts: Vec<StakingPoolAccount> = (0..staking_pool_ids.len() as u64)
+            .map(|index| match self.promise_result(index) {
+                PromiseResult::Successful(result) => {
+                    serde_json::from_slice(&result).expect(GET_ACCOUNT_FAILURE)
+                }
+                _ => panic!(UNSTAKE_FAILURE),
+            })
+            .collect();
+
+        self.create_redeem_stake_batch_receipt();
+        let batch = self
+            .redeem_stake_batch
+            .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST);
+        let receipt = self
+            .redeem_stake_batch_receipts
+            .get(&batch.id())
+            .expect(REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST);
+        for (staking_pool_id, staking_pool_account) in
+            staking_pool_ids.iter().zip(staking_pool_accounts.iter())
+        {
+            self.update_staking_pool_balances(staking_pool_id, staking_pool_account);
+            let unstaked_near = staking_pool_account.unstaked_balance.0;
+            log(UnstakedCohortAdded {
+                batch_id: batch.id().value().into(),
+                staking_pool_id,
+                near_liquidity: 0.into(),
+                unstaked_near: unstaked_near.into(),
+                unstaked_near_withdrawal_availability: receipt
+                    .unstaked_near_withdrawal_availability()
+                    .value()
+                    .into(),
+            });
+            self.unstaked_cohorts.push(UnstakedCohort::new(
+                batch.id(),
+                staking_pool_id,
+                unstaked_near.into(),
+            ));
+        }
+
+        self.redeem_stake_batch_lock = None;
+        self.pop_redeem_stake_batch();
+        // requests that are queued up for the batch will be claimed from the batch receipt once the
+        // cohorts are withdrawn
+        self.allocate_redeem_queue_liquidity();
+
+        let keeper_id = self.keeper_rewards.take_redeem_stake_batch_keeper();
+        self.pay_keeper_reward(keeper_id);
     }
 
     #[private]
     pub fn on_redeeming_stake_pending_withdrawal(
         &mut self,
+        staking_pool_id: AccountId,
         #[callback] staking_pool_account: StakingPoolAccount,
     ) -> PromiseOrValue<BatchId> {
         assert!(self.promise_result_succeeded(), GET_ACCOUNT_FAILURE);
+        self.update_staking_pool_balances(&staking_pool_id, &staking_pool_account);
 
         let unstaked_balance = staking_pool_account.unstaked_balance.0;
         // if unstaked balance is zero, then it means the unstaked NEAR funds were withdrawn
@@ -91,10 +217,10 @@ impl Contract {
                 UNSTAKED_FUNDS_NOT_AVAILABLE_FOR_WITHDRAWAL
             );
 
-            self.staking_pool_promise()
+            self.staking_pool_promise(&staking_pool_id)
                 .withdraw_all()
                 .promise()
-                .then(self.invoke_on_redeeming_stake_post_withdrawal())
+                .then(self.invoke_on_redeeming_stake_post_withdrawal(&staking_pool_id))
                 .into()
         } else {
             PromiseOrValue::Value(self.finalize_redeem_batch())
@@ -102,11 +228,66 @@ impl Contract {
     }
 
     #[private]
-    pub fn on_redeeming_stake_post_withdrawal(&mut self) -> BatchId {
+    pub fn on_redeeming_stake_post_withdrawal(&mut self, staking_pool_id: AccountId) -> BatchId {
         assert!(self.promise_result_succeeded(), WITHDRAW_ALL_FAILURE);
+        // all unstaked NEAR has been withdrawn from the staking pool
+        let staking_pool = self.staking_pool_mut(&staking_pool_id);
+        let staked_balance = staking_pool.staked_balance();
+        staking_pool.update_balances(staked_balance, 0.into());
         self.finalize_redeem_batch()
     }
 
+    #[private]
+    pub fn on_unstaked_cohort_pending_withdrawal(
+        &mut self,
+        batch_id: BatchId,
+        staking_pool_id: AccountId,
+        keeper_id: AccountId,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> PromiseOrValue<BatchId> {
+        assert!(self.promise_result_succeeded(), GET_ACCOUNT_FAILURE);
+        self.update_staking_pool_balances(&staking_pool_id, &staking_pool_account);
+
+        // if unstaked balance is zero, then it means the unstaked NEAR funds were already withdrawn
+        if staking_pool_account.unstaked_balance.0 > 0 {
+            assert!(
+                staking_pool_account.can_withdraw,
+                UNSTAKED_FUNDS_NOT_AVAILABLE_FOR_WITHDRAWAL
+            );
+
+            self.staking_pool_promise(&staking_pool_id)
+                .withdraw_all()
+                .promise()
+                .then(self.invoke_on_unstaked_cohort_withdrawn(
+                    batch_id.into(),
+                    &staking_pool_id,
+                    &keeper_id,
+                ))
+                .into()
+        } else {
+            PromiseOrValue::Value(self.finalize_unstaked_cohort(
+                batch_id.into(),
+                &staking_pool_id,
+                keeper_id,
+            ))
+        }
+    }
+
+    #[private]
+    pub fn on_unstaked_cohort_withdrawn(
+        &mut self,
+        batch_id: BatchId,
+        staking_pool_id: AccountId,
+        keeper_id: AccountId,
+    ) -> BatchId {
+        assert!(self.promise_result_succeeded(), WITHDRAW_ALL_FAILURE);
+        // all unstaked NEAR has been withdrawn from the staking pool
+        let staking_pool = self.staking_pool_mut(&staking_pool_id);
+        let staked_balance = staking_pool.staked_balance();
+        staking_pool.update_balances(staked_balance, 0.into());
+        self.finalize_unstaked_cohort(batch_id.into(), &staking_pool_id, keeper_id)
+    }
+
     fn finalize_redeem_batch(&mut self) -> BatchId {
         let batch = self
             .redeem_stake_batch
@@ -118,24 +299,61 @@ impl Contract {
 
         // update the total NEAR balance that is available for withdrawal
         self.total_near.credit(receipt.stake_near_value());
+        self.add_withdrawn_haircut_to_liquidity(receipt.haircut());
 
         self.redeem_stake_batch_lock = None;
+        self.unstaking_pool_id = None;
         self.pop_redeem_stake_batch();
 
+        let keeper_id = self.keeper_rewards.take_redeem_stake_batch_keeper();
+        self.pay_keeper_reward(keeper_id);
+
         batch.id().into()
     }
 }
 
 impl Contract {
-    fn create_redeem_stake_batch_receipt(&mut self) {
+    /// the cohort may have already been finalized by a withdrawal that was run concurrently, in
+    /// which case this is a no-op
+    /// a batch can be split into multiple cohorts, i.e., one per staking pool
+    fn finalize_unstaked_cohort(
+        &mut self,
+        batch_id: domain::BatchId,
+        staking_pool_id: &str,
+        keeper_id: AccountId,
+    ) -> BatchId {
+        if let Some(index) = self.unstaked_cohorts.iter().position(|cohort| {
+            cohort.batch_id() == batch_id && cohort.staking_pool_id() == staking_pool_id
+        }) {
+            let cohort = self.unstaked_cohorts.remove(index);
+            // update the total NEAR balance that is available for withdrawal
+            self.total_near.credit(cohort.unstaked_near());
+            self.add_withdrawn_haircut_to_liquidity(cohort.haircut());
+            log(UnstakedCohortWithdrawn {
+                batch_id: batch_id.value().into(),
+                staking_pool_id: cohort.staking_pool_id(),
+                near: cohort.unstaked_near().value().into(),
+            });
+            self.pay_keeper_reward(Some(keeper_id));
+        }
+        batch_id.into()
+    }
+
+    pub(crate) fn create_redeem_stake_batch_receipt(&mut self) {
         let batch = self.redeem_stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
         let batch_receipt = batch.create_receipt(self.stake_token_value);
         self.redeem_stake_batch_receipts
             .insert(&batch.id(), &batch_receipt);
+        self.redeem_stake_batch_receipt_ids.insert(&batch.id());
 
         // update the total STAKE supply
         self.total_stake.debit(batch_receipt.redeemed_stake());
 
+        log(FtBurn {
+            owner_id: &env::current_account_id(),
+            amount: batch_receipt.redeemed_stake().into(),
+            memo: Some(&format!("RedeemStakeBatch({})", batch.id().value())),
+        });
         log(Unstaked::new(batch.id(), &batch_receipt));
     }
 
@@ -147,8 +365,9 @@ impl Contract {
 
 /// redeeming STAKE workflow callback invocations
 impl Contract {
-    pub(crate) fn invoke_on_run_redeem_stake_batch(&self) -> Promise {
+    pub(crate) fn invoke_on_run_redeem_stake_batch(&self, staking_pool_id: &str) -> Promise {
         ext_redeeming_workflow_callbacks::on_run_redeem_stake_batch(
+            staking_pool_id.to_string(),
             &env::current_account_id(),
             NO_DEPOSIT.into(),
             self.config
@@ -167,8 +386,12 @@ impl Contract {
         )
     }
 
-    pub(crate) fn invoke_on_redeeming_stake_pending_withdrawal(&mut self) -> Promise {
+    pub(crate) fn invoke_on_redeeming_stake_pending_withdrawal(
+        &mut self,
+        staking_pool_id: &str,
+    ) -> Promise {
         ext_redeeming_workflow_callbacks::on_redeeming_stake_pending_withdrawal(
+            staking_pool_id.to_string(),
             &env::current_account_id(),
             NO_DEPOSIT.into(),
             self.config
@@ -179,8 +402,12 @@ impl Contract {
         )
     }
 
-    pub(crate) fn invoke_on_redeeming_stake_post_withdrawal(&mut self) -> Promise {
+    pub(crate) fn invoke_on_redeeming_stake_post_withdrawal(
+        &mut self,
+        staking_pool_id: &str,
+    ) -> Promise {
         ext_redeeming_workflow_callbacks::on_redeeming_stake_post_withdrawal(
+            staking_pool_id.to_string(),
             &env::current_account_id(),
             NO_DEPOSIT.into(),
             self.config
@@ -191,8 +418,58 @@ impl Contract {
         )
     }
 
-    pub(crate) fn invoke_on_unstake(&self) -> Promise {
+    pub(crate) fn invoke_on_unstaked_cohort_pending_withdrawal(
+        &self,
+        batch_id: domain::BatchId,
+        staking_pool_id: &str,
+        keeper_id: &str,
+    ) -> Promise {
+        ext_redeeming_workflow_callbacks::on_unstaked_cohort_pending_withdrawal(
+            batch_id.into(),
+            staking_pool_id.to_string(),
+            keeper_id.to_string(),
+            &env::current_account_id(),
+            NO_DEPOSIT.into(),
+            self.config
+                .gas_config()
+                .callbacks()
+                .on_redeeming_stake_pending_withdrawal()
+                .value(),
+        )
+    }
+
+    pub(crate) fn invoke_on_unstaked_cohort_withdrawn(
+        &self,
+        batch_id: domain::BatchId,
+        staking_pool_id: &str,
+        keeper_id: &str,
+    ) -> Promise {
+        ext_redeeming_workflow_callbacks::on_unstaked_cohort_withdrawn(
+            batch_id.into(),
+            staking_pool_id.to_string(),
+            keeper_id.to_string(),
+            &env::current_account_id(),
+            NO_DEPOSIT.into(),
+            self.config
+                .gas_config()
+                .callbacks()
+                .on_redeeming_stake_post_withdrawal()
+                .value(),
+        )
+    }
+
+    pub(crate) fn invoke_on_unstake(&self, staking_pool_id: &str) -> Promise {
         ext_redeeming_workflow_callbacks::on_unstake(
+            staking_pool_id.to_string(),
+            &env::current_account_id(),
+            NO_DEPOSIT.into(),
+            self.config.gas_config().callbacks().on_unstake().value(),
+        )
+    }
+
+    fn invoke_on_unstake_split(&self, staking_pool_ids: Vec<AccountId>) -> Promise {
+        ext_redeeming_workflow_callbacks::on_unstake_split(
+            staking_pool_ids,
             &env::current_account_id(),
             NO_DEPOSIT.into(),
             self.config.gas_config().callbacks().on_unstake().value(),
@@ -249,12 +526,15 @@ mod test {
             staked_balance,
             can_withdraw: true,
         };
-        contract.on_run_redeem_stake_batch(staking_pool_account.clone());
+        contract.on_run_redeem_stake_batch(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account.clone(),
+        );
         let receipts = deserialize_receipts();
         assert_eq!(receipts.len(), 2);
         {
             let receipt = &receipts[0];
-            assert_eq!(receipt.receiver_id, contract.staking_pool_id);
+            assert_eq!(receipt.receiver_id, TEST_STAKING_POOL_ID);
             match &receipt.actions[0] {
                 Action::FunctionCall {
                     method_name,
@@ -292,7 +572,7 @@ mod test {
                     ..
                 } => {
                     assert_eq!(method_name, "on_unstake");
-                    assert!(args.is_empty());
+                    assert!(args.contains(TEST_STAKING_POOL_ID));
                     assert_eq!(
                         contract
                             .config
@@ -336,12 +616,12 @@ mod test {
             staked_balance: staked_balance.clone(),
             can_withdraw: true,
         };
-        contract.on_run_redeem_stake_batch(staking_pool_account);
+        contract.on_run_redeem_stake_batch(TEST_STAKING_POOL_ID.to_string(), staking_pool_account);
         let receipts = deserialize_receipts();
         assert_eq!(receipts.len(), 2);
         {
             let receipt = &receipts[0];
-            assert_eq!(receipt.receiver_id, contract.staking_pool_id);
+            assert_eq!(receipt.receiver_id, TEST_STAKING_POOL_ID);
             match &receipt.actions[0] {
                 Action::FunctionCall {
                     method_name,
@@ -401,12 +681,15 @@ mod test {
         testing_env!(context.clone());
 
         contract.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
-        contract.on_run_redeem_stake_batch(StakingPoolAccount {
-            account_id: test_context.account_id.to_string(),
-            unstaked_balance: U128(0),
-            staked_balance: U128(0),
-            can_withdraw: false,
-        });
+        contract.on_run_redeem_stake_batch(
+            TEST_STAKING_POOL_ID.to_string(),
+            StakingPoolAccount {
+                account_id: test_context.account_id.to_string(),
+                unstaked_balance: U128(0),
+                staked_balance: U128(0),
+                can_withdraw: false,
+            },
+        );
     }
 
     /// When on_unstake is invoked
@@ -432,7 +715,7 @@ mod test {
         context.block_index += 1;
         context.block_timestamp += 1;
         testing_env!(context.clone());
-        contract.on_unstake();
+        contract.on_unstake(TEST_STAKING_POOL_ID.to_string());
 
         assert_eq!(contract.total_stake.amount(), (900 * YOCTO).into());
         let receipt = contract
@@ -455,6 +738,10 @@ mod test {
             contract.redeem_stake_batch_lock,
             Some(RedeemLock::PendingWithdrawal)
         );
+        assert_eq!(
+            contract.unstaking_pool_id,
+            Some(TEST_STAKING_POOL_ID.to_string())
+        );
     }
 
     #[test]
@@ -474,7 +761,7 @@ mod test {
         context.predecessor_account_id = context.current_account_id.clone();
         testing_env!(context.clone());
         set_env_with_failed_promise_result(contract);
-        contract.on_unstake();
+        contract.on_unstake(TEST_STAKING_POOL_ID.to_string());
     }
 
     /// Given the unstaked balance with the staking pool is 0
@@ -508,7 +795,10 @@ mod test {
             staked_balance: (1100 * YOCTO).into(),
             can_withdraw: true,
         };
-        match contract.on_redeeming_stake_pending_withdrawal(staking_pool_account) {
+        match contract.on_redeeming_stake_pending_withdrawal(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account,
+        ) {
             PromiseOrValue::Value(batch_id) => assert_eq!(batch_id, batch.id().into()),
             _ => panic!("redeem stake batch should have completed"),
         }
@@ -544,13 +834,16 @@ mod test {
             staked_balance: (1100 * YOCTO).into(),
             can_withdraw: true,
         };
-        contract.on_redeeming_stake_pending_withdrawal(staking_pool_account);
+        contract.on_redeeming_stake_pending_withdrawal(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account,
+        );
         let receipts = deserialize_receipts();
         println!("{:#?}", receipts);
         assert_eq!(receipts.len(), 2);
         {
             let receipt = &receipts[0];
-            assert_eq!(receipt.receiver_id, contract.staking_pool_id);
+            assert_eq!(receipt.receiver_id, TEST_STAKING_POOL_ID);
             match &receipt.actions[0] {
                 Action::FunctionCall {
                     method_name,
@@ -584,7 +877,7 @@ mod test {
                     ..
                 } => {
                     assert_eq!(method_name, "on_redeeming_stake_post_withdrawal");
-                    assert!(args.is_empty());
+                    assert!(args.contains(TEST_STAKING_POOL_ID));
                     assert_eq!(
                         contract
                             .config
@@ -600,6 +893,48 @@ mod test {
         }
     }
 
+    #[test]
+    fn on_unstaked_cohort_withdrawn() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+
+        *contract.batch_id_sequence += 1;
+        let batch_id = contract.batch_id_sequence;
+        contract.unstaked_cohorts.push(domain::UnstakedCohort::new(
+            batch_id,
+            TEST_STAKING_POOL_ID,
+            (10 * YOCTO).into(),
+        ));
+        let total_near = contract.total_near.amount();
+
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        set_env_with_success_promise_result(contract);
+
+        contract.on_unstaked_cohort_withdrawn(
+            batch_id.into(),
+            TEST_STAKING_POOL_ID.to_string(),
+            test_context.account_id.to_string(),
+        );
+        assert!(contract.unstaked_cohorts.is_empty());
+        assert_eq!(
+            contract.total_near.amount(),
+            total_near + (10 * YOCTO).into()
+        );
+
+        // when the cohort was already withdrawn concurrently, then the NEAR is not credited twice
+        contract.on_unstaked_cohort_withdrawn(
+            batch_id.into(),
+            TEST_STAKING_POOL_ID.to_string(),
+            test_context.account_id.to_string(),
+        );
+        assert_eq!(
+            contract.total_near.amount(),
+            total_near + (10 * YOCTO).into()
+        );
+    }
+
     #[test]
     fn serialize_u128() {
         let value = U128(2832187358794090528436378);
@@ -0,0 +1,311 @@
+//! If the staking pools lose funds, e.g., because a validator was slashed, then the staked NEAR
+//! balance drops below the NEAR value of the STAKE supply. The loss is absorbed in the following order:
+//! 1. NEAR liquidity that has not yet been restaked
+//! 2. the [InsuranceBuffer](crate::domain::InsuranceBuffer), which is funded from contract earnings
+//! 3. the remaining loss is shared proportionally between STAKE holders, by lowering the STAKE token
+//!    value, and the [RedeemStakeBatchReceipt](crate::domain::RedeemStakeBatchReceipt)s whose
+//!    unstaked NEAR is still pending withdrawal, by haircutting their NEAR claims
+use crate::*;
+use crate::{
+    core::U256,
+    domain::{BlockTimeHeight, StakeTokenValue},
+    errors::illegal_state::REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST,
+    interface::staking_service::events::{
+        NearLiquidityAdded, RedeemStakeBatchReceiptHaircut, StakingLoss,
+    },
+    near::{log, YOCTO},
+};
+
+impl Contract {
+    /// sets aside a percentage of the earnings for the insurance buffer
+    /// - contract gas earnings are drawn on first, and then collected earnings
+    ///
+    /// Returns the amount that was added to the insurance buffer
+    pub(crate) fn fund_insurance_buffer(&mut self) -> domain::YoctoNear {
+        let funding = self
+            .config
+            .insurance_buffer_funding(self.total_earnings())
+            .value();
+        if funding == 0 {
+            return 0.into();
+        }
+
+        let contract_earnings = self.contract_earnings().value();
+        if funding > contract_earnings {
+            self.collected_earnings -= (funding - contract_earnings).into();
+        }
+        self.insurance_buffer.fund(funding.into());
+        funding.into()
+    }
+
+    /// returns the STAKE token value after the loss has been absorbed
+    /// - `staked_near_shortfall` is the amount that the staked NEAR balance has fallen below the
+    ///   NEAR value of the STAKE supply
+    pub(crate) fn recognize_staking_loss(
+        &mut self,
+        total_staked_near_balance: domain::YoctoNear,
+        staked_near_shortfall: domain::YoctoNear,
+    ) -> StakeTokenValue {
+        // while a withdrawal is pending, the NEAR liquidity is already included in the staked NEAR
+        // balance - otherwise it will be restaked with the next stake batch
+        let unstaked_liquidity = if self.get_pending_withdrawal().is_some() {
+            0.into()
+        } else {
+            self.near_liquidity_pool
+        };
+        let liquidity_backing = staked_near_shortfall.min(unstaked_liquidity);
+        let loss = staked_near_shortfall - liquidity_backing;
+        if loss.value() == 0 {
+            return StakeTokenValue::new(
+                BlockTimeHeight::from_env(),
+                total_staked_near_balance + staked_near_shortfall,
+                self.total_stake.amount(),
+            );
+        }
+
+        let insurance_buffer_absorbed = self.insurance_buffer.absorb(loss);
+        if insurance_buffer_absorbed.value() > 0 {
+            // the insurance NEAR is restaked along with the NEAR liquidity
+            self.near_liquidity_pool += insurance_buffer_absorbed;
+            log(NearLiquidityAdded {
+                amount: insurance_buffer_absorbed.value().into(),
+                balance: self.near_liquidity_pool.value().into(),
+            });
+        }
+        let uncovered_loss = loss - insurance_buffer_absorbed;
+        let receipts_haircut = self.haircut_pending_withdrawals(
+            uncovered_loss,
+            total_staked_near_balance + staked_near_shortfall,
+        );
+
+        let stake_token_value = StakeTokenValue::new(
+            BlockTimeHeight::from_env(),
+            total_staked_near_balance
+                + liquidity_backing
+                + insurance_buffer_absorbed
+                + receipts_haircut,
+            self.total_stake.amount(),
+        );
+        log(StakingLoss {
+            loss: loss.value().into(),
+            insurance_buffer_absorbed: insurance_buffer_absorbed.value().into(),
+            receipts_haircut: receipts_haircut.value().into(),
+            stake_holders_loss: (uncovered_loss - receipts_haircut).value().into(),
+            stake_token_value: stake_token_value.stake_to_near(YOCTO.into()).value().into(),
+        });
+        stake_token_value
+    }
+
+    /// the unstaked NEAR that is pending withdrawal is exposed to the loss alongside the staked NEAR
+    /// that backs the STAKE supply - each receipt is haircut by its proportional share of the loss
+    /// - the haircut NEAR remains in the staking pool, and is added to the NEAR liquidity pool once
+    ///   it is withdrawn
+    ///
+    /// Returns the total haircut
+    fn haircut_pending_withdrawals(
+        &mut self,
+        loss: domain::YoctoNear,
+        staked_near_balance: domain::YoctoNear,
+    ) -> domain::YoctoNear {
+        if loss.value() == 0 {
+            return 0.into();
+        }
+
+        let pending_withdrawal = self.redeem_stake_batch.and_then(|batch| {
+            self.redeem_stake_batch_receipts
+                .get(&batch.id())
+                .map(|receipt| (batch.id(), receipt))
+        });
+        let pending_withdrawal_near = pending_withdrawal
+            .as_ref()
+            .map_or(0.into(), |(_, receipt)| receipt.stake_near_value());
+        let exposure =
+            staked_near_balance + pending_withdrawal_near + self.unstaked_cohorts_near_balance();
+        let haircut = |near: domain::YoctoNear| -> domain::YoctoNear {
+            (U256::from(loss) * U256::from(near) / U256::from(exposure))
+                .as_u128()
+                .into()
+        };
+
+        let mut total_haircut = domain::YoctoNear(0);
+        if let Some((batch_id, mut receipt)) = pending_withdrawal {
+            let receipt_haircut = haircut(pending_withdrawal_near);
+            if receipt_haircut.value() > 0 {
+                receipt.apply_haircut(receipt_haircut);
+                self.save_haircut_receipt(batch_id, &receipt, receipt_haircut);
+                total_haircut += receipt_haircut;
+            }
+        }
+
+        let mut unstaked_cohorts = std::mem::take(&mut self.unstaked_cohorts);
+        for cohort in unstaked_cohorts.iter_mut() {
+            let cohort_haircut = haircut(cohort.unstaked_near());
+            if cohort_haircut.value() == 0 {
+                continue;
+            }
+            let mut receipt = self
+                .redeem_stake_batch_receipts
+                .get(&cohort.batch_id())
+                .expect(REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST);
+            receipt.apply_haircut(cohort_haircut);
+            self.save_haircut_receipt(cohort.batch_id(), &receipt, cohort_haircut);
+            cohort.apply_haircut(cohort_haircut);
+            total_haircut += cohort_haircut;
+        }
+        self.unstaked_cohorts = unstaked_cohorts;
+
+        total_haircut
+    }
+
+    fn save_haircut_receipt(
+        &mut self,
+        batch_id: domain::BatchId,
+        receipt: &domain::RedeemStakeBatchReceipt,
+        haircut: domain::YoctoNear,
+    ) {
+        self.redeem_stake_batch_receipts.insert(&batch_id, receipt);
+        log(RedeemStakeBatchReceiptHaircut {
+            batch_id: batch_id.value().into(),
+            haircut: haircut.value().into(),
+            redeemed_stake_value: receipt.stake_near_value().value().into(),
+        });
+    }
+
+    /// NEAR that was haircut from redeem stake batch receipts is added to the NEAR liquidity pool
+    /// once the unstaked NEAR has been withdrawn from the staking pool, i.e., it is restaked on
+    /// behalf of the STAKE holders
+    pub(crate) fn add_withdrawn_haircut_to_liquidity(&mut self, haircut: domain::YoctoNear) {
+        if haircut.value() == 0 {
+            return;
+        }
+        self.near_liquidity_pool += haircut;
+        log(NearLiquidityAdded {
+            amount: haircut.value().into(),
+            balance: self.near_liquidity_pool.value().into(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        domain::{RedeemLock, RedeemStakeBatch, RedeemStakeBatchReceipt, TimestampedStakeBalance},
+        test_utils::*,
+    };
+
+    /// 100 STAKE is worth 100 NEAR
+    fn set_stake_token_value(contract: &mut Contract) {
+        contract.total_stake = TimestampedStakeBalance::new((100 * YOCTO).into());
+        contract.update_stake_token_value((100 * YOCTO).into());
+    }
+
+    #[test]
+    fn loss_is_absorbed_by_insurance_buffer() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        set_stake_token_value(contract);
+        contract.insurance_buffer.fund((5 * YOCTO).into());
+
+        contract.update_stake_token_value((97 * YOCTO).into());
+
+        assert_eq!(
+            contract.stake_token_value.stake_to_near(YOCTO.into()),
+            YOCTO.into()
+        );
+        assert_eq!(contract.insurance_buffer.balance(), (2 * YOCTO).into());
+        assert_eq!(
+            contract.insurance_buffer.total_absorbed_losses(),
+            (3 * YOCTO).into()
+        );
+        assert_eq!(contract.near_liquidity_pool, (3 * YOCTO).into());
+    }
+
+    #[test]
+    fn uncovered_loss_lowers_stake_token_value() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        set_stake_token_value(contract);
+        contract.insurance_buffer.fund((2 * YOCTO).into());
+
+        contract.update_stake_token_value((90 * YOCTO).into());
+
+        assert_eq!(
+            contract
+                .stake_token_value
+                .stake_to_near((100 * YOCTO).into()),
+            (92 * YOCTO).into()
+        );
+        assert_eq!(contract.insurance_buffer.balance(), 0.into());
+        assert_eq!(
+            contract.insurance_buffer.total_uncovered_losses(),
+            (8 * YOCTO).into()
+        );
+
+        // the STAKE token value recovers as rewards are earned
+        contract.update_stake_token_value((95 * YOCTO).into());
+        assert_eq!(
+            contract
+                .stake_token_value
+                .stake_to_near((100 * YOCTO).into()),
+            (95 * YOCTO).into()
+        );
+    }
+
+    #[test]
+    fn unstaked_liquidity_is_not_a_loss() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        set_stake_token_value(contract);
+        contract.near_liquidity_pool = (20 * YOCTO).into();
+        contract.insurance_buffer.fund((5 * YOCTO).into());
+
+        contract.update_stake_token_value((85 * YOCTO).into());
+
+        assert_eq!(
+            contract.stake_token_value.stake_to_near(YOCTO.into()),
+            YOCTO.into()
+        );
+        assert_eq!(contract.insurance_buffer.balance(), (5 * YOCTO).into());
+    }
+
+    #[test]
+    fn pending_withdrawal_receipt_is_haircut() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        set_stake_token_value(contract);
+
+        // 10 STAKE was unstaked at 1:1 and is pending withdrawal
+        *contract.batch_id_sequence += 1;
+        let batch = RedeemStakeBatch::new(contract.batch_id_sequence, (10 * YOCTO).into());
+        contract.redeem_stake_batch = Some(batch);
+        contract.redeem_stake_batch_receipts.insert(
+            &batch.id(),
+            &RedeemStakeBatchReceipt::new(
+                (10 * YOCTO).into(),
+                StakeTokenValue::new(
+                    BlockTimeHeight::from_env(),
+                    (110 * YOCTO).into(),
+                    (110 * YOCTO).into(),
+                ),
+            ),
+        );
+        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+
+        // 11 NEAR is lost - the loss is shared 10:1 between the STAKE holders and the receipt
+        contract.update_stake_token_value((89 * YOCTO).into());
+
+        let receipt = contract
+            .redeem_stake_batch_receipts
+            .get(&batch.id())
+            .unwrap();
+        assert_eq!(receipt.stake_near_value(), (9 * YOCTO).into());
+        assert_eq!(receipt.haircut(), YOCTO.into());
+        assert_eq!(
+            contract
+                .stake_token_value
+                .stake_to_near((100 * YOCTO).into()),
+            (90 * YOCTO).into()
+        );
+    }
+}
@@ -1,12 +1,15 @@
 use crate::config::Config;
-use crate::domain::YoctoNear;
+use crate::domain::{StakingPool, YoctoNear};
+use crate::errors::{
+    staking_pool_failures::GET_ACCOUNT_FAILURE, staking_pools::STAKING_POOL_NOT_FOUND,
+};
 use crate::near::NO_DEPOSIT;
-use crate::Contract;
+use crate::{Contract, StakingPoolAccount};
 use near_sdk::{
     env,
     json_types::U128,
     serde::{Deserialize, Serialize},
-    serde_json, AccountId, Promise,
+    serde_json, AccountId, Promise, PromiseResult,
 };
 
 pub struct StakingPoolPromiseBuilder<'a>(Promise, &'a Config);
@@ -173,7 +176,580 @@ impl From<YoctoNear> for UnStakeArgs {
 }
 
 impl Contract {
-    pub(crate) fn staking_pool_promise(&self) -> StakingPoolPromiseBuilder {
-        StakingPoolPromiseBuilder::new(self.staking_pool_id.clone(), &self.config)
+    pub(crate) fn staking_pool_promise(&self, staking_pool_id: &str) -> StakingPoolPromiseBuilder {
+        StakingPoolPromiseBuilder::new(staking_pool_id.to_string(), &self.config)
+    }
+
+    /// ## Panics
+    /// if the staking pool is not registered with the contract
+    pub(crate) fn staking_pool_mut(&mut self, staking_pool_id: &str) -> &mut StakingPool {
+        self.staking_pools
+            .iter_mut()
+            .find(|pool| pool.account_id() == staking_pool_id)
+            .expect(STAKING_POOL_NOT_FOUND)
+    }
+
+    pub(crate) fn total_staking_pool_weight(&self) -> u32 {
+        self.staking_pools.iter().map(StakingPool::weight).sum()
+    }
+
+    /// returns the (staked, unstaked) balances summed across all staking pools
+    pub(crate) fn total_staking_pool_balances(&self) -> (YoctoNear, YoctoNear) {
+        self.staking_pools
+            .iter()
+            .fold((0.into(), 0.into()), |(staked, unstaked), pool| {
+                (
+                    staked + pool.staked_balance(),
+                    unstaked + pool.unstaked_balance(),
+                )
+            })
+    }
+
+    /// caches the staking pool balances that were returned by `get_account`
+    pub(crate) fn update_staking_pool_balances(
+        &mut self,
+        staking_pool_id: &str,
+        staking_pool_account: &StakingPoolAccount,
+    ) {
+        self.staking_pool_mut(staking_pool_id).update_balances(
+            staking_pool_account.staked_balance.0.into(),
+            staking_pool_account.unstaked_balance.0.into(),
+        );
+    }
+
+    /// pings each staking pool and then gets the contract's account from each staking pool
+    /// - the promises are joined, which means the callback receives a promise result per staking
+    ///   pool, in the order that the staking pools were registered
+    pub(crate) fn ping_and_get_staking_pool_accounts(&self) -> Promise {
+        self.staking_pools
+            .iter()
+            .map(|pool| {
+                self.staking_pool_promise(pool.account_id())
+                    .ping()
+                    .get_account()
+                    .promise()
+            })
+            .fold(None, |joined: Option<Promise>, promise| match joined {
+                None => Some(promise),
+                Some(joined) => Some(joined.and(promise)),
+            })
+            .expect(STAKING_POOL_NOT_FOUND)
+    }
+
+    /// ## Panics
+    /// if any of the promise results failed
+    pub(crate) fn update_staking_pool_balances_from_promise_results(&mut self) {
+        let staking_pool_ids: Vec<AccountId> = self
+            .staking_pools
+            .iter()
+            .take(self.promise_results_count() as usize)
+            .map(|pool| pool.account_id().to_string())
+            .collect();
+        for (index, staking_pool_id) in staking_pool_ids.iter().enumerate() {
+            let staking_pool_account: StakingPoolAccount = match self.promise_result(index as u64) {
+                PromiseResult::Successful(result) => {
+                    serde_json::from_slice(&result).expect(GET_ACCOUNT_FAILURE)
+                }
+                _ => panic!(GET_ACCOUNT_FAILURE),
+            };
+            self.update_staking_pool_balances(staking_pool_id, &staking_pool_account);
+        }
+    }
+
+    /// selects the staking pool to stake the [StakeBatch](crate::domain::StakeBatch) with
+    /// - if liquidity is needed, then the staking pool that holds the unstaked NEAR for the pending
+    ///   withdrawal is selected because liquidity is added by restaking the unstaked NEAR
+    /// - otherwise, the most underweight staking pool is selected, i.e., the staking pool whose
+    ///   staked balance is furthest below its target once the `stake_amount` is staked
+    pub(crate) fn stake_batch_staking_pool_id(&self, stake_amount: YoctoNear) -> AccountId {
+        if self.is_liquidity_needed() {
+            if let Some(staking_pool_id) = self.unstaking_pool_id.as_ref() {
+                return staking_pool_id.clone();
+            }
+        }
+
+        let (total_staked_balance, _) = self.total_staking_pool_balances();
+        let total_staked_balance = total_staked_balance + stake_amount;
+        let total_weight = self.total_staking_pool_weight();
+        // ties are resolved in favor of the staking pool that was added first
+        self.staking_pools
+            .iter()
+            .rev()
+            .filter(|pool| pool.weight() > 0)
+            .max_by_key(|pool| {
+                pool.target_staked_balance(total_staked_balance, total_weight)
+                    .value()
+                    .saturating_sub(pool.staked_balance().value())
+            })
+            .expect(STAKING_POOL_NOT_FOUND)
+            .account_id()
+            .to_string()
+    }
+
+    /// selects the staking pool to unstake the [RedeemStakeBatch](crate::domain::RedeemStakeBatch) from
+    /// - the most overweight staking pool that has enough staked NEAR to cover the `unstake_amount`
+    ///   is selected, i.e., the staking pool whose staked balance is furthest above its target once
+    ///   the `unstake_amount` is unstaked
+    /// - if no single staking pool can cover the `unstake_amount`, then the staking pool with the
+    ///   largest staked balance is selected, and the shortfall is unstaked from the other staking
+    ///   pools - see [redeem_stake_batch_unstake_overflow](Contract::redeem_stake_batch_unstake_overflow)
+    /// - staking pools that are holding unstaked NEAR that is pending withdrawal are skipped because
+    ///   unstaking would reset the unlock epoch for the unstaked NEAR
+    ///
+    /// Returns None if all staking pools are holding unstaked NEAR that is pending withdrawal
+    pub(crate) fn redeem_stake_batch_staking_pool_id(
+        &self,
+        unstake_amount: YoctoNear,
+    ) -> Option<AccountId> {
+        let (total_staked_balance, _) = self.total_staking_pool_balances();
+        let total_staked_balance = total_staked_balance
+            .value()
+            .saturating_sub(unstake_amount.value());
+        let total_weight = self.total_staking_pool_weight();
+        // ties are resolved in favor of the staking pool that was added first
+        self.staking_pools
+            .iter()
+            .rev()
+            .filter(|pool| !self.is_staking_pool_pending_withdrawal(pool.account_id()))
+            .filter(|pool| pool.staked_balance() >= unstake_amount)
+            .max_by_key(|pool| {
+                pool.staked_balance().value().saturating_sub(
+                    pool.target_staked_balance(total_staked_balance.into(), total_weight)
+                        .value(),
+                )
+            })
+            .or_else(|| {
+                self.staking_pools
+                    .iter()
+                    .rev()
+                    .filter(|pool| !self.is_staking_pool_pending_withdrawal(pool.account_id()))
+                    .max_by_key(|pool| pool.staked_balance())
+            })
+            .map(|pool| pool.account_id().to_string())
+    }
+
+    /// when the selected staking pool's `staked_balance` cannot cover the `unstake_amount`, then the
+    /// shortfall is unstaked from the other staking pools, largest staked balance first
+    /// - staking pools that are holding unstaked NEAR that is pending withdrawal are skipped
+    /// - the amounts are based on the cached staking pool balances
+    ///
+    /// Returns the NEAR to unstake per staking pool, which is empty if the selected staking pool can
+    /// cover the `unstake_amount` or if there are no other staking pools to unstake from
+    pub(crate) fn redeem_stake_batch_unstake_overflow(
+        &self,
+        staking_pool_id: &str,
+        staked_balance: YoctoNear,
+        unstake_amount: YoctoNear,
+    ) -> Vec<(AccountId, YoctoNear)> {
+        let mut shortfall = unstake_amount
+            .value()
+            .saturating_sub(staked_balance.value());
+        let mut staking_pools: Vec<&StakingPool> = self
+            .staking_pools
+            .iter()
+            .filter(|pool| pool.account_id() != staking_pool_id)
+            .filter(|pool| pool.staked_balance().value() > 0)
+            .filter(|pool| !self.is_staking_pool_pending_withdrawal(pool.account_id()))
+            .collect();
+        // the sort is stable, i.e., ties are resolved in favor of the staking pool that was added first
+        staking_pools
+            .sort_by(|pool_1, pool_2| pool_2.staked_balance().cmp(&pool_1.staked_balance()));
+
+        let mut overflow = vec![];
+        for pool in staking_pools {
+            if shortfall == 0 {
+                break;
+            }
+            let amount = shortfall.min(pool.staked_balance().value());
+            shortfall -= amount;
+            overflow.push((pool.account_id().to_string(), amount.into()));
+        }
+        overflow
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{Operator, StakingService};
+    use crate::near_env::Env;
+    use crate::{
+        domain::{RedeemStakeBatch, StakeLock, TimestampedStakeBalance},
+        near::YOCTO,
+        test_utils::*,
+    };
+    use near_sdk::{testing_env, MockedBlockchain, VMContext};
+
+    const TEST_STAKING_POOL_ID_2: &str = "staking-pool-2.near";
+
+    fn add_staking_pool(contract: &mut Contract, context: &VMContext, staking_pool_id: &str) {
+        let mut context = context.clone();
+        context.predecessor_account_id = contract.operator_id.clone();
+        testing_env!(context.clone());
+        contract.add_staking_pool(to_valid_account_id(staking_pool_id), 100);
+    }
+
+    /// Given there are 2 staking pools with equal weights
+    /// And the first staking pool has staked NEAR
+    /// When the stake batch is run
+    /// Then the batch is staked with the second staking pool
+    #[test]
+    fn stake_batch_is_staked_with_most_underweight_staking_pool() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        add_staking_pool(contract, &context, TEST_STAKING_POOL_ID_2);
+        contract
+            .staking_pool_mut(TEST_STAKING_POOL_ID)
+            .update_balances((100 * YOCTO).into(), 0.into());
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit();
+        contract.stake();
+
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts[0].receiver_id, TEST_STAKING_POOL_ID_2);
+        match &receipts[1].actions[0] {
+            Action::FunctionCall {
+                method_name, args, ..
+            } => {
+                assert_eq!(method_name, "on_deposit_and_stake");
+                assert!(args.contains(TEST_STAKING_POOL_ID_2));
+            }
+            _ => panic!("expected FunctionCall"),
+        }
+    }
+
+    /// Given there are 2 staking pools with equal weights
+    /// And the second staking pool has more staked NEAR
+    /// When the redeem stake batch is run
+    /// Then the batch is unstaked from the second staking pool
+    #[test]
+    fn redeem_stake_batch_is_unstaked_from_most_overweight_staking_pool() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        add_staking_pool(contract, &context, TEST_STAKING_POOL_ID_2);
+        contract
+            .staking_pool_mut(TEST_STAKING_POOL_ID)
+            .update_balances((100 * YOCTO).into(), 0.into());
+        contract
+            .staking_pool_mut(TEST_STAKING_POOL_ID_2)
+            .update_balances((300 * YOCTO).into(), 0.into());
+
+        *contract.batch_id_sequence += 1;
+        contract.redeem_stake_batch = Some(RedeemStakeBatch::new(
+            contract.batch_id_sequence,
+            (10 * YOCTO).into(),
+        ));
+        testing_env!(context.clone());
+        contract.unstake();
+
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts[0].receiver_id, TEST_STAKING_POOL_ID_2);
+        match &receipts[1].actions[0] {
+            Action::FunctionCall {
+                method_name, args, ..
+            } => {
+                assert_eq!(method_name, "on_run_redeem_stake_batch");
+                assert!(args.contains(TEST_STAKING_POOL_ID_2));
+            }
+            _ => panic!("expected FunctionCall"),
+        }
+    }
+
+    /// Given there are 2 staking pools
+    /// And neither staking pool can cover the redeem stake batch
+    /// When the redeem stake batch is run
+    /// Then the staking pool with the largest staked balance unstakes its full staked balance
+    /// And the shortfall is unstaked from the other staking pool
+    /// And the NEAR unstaked from each staking pool is moved into an unstaked cohort
+    #[test]
+    fn redeem_stake_batch_is_split_when_no_single_staking_pool_can_cover_it() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        add_staking_pool(contract, &context, TEST_STAKING_POOL_ID_2);
+        contract
+            .staking_pool_mut(TEST_STAKING_POOL_ID)
+            .update_balances((60 * YOCTO).into(), 0.into());
+        contract
+            .staking_pool_mut(TEST_STAKING_POOL_ID_2)
+            .update_balances((50 * YOCTO).into(), 0.into());
+        contract.total_stake = TimestampedStakeBalance::new((110 * YOCTO).into());
+        contract.update_stake_token_value((110 * YOCTO).into());
+
+        *contract.batch_id_sequence += 1;
+        let batch_id = contract.batch_id_sequence;
+        contract.redeem_stake_batch = Some(RedeemStakeBatch::new(batch_id, (100 * YOCTO).into()));
+        testing_env!(context.clone());
+        contract.unstake();
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts[0].receiver_id, TEST_STAKING_POOL_ID);
+
+        // Act - the selected staking pool cannot cover the batch
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        set_env_with_success_promise_result(contract);
+        contract.on_run_redeem_stake_batch(
+            TEST_STAKING_POOL_ID.to_string(),
+            StakingPoolAccount {
+                account_id: context.current_account_id.clone(),
+                unstaked_balance: 0.into(),
+                staked_balance: (60 * YOCTO).into(),
+                can_withdraw: true,
+            },
+        );
+
+        // Assert - the unstaking is split across both staking pools
+        let receipts = deserialize_receipts();
+        let calls: Vec<(&str, &str, &str)> = receipts
+            .iter()
+            .map(|receipt| match &receipt.actions[0] {
+                Action::FunctionCall {
+                    method_name, args, ..
+                } => (
+                    receipt.receiver_id.as_str(),
+                    method_name.as_str(),
+                    args.as_str(),
+                ),
+                _ => panic!("expected FunctionCall"),
+            })
+            .collect();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].0, TEST_STAKING_POOL_ID);
+        assert_eq!(calls[0].1, "unstake_all");
+        assert_eq!(calls[1].0, TEST_STAKING_POOL_ID_2);
+        assert_eq!(calls[1].1, "unstake");
+        assert!(calls[1].2.contains(&(40 * YOCTO).to_string()));
+        assert_eq!(calls[2].1, "on_unstake_split");
+        // the staking pool accounts are retrieved after unstaking
+        for receipt in &receipts[..2] {
+            match &receipt.actions[1] {
+                Action::FunctionCall { method_name, .. } => {
+                    assert_eq!(method_name, "get_account")
+                }
+                _ => panic!("expected FunctionCall"),
+            }
+        }
+
+        fn promise_results_count() -> u64 {
+            2
+        }
+
+        fn promise_result(result_index: u64) -> PromiseResult {
+            let (staked_balance, unstaked_balance) = match result_index {
+                0 => (0, 60 * YOCTO),
+                _ => (10 * YOCTO, 40 * YOCTO),
+            };
+            let staking_pool_account = StakingPoolAccount {
+                account_id: "stake.oysterpack.near".to_string(),
+                unstaked_balance: unstaked_balance.into(),
+                staked_balance: staked_balance.into(),
+                can_withdraw: false,
+            };
+            PromiseResult::Successful(serde_json::to_vec(&staking_pool_account).unwrap())
+        }
+
+        contract.set_env(Env {
+            promise_results_count_: promise_results_count,
+            promise_result_: promise_result,
+        });
+
+        // Act
+        contract.on_unstake_split(vec![
+            TEST_STAKING_POOL_ID.to_string(),
+            TEST_STAKING_POOL_ID_2.to_string(),
+        ]);
+
+        // Assert - the NEAR unstaked from each staking pool is tracked as a cohort
+        assert!(contract.redeem_stake_batch.is_none());
+        assert!(contract.redeem_stake_batch_lock.is_none());
+        assert!(contract.unstaking_pool_id.is_none());
+        assert_eq!(
+            contract.unstaked_cohorts,
+            vec![
+                domain::UnstakedCohort::new(batch_id, TEST_STAKING_POOL_ID, (60 * YOCTO).into()),
+                domain::UnstakedCohort::new(batch_id, TEST_STAKING_POOL_ID_2, (40 * YOCTO).into()),
+            ]
+        );
+        // the cached staking pool balances are updated from the staking pool accounts
+        let staking_pools = contract.staking_pools();
+        assert_eq!(staking_pools[0].staked_balance, 0.into());
+        assert_eq!(staking_pools[1].staked_balance, (10 * YOCTO).into());
+        assert!(contract
+            .claimable_redeem_stake_batch_receipt(batch_id)
+            .is_none());
+
+        // Act - the cohorts are withdrawn independently
+        let total_near = contract.total_near.amount().value();
+        contract.on_unstaked_cohort_withdrawn(
+            batch_id.into(),
+            TEST_STAKING_POOL_ID_2.to_string(),
+            test_ctx.account_id.to_string(),
+        );
+        assert_eq!(contract.unstaked_cohorts.len(), 1);
+        assert_eq!(
+            contract.unstaked_cohorts[0].staking_pool_id(),
+            TEST_STAKING_POOL_ID
+        );
+        assert_eq!(
+            contract.total_near.amount().value(),
+            total_near + 40 * YOCTO
+        );
+        assert!(contract
+            .claimable_redeem_stake_batch_receipt(batch_id)
+            .is_none());
+
+        contract.on_unstaked_cohort_withdrawn(
+            batch_id.into(),
+            TEST_STAKING_POOL_ID.to_string(),
+            test_ctx.account_id.to_string(),
+        );
+        assert!(contract.unstaked_cohorts.is_empty());
+        assert_eq!(
+            contract.total_near.amount().value(),
+            total_near + 100 * YOCTO
+        );
+        assert!(contract
+            .claimable_redeem_stake_batch_receipt(batch_id)
+            .is_some());
+    }
+
+    /// Given all staking pools are holding unstaked NEAR that is pending withdrawal
+    /// Then no staking pool is selected to unstake the redeem stake batch from
+    /// And the redeem stake batch cannot be unstaked
+    #[test]
+    #[should_panic(expected = "unstaking is blocked until all unstaked NEAR can be withdrawn")]
+    fn redeem_stake_batch_is_blocked_when_all_staking_pools_are_pending_withdrawal() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        add_staking_pool(contract, &context, TEST_STAKING_POOL_ID_2);
+        for staking_pool_id in &[TEST_STAKING_POOL_ID, TEST_STAKING_POOL_ID_2] {
+            contract
+                .staking_pool_mut(staking_pool_id)
+                .update_balances((100 * YOCTO).into(), 0.into());
+        }
+
+        *contract.batch_id_sequence += 1;
+        let batch_id = contract.batch_id_sequence;
+        contract.unstaked_cohorts = vec![
+            domain::UnstakedCohort::new(batch_id, TEST_STAKING_POOL_ID, (5 * YOCTO).into()),
+            domain::UnstakedCohort::new(batch_id, TEST_STAKING_POOL_ID_2, (5 * YOCTO).into()),
+        ];
+        *contract.batch_id_sequence += 1;
+        contract.redeem_stake_batch = Some(RedeemStakeBatch::new(
+            contract.batch_id_sequence,
+            (10 * YOCTO).into(),
+        ));
+
+        assert!(contract
+            .redeem_stake_batch_staking_pool_id((10 * YOCTO).into())
+            .is_none());
+        assert!(!contract.can_unstake());
+
+        testing_env!(context.clone());
+        contract.unstake();
+    }
+
+    /// When the staked batch balances are locked in
+    /// Then the balances are summed across all staking pools
+    #[test]
+    fn on_deposit_and_stake_sums_balances_across_staking_pools() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        add_staking_pool(contract, &context, TEST_STAKING_POOL_ID_2);
+        contract
+            .staking_pool_mut(TEST_STAKING_POOL_ID_2)
+            .update_balances((50 * YOCTO).into(), 1.into());
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit();
+        contract.stake();
+
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        contract.on_deposit_and_stake(
+            TEST_STAKING_POOL_ID.to_string(),
+            None,
+            StakingPoolAccount {
+                account_id: context.current_account_id.clone(),
+                unstaked_balance: 2.into(),
+                staked_balance: (10 * YOCTO).into(),
+                can_withdraw: true,
+            },
+        );
+
+        match contract.stake_batch_lock {
+            Some(StakeLock::Staked {
+                staked_balance,
+                unstaked_balance,
+                ..
+            }) => {
+                assert_eq!(staked_balance, (60 * YOCTO).into());
+                assert_eq!(unstaked_balance, 3.into());
+            }
+            _ => panic!("expected StakeLock::Staked"),
+        }
+        assert_eq!(
+            contract.staking_pools()[0].staked_balance,
+            (10 * YOCTO).into()
+        );
+    }
+
+    /// Given there are 2 staking pools
+    /// When the STAKE token value is refreshed
+    /// Then the balances from each staking pool are cached
+    /// And the STAKE token value is computed using the balances summed across all staking pools
+    #[test]
+    fn on_refresh_stake_token_value_with_multiple_staking_pools() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        add_staking_pool(contract, &context, TEST_STAKING_POOL_ID_2);
+        contract.total_stake = TimestampedStakeBalance::new((100 * YOCTO).into());
+        contract.stake_batch_lock = Some(StakeLock::RefreshingStakeTokenValue);
+
+        fn promise_results_count() -> u64 {
+            2
+        }
+
+        fn promise_result(result_index: u64) -> PromiseResult {
+            let staking_pool_account = StakingPoolAccount {
+                account_id: "stake.oysterpack.near".to_string(),
+                unstaked_balance: 0.into(),
+                staked_balance: ((result_index as u128 + 1) * 100 * YOCTO).into(),
+                can_withdraw: true,
+            };
+            PromiseResult::Successful(serde_json::to_vec(&staking_pool_account).unwrap())
+        }
+
+        contract.set_env(Env {
+            promise_results_count_: promise_results_count,
+            promise_result_: promise_result,
+        });
+
+        context.predecessor_account_id = context.current_account_id.clone();
+        testing_env!(context.clone());
+        let stake_token_value = contract.on_refresh_stake_token_value();
+
+        let staking_pools = contract.staking_pools();
+        assert_eq!(staking_pools[0].staked_balance, (100 * YOCTO).into());
+        assert_eq!(staking_pools[1].staked_balance, (200 * YOCTO).into());
+        assert_eq!(
+            stake_token_value.total_staked_near_balance,
+            (300 * YOCTO).into()
+        );
+        assert!(contract.stake_batch_lock.is_none());
     }
 }
@@ -0,0 +1,528 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::*;
+use crate::{
+    domain::{StakingPool, StakingPoolMigration, StakingPoolMigrationStatus},
+    errors::{
+        illegal_state::STAKING_POOL_MIGRATION_SHOULD_EXIST,
+        staking_pool_failures::GET_ACCOUNT_FAILURE, staking_pools::STAKING_POOL_NOT_FOUND,
+    },
+    ext_staking_pool_migration_callbacks,
+    interface::operator::events::{
+        StakingPoolMigrationAborted, StakingPoolMigrationCompleted, StakingPoolMigrationProgress,
+    },
+    near::{log, NO_DEPOSIT},
+};
+use near_sdk::{env, near_bindgen, serde_json, Promise, PromiseOrValue, PromiseResult};
+
+#[near_bindgen]
+impl Contract {
+    #[private]
+    pub fn on_staking_pool_migration_unstaked(&mut self) {
+        let mut migration = self
+            .staking_pool_migration
+            .take()
+            .expect(STAKING_POOL_MIGRATION_SHOULD_EXIST);
+
+        match self.staking_pool_account_promise_result() {
+            Some(staking_pool_account) => {
+                self.update_staking_pool_balances(
+                    migration.from_staking_pool_id(),
+                    &staking_pool_account,
+                );
+                migration.unstaked(staking_pool_account.unstaked_balance.0.into());
+                Self::log_staking_pool_migration_progress(&migration);
+                self.staking_pool_migration = Some(migration);
+            }
+            // nothing was unstaked, thus it is safe to abort the migration
+            None => log(StakingPoolMigrationAborted {
+                from_staking_pool_id: migration.from_staking_pool_id(),
+                to_staking_pool_id: migration.to_staking_pool_id(),
+            }),
+        }
+    }
+
+    #[private]
+    pub fn on_staking_pool_migration_withdrawn(&mut self) -> PromiseOrValue<()> {
+        let mut migration = self
+            .staking_pool_migration
+            .take()
+            .expect(STAKING_POOL_MIGRATION_SHOULD_EXIST);
+
+        match self.staking_pool_account_promise_result() {
+            Some(staking_pool_account) => {
+                self.update_staking_pool_balances(
+                    migration.from_staking_pool_id(),
+                    &staking_pool_account,
+                );
+                migration.set_status(StakingPoolMigrationStatus::Withdrawn);
+                Self::log_staking_pool_migration_progress(&migration);
+                self.staking_pool_migration = Some(migration);
+                self.stake_migrated_near().into()
+            }
+            None => {
+                migration.set_status(StakingPoolMigrationStatus::PendingWithdrawal);
+                Self::log_staking_pool_migration_progress(&migration);
+                self.staking_pool_migration = Some(migration);
+                PromiseOrValue::Value(())
+            }
+        }
+    }
+
+    #[private]
+    pub fn on_staking_pool_migration_staked(&mut self) {
+        let mut migration = self
+            .staking_pool_migration
+            .take()
+            .expect(STAKING_POOL_MIGRATION_SHOULD_EXIST);
+
+        match self.staking_pool_account_promise_result() {
+            Some(staking_pool_account) => {
+                let index = self
+                    .staking_pools
+                    .iter()
+                    .position(|pool| pool.account_id() == migration.from_staking_pool_id())
+                    .expect(STAKING_POOL_NOT_FOUND);
+                let from_staking_pool = self.staking_pools.remove(index);
+                match self
+                    .staking_pools
+                    .iter_mut()
+                    .find(|pool| pool.account_id() == migration.to_staking_pool_id())
+                {
+                    Some(pool) => pool.set_weight(pool.weight() + from_staking_pool.weight()),
+                    None => self.staking_pools.insert(
+                        index,
+                        StakingPool::new(
+                            migration.to_staking_pool_id(),
+                            from_staking_pool.weight(),
+                        ),
+                    ),
+                }
+                self.update_staking_pool_balances(
+                    migration.to_staking_pool_id(),
+                    &staking_pool_account,
+                );
+
+                log(StakingPoolMigrationCompleted {
+                    from_staking_pool_id: migration.from_staking_pool_id(),
+                    to_staking_pool_id: migration.to_staking_pool_id(),
+                    staked_balance: migration.unstaked_balance().into(),
+                });
+
+                // move up the batches that were queued while migrating
+                if self.stake_batch.is_none() {
+                    self.pop_stake_batch();
+                }
+                if self.redeem_stake_batch.is_none() {
+                    self.pop_redeem_stake_batch();
+                }
+            }
+            // the deposit is refunded back to the contract
+            None => {
+                migration.set_status(StakingPoolMigrationStatus::Withdrawn);
+                Self::log_staking_pool_migration_progress(&migration);
+                self.staking_pool_migration = Some(migration);
+            }
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn is_migrating_staking_pool(&self) -> bool {
+        self.staking_pool_migration.is_some()
+    }
+
+    /// returns true if the staking pool migration is waiting on a staking pool request to complete
+    pub(crate) fn is_staking_pool_migration_running(&self) -> bool {
+        self.staking_pool_migration
+            .as_ref()
+            .map_or(false, StakingPoolMigration::is_running)
+    }
+
+    /// NEAR that has been withdrawn from the staking pool being migrated, which is held by the
+    /// contract until it is staked with the staking pool being migrated to
+    pub(crate) fn staking_pool_migration_withdrawn_balance(&self) -> YoctoNear {
+        self.staking_pool_migration
+            .as_ref()
+            .map_or(0.into(), StakingPoolMigration::withdrawn_balance)
+    }
+
+    pub(crate) fn start_staking_pool_migration(
+        &mut self,
+        migration: StakingPoolMigration,
+    ) -> Promise {
+        Self::log_staking_pool_migration_progress(&migration);
+        let promise = self
+            .staking_pool_promise(migration.from_staking_pool_id())
+            .unstake_all()
+            .get_account()
+            .promise()
+            .then(self.invoke_on_staking_pool_migration_unstaked());
+        self.staking_pool_migration = Some(migration);
+        promise
+    }
+
+    pub(crate) fn withdraw_migrated_near(&mut self) -> Promise {
+        let migration = self
+            .staking_pool_migration
+            .as_mut()
+            .expect(STAKING_POOL_MIGRATION_SHOULD_EXIST);
+        migration.set_status(StakingPoolMigrationStatus::Withdrawing);
+        Self::log_staking_pool_migration_progress(migration);
+        let from_staking_pool_id = migration.from_staking_pool_id().to_string();
+
+        self.staking_pool_promise(&from_staking_pool_id)
+            .withdraw_all()
+            .get_account()
+            .promise()
+            .then(self.invoke_on_staking_pool_migration_withdrawn())
+    }
+
+    pub(crate) fn stake_migrated_near(&mut self) -> Promise {
+        let migration = self
+            .staking_pool_migration
+            .as_mut()
+            .expect(STAKING_POOL_MIGRATION_SHOULD_EXIST);
+        migration.set_status(StakingPoolMigrationStatus::Staking);
+        Self::log_staking_pool_migration_progress(migration);
+        let to_staking_pool_id = migration.to_staking_pool_id().to_string();
+        let amount = migration.unstaked_balance();
+
+        self.staking_pool_promise(&to_staking_pool_id)
+            .deposit_and_stake(amount)
+            .get_account()
+            .promise()
+            .then(self.invoke_on_staking_pool_migration_staked())
+    }
+
+    /// returns None if the staking pool request failed
+    ///
+    /// ## Panics
+    /// if the `get_account` result fails to deserialize
+    fn staking_pool_account_promise_result(&self) -> Option<StakingPoolAccount> {
+        match self.promise_result(0) {
+            PromiseResult::Successful(result) => {
+                Some(serde_json::from_slice(&result).expect(GET_ACCOUNT_FAILURE))
+            }
+            _ => None,
+        }
+    }
+
+    fn log_staking_pool_migration_progress(migration: &StakingPoolMigration) {
+        log(StakingPoolMigrationProgress {
+            from_staking_pool_id: migration.from_staking_pool_id(),
+            to_staking_pool_id: migration.to_staking_pool_id(),
+            status: migration.status(),
+            unstaked_balance: migration.unstaked_balance().into(),
+        });
+    }
+}
+
+/// staking pool migration workflow callback invocations
+impl Contract {
+    fn invoke_on_staking_pool_migration_unstaked(&self) -> Promise {
+        ext_staking_pool_migration_callbacks::on_staking_pool_migration_unstaked(
+            &env::current_account_id(),
+            NO_DEPOSIT.into(),
+            self.config
+                .gas_config()
+                .callbacks()
+                .on_staking_pool_migration_unstaked()
+                .value(),
+        )
+    }
+
+    fn invoke_on_staking_pool_migration_withdrawn(&self) -> Promise {
+        ext_staking_pool_migration_callbacks::on_staking_pool_migration_withdrawn(
+            &env::current_account_id(),
+            NO_DEPOSIT.into(),
+            self.config
+                .gas_config()
+                .callbacks()
+                .on_staking_pool_migration_withdrawn()
+                .value(),
+        )
+    }
+
+    fn invoke_on_staking_pool_migration_staked(&self) -> Promise {
+        ext_staking_pool_migration_callbacks::on_staking_pool_migration_staked(
+            &env::current_account_id(),
+            NO_DEPOSIT.into(),
+            self.config
+                .gas_config()
+                .callbacks()
+                .on_staking_pool_migration_staked()
+                .value(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::interface::{Operator, StakingService};
+    use crate::{domain::StakeLock, near::YOCTO, test_utils::*};
+    use near_sdk::{testing_env, MockedBlockchain, VMContext};
+
+    const TEST_STAKING_POOL_ID_2: &str = "staking-pool-2.near";
+
+    fn operator_context(contract: &Contract, context: &VMContext) -> VMContext {
+        let mut context = context.clone();
+        context.predecessor_account_id = contract.operator_id.clone();
+        context
+    }
+
+    fn self_context(context: &VMContext) -> VMContext {
+        let mut context = context.clone();
+        context.predecessor_account_id = context.current_account_id.clone();
+        context
+    }
+
+    fn staking_pool_account_result(staked_balance: u128, unstaked_balance: u128) -> PromiseResult {
+        PromiseResult::Successful(
+            serde_json::to_vec(&StakingPoolAccount {
+                account_id: "stake.oysterpack.near".to_string(),
+                unstaked_balance: unstaked_balance.into(),
+                staked_balance: staked_balance.into(),
+                can_withdraw: true,
+            })
+            .unwrap(),
+        )
+    }
+
+    fn function_call_method_name(receipt: &Receipt, index: usize) -> String {
+        match &receipt.actions[index] {
+            Action::FunctionCall { method_name, .. } => method_name.clone(),
+            _ => panic!("expected FunctionCall"),
+        }
+    }
+
+    /// migrates all NEAR staked with the first staking pool over to a new staking pool, while a
+    /// deposit is made in the middle of the migration
+    #[test]
+    fn migrate_staking_pool_workflow() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        contract
+            .staking_pool_mut(TEST_STAKING_POOL_ID)
+            .update_balances((100 * YOCTO).into(), 0.into());
+
+        // Step 1: unstake all NEAR from the staking pool being migrated
+        testing_env!(operator_context(contract, &context));
+        contract.migrate_staking_pool(
+            to_valid_account_id(TEST_STAKING_POOL_ID),
+            to_valid_account_id(TEST_STAKING_POOL_ID_2),
+        );
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts[0].receiver_id, TEST_STAKING_POOL_ID);
+        assert_eq!(function_call_method_name(&receipts[0], 0), "unstake_all");
+        assert_eq!(function_call_method_name(&receipts[0], 1), "get_account");
+        assert_eq!(
+            function_call_method_name(&receipts[1], 0),
+            "on_staking_pool_migration_unstaked"
+        );
+        assert_eq!(
+            contract.staking_pool_migration().unwrap().status,
+            StakingPoolMigrationStatus::Unstaking
+        );
+
+        // deposits are queued in the next stake batch while migrating
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit_and_stake();
+        assert!(deserialize_receipts().is_empty());
+        assert!(contract.stake_batch.is_none());
+        assert_eq!(
+            contract.next_stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+        context.attached_deposit = 0;
+
+        fn unstaked_result(_: u64) -> PromiseResult {
+            staking_pool_account_result(0, 100 * YOCTO)
+        }
+        set_env_with_promise_result(contract, unstaked_result);
+        testing_env!(self_context(&context));
+        contract.on_staking_pool_migration_unstaked();
+        let migration = contract.staking_pool_migration().unwrap();
+        assert_eq!(
+            migration.status,
+            StakingPoolMigrationStatus::PendingWithdrawal
+        );
+        assert_eq!(migration.unstaked_balance, (100 * YOCTO).into());
+        // the unstaked NEAR still counts towards the staked NEAR balance
+        assert_eq!(
+            contract.staked_near_balance(0.into(), (100 * YOCTO).into()),
+            (100 * YOCTO).into()
+        );
+
+        // Step 2: withdraw all unstaked NEAR once it is available for withdrawal
+        context.epoch_height += 4;
+        testing_env!(operator_context(contract, &context));
+        contract.run_staking_pool_migration();
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts[0].receiver_id, TEST_STAKING_POOL_ID);
+        assert_eq!(function_call_method_name(&receipts[0], 0), "withdraw_all");
+        assert_eq!(
+            function_call_method_name(&receipts[1], 0),
+            "on_staking_pool_migration_withdrawn"
+        );
+
+        // Step 3: deposit and stake the withdrawn NEAR with the new staking pool
+        fn withdrawn_result(_: u64) -> PromiseResult {
+            staking_pool_account_result(0, 0)
+        }
+        set_env_with_promise_result(contract, withdrawn_result);
+        testing_env!(self_context(&context));
+        contract.on_staking_pool_migration_withdrawn();
+        assert_eq!(
+            contract.staking_pool_migration().unwrap().status,
+            StakingPoolMigrationStatus::Staking
+        );
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts[0].receiver_id, TEST_STAKING_POOL_ID_2);
+        match &receipts[0].actions[0] {
+            Action::FunctionCall {
+                method_name,
+                deposit,
+                ..
+            } => {
+                assert_eq!(method_name, "deposit_and_stake");
+                assert_eq!(*deposit, 100 * YOCTO);
+            }
+            _ => panic!("expected FunctionCall"),
+        }
+        assert_eq!(
+            function_call_method_name(&receipts[1], 0),
+            "on_staking_pool_migration_staked"
+        );
+        // the withdrawn NEAR held by the contract still counts towards the staked NEAR balance
+        assert_eq!(
+            contract.staked_near_balance(0.into(), 0.into()),
+            (100 * YOCTO).into()
+        );
+
+        fn staked_result(_: u64) -> PromiseResult {
+            staking_pool_account_result(100 * YOCTO, 1)
+        }
+        set_env_with_promise_result(contract, staked_result);
+        testing_env!(self_context(&context));
+        contract.on_staking_pool_migration_staked();
+
+        assert!(contract.staking_pool_migration.is_none());
+        let staking_pools = contract.staking_pools();
+        assert_eq!(staking_pools.len(), 1);
+        assert_eq!(staking_pools[0].account_id, TEST_STAKING_POOL_ID_2);
+        assert_eq!(staking_pools[0].weight, 100);
+        assert_eq!(staking_pools[0].staked_balance, (100 * YOCTO).into());
+        assert_eq!(staking_pools[0].unstaked_balance, 1.into());
+        // the queued stake batch is moved up
+        assert_eq!(
+            contract.stake_batch.unwrap().balance().amount(),
+            (10 * YOCTO).into()
+        );
+        assert!(contract.next_stake_batch.is_none());
+    }
+
+    #[test]
+    fn on_staking_pool_migration_unstaked_failure_aborts_migration() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        testing_env!(operator_context(contract, &context));
+        contract.migrate_staking_pool(
+            to_valid_account_id(TEST_STAKING_POOL_ID),
+            to_valid_account_id(TEST_STAKING_POOL_ID_2),
+        );
+
+        set_env_with_failed_promise_result(contract);
+        testing_env!(self_context(&context));
+        contract.on_staking_pool_migration_unstaked();
+
+        assert!(contract.staking_pool_migration.is_none());
+        assert_eq!(contract.staking_pools()[0].account_id, TEST_STAKING_POOL_ID);
+    }
+
+    #[test]
+    fn on_staking_pool_migration_staked_failure_reverts_to_withdrawn() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        let mut migration = StakingPoolMigration::new(TEST_STAKING_POOL_ID, TEST_STAKING_POOL_ID_2);
+        migration.unstaked((100 * YOCTO).into());
+        migration.set_status(StakingPoolMigrationStatus::Staking);
+        contract.staking_pool_migration = Some(migration);
+
+        set_env_with_failed_promise_result(contract);
+        testing_env!(self_context(&context));
+        contract.on_staking_pool_migration_staked();
+        assert_eq!(
+            contract.staking_pool_migration().unwrap().status,
+            StakingPoolMigrationStatus::Withdrawn
+        );
+
+        // the withdrawn NEAR is staked when the migration is run again
+        testing_env!(operator_context(contract, &context));
+        contract.run_staking_pool_migration();
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts[0].receiver_id, TEST_STAKING_POOL_ID_2);
+        assert_eq!(
+            function_call_method_name(&receipts[0], 0),
+            "deposit_and_stake"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unstaked NEAR funds are not yet available for withdrawal")]
+    fn run_staking_pool_migration_before_unstaked_near_is_available() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        testing_env!(context.clone());
+        let mut migration = StakingPoolMigration::new(TEST_STAKING_POOL_ID, TEST_STAKING_POOL_ID_2);
+        migration.unstaked((100 * YOCTO).into());
+        contract.staking_pool_migration = Some(migration);
+
+        testing_env!(operator_context(contract, &context));
+        contract.run_staking_pool_migration();
+    }
+
+    #[test]
+    #[should_panic(expected = "action is blocked because a staking pool migration is in progress")]
+    fn stake_while_migrating_staking_pool() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let mut context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        context.attached_deposit = 10 * YOCTO;
+        testing_env!(context.clone());
+        contract.deposit();
+
+        let mut migration = StakingPoolMigration::new(TEST_STAKING_POOL_ID, TEST_STAKING_POOL_ID_2);
+        migration.unstaked((100 * YOCTO).into());
+        contract.staking_pool_migration = Some(migration);
+
+        context.attached_deposit = 0;
+        testing_env!(context.clone());
+        contract.stake();
+    }
+
+    #[test]
+    #[should_panic(expected = "action is blocked because a batch is running")]
+    fn migrate_staking_pool_while_batch_is_running() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let context = test_ctx.context.clone();
+        let contract = &mut test_ctx.contract;
+
+        contract.stake_batch_lock = Some(StakeLock::Staking);
+        testing_env!(operator_context(contract, &context));
+        contract.migrate_staking_pool(
+            to_valid_account_id(TEST_STAKING_POOL_ID),
+            to_valid_account_id(TEST_STAKING_POOL_ID_2),
+        );
+    }
+}
@@ -1,42 +1,59 @@
 //required in order for near_bindgen macro to work outside of lib.rs
+use crate::config::{FeeCategory, STAKED_NEAR_ROUNDING_TOLERANCE};
 use crate::core::U256;
-use crate::interface::Operator;
+use crate::interface::{AccountManagement, ContractOwner, Operator};
 use crate::near::NO_DEPOSIT;
 use crate::*;
 use crate::{
-    domain::{self, Account, RedeemLock, RedeemStakeBatch, RegisteredAccount, StakeBatch},
+    domain::{
+        self, Account, PendingBatch, RedeemBeneficiary, RedeemLock, RedeemStakeBatch,
+        RegisteredAccount, StakeBatch,
+    },
     errors::{
         illegal_state::{
             REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST, REDEEM_STAKE_BATCH_SHOULD_EXIST,
-            STAKE_BATCH_SHOULD_EXIST,
+            STAKE_BATCH_SHOULD_EXIST, UNSTAKING_POOL_SHOULD_EXIST,
+        },
+        redeeming_stake_errors::{
+            NETTING_BLOCKED_BY_STAKING_POOL_BALANCE, NO_REDEEM_STAKE_BATCH_TO_RUN,
+            UNSTAKED_FUNDS_NOT_AVAILABLE_FOR_WITHDRAWAL, UNSTAKING_BLOCKED_BY_PENDING_WITHDRAWAL,
         },
-        redeeming_stake_errors::NO_REDEEM_STAKE_BATCH_TO_RUN,
         staking_errors::{
             BLOCKED_BY_BATCH_RUNNING, BLOCKED_BY_STAKE_TOKEN_VALUE_REFRESH,
-            NO_FUNDS_IN_STAKE_BATCH_TO_WITHDRAW,
+            BLOCKED_BY_STAKING_POOL_MIGRATION, NO_FUNDS_IN_STAKE_BATCH_TO_WITHDRAW,
+            NO_STAKE_BATCH_TO_RUN,
         },
         staking_service::{
-            BATCH_BALANCE_INSUFFICIENT, DEPOSIT_REQUIRED_FOR_STAKE,
-            INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST, ZERO_REDEEM_AMOUNT,
+            BATCH_BALANCE_INSUFFICIENT, CLAIM_RECEIPTS_FOR_NOT_PERMITTED,
+            DEPOSIT_REQUIRED_FOR_STAKE, INSTANT_REDEEM_BELOW_MIN_NEAR_OUT,
+            INSUFFICIENT_DEPOSIT_TO_REGISTER_BENEFICIARY,
+            INSUFFICIENT_LIQUIDITY_FOR_INSTANT_REDEEM, INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST,
+            INSUFFICIENT_STORAGE_ESCROW_FOR_REDEEM_BENEFICIARY, REDEEM_BENEFICIARY_CONFLICT,
+            ZERO_REDEEM_AMOUNT,
         },
     },
     interface::{
-        staking_service::events, BatchId, RedeemStakeBatchReceipt, StakingService, YoctoNear,
-        YoctoStake,
+        fungible_token::events::{FtBurn, FtMint, FtTransfer},
+        staking_service::events,
+        BatchId, RedeemStakeBatchReceipt, StakingService, UnstakedCohort, YoctoNear, YoctoStake,
     },
     near::{log, YOCTO},
-    staking_pool::StakingPoolPromiseBuilder,
 };
 use near_sdk::{
-    env, ext_contract, near_bindgen,
+    env, ext_contract,
+    json_types::U128,
+    near_bindgen,
     serde::{Deserialize, Serialize},
     AccountId, Promise, PromiseOrValue,
 };
 
 #[near_bindgen]
 impl StakingService for Contract {
-    fn staking_pool_id(&self) -> AccountId {
-        self.staking_pool_id.clone()
+    fn staking_pools(&self) -> Vec<interface::StakingPool> {
+        self.staking_pools
+            .iter()
+            .map(interface::StakingPool::from)
+            .collect()
     }
 
     fn stake_batch_receipt(&self, batch_id: BatchId) -> Option<interface::StakeBatchReceipt> {
@@ -58,10 +75,45 @@ impl StakingService for Contract {
     fn deposit(&mut self) -> BatchId {
         let mut account = self.predecessor_registered_account();
 
-        let near_amount = env::attached_deposit().into();
+        let near_amount = self.charge_near_fee(
+            FeeCategory::Deposit,
+            &account.account_id,
+            env::attached_deposit().into(),
+        );
+        let batch_id = self.deposit_near_for_account_to_stake(&mut account, near_amount);
+
+        self.check_min_required_near_deposit(&account, batch_id);
+
+        self.save_registered_account(&account);
+        self.log_stake_batch(batch_id);
+        batch_id.into()
+    }
+
+    #[payable]
+    fn deposit_with_limits(
+        &mut self,
+        min_stake_out: YoctoStake,
+        deadline_epoch: interface::EpochHeight,
+    ) -> BatchId {
+        let deadline_epoch = deadline_epoch.into();
+        Self::assert_limit_order_deadline(deadline_epoch);
+        let mut account = self.predecessor_registered_account();
+
+        let near_amount = self.charge_near_fee(
+            FeeCategory::Deposit,
+            &account.account_id,
+            env::attached_deposit().into(),
+        );
         let batch_id = self.deposit_near_for_account_to_stake(&mut account, near_amount);
 
         self.check_min_required_near_deposit(&account, batch_id);
+        self.submit_deposit_limit_order(
+            &account,
+            batch_id,
+            near_amount,
+            min_stake_out.into(),
+            deadline_epoch,
+        );
 
         self.save_registered_account(&account);
         self.log_stake_batch(batch_id);
@@ -75,6 +127,8 @@ impl StakingService for Contract {
             Some(StakeLock::Staking) => panic!(BLOCKED_BY_BATCH_RUNNING),
             Some(StakeLock::Staked { .. }) => {
                 let batch = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
+                self.keeper_rewards
+                    .set_stake_batch_keeper(env::predecessor_account_id());
                 self.process_staked_batch();
                 PromiseOrValue::Value(batch.id().into())
             }
@@ -88,7 +142,38 @@ impl StakingService for Contract {
     fn deposit_and_stake(&mut self) -> PromiseOrValue<BatchId> {
         let batch_id = self.deposit();
 
-        if self.can_run_batch() {
+        if self.can_run_batch() && !self.is_migrating_staking_pool() {
+            self.stake()
+        } else {
+            PromiseOrValue::Value(batch_id)
+        }
+    }
+
+    #[payable]
+    fn deposit_for(&mut self, beneficiary_id: ValidAccountId) -> BatchId {
+        let near_amount = self.register_beneficiary_from_attached_deposit(beneficiary_id.as_ref());
+        // the fee is charged to the account that pays for the deposit
+        let near_amount = self.charge_near_fee(
+            FeeCategory::Deposit,
+            &env::predecessor_account_id(),
+            near_amount,
+        );
+        let mut account = self.registered_account(beneficiary_id.as_ref());
+
+        let batch_id = self.deposit_near_for_account_to_stake(&mut account, near_amount);
+
+        self.check_min_required_near_deposit(&account, batch_id);
+
+        self.save_registered_account(&account);
+        self.log_stake_batch(batch_id);
+        batch_id.into()
+    }
+
+    #[payable]
+    fn deposit_and_stake_for(&mut self, beneficiary_id: ValidAccountId) -> PromiseOrValue<BatchId> {
+        let batch_id = self.deposit_for(beneficiary_id);
+
+        if self.can_run_batch() && !self.is_migrating_staking_pool() {
             self.stake()
         } else {
             PromiseOrValue::Value(batch_id)
@@ -217,7 +302,29 @@ impl StakingService for Contract {
 
     fn redeem(&mut self, amount: YoctoStake) -> BatchId {
         let mut account = self.predecessor_registered_account();
-        let batch_id = self.redeem_stake_for_account(&mut account, amount.into());
+        let (batch_id, _) = self.redeem_stake_for_account(&mut account, amount.into());
+        self.save_registered_account(&account);
+        self.log_redeem_stake_batch(batch_id.clone().into());
+        batch_id
+    }
+
+    fn redeem_with_limits(
+        &mut self,
+        amount: YoctoStake,
+        min_near_out: YoctoNear,
+        deadline_epoch: interface::EpochHeight,
+    ) -> BatchId {
+        let deadline_epoch = deadline_epoch.into();
+        Self::assert_limit_order_deadline(deadline_epoch);
+        let mut account = self.predecessor_registered_account();
+        let (batch_id, stake) = self.redeem_stake_for_account(&mut account, amount.into());
+        self.submit_redeem_limit_order(
+            &account,
+            batch_id.clone().into(),
+            stake,
+            min_near_out.into(),
+            deadline_epoch,
+        );
         self.save_registered_account(&account);
         self.log_redeem_stake_batch(batch_id.clone().into());
         batch_id
@@ -228,7 +335,7 @@ impl StakingService for Contract {
         self.claim_receipt_funds(&mut account);
         account.stake.map(|stake| {
             let amount = stake.amount();
-            let batch_id = self.redeem_stake_for_account(&mut account, amount);
+            let (batch_id, _) = self.redeem_stake_for_account(&mut account, amount);
             self.save_registered_account(&account);
             self.log_redeem_stake_batch(batch_id.clone().into());
             batch_id
@@ -240,7 +347,9 @@ impl StakingService for Contract {
         self.claim_receipt_funds(&mut account);
 
         if self.redeem_stake_batch_lock.is_none() {
-            if let Some(batch) = account.redeem_stake_batch {
+            if let Some((batch, is_next_batch_slot)) =
+                self.account_current_redeem_stake_batch(&account)
+            {
                 let amount = batch.balance().amount();
                 let batch_id = batch.id();
 
@@ -257,12 +366,24 @@ impl StakingService for Contract {
                 }
 
                 account.apply_stake_credit(amount);
-                account.redeem_stake_batch = None;
+                Self::log_stake_removed_from_redeem_stake_batch(&account, amount);
+                if is_next_batch_slot {
+                    account.next_redeem_stake_batch = None;
+                } else {
+                    account.redeem_stake_batch = None;
+                }
                 self.save_registered_account(&account);
                 self.log_redeem_stake_batch(batch_id);
+                self.sync_redeem_beneficiary(&mut account, batch_id);
                 return amount.into();
             }
-        } else if let Some(batch) = account.next_redeem_stake_batch {
+        } else if let Some(batch) = account
+            .next_redeem_stake_batch
+            // the account's next batch slot may still be holding the contract's current batch
+            .filter(|batch| {
+                Self::is_same_redeem_stake_batch(Some(*batch), self.next_redeem_stake_batch)
+            })
+        {
             let amount = batch.balance().amount();
             let batch_id = batch.id();
 
@@ -279,9 +400,11 @@ impl StakingService for Contract {
             }
 
             account.apply_stake_credit(amount);
+            Self::log_stake_removed_from_redeem_stake_batch(&account, amount);
             account.next_redeem_stake_batch = None;
             self.save_registered_account(&account);
             self.log_redeem_stake_batch(batch_id);
+            self.sync_redeem_beneficiary(&mut account, batch_id);
             return amount.into();
         }
 
@@ -293,7 +416,9 @@ impl StakingService for Contract {
         self.claim_receipt_funds(&mut account);
 
         if self.redeem_stake_batch_lock.is_none() {
-            if let Some(mut batch) = account.redeem_stake_batch {
+            if let Some((mut batch, is_next_batch_slot)) =
+                self.account_current_redeem_stake_batch(&account)
+            {
                 let amount: domain::YoctoStake = amount.into();
                 assert!(
                     amount <= batch.balance().amount(),
@@ -313,15 +438,28 @@ impl StakingService for Contract {
                 }
 
                 account.apply_stake_credit(amount);
-                if batch.remove(amount).value() == 0 {
-                    account.redeem_stake_batch = None;
+                Self::log_stake_removed_from_redeem_stake_batch(&account, amount);
+                let account_batch = if batch.remove(amount).value() == 0 {
+                    None
+                } else {
+                    Some(batch)
+                };
+                if is_next_batch_slot {
+                    account.next_redeem_stake_batch = account_batch;
                 } else {
-                    account.redeem_stake_batch = Some(batch);
+                    account.redeem_stake_batch = account_batch;
                 }
                 self.save_registered_account(&account);
                 self.log_redeem_stake_batch(batch.id());
+                self.sync_redeem_beneficiary(&mut account, batch.id());
             }
-        } else if let Some(mut batch) = account.next_redeem_stake_batch {
+        } else if let Some(mut batch) = account
+            .next_redeem_stake_batch
+            // the account's next batch slot may still be holding the contract's current batch
+            .filter(|batch| {
+                Self::is_same_redeem_stake_batch(Some(*batch), self.next_redeem_stake_batch)
+            })
+        {
             let amount: domain::YoctoStake = amount.into();
             assert!(
                 amount <= batch.balance().amount(),
@@ -341,6 +479,7 @@ impl StakingService for Contract {
             }
 
             account.apply_stake_credit(amount);
+            Self::log_stake_removed_from_redeem_stake_batch(&account, amount);
             if batch.remove(amount).value() == 0 {
                 account.next_redeem_stake_batch = None;
             } else {
@@ -348,37 +487,71 @@ impl StakingService for Contract {
             }
             self.save_registered_account(&account);
             self.log_redeem_stake_batch(batch.id());
+            self.sync_redeem_beneficiary(&mut account, batch.id());
         }
     }
 
     fn unstake(&mut self) -> Promise {
+        assert!(
+            !self.is_migrating_staking_pool(),
+            BLOCKED_BY_STAKING_POOL_MIGRATION
+        );
         assert!(self.can_run_batch(), BLOCKED_BY_BATCH_RUNNING);
 
+        self.keeper_rewards
+            .set_redeem_stake_batch_keeper(env::predecessor_account_id());
         match self.redeem_stake_batch_lock {
             None => {
-                assert!(
-                    self.redeem_stake_batch.is_some(),
-                    NO_REDEEM_STAKE_BATCH_TO_RUN
-                );
+                let batch = self.redeem_stake_batch.expect(NO_REDEEM_STAKE_BATCH_TO_RUN);
                 self.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
 
-                self.staking_pool_promise()
+                let unstake_amount = self
+                    .stake_token_value
+                    .stake_to_near(batch.balance().amount());
+                let staking_pool_id = self
+                    .redeem_stake_batch_staking_pool_id(unstake_amount)
+                    .expect(UNSTAKING_BLOCKED_BY_PENDING_WITHDRAWAL);
+                self.staking_pool_promise(&staking_pool_id)
                     .get_account()
                     .promise()
-                    .then(self.invoke_on_run_redeem_stake_batch())
+                    .then(self.invoke_on_run_redeem_stake_batch(&staking_pool_id))
                     .then(self.invoke_clear_redeem_lock())
             }
-            Some(RedeemLock::PendingWithdrawal) => self
-                .staking_pool_promise()
-                .get_account()
-                .promise()
-                .then(self.invoke_on_redeeming_stake_pending_withdrawal()),
+            Some(RedeemLock::PendingWithdrawal) => {
+                if self.can_move_pending_withdrawal_to_unstaked_cohort() {
+                    // the next batch does not need to wait for the pending withdrawal
+                    self.move_pending_withdrawal_to_unstaked_cohort();
+                    return self.unstake();
+                }
+                let staking_pool_id = self
+                    .unstaking_pool_id
+                    .clone()
+                    .expect(UNSTAKING_POOL_SHOULD_EXIST);
+                self.staking_pool_promise(&staking_pool_id)
+                    .get_account()
+                    .promise()
+                    .then(self.invoke_on_redeeming_stake_pending_withdrawal(&staking_pool_id))
+            }
             // this should already be handled by above assert and should never be hit
             // but it was added to satisfy the match clause for completeness
             Some(RedeemLock::Unstaking) => panic!(BLOCKED_BY_BATCH_RUNNING),
         }
     }
 
+    fn withdraw_unstaked_cohort(&mut self) -> Promise {
+        let cohort = self
+            .withdrawable_unstaked_cohort()
+            .expect(UNSTAKED_FUNDS_NOT_AVAILABLE_FOR_WITHDRAWAL);
+        self.staking_pool_promise(cohort.staking_pool_id())
+            .get_account()
+            .promise()
+            .then(self.invoke_on_unstaked_cohort_pending_withdrawal(
+                cohort.batch_id(),
+                cohort.staking_pool_id(),
+                &env::predecessor_account_id(),
+            ))
+    }
+
     fn redeem_and_unstake(&mut self, amount: YoctoStake) -> PromiseOrValue<BatchId> {
         let batch_id = self.redeem(amount);
 
@@ -402,14 +575,182 @@ impl StakingService for Contract {
         }
     }
 
+    fn run_netted_batches(&mut self) -> Promise {
+        assert!(
+            !self.is_migrating_staking_pool(),
+            BLOCKED_BY_STAKING_POOL_MIGRATION
+        );
+        assert!(self.can_run_batch(), BLOCKED_BY_BATCH_RUNNING);
+        assert!(
+            self.redeem_stake_batch_lock.is_none(),
+            UNSTAKING_BLOCKED_BY_PENDING_WITHDRAWAL
+        );
+        let stake_batch = self.stake_batch.expect(NO_STAKE_BATCH_TO_RUN);
+        let redeem_stake_batch = self.redeem_stake_batch.expect(NO_REDEEM_STAKE_BATCH_TO_RUN);
+
+        self.stake_batch_lock = Some(StakeLock::Staking);
+        self.redeem_stake_batch_lock = Some(RedeemLock::Unstaking);
+        self.keeper_rewards
+            .set_stake_batch_keeper(env::predecessor_account_id());
+        self.keeper_rewards
+            .set_redeem_stake_batch_keeper(env::predecessor_account_id());
+
+        self.distribute_earnings();
+
+        // the cached STAKE token value is used to estimate the net amount in order to select the
+        // staking pool - the batches are netted using the refreshed STAKE token value
+        let stake_amount = stake_batch.balance().amount() + self.near_liquidity_pool;
+        let redeem_amount = self
+            .stake_token_value
+            .stake_to_near(redeem_stake_batch.balance().amount());
+        let staking_pool_id = if stake_amount >= redeem_amount {
+            self.stake_batch_staking_pool_id(stake_amount - redeem_amount)
+        } else {
+            let unstake_amount = redeem_amount - stake_amount;
+            let staking_pool_id = self
+                .redeem_stake_batch_staking_pool_id(unstake_amount)
+                .expect(UNSTAKING_BLOCKED_BY_PENDING_WITHDRAWAL);
+            // the net unstake is not split across staking pools
+            let staked_balance = self
+                .staking_pools
+                .iter()
+                .find(|pool| pool.account_id() == staking_pool_id)
+                .map_or(0.into(), |pool| pool.staked_balance());
+            assert!(
+                staked_balance >= unstake_amount,
+                NETTING_BLOCKED_BY_STAKING_POOL_BALANCE
+            );
+            staking_pool_id
+        };
+        self.staking_pool_promise(&staking_pool_id)
+            .get_account()
+            .promise()
+            .then(self.invoke_on_run_netted_batches(&staking_pool_id))
+            .then(self.invoke_clear_stake_lock())
+            .then(self.invoke_clear_redeem_lock())
+    }
+
+    fn instant_redeem(&mut self, amount: YoctoStake, min_near_out: YoctoNear) -> YoctoNear {
+        assert!(self.can_run_batch(), BLOCKED_BY_BATCH_RUNNING);
+        let amount: domain::YoctoStake = amount.into();
+        assert!(amount.value() > 0, ZERO_REDEEM_AMOUNT);
+
+        let mut account = self.predecessor_registered_account();
+        self.claim_receipt_funds(&mut account);
+        assert!(
+            account.can_redeem(amount),
+            INSUFFICIENT_STAKE_FOR_REDEEM_REQUEST
+        );
+
+        let near_value = self.stake_token_value.stake_to_near(amount);
+        let fee = self.config.instant_redeem_fee(near_value);
+        let near_out = near_value - fee;
+        assert!(
+            near_out >= min_near_out.into(),
+            INSTANT_REDEEM_BELOW_MIN_NEAR_OUT
+        );
+
+        account.apply_stake_debit(amount);
+        self.save_registered_account(&account);
+
+        // LP liquidity is drawn on first because LPs earn the fee by buying the STAKE at a discount
+        self.claim_liquidity_provider_pool_receipts();
+        let from_liquidity_provider_pool = self.liquidity_provider_pool.near() >= near_out;
+        if from_liquidity_provider_pool {
+            self.liquidity_provider_pool.debit_near(near_out);
+            self.liquidity_provider_pool.record_fee(fee);
+            // the STAKE is held by the contract on behalf of the LP pool until it is burned
+            // when the batch is unstaked
+            let mut pool_account = self.liquidity_provider_pool.account();
+            self.add_to_redeem_stake_batch(&env::current_account_id(), &mut pool_account, amount);
+            self.liquidity_provider_pool.set_account(pool_account);
+            log(FtTransfer {
+                old_owner_id: &account.account_id,
+                new_owner_id: &env::current_account_id(),
+                amount: amount.into(),
+                memo: Some("instant_redeem"),
+            });
+        } else {
+            assert!(
+                self.available_instant_redeem_liquidity() >= near_value,
+                INSUFFICIENT_LIQUIDITY_FOR_INSTANT_REDEEM
+            );
+            // while there is a pending withdrawal, the liquidity counts towards the staked NEAR balance
+            let total_staked_near_balance = if self.get_pending_withdrawal().is_some() {
+                self.stake_token_value.total_staked_near_balance() - near_value
+            } else {
+                self.stake_token_value.total_staked_near_balance()
+            };
+            self.total_stake.debit(amount);
+            self.near_liquidity_pool -= near_value;
+            self.instant_redeem_fees += fee;
+            self.update_stake_token_value(total_staked_near_balance);
+            log(FtBurn {
+                owner_id: &account.account_id,
+                amount: amount.into(),
+                memo: Some("instant_redeem"),
+            });
+        }
+
+        log(events::InstantRedeemed {
+            account_id: &account.account_id,
+            stake: amount.into(),
+            near: near_out.into(),
+            fee: fee.into(),
+            from_liquidity_provider_pool,
+            near_liquidity_pool: self.near_liquidity_pool.into(),
+        });
+
+        Promise::new(account.account_id.clone()).transfer(near_out.value());
+        near_out.into()
+    }
+
+    fn instant_redeem_liquidity(&self) -> YoctoNear {
+        self.liquidity_provider_pool
+            .near()
+            .max(self.available_instant_redeem_liquidity())
+            .into()
+    }
+
     fn pending_withdrawal(&self) -> Option<RedeemStakeBatchReceipt> {
         self.get_pending_withdrawal()
             .map(RedeemStakeBatchReceipt::from)
     }
 
+    fn unstaked_cohorts(&self) -> Vec<UnstakedCohort> {
+        self.unstaked_cohorts
+            .iter()
+            .map(|cohort| UnstakedCohort::new(cohort, &self.unstaked_cohort_receipt(cohort)))
+            .collect()
+    }
+
+    fn redeem_queue_position(
+        &self,
+        account_id: ValidAccountId,
+    ) -> Option<interface::RedeemQueuePosition> {
+        self.redeem_queue_position_for(account_id.as_ref())
+    }
+
     fn claim_receipts(&mut self) {
+        self.allocate_redeem_queue_liquidity();
         let mut account = self.predecessor_registered_account();
-        self.claim_receipt_funds(&mut account);
+        self.claim_receipt_funds_and_pay_redeem_beneficiaries(&mut account);
+    }
+
+    fn claim_receipts_for(&mut self, account_id: ValidAccountId) {
+        let predecessor_account_id = env::predecessor_account_id();
+        assert!(
+            predecessor_account_id == account_id.as_ref()
+                || self.is_redeem_beneficiary(
+                    &self.registered_account(account_id.as_ref()),
+                    &predecessor_account_id
+                ),
+            "{}",
+            CLAIM_RECEIPTS_FOR_NOT_PERMITTED
+        );
+        self.allocate_redeem_queue_liquidity();
+        let mut account = self.registered_account(account_id.as_ref());
+        self.claim_receipt_funds_and_pay_redeem_beneficiaries(&mut account);
     }
 
     fn withdraw(&mut self, amount: interface::YoctoNear) {
@@ -422,10 +763,9 @@ impl StakingService for Contract {
         self.claim_receipt_funds(&mut account);
         match account.near {
             None => 0.into(),
-            Some(balance) => {
-                self.withdraw_near_funds(&mut account, balance.amount());
-                balance.amount().into()
-            }
+            Some(balance) => self
+                .withdraw_near_funds(&mut account, balance.amount())
+                .into(),
         }
     }
 
@@ -439,10 +779,9 @@ impl StakingService for Contract {
         self.claim_receipt_funds(&mut account);
         match account.near {
             None => 0.into(),
-            Some(balance) => {
-                self.transfer_near_funds(&mut account, balance.amount(), recipient);
-                balance.amount().into()
-            }
+            Some(balance) => self
+                .transfer_near_funds(&mut account, balance.amount(), recipient)
+                .into(),
         }
     }
 
@@ -450,15 +789,45 @@ impl StakingService for Contract {
         self.min_required_near_deposit().into()
     }
 
+    fn quote_fee(
+        &self,
+        category: FeeCategory,
+        account_id: ValidAccountId,
+        amount: U128,
+    ) -> interface::FeeQuote {
+        self.fee_quote(category, account_id.as_ref(), amount.0)
+    }
+
+    fn quote_stake(
+        &self,
+        amount: YoctoNear,
+        account_id: Option<ValidAccountId>,
+    ) -> interface::StakeQuote {
+        let account_id =
+            account_id.map_or_else(String::new, |account_id| account_id.as_ref().to_string());
+        self.stake_quote(amount.into(), &account_id)
+    }
+
+    fn quote_redeem(
+        &self,
+        amount: YoctoStake,
+        account_id: Option<ValidAccountId>,
+    ) -> interface::RedeemQuote {
+        let account_id =
+            account_id.map_or_else(String::new, |account_id| account_id.as_ref().to_string());
+        self.redeem_quote(amount.into(), &account_id)
+    }
+
     fn refresh_stake_token_value(&mut self) -> Promise {
         match self.stake_batch_lock {
             None => {
                 assert!(!self.is_unstaking(), BLOCKED_BY_BATCH_RUNNING);
+                assert!(
+                    !self.is_staking_pool_migration_running(),
+                    BLOCKED_BY_STAKING_POOL_MIGRATION
+                );
                 self.stake_batch_lock = Some(StakeLock::RefreshingStakeTokenValue);
-                StakingPoolPromiseBuilder::new(self.staking_pool_id.clone(), &self.config)
-                    .ping()
-                    .get_account()
-                    .promise()
+                self.ping_and_get_staking_pool_accounts()
                     .then(self.invoke_refresh_stake_token_value())
             }
             Some(StakeLock::RefreshingStakeTokenValue) => {
@@ -471,10 +840,58 @@ impl StakingService for Contract {
     fn stake_token_value(&self) -> interface::StakeTokenValue {
         self.stake_token_value.into()
     }
+
+    fn stake_token_value_history(
+        &self,
+        from_epoch: u64,
+        limit: u64,
+    ) -> Vec<interface::StakeTokenValue> {
+        self.stake_token_value_history
+            .page(from_epoch.into(), limit)
+            .into_iter()
+            .map(Into::into)
+            .collect()
+    }
+
+    fn apy(&self, epochs: u64) -> Option<interface::StakeTokenValueApy> {
+        let to = self.stake_token_value_history.latest()?;
+        let from_epoch = to
+            .block_time_height()
+            .epoch_height()
+            .value()
+            .checked_sub(epochs)?;
+        let from = self
+            .stake_token_value_history
+            .at_or_before(from_epoch.into())?;
+        let apy_basis_points = domain::apy_basis_points(&from, &to)?;
+        Some(interface::StakeTokenValueApy {
+            epochs: domain::EpochHeight(
+                to.block_time_height().epoch_height().value()
+                    - from.block_time_height().epoch_height().value(),
+            )
+            .into(),
+            from: from.into(),
+            to: to.into(),
+            apy_basis_points,
+        })
+    }
 }
 
 // staking pool func call invocations
 impl Contract {
+    /// STAKE that is removed from a redeem batch is transferred back from the contract to the account
+    fn log_stake_removed_from_redeem_stake_batch(
+        account: &RegisteredAccount,
+        amount: domain::YoctoStake,
+    ) {
+        log(FtTransfer {
+            old_owner_id: &env::current_account_id(),
+            new_owner_id: &account.account_id,
+            amount: amount.into(),
+            memo: Some("remove from RedeemStakeBatch"),
+        });
+    }
+
     fn log_stake_batch(&self, batch_id: domain::BatchId) {
         if let Some(batch) = self.stake_batch {
             if batch_id == batch.id() {
@@ -486,7 +903,7 @@ impl Contract {
             }
         } else {
             log(events::StakeBatchCancelled {
-                batch_id: batch_id.value(),
+                batch_id: batch_id.value().into(),
             });
         }
     }
@@ -502,7 +919,7 @@ impl Contract {
             }
         } else {
             log(events::RedeemStakeBatchCancelled {
-                batch_id: batch_id.value(),
+                batch_id: batch_id.value().into(),
             });
         }
     }
@@ -510,30 +927,61 @@ impl Contract {
 
 /// NEAR transfers
 impl Contract {
-    fn withdraw_near_funds(&mut self, account: &mut RegisteredAccount, amount: domain::YoctoNear) {
+    /// Returns the amount transferred after the withdraw fee
+    fn withdraw_near_funds(
+        &mut self,
+        account: &mut RegisteredAccount,
+        amount: domain::YoctoNear,
+    ) -> domain::YoctoNear {
         self.claim_receipt_funds(account);
-        account.apply_near_debit(amount);
-        self.save_registered_account(&account);
-        // check if there are enough funds to fulfill the request - if not then draw from liquidity
-        if self.total_near.amount() < amount {
-            // access liquidity
-            // NOTE: will panic if there are not enough funds in liquidity pool
-            //       - should never panic unless there is a bug
-            let difference = amount - self.total_near.amount();
-            self.near_liquidity_pool -= difference;
-            self.total_near.credit(difference);
-        }
-        self.total_near.debit(amount);
-        Promise::new(env::predecessor_account_id()).transfer(amount.value());
+        self.pay_out_near_funds_less_withdraw_fee(account, amount, &env::predecessor_account_id())
     }
 
+    /// Returns the amount transferred after the withdraw fee
     fn transfer_near_funds(
         &mut self,
         account: &mut RegisteredAccount,
         amount: domain::YoctoNear,
         recipient: ValidAccountId,
-    ) {
+    ) -> domain::YoctoNear {
         self.claim_receipt_funds(account);
+        self.pay_out_near_funds_less_withdraw_fee(account, amount, recipient.as_ref())
+    }
+
+    /// the full amount is debited from the account's NEAR balance, and the withdraw fee is deducted
+    /// from the NEAR that is transferred to the recipient
+    ///
+    /// NOTE: the account is saved
+    fn pay_out_near_funds_less_withdraw_fee(
+        &mut self,
+        account: &mut RegisteredAccount,
+        amount: domain::YoctoNear,
+        recipient: &str,
+    ) -> domain::YoctoNear {
+        self.debit_near_funds(account, amount);
+        let account_id = account.account_id.clone();
+        let amount = self.charge_near_fee(FeeCategory::Withdraw, &account_id, amount);
+        Promise::new(recipient.to_string()).transfer(amount.value());
+        amount
+    }
+
+    /// debits the account's NEAR balance and transfers the NEAR to the recipient
+    ///
+    /// NOTE: the account is saved
+    pub(crate) fn pay_out_near_funds(
+        &mut self,
+        account: &mut RegisteredAccount,
+        amount: domain::YoctoNear,
+        recipient: &str,
+    ) {
+        self.debit_near_funds(account, amount);
+        Promise::new(recipient.to_string()).transfer(amount.value());
+    }
+
+    /// debits the account's NEAR balance and the contract's total NEAR balance
+    ///
+    /// NOTE: the account is saved
+    fn debit_near_funds(&mut self, account: &mut RegisteredAccount, amount: domain::YoctoNear) {
         account.apply_near_debit(amount);
         self.save_registered_account(&account);
         // check if there are enough funds to fulfill the request - if not then draw from liquidity
@@ -546,24 +994,228 @@ impl Contract {
             self.total_near.credit(difference);
         }
         self.total_near.debit(amount);
-        Promise::new(recipient.as_ref().to_string()).transfer(amount.value());
+    }
+}
+
+/// redeeming STAKE on behalf of a beneficiary
+impl Contract {
+    /// account IDs cannot contain ':', which makes the (account, batch) key unambiguous
+    pub(crate) fn redeem_beneficiary_key(account_id: &str, batch_id: domain::BatchId) -> Hash {
+        Hash::from(format!("{}:{}", account_id, batch_id.value()).as_str())
+    }
+
+    /// redeems the STAKE and records the beneficiary that the NEAR will be transferred to when the
+    /// account claims the NEAR for the redeemed STAKE
+    ///
+    /// NOTE: the account is saved
+    ///
+    /// ## Panics
+    /// - if the account already has a different beneficiary for the batch
+    /// - see [redeem_stake_for_account](Contract::redeem_stake_for_account)
+    pub(crate) fn redeem_stake_for_beneficiary(
+        &mut self,
+        account: &mut RegisteredAccount,
+        amount: domain::YoctoStake,
+        beneficiary_id: &str,
+    ) -> BatchId {
+        let (batch_id, amount) = self.redeem_stake_for_account(account, amount);
+
+        let key = Self::redeem_beneficiary_key(&account.account_id, batch_id.clone().into());
+        let mut beneficiary = match self.redeem_beneficiaries.get(&key) {
+            Some(beneficiary) => beneficiary,
+            None => {
+                // the account pays for the beneficiary storage until the NEAR is paid out
+                account.redeem_beneficiaries_len += 1;
+                assert!(
+                    account.storage_escrow.amount() >= self.registered_account_storage_fee(account),
+                    INSUFFICIENT_STORAGE_ESCROW_FOR_REDEEM_BENEFICIARY
+                );
+                RedeemBeneficiary::new(beneficiary_id)
+            }
+        };
+        assert_eq!(
+            beneficiary.account_id(),
+            beneficiary_id,
+            "{}",
+            REDEEM_BENEFICIARY_CONFLICT
+        );
+        beneficiary.credit(amount);
+        self.redeem_beneficiaries.insert(&key, &beneficiary);
+
+        self.save_registered_account(account);
+        self.log_redeem_stake_batch(batch_id.clone().into());
+        batch_id
+    }
+
+    /// when STAKE is removed from a redeem batch, the beneficiary's STAKE is capped by the account's
+    /// batch balance - if the account no longer has funds in the batch, then the beneficiary is deleted
+    /// and its storage is released back to the account
+    ///
+    /// NOTE: the account is saved if the beneficiary is deleted
+    pub(crate) fn sync_redeem_beneficiary(
+        &mut self,
+        account: &mut RegisteredAccount,
+        batch_id: domain::BatchId,
+    ) {
+        let key = Self::redeem_beneficiary_key(&account.account_id, batch_id);
+        if let Some(mut beneficiary) = self.redeem_beneficiaries.get(&key) {
+            let batch_balance = self.account_redeem_stake_batch_balance(account, batch_id);
+            if batch_balance.value() == 0 {
+                self.redeem_beneficiaries.remove(&key);
+                account.redeem_beneficiaries_len -= 1;
+                self.save_registered_account(account);
+            } else if beneficiary.stake() > batch_balance {
+                beneficiary.debit(beneficiary.stake() - batch_balance);
+                self.redeem_beneficiaries.insert(&key, &beneficiary);
+            }
+        }
+    }
+
+    fn account_redeem_stake_batch_balance(
+        &self,
+        account: &RegisteredAccount,
+        batch_id: domain::BatchId,
+    ) -> domain::YoctoStake {
+        self.account_redeem_stake_batches(&account.account_id, account)
+            .iter()
+            .find(|batch| batch.id() == batch_id)
+            .map_or(0.into(), |batch| batch.balance().amount())
+    }
+
+    /// returns true if the account redeemed STAKE on behalf of the beneficiary in any of its redeem
+    /// batches that have not been paid out yet
+    fn is_redeem_beneficiary(&self, account: &RegisteredAccount, beneficiary_id: &str) -> bool {
+        self.account_redeem_stake_batches(&account.account_id, account)
+            .iter()
+            .filter_map(|batch| {
+                self.redeem_beneficiaries.get(&Self::redeem_beneficiary_key(
+                    &account.account_id,
+                    batch.id(),
+                ))
+            })
+            .any(|beneficiary| beneficiary.account_id() == beneficiary_id)
+    }
+
+    /// returns the IDs of the account's redeem batches that have beneficiaries
+    fn redeem_stake_batch_ids_with_beneficiaries(
+        &self,
+        account: &RegisteredAccount,
+    ) -> Vec<domain::BatchId> {
+        self.account_redeem_stake_batches(&account.account_id, account)
+            .iter()
+            .map(RedeemStakeBatch::id)
+            .filter(|batch_id| {
+                self.redeem_beneficiaries
+                    .contains_key(&Self::redeem_beneficiary_key(
+                        &account.account_id,
+                        *batch_id,
+                    ))
+            })
+            .collect()
+    }
+
+    /// returns the account's redeem batches that have beneficiaries and receipts, along with the
+    /// STAKE token value that will be used to claim the NEAR
+    /// - used to snapshot the batches before the receipts are claimed
+    fn redeem_stake_batches_with_beneficiaries(
+        &self,
+        account: &RegisteredAccount,
+    ) -> Vec<(RedeemStakeBatch, domain::StakeTokenValue)> {
+        self.account_redeem_stake_batches(&account.account_id, account)
+            .into_iter()
+            .filter_map(|batch| {
+                self.redeem_stake_batch_receipts
+                    .get(&batch.id())
+                    .filter(|_| {
+                        self.redeem_beneficiaries
+                            .contains_key(&Self::redeem_beneficiary_key(
+                                &account.account_id,
+                                batch.id(),
+                            ))
+                    })
+                    .map(|receipt| (batch, receipt.stake_token_value()))
+            })
+            .collect()
+    }
+
+    /// transfers the NEAR that was claimed for STAKE redeemed on behalf of beneficiaries
+    /// - `batches` is the snapshot of the account's redeem batches before receipts were claimed
+    /// - beneficiaries that have been paid out in full are deleted, and their storage is released
+    ///   back to the account
+    ///
+    /// NOTE: the account is saved
+    fn pay_redeem_beneficiaries(
+        &mut self,
+        account: &mut RegisteredAccount,
+        batches: Vec<(RedeemStakeBatch, domain::StakeTokenValue)>,
+    ) {
+        let redeem_beneficiaries_len = account.redeem_beneficiaries_len;
+        for (batch, stake_token_value) in batches {
+            let batch_balance = self.account_redeem_stake_batch_balance(account, batch.id());
+            let claimed_stake = batch.balance().amount() - batch_balance;
+            if claimed_stake.value() == 0 {
+                continue;
+            }
+
+            let key = Self::redeem_beneficiary_key(&account.account_id, batch.id());
+            let mut beneficiary = match self.redeem_beneficiaries.get(&key) {
+                Some(beneficiary) => beneficiary,
+                None => continue,
+            };
+            let stake = beneficiary.debit(claimed_stake);
+            if batch_balance.value() == 0 || beneficiary.stake().value() == 0 {
+                self.redeem_beneficiaries.remove(&key);
+                account.redeem_beneficiaries_len -= 1;
+            } else {
+                self.redeem_beneficiaries.insert(&key, &beneficiary);
+            }
+
+            let near = stake_token_value.stake_to_near(stake);
+            // guard against rounding when NEAR was claimed against the liquidity pool
+            let near = account
+                .near
+                .as_ref()
+                .map_or(0.into(), |balance| balance.amount())
+                .min(near);
+            if near.value() > 0 {
+                self.pay_out_near_funds(account, near, beneficiary.account_id());
+                log(events::RedeemBeneficiaryPaid {
+                    account_id: &account.account_id,
+                    beneficiary_id: beneficiary.account_id(),
+                    batch_id: batch.id().value().into(),
+                    stake: stake.into(),
+                    near: near.into(),
+                });
+            }
+        }
+        if account.redeem_beneficiaries_len != redeem_beneficiaries_len {
+            self.save_registered_account(account);
+        }
     }
 }
 
 impl Contract {
     fn run_stake_batch(&mut self) -> Promise {
+        assert!(
+            !self.is_migrating_staking_pool(),
+            BLOCKED_BY_STAKING_POOL_MIGRATION
+        );
         assert!(self.can_run_batch(), BLOCKED_BY_BATCH_RUNNING);
         let batch = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
 
         self.stake_batch_lock = Some(StakeLock::Staking);
+        self.keeper_rewards
+            .set_stake_batch_keeper(env::predecessor_account_id());
 
         self.distribute_earnings();
 
-        if self.is_liquidity_needed() {
-            self.staking_pool_promise()
+        let staking_pool_id = self.stake_batch_staking_pool_id(batch.balance().amount());
+        // limit orders are checked before the NEAR is deposited - see `on_run_stake_batch`
+        if self.is_liquidity_needed() || self.deposit_limit_orders.contains(batch.id()) {
+            self.staking_pool_promise(&staking_pool_id)
                 .get_account()
                 .promise()
-                .then(self.invoke_on_run_stake_batch())
+                .then(self.invoke_on_run_stake_batch(&staking_pool_id))
                 .then(self.invoke_clear_stake_lock())
         } else {
             // if liquidity is not needed, then lets stake it
@@ -571,11 +1223,11 @@ impl Contract {
             //       the staking pool because of the shares rounding issue on the staking pool side
             let stake_amount = batch.balance().amount() + self.near_liquidity_pool;
             self.near_liquidity_pool = 0.into();
-            self.staking_pool_promise()
+            self.staking_pool_promise(&staking_pool_id)
                 .deposit_and_stake(stake_amount)
                 .get_account()
                 .promise()
-                .then(self.invoke_on_deposit_and_stake(None))
+                .then(self.invoke_on_deposit_and_stake(&staking_pool_id, None))
                 .then(self.invoke_clear_stake_lock())
         }
     }
@@ -611,14 +1263,31 @@ impl Contract {
             .flatten()
     }
 
-    fn can_run_batch(&self) -> bool {
+    /// liquidity that is needed to cover the pending withdrawal is reserved for the accounts that
+    /// are waiting on the pending withdrawal
+    pub(crate) fn available_instant_redeem_liquidity(&self) -> domain::YoctoNear {
+        let reserved_liquidity = self
+            .get_pending_withdrawal()
+            .map_or(0, |receipt| receipt.stake_near_value().value());
+        self.near_liquidity_pool
+            .saturating_sub(reserved_liquidity)
+            .into()
+    }
+
+    pub(crate) fn can_run_batch(&self) -> bool {
         !self.stake_batch_locked() && !self.is_unstaking()
     }
 
-    fn can_unstake(&self) -> bool {
+    pub(crate) fn can_unstake(&self) -> bool {
+        if self.is_migrating_staking_pool() {
+            return false;
+        }
         if self.can_run_batch() {
             match self.redeem_stake_batch_lock {
-                None => self.redeem_stake_batch.is_some(),
+                None => {
+                    self.redeem_stake_batch.is_some()
+                        && self.redeem_stake_batch_staking_pool_id(0.into()).is_some()
+                }
                 Some(RedeemLock::PendingWithdrawal) => {
                     let batch = self
                         .redeem_stake_batch
@@ -628,6 +1297,7 @@ impl Contract {
                         .get(&batch.id())
                         .expect(REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST);
                     batch_receipt.unstaked_funds_available_for_withdrawal()
+                        || self.can_move_pending_withdrawal_to_unstaked_cohort()
                 }
                 Some(RedeemLock::Unstaking) => false,
             }
@@ -636,6 +1306,32 @@ impl Contract {
         }
     }
 
+    /// If the beneficiary account is not registered, then the account is registered and the account
+    /// storage fee is paid out of the attached deposit.
+    ///
+    /// Returns the attached deposit that remains to be staked.
+    ///
+    /// ## Panics
+    /// if the beneficiary account is not registered and the attached deposit does not cover the
+    /// account storage fee plus the NEAR to stake
+    fn register_beneficiary_from_attached_deposit(
+        &mut self,
+        beneficiary_id: &str,
+    ) -> domain::YoctoNear {
+        let deposit: domain::YoctoNear = env::attached_deposit().into();
+        if self.accounts.contains_key(&Hash::from(beneficiary_id)) {
+            return deposit;
+        }
+
+        let account_storage_fee: domain::YoctoNear = self.account_storage_fee().into();
+        assert!(
+            deposit > account_storage_fee,
+            INSUFFICIENT_DEPOSIT_TO_REGISTER_BENEFICIARY
+        );
+        self.register(beneficiary_id);
+        deposit - account_storage_fee
+    }
+
     /// batches the NEAR to stake at the contract level and account level
     ///
     /// ## Panics
@@ -653,7 +1349,8 @@ impl Contract {
         self.claim_receipt_funds(account);
 
         // use current batch if not staking, i.e., the stake batch is not running
-        if !self.stake_batch_locked() {
+        // - while staking pools are being migrated, deposits are queued in the next batch
+        if !self.stake_batch_locked() && !self.is_migrating_staking_pool() {
             // apply at contract level
             let mut contract_batch = self.stake_batch.unwrap_or_else(|| self.new_stake_batch());
             contract_batch.add(amount);
@@ -661,11 +1358,31 @@ impl Contract {
 
             // apply at account level
             // NOTE: account batch ID must match contract batch ID
-            let mut account_batch = account
-                .stake_batch
-                .unwrap_or_else(|| contract_batch.id().new_stake_batch());
+            // - if the account's current batch slot is still holding an older batch that has not been
+            //   claimed yet, then the account's next batch slot is used for the contract's current
+            //   batch, and any older batch in the next batch slot is moved into the account's pending
+            //   batches
+            let account_batch_slot = match account.stake_batch {
+                Some(batch) if batch.id() != contract_batch.id() => {
+                    if let Some(batch) = account
+                        .next_stake_batch
+                        .filter(|batch| batch.id() != contract_batch.id())
+                    {
+                        account.next_stake_batch = None;
+                        self.push_pending_batch(
+                            &account.account_id,
+                            &mut account.account,
+                            PendingBatch::Stake(batch),
+                        );
+                    }
+                    &mut account.next_stake_batch
+                }
+                _ => &mut account.stake_batch,
+            };
+            let mut account_batch =
+                account_batch_slot.unwrap_or_else(|| contract_batch.id().new_stake_batch());
             account_batch.add(amount);
-            account.stake_batch = Some(account_batch);
+            *account_batch_slot = Some(account_batch);
 
             account_batch.id()
         } else {
@@ -678,6 +1395,27 @@ impl Contract {
 
             // apply at account level
             // NOTE: account batch ID must match contract batch ID
+            // - if the account's next batch slot is still holding an older batch, then the batch is
+            //   moved into the current batch slot if it is the contract's current batch, and into the
+            //   account's pending batches otherwise
+            if let Some(batch) = account
+                .next_stake_batch
+                .filter(|batch| batch.id() != contract_batch.id())
+            {
+                account.next_stake_batch = None;
+                let evicted_batch = if Self::is_same_stake_batch(Some(batch), self.stake_batch) {
+                    account.stake_batch.replace(batch)
+                } else {
+                    Some(batch)
+                };
+                if let Some(batch) = evicted_batch {
+                    self.push_pending_batch(
+                        &account.account_id,
+                        &mut account.account,
+                        PendingBatch::Stake(batch),
+                    );
+                }
+            }
             let mut account_batch = account
                 .next_stake_batch
                 .unwrap_or_else(|| contract_batch.id().new_stake_batch());
@@ -688,6 +1426,13 @@ impl Contract {
         }
     }
 
+    fn is_same_stake_batch(batch: Option<StakeBatch>, other: Option<StakeBatch>) -> bool {
+        match (batch, other) {
+            (Some(batch), Some(other)) => batch.id() == other.id(),
+            _ => false,
+        }
+    }
+
     fn new_stake_batch(&mut self) -> StakeBatch {
         *self.batch_id_sequence += 1;
         self.batch_id_sequence.new_stake_batch()
@@ -701,11 +1446,14 @@ impl Contract {
     ///
     /// ## Notes
     /// - before applying the deposit, batch receipts are processed [claim_receipt_funds]
+    /// - the redeem fee is deducted from the amount that is added to the batch
+    ///
+    /// Returns the batch ID and the STAKE that was added to the batch
     fn redeem_stake_for_account(
         &mut self,
         account: &mut RegisteredAccount,
         amount: domain::YoctoStake,
-    ) -> BatchId {
+    ) -> (BatchId, domain::YoctoStake) {
         assert!(amount.value() > 0, ZERO_REDEEM_AMOUNT);
 
         self.claim_receipt_funds(account);
@@ -722,10 +1470,34 @@ impl Contract {
         } else {
             account.stake = None;
         }
+        // the redeemed STAKE is held by the contract until it is burned when the batch is unstaked
+        log(FtTransfer {
+            old_owner_id: &account.account_id,
+            new_owner_id: &env::current_account_id(),
+            amount: amount.into(),
+            memo: Some("redeem"),
+        });
 
-        match self.redeem_stake_batch_lock {
+        let account_id = account.account_id.clone();
+        let amount = self.charge_stake_fee(FeeCategory::Redeem, &account_id, amount);
+        (
+            self.add_to_redeem_stake_batch(&account_id, account, amount),
+            amount,
+        )
+    }
+
+    /// adds the STAKE to the account's redeem stake batch and queues up the [RedeemRequest](domain::RedeemRequest)
+    /// - the STAKE must already have been debited from the account's STAKE balance
+    fn add_to_redeem_stake_batch(
+        &mut self,
+        account_id: &str,
+        account: &mut Account,
+        amount: domain::YoctoStake,
+    ) -> BatchId {
+        let batch_id = match self.redeem_stake_batch_lock {
             // use current batch
-            None => {
+            // - while staking pools are being migrated, redemptions are queued in the next batch
+            None if !self.is_migrating_staking_pool() => {
                 // apply at contract level
                 let mut contract_batch = self
                     .redeem_stake_batch
@@ -735,13 +1507,32 @@ impl Contract {
 
                 // apply at account level
                 // NOTE: account batch ID must match contract batch ID
-                let mut account_batch = account
-                    .redeem_stake_batch
+                // - if the account's current batch was moved into an unstaked cohort, then the
+                //   account's next batch slot is used for the contract's current batch, and any
+                //   older batch in the next batch slot is moved into the account's pending batches
+                let account_batch_slot = match account.redeem_stake_batch {
+                    Some(batch) if batch.id() != contract_batch.id() => {
+                        if let Some(batch) = account
+                            .next_redeem_stake_batch
+                            .filter(|batch| batch.id() != contract_batch.id())
+                        {
+                            account.next_redeem_stake_batch = None;
+                            self.push_pending_batch(
+                                account_id,
+                                account,
+                                PendingBatch::Redeem(batch),
+                            );
+                        }
+                        &mut account.next_redeem_stake_batch
+                    }
+                    _ => &mut account.redeem_stake_batch,
+                };
+                let mut account_batch = account_batch_slot
                     .unwrap_or_else(|| contract_batch.id().new_redeem_stake_batch());
                 account_batch.add(amount);
-                account.redeem_stake_batch = Some(account_batch);
+                *account_batch_slot = Some(account_batch);
 
-                account_batch.id().into()
+                account_batch.id()
             }
             // use next batch
             _ => {
@@ -754,14 +1545,65 @@ impl Contract {
 
                 // apply at account level
                 // NOTE: account batch ID must match contract batch ID
+                // - the account's next batch slot may still be holding the contract's current batch
+                //   if the account's current batch was moved into an unstaked cohort - the batch is
+                //   moved into the current batch slot, and the batch in the unstaked cohort is moved
+                //   into the account's pending batches
+                if let Some(batch) = account
+                    .next_redeem_stake_batch
+                    .filter(|batch| batch.id() != contract_batch.id())
+                {
+                    account.next_redeem_stake_batch = None;
+                    let evicted_batch =
+                        if Self::is_same_redeem_stake_batch(Some(batch), self.redeem_stake_batch) {
+                            account.redeem_stake_batch.replace(batch)
+                        } else {
+                            Some(batch)
+                        };
+                    if let Some(batch) = evicted_batch {
+                        self.push_pending_batch(account_id, account, PendingBatch::Redeem(batch));
+                    }
+                }
                 let mut account_batch = account
                     .next_redeem_stake_batch
                     .unwrap_or_else(|| contract_batch.id().new_redeem_stake_batch());
                 account_batch.add(amount);
                 account.next_redeem_stake_batch = Some(account_batch);
 
-                account_batch.id().into()
+                account_batch.id()
             }
+        };
+        self.redeem_queue
+            .push(domain::RedeemRequest::new(account_id, batch_id, amount));
+        batch_id.into()
+    }
+
+    /// returns the account's batch for the contract's current redeem stake batch, and whether it is
+    /// held in the account's next batch slot, which is the case when the account's current batch
+    /// was moved into an unstaked cohort
+    fn account_current_redeem_stake_batch(
+        &self,
+        account: &Account,
+    ) -> Option<(RedeemStakeBatch, bool)> {
+        if Self::is_same_redeem_stake_batch(account.redeem_stake_batch, self.redeem_stake_batch) {
+            account.redeem_stake_batch.map(|batch| (batch, false))
+        } else if Self::is_same_redeem_stake_batch(
+            account.next_redeem_stake_batch,
+            self.redeem_stake_batch,
+        ) {
+            account.next_redeem_stake_batch.map(|batch| (batch, true))
+        } else {
+            None
+        }
+    }
+
+    fn is_same_redeem_stake_batch(
+        batch: Option<RedeemStakeBatch>,
+        other: Option<RedeemStakeBatch>,
+    ) -> bool {
+        match (batch, other) {
+            (Some(batch), Some(other)) => batch.id() == other.id(),
+            _ => false,
         }
     }
 
@@ -770,21 +1612,82 @@ impl Contract {
         self.batch_id_sequence.new_redeem_stake_batch()
     }
 
+    /// claims the account's receipts implicitly, i.e., as part of any account action
+    /// - redeem batches with beneficiaries are held back, i.e., beneficiaries are only paid out
+    ///   when receipts are claimed explicitly - see [claim_receipt_funds_and_pay_redeem_beneficiaries](Contract::claim_receipt_funds_and_pay_redeem_beneficiaries)
+    ///
     /// NOTE: the account is saved to storage if funds were claimed
     pub(crate) fn claim_receipt_funds(&mut self, account: &mut RegisteredAccount) {
-        let claimed_stake_tokens = self.claim_stake_batch_receipts(&mut account.account);
-        let claimed_near_tokens = self.claim_redeem_stake_batch_receipts(&mut account.account);
+        let batch_ids_with_beneficiaries = self.redeem_stake_batch_ids_with_beneficiaries(account);
+        self.claim_account_receipt_funds(account, &batch_ids_with_beneficiaries);
+    }
+
+    /// claims all of the account's receipts and transfers the NEAR that was claimed for STAKE that
+    /// was redeemed on behalf of beneficiaries to the beneficiaries
+    /// - used by the explicit claim entry points, i.e., [StakingService::claim_receipts] and
+    ///   [StakingService::claim_receipts_for]
+    ///
+    /// NOTE: the account is saved to storage if funds were claimed
+    pub(crate) fn claim_receipt_funds_and_pay_redeem_beneficiaries(
+        &mut self,
+        account: &mut RegisteredAccount,
+    ) {
+        let redeem_stake_batches_with_beneficiaries =
+            self.redeem_stake_batches_with_beneficiaries(account);
+        if self.claim_account_receipt_funds(account, &[]) {
+            self.pay_redeem_beneficiaries(account, redeem_stake_batches_with_beneficiaries);
+        }
+    }
+
+    /// claims the account's receipts, except for the redeem batches that are held back
+    /// - returns true if NEAR funds were claimed
+    ///
+    /// NOTE: the account is saved to storage if funds were claimed
+    ///
+    /// ## NEP-141 Events
+    /// STAKE that is minted for a batch is held by the contract until it is claimed. When claimed,
+    /// an `ft_transfer` event is logged from the contract to the account.
+    fn claim_account_receipt_funds(
+        &mut self,
+        account: &mut RegisteredAccount,
+        held_redeem_batch_ids: &[domain::BatchId],
+    ) -> bool {
+        let stake_balance = account.stake_balance();
+        let claimed_stake_tokens =
+            self.claim_stake_batch_receipts(&account.account_id, &mut account.account);
+        let claimed_near_tokens = self.claim_redeem_stake_batch_receipts(
+            &account.account_id,
+            &mut account.account,
+            held_redeem_batch_ids,
+        );
         let funds_were_claimed = claimed_stake_tokens || claimed_near_tokens;
         if funds_were_claimed {
             self.save_registered_account(&account);
         }
+        if claimed_stake_tokens {
+            let claimed_stake = account.stake_balance() - stake_balance;
+            if claimed_stake.value() > 0 {
+                log(FtTransfer {
+                    old_owner_id: &env::current_account_id(),
+                    new_owner_id: &account.account_id,
+                    amount: claimed_stake.into(),
+                    memo: Some("claim StakeBatchReceipt"),
+                });
+            }
+        }
+        claimed_near_tokens
     }
 
     /// the purpose of this method is to to compute the account's STAKE balance taking into consideration
     /// that there may be unclaimed receipts on the account
     /// - this enables the latest account info to be returned within the context of a contract 'view'
     ///   call - no receipts are physically claimed, i.e., contract state does not change
-    pub(crate) fn apply_receipt_funds_for_view(&self, account: &Account) -> Account {
+    pub(crate) fn apply_receipt_funds_for_view(
+        &self,
+        account_id: &str,
+        account: &Account,
+    ) -> Account {
+        let pending_batches = self.pending_batches(account_id, account);
         let mut account = account.clone();
 
         {
@@ -811,6 +1714,12 @@ impl Contract {
                     account.next_stake_batch = None;
                 }
             }
+
+            for batch in pending_batches.iter().filter_map(PendingBatch::stake_batch) {
+                if let Some(receipt) = self.stake_batch_receipts.get(&batch.id()) {
+                    apply_stake_credit(&mut account, batch, receipt);
+                }
+            }
         }
 
         {
@@ -830,7 +1739,8 @@ impl Contract {
 
                 if let Some(batch) = account.redeem_stake_batch {
                     if batch_pending_withdrawal_id != batch.id() {
-                        if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
+                        if let Some(receipt) = self.claimable_redeem_stake_batch_receipt(batch.id())
+                        {
                             apply_near_credit(&mut account, batch, receipt);
                             account.redeem_stake_batch = None
                         }
@@ -839,7 +1749,8 @@ impl Contract {
 
                 if let Some(batch) = account.next_redeem_stake_batch {
                     if batch_pending_withdrawal_id != batch.id() {
-                        if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
+                        if let Some(receipt) = self.claimable_redeem_stake_batch_receipt(batch.id())
+                        {
                             apply_near_credit(&mut account, batch, receipt);
                             account.next_redeem_stake_batch = None
                         }
@@ -847,25 +1758,34 @@ impl Contract {
                 }
             } else {
                 if let Some(batch) = account.redeem_stake_batch {
-                    if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
+                    if let Some(receipt) = self.claimable_redeem_stake_batch_receipt(batch.id()) {
                         apply_near_credit(&mut account, batch, receipt);
                         account.redeem_stake_batch = None
                     }
                 }
 
                 if let Some(batch) = account.next_redeem_stake_batch {
-                    if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
+                    if let Some(receipt) = self.claimable_redeem_stake_batch_receipt(batch.id()) {
                         apply_near_credit(&mut account, batch, receipt);
                         account.next_redeem_stake_batch = None
                     }
                 }
             }
+
+            for batch in pending_batches
+                .iter()
+                .filter_map(PendingBatch::redeem_stake_batch)
+            {
+                if let Some(receipt) = self.claimable_redeem_stake_batch_receipt(batch.id()) {
+                    apply_near_credit(&mut account, batch, receipt);
+                }
+            }
         }
 
         account
     }
 
-    fn claim_stake_batch_receipts(&mut self, account: &mut Account) -> bool {
+    fn claim_stake_batch_receipts(&mut self, account_id: &str, account: &mut Account) -> bool {
         fn claim_stake_tokens_for_batch(
             contract: &mut Contract,
             account: &mut Account,
@@ -884,6 +1804,7 @@ impl Contract {
             if receipt.all_claimed() {
                 // then delete the receipt and free the storage
                 contract.stake_batch_receipts.remove(&batch.id());
+                contract.stake_batch_receipt_ids.remove(&batch.id());
             } else {
                 contract.stake_batch_receipts.insert(&batch.id(), &receipt);
             }
@@ -907,6 +1828,24 @@ impl Contract {
             }
         }
 
+        // pending batches are claimed once the batch has been staked
+        let mut pending_batches = self.pending_batches(account_id, account);
+        let pending_batches_len = pending_batches.len();
+        pending_batches.retain(|pending_batch| match pending_batch {
+            PendingBatch::Stake(batch) => match self.stake_batch_receipts.get(&batch.id()) {
+                Some(receipt) => {
+                    claim_stake_tokens_for_batch(self, account, *batch, receipt);
+                    false
+                }
+                None => true,
+            },
+            PendingBatch::Redeem(_) => true,
+        });
+        if pending_batches.len() < pending_batches_len {
+            self.save_pending_batches(account_id, account, pending_batches);
+            claimed_funds = true;
+        }
+
         // move the next batch into the current batch as long as the contract is not locked and the
         // funds for the current batch have been claimed
         //
@@ -919,8 +1858,60 @@ impl Contract {
         claimed_funds
     }
 
+    /// for a pending withdrawal, funds can also be claimed against the liquidity pool
+    /// - up to `max_stake` of the account batch is claimed, limited by the available liquidity
+    ///
+    /// Returns the amount of NEAR that was claimed, which is credited to the account.
+    ///
+    /// NOTE: if the receipt is fully claimed, then the redeem workflow is finalized
+    pub(crate) fn claim_pending_withdrawal_from_liquidity(
+        &mut self,
+        account: &mut Account,
+        account_batch: &mut domain::RedeemStakeBatch,
+        mut receipt: domain::RedeemStakeBatchReceipt,
+        max_stake: domain::YoctoStake,
+    ) -> domain::YoctoNear {
+        // how much STAKE did the account redeem in the batch
+        let redeemed_stake = account_batch.balance().amount().min(max_stake);
+
+        let redeemed_stake_near_value = receipt.stake_token_value().stake_to_near(redeemed_stake);
+        let claimed_near = if self.near_liquidity_pool >= redeemed_stake_near_value {
+            redeemed_stake_near_value
+        } else {
+            self.near_liquidity_pool
+        };
+        let redeemable_stake = receipt.stake_token_value().near_to_stake(claimed_near);
+        account_batch.remove(redeemable_stake);
+
+        // claim the STAKE tokens for the account
+        account.apply_near_credit(claimed_near);
+        self.near_liquidity_pool -= claimed_near;
+        self.total_near.credit(claimed_near);
+
+        // track that the STAKE tokens were claimed
+        receipt.stake_tokens_redeemed(redeemable_stake);
+        if receipt.all_claimed() {
+            // this means that effectively all funds have been withdrawn
+            // which means we need to finalize the redeem workflow
+            self.redeem_stake_batch_receipts.remove(&account_batch.id());
+            self.redeem_stake_batch_receipt_ids
+                .remove(&account_batch.id());
+            self.redeem_stake_batch_lock = None;
+            self.pop_redeem_stake_batch();
+        } else {
+            self.redeem_stake_batch_receipts
+                .insert(&account_batch.id(), &receipt);
+        }
+        claimed_near
+    }
+
     /// claim NEAR tokens for redeeming STAKE
-    fn claim_redeem_stake_batch_receipts(&mut self, account: &mut Account) -> bool {
+    pub(crate) fn claim_redeem_stake_batch_receipts(
+        &mut self,
+        account_id: &str,
+        account: &mut Account,
+        held_batch_ids: &[domain::BatchId],
+    ) -> bool {
         fn claim_redeemed_stake_for_batch(
             contract: &mut Contract,
             account: &mut Account,
@@ -941,49 +1932,9 @@ impl Contract {
                 contract
                     .redeem_stake_batch_receipts
                     .remove(&account_batch.id());
-            } else {
-                contract
-                    .redeem_stake_batch_receipts
-                    .insert(&account_batch.id(), &receipt);
-            }
-        }
-
-        /// for a pending withdrawal, funds can also be claimed against the liquidity pool
-        fn claim_redeemed_stake_for_batch_pending_withdrawal(
-            contract: &mut Contract,
-            account: &mut Account,
-            account_batch: &mut domain::RedeemStakeBatch,
-            mut receipt: domain::RedeemStakeBatchReceipt,
-        ) {
-            // how much STAKE did the account redeem in the batch
-            let redeemed_stake = account_batch.balance().amount();
-
-            let redeemed_stake_near_value =
-                receipt.stake_token_value().stake_to_near(redeemed_stake);
-            let claimed_near = if contract.near_liquidity_pool >= redeemed_stake_near_value {
-                redeemed_stake_near_value
-            } else {
-                contract.near_liquidity_pool
-            };
-            let redeemable_stake = receipt.stake_token_value().near_to_stake(claimed_near);
-            account_batch.remove(redeemable_stake);
-
-            // claim the STAKE tokens for the account
-            // let near = receipt.stake_token_value().stake_to_near(redeemable_stake);
-            account.apply_near_credit(claimed_near);
-            contract.near_liquidity_pool -= claimed_near;
-            contract.total_near.credit(claimed_near);
-
-            // track that the STAKE tokens were claimed
-            receipt.stake_tokens_redeemed(redeemable_stake);
-            if receipt.all_claimed() {
-                // this means that effectively all funds have been withdrawn
-                // which means we need to finalize the redeem workflow
                 contract
-                    .redeem_stake_batch_receipts
+                    .redeem_stake_batch_receipt_ids
                     .remove(&account_batch.id());
-                contract.redeem_stake_batch_lock = None;
-                contract.pop_redeem_stake_batch();
             } else {
                 contract
                     .redeem_stake_batch_receipts
@@ -992,6 +1943,8 @@ impl Contract {
         }
 
         let mut claimed_funds = false;
+        // batches that are held back remain in the account until they are claimed explicitly
+        let is_claimable = |batch: &domain::RedeemStakeBatch| !held_batch_ids.contains(&batch.id());
 
         match self.redeem_stake_batch_lock {
             // NEAR funds can be claimed for receipts that are not pending on the unstaked NEAR withdrawal
@@ -1003,17 +1956,24 @@ impl Contract {
                     .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST)
                     .id();
 
-                if let Some(mut batch) = account.redeem_stake_batch {
+                if let Some(mut batch) = account.redeem_stake_batch.filter(is_claimable) {
                     if batch.id() != pending_batch_id {
-                        if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
+                        if let Some(receipt) = self.claimable_redeem_stake_batch_receipt(batch.id())
+                        {
                             claim_redeemed_stake_for_batch(self, account, batch, receipt);
                             account.redeem_stake_batch = None;
                             claimed_funds = true;
                         }
-                    } else if self.near_liquidity_pool.value() > 0 {
+                    } else if self.near_liquidity_pool.value() > 0
+                        && !self.has_queued_redeem_requests(pending_batch_id)
+                    {
                         if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                            claim_redeemed_stake_for_batch_pending_withdrawal(
-                                self, account, &mut batch, receipt,
+                            let redeemed_stake = batch.balance().amount();
+                            self.claim_pending_withdrawal_from_liquidity(
+                                account,
+                                &mut batch,
+                                receipt,
+                                redeemed_stake,
                             );
                             if batch.balance().amount().value() == 0 {
                                 account.redeem_stake_batch = None;
@@ -1025,17 +1985,24 @@ impl Contract {
                     }
                 }
 
-                if let Some(mut batch) = account.next_redeem_stake_batch {
+                if let Some(mut batch) = account.next_redeem_stake_batch.filter(is_claimable) {
                     if batch.id() != pending_batch_id {
-                        if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
+                        if let Some(receipt) = self.claimable_redeem_stake_batch_receipt(batch.id())
+                        {
                             claim_redeemed_stake_for_batch(self, account, batch, receipt);
                             account.next_redeem_stake_batch = None;
                             claimed_funds = true;
                         }
-                    } else if self.near_liquidity_pool.value() > 0 {
+                    } else if self.near_liquidity_pool.value() > 0
+                        && !self.has_queued_redeem_requests(pending_batch_id)
+                    {
                         if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
-                            claim_redeemed_stake_for_batch_pending_withdrawal(
-                                self, account, &mut batch, receipt,
+                            let redeemed_stake = batch.balance().amount();
+                            self.claim_pending_withdrawal_from_liquidity(
+                                account,
+                                &mut batch,
+                                receipt,
+                                redeemed_stake,
                             );
                             if batch.balance().amount().value() == 0 {
                                 account.next_redeem_stake_batch = None;
@@ -1048,16 +2015,16 @@ impl Contract {
                 }
             }
             None => {
-                if let Some(batch) = account.redeem_stake_batch {
-                    if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
+                if let Some(batch) = account.redeem_stake_batch.filter(is_claimable) {
+                    if let Some(receipt) = self.claimable_redeem_stake_batch_receipt(batch.id()) {
                         claim_redeemed_stake_for_batch(self, account, batch, receipt);
                         account.redeem_stake_batch = None;
                         claimed_funds = true;
                     }
                 }
 
-                if let Some(batch) = account.next_redeem_stake_batch {
-                    if let Some(receipt) = self.redeem_stake_batch_receipts.get(&batch.id()) {
+                if let Some(batch) = account.next_redeem_stake_batch.filter(is_claimable) {
+                    if let Some(receipt) = self.claimable_redeem_stake_batch_receipt(batch.id()) {
                         claim_redeemed_stake_for_batch(self, account, batch, receipt);
                         account.next_redeem_stake_batch = None;
                         claimed_funds = true;
@@ -1071,11 +2038,40 @@ impl Contract {
             }
         }
 
+        // pending batches are never the contract's current batch, i.e., they are never pending
+        // withdrawal from the staking pool
+        let mut pending_batches = self.pending_batches(account_id, account);
+        let pending_batches_len = pending_batches.len();
+        pending_batches.retain(|pending_batch| match pending_batch {
+            PendingBatch::Redeem(batch) if is_claimable(batch) => {
+                match self.claimable_redeem_stake_batch_receipt(batch.id()) {
+                    Some(receipt) => {
+                        claim_redeemed_stake_for_batch(self, account, *batch, receipt);
+                        false
+                    }
+                    None => true,
+                }
+            }
+            _ => true,
+        });
+        if pending_batches.len() < pending_batches_len {
+            self.save_pending_batches(account_id, account, pending_batches);
+            claimed_funds = true;
+        }
+
         // shift the next batch into the current batch if the funds have been claimed for the current batch
         // and if the contract is not locked because it is running redeem stake batch workflow.
+        // The next batch is also shifted once it has moved ahead at the contract level, which happens
+        // when the current batch was moved into an unstaked cohort.
         //
         // NOTE: while a contract is locked, all redeem requests must be collected in the next batch
-        if self.redeem_stake_batch_lock.is_none() && account.redeem_stake_batch.is_none() {
+        if account.redeem_stake_batch.is_none()
+            && (self.redeem_stake_batch_lock.is_none()
+                || !Self::is_same_redeem_stake_batch(
+                    account.next_redeem_stake_batch,
+                    self.next_redeem_stake_batch,
+                ))
+        {
             account.redeem_stake_batch = account.next_redeem_stake_batch.take();
         }
 
@@ -1091,10 +2087,72 @@ impl Contract {
 
     /// returns a new [StakeTokenValue](crate::domain::StakeTokenValue) updated with the new staked
     /// NEAR balance.
+    /// staking rewards are the growth in the staked NEAR balance since the STAKE token value was last
+    /// updated. If a [performance fee](crate::config::Config::performance_fee_basis_points) is
+    /// configured, then the fee share of the rewards is minted as STAKE to the treasury, i.e., STAKE
+    /// holders are diluted only by the fee share.
+    /// - the fee STAKE is minted at the STAKE token value net of the fee
+    /// - if the treasury account is not registered, then no fee is minted
+    ///
+    /// NOTE: this must be called before the STAKE token value is updated using the staked NEAR
+    /// balance that was refreshed from the staking pools
+    pub(crate) fn accrue_staking_rewards(&mut self, total_staked_near_balance: domain::YoctoNear) {
+        let total_stake_supply = self.total_stake.amount();
+        let staked_near_value = self.stake_token_value.stake_to_near(total_stake_supply);
+        if total_stake_supply.value() == 0 || total_staked_near_balance <= staked_near_value {
+            return;
+        }
+        let rewards = total_staked_near_balance - staked_near_value;
+
+        let performance_fee = self.config.performance_fee(rewards);
+        let treasury = if performance_fee.value() > 0 {
+            self.lookup_registered_account(&self.treasury_id())
+        } else {
+            None
+        };
+        let performance_fee = match treasury {
+            Some(mut treasury) => {
+                let stake: domain::YoctoStake = (U256::from(performance_fee.value())
+                    * U256::from(total_stake_supply.value())
+                    / U256::from((total_staked_near_balance - performance_fee).value()))
+                .as_u128()
+                .into();
+                treasury.apply_stake_credit(stake);
+                self.total_stake.credit(stake);
+                self.save_registered_account(&treasury);
+                log(FtMint {
+                    owner_id: &treasury.account_id,
+                    amount: stake.into(),
+                    memo: Some("performance fee"),
+                });
+                log(events::PerformanceFeeMinted {
+                    treasury_id: &treasury.account_id,
+                    near: performance_fee.value().into(),
+                    stake: stake.value().into(),
+                });
+                performance_fee
+            }
+            None => 0.into(),
+        };
+
+        log(events::StakingRewardsAccrued {
+            rewards: rewards.value().into(),
+            performance_fee: performance_fee.value().into(),
+            total_staked_near: total_staked_near_balance.value().into(),
+        });
+    }
+
+    /// the updated STAKE token value is recorded in the [StakeTokenValueHistory](crate::domain::StakeTokenValueHistory)
     pub(crate) fn update_stake_token_value(
         &mut self,
         total_staked_near_balance: domain::YoctoNear,
     ) {
+        self.set_stake_token_value(total_staked_near_balance);
+        self.stake_token_value_history
+            .record(self.stake_token_value);
+    }
+
+    fn set_stake_token_value(&mut self, total_staked_near_balance: domain::YoctoNear) {
         let new_stake_token_value = domain::StakeTokenValue::new(
             domain::BlockTimeHeight::from_env(),
             total_staked_near_balance,
@@ -1119,36 +2177,51 @@ impl Contract {
         // withdraw unstaked NEAR that should have been staked but couldn't because of the share conversion
         // rounding. When we need to compensate, then we need to add the compensation to the liquidity
         // to balance everything out.
+        //
+        // However, if the staked NEAR balance drops beyond the rounding tolerance, then the staking
+        // pools have lost funds, e.g., slashing, and the STAKE token value is allowed to decrease -
+        // see [recognize_staking_loss](Contract::recognize_staking_loss)
         let new_stake_near_value = new_stake_token_value.stake_to_near(YOCTO.into());
         let current_stake_near_value = self.stake_token_value.stake_to_near(YOCTO.into());
-        self.stake_token_value = if new_stake_near_value >= current_stake_near_value
+        if new_stake_near_value >= current_stake_near_value
             || total_staked_near_balance.value() == 0
         {
-            new_stake_token_value
-        } else {
-            let current_stake_near_value: U256 = U256::from(current_stake_near_value);
-            let total_stake_supply: U256 = U256::from(self.total_stake.amount());
-            let total_staked_near_balance: U256 = U256::from(total_staked_near_balance.value());
-            // (staked_near_compensation + total_staked_near_balance)    current_stake_near_value
-            // ------------------------------------------------------ =  ------------------------
-            //           total_staked_near_balance                               YOCTO
-            let staked_near_compensation = (current_stake_near_value * total_stake_supply
-                / U256::from(YOCTO))
-                - total_staked_near_balance;
-            // compensation needs to be added back to NEAR liquidity to rebalance the amounts
-            *self.near_liquidity_pool += staked_near_compensation.as_u128();
-            log(events::NearLiquidityAdded {
-                amount: staked_near_compensation.as_u128(),
-                balance: self.near_liquidity_pool.value(),
-            });
-            domain::StakeTokenValue::new(
-                new_stake_token_value.block_time_height(),
-                (total_staked_near_balance + staked_near_compensation)
-                    .as_u128()
-                    .into(),
-                self.total_stake.amount(),
-            )
+            self.stake_token_value = new_stake_token_value;
+            return;
+        }
+
+        let staked_near_shortfall = self
+            .stake_token_value
+            .stake_to_near(self.total_stake.amount())
+            .saturating_sub(total_staked_near_balance.value());
+        if staked_near_shortfall > STAKED_NEAR_ROUNDING_TOLERANCE.value() {
+            self.stake_token_value = self
+                .recognize_staking_loss(total_staked_near_balance, staked_near_shortfall.into());
+            return;
         }
+
+        let current_stake_near_value: U256 = U256::from(current_stake_near_value);
+        let total_stake_supply: U256 = U256::from(self.total_stake.amount());
+        let total_staked_near_balance: U256 = U256::from(total_staked_near_balance.value());
+        // (staked_near_compensation + total_staked_near_balance)    current_stake_near_value
+        // ------------------------------------------------------ =  ------------------------
+        //           total_staked_near_balance                               YOCTO
+        let staked_near_compensation = (current_stake_near_value * total_stake_supply
+            / U256::from(YOCTO))
+            - total_staked_near_balance;
+        // compensation needs to be added back to NEAR liquidity to rebalance the amounts
+        *self.near_liquidity_pool += staked_near_compensation.as_u128();
+        log(events::NearLiquidityAdded {
+            amount: staked_near_compensation.as_u128().into(),
+            balance: self.near_liquidity_pool.value().into(),
+        });
+        self.stake_token_value = domain::StakeTokenValue::new(
+            new_stake_token_value.block_time_height(),
+            (total_staked_near_balance + staked_near_compensation)
+                .as_u128()
+                .into(),
+            self.total_stake.amount(),
+        );
     }
 }
 
@@ -1170,23 +2243,51 @@ pub struct StakingPoolAccount {
 pub trait ExtRedeemingWorkflowCallbacks {
     fn on_run_redeem_stake_batch(
         &mut self,
-        #[callback] staked_balance: near_sdk::json_types::U128,
-    ) -> Promise;
+        staking_pool_id: AccountId,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> near_sdk::PromiseOrValue<()>;
 
     /// ## Success Workflow
     /// 1. store the redeem stake batch receipt
     /// 2. set the redeem stake batch lock state to pending withdrawal
-    fn on_unstake(&mut self);
+    /// 3. record the staking pool that holds the unstaked NEAR
+    fn on_unstake(&mut self, staking_pool_id: AccountId);
+
+    /// the promise results are the staking pool accounts retrieved after unstaking, one per staking
+    /// pool ID
+    ///
+    /// ## Success Workflow
+    /// 1. store the redeem stake batch receipt
+    /// 2. move the NEAR that was unstaked from each staking pool into an unstaked cohort
+    /// 3. pop the redeem stake batch
+    fn on_unstake_split(&mut self, staking_pool_ids: Vec<AccountId>);
 
     fn clear_redeem_lock(&mut self);
 
     /// batch ID is returned when all unstaked NEAR has been withdrawn
     fn on_redeeming_stake_pending_withdrawal(
         &mut self,
+        staking_pool_id: AccountId,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> near_sdk::PromiseOrValue<BatchId>;
+
+    fn on_redeeming_stake_post_withdrawal(&mut self, staking_pool_id: AccountId) -> BatchId;
+
+    /// batch ID is returned when all unstaked NEAR for the cohort has been withdrawn
+    fn on_unstaked_cohort_pending_withdrawal(
+        &mut self,
+        batch_id: BatchId,
+        staking_pool_id: AccountId,
+        keeper_id: AccountId,
         #[callback] staking_pool_account: StakingPoolAccount,
     ) -> near_sdk::PromiseOrValue<BatchId>;
 
-    fn on_redeeming_stake_post_withdrawal(&mut self) -> BatchId;
+    fn on_unstaked_cohort_withdrawn(
+        &mut self,
+        batch_id: BatchId,
+        staking_pool_id: AccountId,
+        keeper_id: AccountId,
+    ) -> BatchId;
 }
 
 #[ext_contract(ext_staking_workflow_callbacks)]
@@ -1194,20 +2295,23 @@ pub trait ExtStakingWorkflowCallbacks {
     /// callback for getting staked balance from staking pool as part of stake batch processing workflow
     ///
     /// ## Success Workflow
-    /// 1. Check if liquidity is needed
-    /// 2. deposit and stake funds with staking pool
-    /// 3. then get account from staking pool
-    /// 4. then invoke [on_deposit_and_stake] callback
+    /// 1. Check the deposit limit orders, if any
+    /// 2. Check if liquidity is needed
+    /// 3. deposit and stake funds with staking pool
+    /// 4. then get account from staking pool
+    /// 5. then invoke [on_deposit_and_stake] callback
     fn on_run_stake_batch(
         &mut self,
+        staking_pool_id: AccountId,
         #[callback] staking_pool_account: StakingPoolAccount,
-    ) -> Promise;
+    ) -> near_sdk::PromiseOrValue<()>;
 
     /// ## Success Workflow
     /// 1. update the StateLock to Staked
     /// 2. invoke [`process_staked_batch`]
     fn on_deposit_and_stake(
         &mut self,
+        staking_pool_id: AccountId,
         near_liquidity: Option<interface::YoctoNear>,
         #[callback] staking_pool_account: StakingPoolAccount,
     ) -> Promise;
@@ -1221,25 +2325,80 @@ pub trait ExtStakingWorkflowCallbacks {
     fn clear_stake_lock(&mut self);
 }
 
-#[ext_contract(ext_callbacks)]
-pub trait Callbacks {
-    fn on_refresh_stake_token_value(
+#[ext_contract(ext_staking_pool_migration_callbacks)]
+pub trait ExtStakingPoolMigrationCallbacks {
+    /// ## Success Workflow
+    /// 1. update the cached balances for the staking pool being migrated
+    /// 2. record the unstaked NEAR that is pending withdrawal
+    ///
+    /// If unstaking failed, then the migration is aborted.
+    fn on_staking_pool_migration_unstaked(&mut self);
+
+    /// ## Success Workflow
+    /// 1. update the cached balances for the staking pool being migrated
+    /// 2. deposit and stake the withdrawn NEAR with the staking pool being migrated to
+    /// 3. then invoke [on_staking_pool_migration_staked] callback
+    ///
+    /// If the withdrawal failed, then the migration reverts back to pending withdrawal.
+    fn on_staking_pool_migration_withdrawn(&mut self) -> PromiseOrValue<()>;
+
+    /// ## Success Workflow
+    /// 1. transfer the weight from the staking pool being migrated to the staking pool being
+    ///    migrated to
+    /// 2. unregister the staking pool being migrated
+    /// 3. queued batches are moved up
+    ///
+    /// If staking failed, then the migration reverts back to withdrawn.
+    fn on_staking_pool_migration_staked(&mut self);
+}
+
+#[ext_contract(ext_netted_batches_workflow_callbacks)]
+pub trait ExtNettedBatchesWorkflowCallbacks {
+    /// ## Success Workflow
+    /// 1. update the STAKE token value
+    /// 2. net the stake batch against the redeem stake batch
+    /// 3. deposit and stake, or unstake, the net NEAR amount with the staking pool
+    /// 4. then get account from staking pool
+    /// 5. then invoke [on_netted_batches_settled] callback
+    ///
+    /// If the batches fully offset each other, then the batches are settled right away.
+    fn on_run_netted_batches(
+        &mut self,
+        staking_pool_id: AccountId,
+        #[callback] staking_pool_account: StakingPoolAccount,
+    ) -> PromiseOrValue<()>;
+
+    /// ## Success Workflow
+    /// 1. create the redeem stake batch receipt
+    /// 2. create the stake batch receipt
+    /// 3. release the locks
+    fn on_netted_batches_settled(
         &mut self,
+        staking_pool_id: AccountId,
         #[callback] staking_pool_account: StakingPoolAccount,
     );
 }
 
+#[ext_contract(ext_callbacks)]
+pub trait Callbacks {
+    /// the staking pool accounts are passed in as promise results - one per staking pool
+    fn on_refresh_stake_token_value(&mut self);
+}
+
 #[near_bindgen]
 impl Contract {
+    /// the promise results are the `get_account` results for each staking pool, in the order that
+    /// the staking pools were registered
+    ///
+    /// ## Panics
+    /// - if not called by self
+    /// - if any of the upstream promises to get the account from the staking pool failed
     #[private]
-    pub fn on_refresh_stake_token_value(
-        &mut self,
-        #[callback] staking_pool_account: StakingPoolAccount,
-    ) -> interface::StakeTokenValue {
-        let staked_balance = self.staked_near_balance(
-            staking_pool_account.staked_balance.into(),
-            staking_pool_account.unstaked_balance.into(),
-        );
+    pub fn on_refresh_stake_token_value(&mut self) -> interface::StakeTokenValue {
+        self.update_staking_pool_balances_from_promise_results();
+        let (staked_balance, unstaked_balance) = self.total_staking_pool_balances();
+        let staked_balance = self.staked_near_balance(staked_balance, unstaked_balance);
+        self.accrue_staking_rewards(staked_balance);
         self.update_stake_token_value(staked_balance);
         self.clear_stake_lock();
         self.stake_token_value.into()
@@ -1507,6 +2666,7 @@ mod test_deposit {
             context.attached_deposit = 0;
             testing_env!(context.clone());
             contract.on_deposit_and_stake(
+                TEST_STAKING_POOL_ID.to_string(),
                 None,
                 StakingPoolAccount {
                     account_id: context.predecessor_account_id.clone(),
@@ -1542,16 +2702,120 @@ mod test_deposit {
         testing_env!(context.clone());
         contract.deposit();
 
-        // Assert
-        let account = contract
-            .lookup_account(to_valid_account_id(test_ctx.account_id))
-            .unwrap();
-        assert_eq!(account.stake.unwrap().amount.value(), YOCTO);
+        // Assert
+        let account = contract
+            .lookup_account(to_valid_account_id(test_ctx.account_id))
+            .unwrap();
+        assert_eq!(account.stake.unwrap().amount.value(), YOCTO);
+
+        // check receipt was claimed
+        context.is_view = true;
+        testing_env!(context.clone());
+        assert!(contract.stake_batch_receipt(batch_id.into()).is_none());
+    }
+
+    #[test]
+    fn deposit_for_registered_beneficiary() {
+        // Arrange
+        let mut test_ctx = TestContext::with_registered_account();
+        let beneficiary_id = "bob.near";
+        test_ctx.register_account(beneficiary_id);
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+
+        // Act
+        let batch_id = test_ctx.deposit_for(to_valid_account_id(beneficiary_id));
+
+        // Assert
+        let beneficiary = test_ctx
+            .lookup_account(to_valid_account_id(beneficiary_id))
+            .unwrap();
+        let stake_batch = beneficiary.stake_batch.unwrap();
+        assert_eq!(stake_batch.id, batch_id);
+        assert_eq!(stake_batch.balance.amount.value(), YOCTO);
+
+        let account = test_ctx
+            .lookup_account(to_valid_account_id(test_ctx.account_id))
+            .unwrap();
+        assert!(account.stake_batch.is_none());
+        assert_eq!(
+            test_ctx.stake_batch.unwrap().balance().amount().value(),
+            YOCTO
+        );
+    }
+
+    #[test]
+    fn deposit_for_unregistered_beneficiary() {
+        // Arrange
+        let mut test_ctx = TestContext::with_registered_account();
+        let beneficiary_id = "bob.near";
+        let account_storage_fee = test_ctx.account_storage_fee().value();
+        let total_account_storage_escrow = test_ctx.total_account_storage_escrow;
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = account_storage_fee + YOCTO;
+        testing_env!(context);
+
+        // Act
+        test_ctx.deposit_for(to_valid_account_id(beneficiary_id));
+
+        // Assert
+        assert!(test_ctx.account_registered(to_valid_account_id(beneficiary_id)));
+        let beneficiary = test_ctx
+            .lookup_account(to_valid_account_id(beneficiary_id))
+            .unwrap();
+        assert_eq!(
+            beneficiary.storage_escrow.amount.value(),
+            account_storage_fee
+        );
+        assert_eq!(
+            beneficiary.stake_batch.unwrap().balance.amount.value(),
+            YOCTO
+        );
+        assert_eq!(
+            test_ctx.total_account_storage_escrow.value(),
+            total_account_storage_escrow.value() + account_storage_fee
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "beneficiary account is not registered - the attached deposit must cover the account storage fee plus the NEAR to stake"
+    )]
+    fn deposit_for_unregistered_beneficiary_with_insufficient_deposit() {
+        let mut test_ctx = TestContext::with_registered_account();
+
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = test_ctx.account_storage_fee().value();
+        testing_env!(context);
+
+        test_ctx.deposit_for(to_valid_account_id("bob.near"));
+    }
+
+    #[test]
+    fn deposit_and_stake_for() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let beneficiary_id = "bob.near";
+        test_ctx.register_account(beneficiary_id);
 
-        // check receipt was claimed
-        context.is_view = true;
-        testing_env!(context.clone());
-        assert!(contract.stake_batch_receipt(batch_id.into()).is_none());
+        let mut context = test_ctx.context.clone();
+        context.attached_deposit = YOCTO;
+        testing_env!(context);
+
+        if let PromiseOrValue::Value(_) =
+            test_ctx.deposit_and_stake_for(to_valid_account_id(beneficiary_id))
+        {
+            panic!("expected deposit to be staked");
+        }
+        let beneficiary = test_ctx
+            .lookup_account(to_valid_account_id(beneficiary_id))
+            .unwrap();
+        assert_eq!(
+            beneficiary.stake_batch.unwrap().balance.amount.value(),
+            YOCTO
+        );
     }
 }
 
@@ -1586,6 +2850,109 @@ mod test_stake_token_value {
                 .into()
         );
     }
+
+    #[test]
+    fn history_and_apy() {
+        const EPOCH_NANOS: u64 = 12 * 60 * 60 * 1_000_000_000;
+
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        test_context.total_stake.credit((100 * YOCTO).into());
+
+        for &(epoch_height, total_staked_near_balance) in
+            &[(10, 100 * YOCTO), (10, 101 * YOCTO), (375, 105 * YOCTO)]
+        {
+            context.epoch_height = epoch_height;
+            context.block_timestamp = epoch_height * EPOCH_NANOS;
+            testing_env!(context.clone());
+            test_context.update_stake_token_value(total_staked_near_balance.into());
+        }
+
+        // the sample is replaced within the same epoch
+        let history = test_context.stake_token_value_history(0, 10);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].total_staked_near_balance, (101 * YOCTO).into());
+        assert_eq!(test_context.stake_token_value_history(11, 10).len(), 1);
+
+        // 1 STAKE grew from 1.01 NEAR to 1.05 NEAR in half a year
+        let apy = test_context.apy(365).unwrap();
+        assert_eq!(apy.epochs, domain::EpochHeight(365).into());
+        assert_eq!(apy.apy_basis_points, 792);
+
+        // not enough history
+        assert!(test_context.apy(366).is_none());
+    }
+}
+
+#[cfg(test)]
+mod test_accrue_staking_rewards {
+    use super::*;
+
+    use crate::{near::YOCTO, test_utils::*};
+
+    #[test]
+    fn performance_fee_is_minted_to_treasury() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let account_id = test_context.account_id;
+        let contract = &mut test_context.contract;
+        contract.treasury_id = Some(account_id.to_string());
+        contract.config.merge(interface::Config {
+            storage_cost_per_byte: None,
+            gas_config: None,
+            contract_owner_earnings_percentage: None,
+            keeper_reward: None,
+            instant_redeem_fee_basis_points: None,
+            performance_fee_basis_points: Some(1000),
+            insurance_buffer_earnings_percentage: None,
+            fee_schedule: None,
+            receipt_expiry_epochs: None,
+        });
+        contract.total_stake.credit((100 * YOCTO).into());
+        contract.update_stake_token_value((100 * YOCTO).into());
+
+        // Act - 10 NEAR staking rewards were earned
+        contract.accrue_staking_rewards((110 * YOCTO).into());
+        contract.update_stake_token_value((110 * YOCTO).into());
+
+        // Assert - 10% of the rewards were minted to the treasury as STAKE
+        let treasury = contract.registered_account(account_id);
+        let minted_stake = treasury.stake_balance();
+        assert!(minted_stake.value() > 0);
+        assert_eq!(
+            contract.total_stake.amount(),
+            (100 * YOCTO).into() + minted_stake
+        );
+        let fee_near_value = contract.stake_token_value.stake_to_near(minted_stake);
+        assert!((YOCTO as i128 - fee_near_value.value() as i128).abs() < 10);
+    }
+
+    #[test]
+    fn no_performance_fee_when_treasury_is_not_registered() {
+        // Arrange
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        contract.treasury_id = Some("treasury.near".to_string());
+        contract.config.force_merge(interface::Config {
+            storage_cost_per_byte: None,
+            gas_config: None,
+            contract_owner_earnings_percentage: None,
+            keeper_reward: None,
+            instant_redeem_fee_basis_points: None,
+            performance_fee_basis_points: Some(1000),
+            insurance_buffer_earnings_percentage: None,
+            fee_schedule: None,
+            receipt_expiry_epochs: None,
+        });
+        contract.total_stake.credit((100 * YOCTO).into());
+        contract.update_stake_token_value((100 * YOCTO).into());
+
+        // Act
+        contract.accrue_staking_rewards((110 * YOCTO).into());
+
+        // Assert
+        assert_eq!(contract.total_stake.amount(), (100 * YOCTO).into());
+    }
 }
 
 #[cfg(test)]
@@ -1851,9 +3218,10 @@ mod test_stake {
                 context.predecessor_account_id = env::current_account_id();
                 testing_env!(context.clone());
                 contract.on_deposit_and_stake(
+                    TEST_STAKING_POOL_ID.to_string(),
                     None,
                     StakingPoolAccount {
-                        account_id: contract.staking_pool_id.clone(),
+                        account_id: env::current_account_id(),
                         unstaked_balance: 7.into(),
                         staked_balance: (YOCTO - 7).into(),
                         can_withdraw: true,
@@ -1923,9 +3291,10 @@ mod test_stake {
         context.predecessor_account_id = env::current_account_id();
         testing_env!(context.clone());
         contract.on_deposit_and_stake(
+            TEST_STAKING_POOL_ID.to_string(),
             None,
             StakingPoolAccount {
-                account_id: contract.staking_pool_id(),
+                account_id: env::current_account_id(),
                 unstaked_balance: 10.into(),
                 staked_balance: (YOCTO - 10).into(),
                 can_withdraw: true,
@@ -2185,6 +3554,7 @@ collected_earnings: {} -> {}
             testing_env!(context);
             test_context.deposit_and_stake();
             test_context.on_deposit_and_stake(
+                TEST_STAKING_POOL_ID.to_string(),
                 None,
                 StakingPoolAccount {
                     account_id: env::current_account_id(),
@@ -2203,15 +3573,18 @@ collected_earnings: {} -> {}
             let mut context = test_context.context.clone();
             context.predecessor_account_id = env::current_account_id();
             testing_env!(context);
-            test_context.on_run_redeem_stake_batch(StakingPoolAccount {
-                account_id: env::current_account_id(),
-                unstaked_balance: 0.into(),
-                staked_balance: YOCTO.into(),
-                can_withdraw: true,
-            });
+            test_context.on_run_redeem_stake_batch(
+                TEST_STAKING_POOL_ID.to_string(),
+                StakingPoolAccount {
+                    account_id: env::current_account_id(),
+                    unstaked_balance: 0.into(),
+                    staked_balance: YOCTO.into(),
+                    can_withdraw: true,
+                },
+            );
 
             set_env_with_success_promise_result(&mut test_context);
-            test_context.on_unstake();
+            test_context.on_unstake(TEST_STAKING_POOL_ID.to_string());
             test_context.clear_redeem_lock();
         }
 
@@ -2254,12 +3627,15 @@ collected_earnings: {} -> {}
         let mut context = test_context.context.clone();
         context.predecessor_account_id = env::current_account_id();
         testing_env!(context);
-        test_context.on_run_stake_batch(StakingPoolAccount {
-            account_id: env::current_account_id(),
-            unstaked_balance: YOCTO.into(),
-            staked_balance: 0.into(),
-            can_withdraw: false,
-        });
+        test_context.on_run_stake_batch(
+            TEST_STAKING_POOL_ID.to_string(),
+            StakingPoolAccount {
+                account_id: env::current_account_id(),
+                unstaked_balance: YOCTO.into(),
+                staked_balance: 0.into(),
+                can_withdraw: false,
+            },
+        );
 
         let receipts = deserialize_receipts();
         assert_eq!(receipts.len(), 2);
@@ -2293,6 +3669,7 @@ collected_earnings: {} -> {}
         context.predecessor_account_id = env::current_account_id();
         testing_env!(context);
         test_context.on_deposit_and_stake(
+            TEST_STAKING_POOL_ID.to_string(),
             Some((YOCTO / 2).into()),
             StakingPoolAccount {
                 account_id: env::current_account_id(),
@@ -2335,6 +3712,7 @@ collected_earnings: {} -> {}
             testing_env!(context);
             test_context.deposit_and_stake();
             test_context.on_deposit_and_stake(
+                TEST_STAKING_POOL_ID.to_string(),
                 None,
                 StakingPoolAccount {
                     account_id: env::current_account_id(),
@@ -2353,15 +3731,18 @@ collected_earnings: {} -> {}
             let mut context = test_context.context.clone();
             context.predecessor_account_id = env::current_account_id();
             testing_env!(context);
-            test_context.on_run_redeem_stake_batch(StakingPoolAccount {
-                account_id: env::current_account_id(),
-                unstaked_balance: 0.into(),
-                staked_balance: YOCTO.into(),
-                can_withdraw: true,
-            });
+            test_context.on_run_redeem_stake_batch(
+                TEST_STAKING_POOL_ID.to_string(),
+                StakingPoolAccount {
+                    account_id: env::current_account_id(),
+                    unstaked_balance: 0.into(),
+                    staked_balance: YOCTO.into(),
+                    can_withdraw: true,
+                },
+            );
 
             set_env_with_success_promise_result(&mut test_context);
-            test_context.on_unstake();
+            test_context.on_unstake(TEST_STAKING_POOL_ID.to_string());
             test_context.clear_redeem_lock();
         }
 
@@ -2404,12 +3785,15 @@ collected_earnings: {} -> {}
         let mut context = test_context.context.clone();
         context.predecessor_account_id = env::current_account_id();
         testing_env!(context);
-        test_context.on_run_stake_batch(StakingPoolAccount {
-            account_id: env::current_account_id(),
-            unstaked_balance: YOCTO.into(),
-            staked_balance: 0.into(),
-            can_withdraw: false,
-        });
+        test_context.on_run_stake_batch(
+            TEST_STAKING_POOL_ID.to_string(),
+            StakingPoolAccount {
+                account_id: env::current_account_id(),
+                unstaked_balance: YOCTO.into(),
+                staked_balance: 0.into(),
+                can_withdraw: false,
+            },
+        );
 
         let receipts = deserialize_receipts();
         assert_eq!(receipts.len(), 2);
@@ -2448,6 +3832,7 @@ collected_earnings: {} -> {}
         context.predecessor_account_id = env::current_account_id();
         testing_env!(context);
         test_context.on_deposit_and_stake(
+            TEST_STAKING_POOL_ID.to_string(),
             Some((YOCTO).into()),
             StakingPoolAccount {
                 account_id: env::current_account_id(),
@@ -2497,6 +3882,7 @@ collected_earnings: {} -> {}
 
         testing_env!(test_context.context.clone());
         test_context.on_deposit_and_stake(
+            TEST_STAKING_POOL_ID.to_string(),
             None,
             StakingPoolAccount {
                 account_id: env::current_account_id(),
@@ -3069,6 +4455,175 @@ mod test_withdraw_all {
     }
 }
 
+#[cfg(test)]
+mod test_instant_redeem {
+    use super::*;
+
+    use crate::{near::YOCTO, test_utils::*};
+
+    fn credit_stake(contract: &mut Contract, account_id: &str, amount: u128) {
+        let mut account = contract.registered_account(account_id);
+        account.apply_stake_credit(amount.into());
+        contract.save_registered_account(&account);
+        contract.total_stake.credit(amount.into());
+    }
+
+    #[test]
+    fn with_sufficient_liquidity() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        credit_stake(contract, test_context.account_id, 10 * YOCTO);
+        contract.near_liquidity_pool = (20 * YOCTO).into();
+        assert_eq!(contract.instant_redeem_liquidity(), (20 * YOCTO).into());
+
+        let fee = contract.config.instant_redeem_fee((10 * YOCTO).into());
+        let near_out = contract.instant_redeem((10 * YOCTO).into(), 0.into());
+        assert_eq!(near_out, (10 * YOCTO - fee.value()).into());
+
+        let account = contract.registered_account(test_context.account_id);
+        assert!(account.stake.is_none());
+        assert_eq!(contract.total_stake.amount(), 0.into());
+        assert_eq!(contract.near_liquidity_pool, (10 * YOCTO).into());
+        assert_eq!(contract.instant_redeem_fees, fee);
+
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].receiver_id, test_context.account_id);
+        match receipts[0].actions.first().unwrap() {
+            Action::Transfer { deposit } => assert_eq!(*deposit, near_out.value()),
+            _ => panic!("expected transfer action"),
+        }
+
+        // the fee is distributed to the user accounts
+        contract.distribute_earnings();
+        assert_eq!(contract.instant_redeem_fees, 0.into());
+        assert!(contract.near_liquidity_pool >= (10 * YOCTO + fee.value()).into());
+    }
+
+    #[test]
+    #[should_panic(expected = "NEAR liquidity is insufficient to fulfill instant redeem request")]
+    fn with_insufficient_liquidity() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        credit_stake(contract, test_context.account_id, 10 * YOCTO);
+        contract.near_liquidity_pool = (5 * YOCTO).into();
+        contract.instant_redeem((10 * YOCTO).into(), 0.into());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "instant redeem NEAR amount after fees is less than the specified min NEAR out"
+    )]
+    fn below_min_near_out() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+
+        credit_stake(contract, test_context.account_id, 10 * YOCTO);
+        contract.near_liquidity_pool = (20 * YOCTO).into();
+        contract.instant_redeem((10 * YOCTO).into(), (10 * YOCTO).into());
+    }
+}
+
+#[cfg(test)]
+mod test_run_netted_batches {
+    use super::*;
+
+    use crate::{near::YOCTO, test_utils::*};
+
+    fn set_batches(contract: &mut Contract) {
+        *contract.batch_id_sequence += 1;
+        contract.stake_batch = Some(StakeBatch::new(
+            contract.batch_id_sequence,
+            (100 * YOCTO).into(),
+        ));
+        *contract.batch_id_sequence += 1;
+        contract.redeem_stake_batch = Some(RedeemStakeBatch::new(
+            contract.batch_id_sequence,
+            (40 * YOCTO).into(),
+        ));
+    }
+
+    /// When the batches are netted
+    /// Then the contract is locked for staking and unstaking
+    /// And the account is looked up from the staking pool
+    /// And the locks are released if the workflow fails
+    #[test]
+    fn no_locks() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        set_batches(contract);
+
+        contract.run_netted_batches();
+        assert_eq!(contract.stake_batch_lock, Some(StakeLock::Staking));
+        assert!(contract.is_unstaking());
+
+        let receipts = deserialize_receipts();
+        let method_names: Vec<&str> = receipts
+            .iter()
+            .map(|receipt| match &receipt.actions[0] {
+                Action::FunctionCall { method_name, .. } => method_name.as_str(),
+                _ => panic!("expected func call action"),
+            })
+            .collect();
+        assert_eq!(
+            method_names,
+            vec![
+                "get_account",
+                "on_run_netted_batches",
+                "clear_stake_lock",
+                "clear_redeem_lock"
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unstaking is blocked until all unstaked NEAR can be withdrawn")]
+    fn pending_withdrawal() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        set_batches(contract);
+        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+
+        contract.run_netted_batches();
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no stake batch")]
+    fn no_stake_batch() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        set_batches(contract);
+        contract.stake_batch = None;
+
+        contract.run_netted_batches();
+    }
+
+    /// Given the net amount is unstaked
+    /// And no single staking pool has enough staked NEAR to cover it
+    /// When the batches are netted
+    /// Then the contract panics because the net unstake is not split across staking pools
+    #[test]
+    #[should_panic(
+        expected = "no single staking pool can cover the net NEAR to unstake - run the batches separately"
+    )]
+    fn net_unstake_exceeds_staking_pool_balance() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        set_batches(contract);
+        contract.redeem_stake_batch = Some(RedeemStakeBatch::new(
+            contract.batch_id_sequence,
+            (200 * YOCTO).into(),
+        ));
+        contract
+            .staking_pool_mut(TEST_STAKING_POOL_ID)
+            .update_balances((50 * YOCTO).into(), 0.into());
+
+        contract.run_netted_batches();
+    }
+}
+
 #[cfg(test)]
 mod test_claim_receipts {
     use super::*;
@@ -4277,12 +5832,19 @@ mod test {
                         staked_balance: (99 * YOCTO).into(),
                         can_withdraw: true,
                     };
-                    contract.on_run_stake_batch(staking_pool_account.clone()); // callback
+                    contract.on_run_stake_batch(
+                        TEST_STAKING_POOL_ID.to_string(),
+                        staking_pool_account.clone(),
+                    ); // callback
 
                     {
                         context.predecessor_account_id = context.current_account_id.clone();
                         testing_env!(context.clone());
-                        contract.on_deposit_and_stake(None, staking_pool_account); // callback
+                        contract.on_deposit_and_stake(
+                            TEST_STAKING_POOL_ID.to_string(),
+                            None,
+                            staking_pool_account,
+                        ); // callback
                         contract.process_staked_batch();
 
                         let _receipt = contract.stake_batch_receipts.get(&batch_id).expect(
@@ -4600,7 +6162,7 @@ mod test {
         let receipts = receipts.as_slice();
         {
             let receipt = receipts.first().unwrap();
-            assert_eq!(receipt.receiver_id, contract.staking_pool_id);
+            assert_eq!(receipt.receiver_id, TEST_STAKING_POOL_ID);
 
             let actions = receipt.actions.as_slice();
             let func_call_action = actions.first().unwrap();
@@ -4627,7 +6189,7 @@ mod test {
                     method_name, args, ..
                 } => {
                     assert_eq!(method_name, "on_run_redeem_stake_batch");
-                    assert!(args.is_empty());
+                    assert!(args.contains(TEST_STAKING_POOL_ID));
                 }
                 _ => panic!("expected func call action"),
             }
@@ -4670,7 +6232,7 @@ mod test {
         let receipts = receipts.as_slice();
         {
             let receipt = receipts.first().unwrap();
-            assert_eq!(receipt.receiver_id, contract.staking_pool_id);
+            assert_eq!(receipt.receiver_id, TEST_STAKING_POOL_ID);
 
             let actions = receipt.actions.as_slice();
             let func_call_action = actions.first().unwrap();
@@ -4697,7 +6259,7 @@ mod test {
                     method_name, args, ..
                 } => {
                     assert_eq!(method_name, "on_run_redeem_stake_batch");
-                    assert!(args.is_empty());
+                    assert!(args.contains(TEST_STAKING_POOL_ID));
                 }
                 _ => panic!("expected func call action"),
             }
@@ -4813,6 +6375,7 @@ mod test {
             &domain::RedeemStakeBatchReceipt::new((10 * YOCTO).into(), contract.stake_token_value),
         );
         contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        contract.unstaking_pool_id = Some(TEST_STAKING_POOL_ID.to_string());
         context.epoch_height += UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK.value();
         testing_env!(context.clone());
         contract.unstake();
@@ -4826,7 +6389,7 @@ mod test {
         let receipts = receipts.as_slice();
         {
             let receipt = receipts.first().unwrap();
-            assert_eq!(receipt.receiver_id, contract.staking_pool_id);
+            assert_eq!(receipt.receiver_id, TEST_STAKING_POOL_ID);
 
             let actions = receipt.actions.as_slice();
             let func_call_action = actions.first().unwrap();
@@ -4851,13 +6414,121 @@ mod test {
                     method_name, args, ..
                 } => {
                     assert_eq!(method_name, "on_redeeming_stake_pending_withdrawal");
-                    assert!(args.is_empty());
+                    assert!(args.contains(TEST_STAKING_POOL_ID));
                 }
                 _ => panic!("expected func call action"),
             }
         }
     }
 
+    /// Given there is a pending withdrawal whose unstaked NEAR is not yet available for withdrawal
+    /// And there is a next redeem stake batch
+    /// And there is another staking pool with staked NEAR
+    /// When the redeem batch is run
+    /// Then the pending withdrawal is moved into an unstaked cohort
+    /// And the next batch is unstaked from the other staking pool
+    #[test]
+    fn unstake_pending_withdrawal_moved_to_unstaked_cohort() {
+        const TEST_STAKING_POOL_ID_2: &str = "staking-pool-2.near";
+
+        let mut test_ctx = TestContext::with_registered_account();
+        let contract = &mut test_ctx.contract;
+
+        contract
+            .staking_pools
+            .push(domain::StakingPool::new(TEST_STAKING_POOL_ID_2, 1));
+        contract.staking_pools[0].update_balances((100 * YOCTO).into(), (10 * YOCTO).into());
+        contract.staking_pools[1].update_balances((100 * YOCTO).into(), 0.into());
+
+        *contract.batch_id_sequence += 1;
+        let pending_batch_id = contract.batch_id_sequence;
+        contract.redeem_stake_batch =
+            Some(RedeemStakeBatch::new(pending_batch_id, (10 * YOCTO).into()));
+        contract.redeem_stake_batch_receipts.insert(
+            &pending_batch_id,
+            &domain::RedeemStakeBatchReceipt::new((10 * YOCTO).into(), contract.stake_token_value),
+        );
+        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        contract.unstaking_pool_id = Some(TEST_STAKING_POOL_ID.to_string());
+        *contract.batch_id_sequence += 1;
+        contract.next_redeem_stake_batch = Some(RedeemStakeBatch::new(
+            contract.batch_id_sequence,
+            (5 * YOCTO).into(),
+        ));
+        assert!(contract.can_unstake());
+
+        contract.unstake();
+        assert_eq!(
+            contract.redeem_stake_batch_lock,
+            Some(RedeemLock::Unstaking)
+        );
+        assert_eq!(
+            contract.redeem_stake_batch.unwrap().id(),
+            contract.batch_id_sequence
+        );
+        let cohorts = contract.unstaked_cohorts();
+        assert_eq!(cohorts.len(), 1);
+        assert_eq!(cohorts[0].batch_id, pending_batch_id.into());
+        assert_eq!(cohorts[0].staking_pool_id, TEST_STAKING_POOL_ID);
+        assert_eq!(cohorts[0].unstaked_near, (10 * YOCTO).into());
+
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts[0].receiver_id, TEST_STAKING_POOL_ID_2);
+        match &receipts[0].actions[0] {
+            Action::FunctionCall { method_name, .. } => assert_eq!(method_name, "get_account"),
+            _ => panic!("expected func call action"),
+        }
+    }
+
+    /// Given an account has redeemed STAKE
+    /// And the batch was moved into an unstaked cohort
+    /// Then the account cannot claim the NEAR funds until the cohort is withdrawn
+    #[test]
+    fn claim_receipt_funds_on_unstaked_cohort() {
+        let mut test_ctx = TestContext::with_registered_account();
+        let contract = &mut test_ctx.contract;
+
+        let mut account = contract.predecessor_registered_account();
+        account.redeem_stake_batch = Some(domain::RedeemStakeBatch::new(
+            contract.batch_id_sequence,
+            (10 * YOCTO).into(),
+        ));
+        contract.redeem_stake_batch_receipts.insert(
+            &contract.batch_id_sequence,
+            &domain::RedeemStakeBatchReceipt::new((10 * YOCTO).into(), contract.stake_token_value),
+        );
+        contract.unstaked_cohorts.push(domain::UnstakedCohort::new(
+            contract.batch_id_sequence,
+            TEST_STAKING_POOL_ID,
+            (10 * YOCTO).into(),
+        ));
+
+        contract.claim_receipt_funds(&mut account);
+        assert!(account.near.is_none());
+        assert!(account.redeem_stake_batch.is_some());
+
+        // redeeming more STAKE is collected in the account's next batch slot
+        account.apply_stake_credit((5 * YOCTO).into());
+        contract.save_registered_account(&account);
+        contract.redeem((5 * YOCTO).into());
+        let account = contract.predecessor_registered_account();
+        assert_eq!(
+            account.next_redeem_stake_batch.unwrap().id(),
+            contract.redeem_stake_batch.unwrap().id()
+        );
+
+        // once the cohort is withdrawn, the NEAR funds can be claimed
+        contract.unstaked_cohorts.clear();
+        contract.claim_receipts();
+        let account = contract.predecessor_registered_account();
+        assert_eq!(account.near.unwrap().amount(), (10 * YOCTO).into());
+        assert_eq!(
+            account.redeem_stake_batch.unwrap().id(),
+            contract.redeem_stake_batch.unwrap().id()
+        );
+        assert!(account.next_redeem_stake_batch.is_none());
+    }
+
     /// Given an account has redeemed STAKE
     /// And the batch has completed
     /// Then the account can claim the NEAR funds
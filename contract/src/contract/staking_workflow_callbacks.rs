@@ -4,13 +4,22 @@ use crate::{
     domain::{self, YoctoNear, YoctoStake, TGAS},
     errors::illegal_state::STAKE_BATCH_SHOULD_EXIST,
     ext_staking_workflow_callbacks,
-    interface::staking_service::events::{NearLiquidityAdded, PendingWithdrawalCleared, Staked},
+    interface::{
+        fungible_token::events::FtMint,
+        staking_service::events::{
+            NearLiquidityAdded, PendingWithdrawalCleared, StakeBatchCancelled, Staked,
+        },
+    },
     near::{log, NO_DEPOSIT},
 };
-use near_sdk::{env, near_bindgen, Promise};
+use near_sdk::{env, near_bindgen, AccountId, Promise, PromiseOrValue};
 
 #[near_bindgen]
 impl Contract {
+    /// if the batch has limit orders, then the STAKE token value is refreshed and the limit orders
+    /// are checked - see [apply_deposit_limit_orders](Contract::apply_deposit_limit_orders)
+    /// - if all NEAR is returned to the accounts, then the batch is cancelled and the workflow ends
+    ///
     /// if unstaked balance is non-zero and liquidity is needed for pending withdrawal, then
     /// [add_liquidity_then_deposit_and_stake](Contract::add_liquidity_then_deposit_and_stake)
     ///
@@ -23,20 +32,45 @@ impl Contract {
     /// - if not called by self
     /// - if there is no [StakeBatch](crate::domain::StakeBatch)
     /// - if the upstream promise to get the account from the staking pool failed
+    /// - if the staking pool is not registered with the contract
     #[private]
     pub fn on_run_stake_batch(
         &mut self,
+        staking_pool_id: AccountId,
         #[callback] staking_pool_account: StakingPoolAccount,
-    ) -> Promise {
+    ) -> PromiseOrValue<()> {
         // the batch should always be present because the purpose of this callback is a step
         // in the batch processing workflow
         // - if the callback was called by itself, and the batch is not present, then there is a bug
         let batch = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
+        self.update_staking_pool_balances(&staking_pool_id, &staking_pool_account);
+
+        if self.deposit_limit_orders.contains(batch.id()) {
+            // the limit orders are checked against the refreshed STAKE token value before the NEAR
+            // is deposited into the staking pool
+            let (staked_balance, unstaked_balance) = self.total_staking_pool_balances();
+            let staked_balance = self.staked_near_balance(staked_balance, unstaked_balance);
+            self.accrue_staking_rewards(staked_balance);
+            self.update_stake_token_value(staked_balance);
+            self.apply_deposit_limit_orders();
+            if self.stake_batch.is_none() {
+                // all NEAR in the batch was returned to the accounts
+                log(StakeBatchCancelled {
+                    batch_id: batch.id().value().into(),
+                });
+                self.pop_stake_batch();
+                self.stake_batch_lock = None;
+                self.keeper_rewards.take_stake_batch_keeper();
+                return PromiseOrValue::Value(());
+            }
+        }
+        let batch = self.stake_batch.expect(STAKE_BATCH_SHOULD_EXIST);
 
         let is_liquidity_needed = self.is_liquidity_needed();
         let unstaked_balance = staking_pool_account.unstaked_balance.0;
         if unstaked_balance > 0 && is_liquidity_needed {
-            self.add_liquidity_then_deposit_and_stake(unstaked_balance, batch)
+            self.add_liquidity_then_deposit_and_stake(&staking_pool_id, unstaked_balance, batch)
+                .into()
         } else {
             // if liquidity is not needed, then stake it
             let stake_amount = if is_liquidity_needed {
@@ -47,11 +81,12 @@ impl Contract {
                 batch.balance().amount()
             };
 
-            self.staking_pool_promise()
+            self.staking_pool_promise(&staking_pool_id)
                 .deposit_and_stake(stake_amount)
                 .get_account()
                 .promise()
-                .then(self.invoke_on_deposit_and_stake(None))
+                .then(self.invoke_on_deposit_and_stake(&staking_pool_id, None))
+                .into()
         }
     }
 
@@ -65,20 +100,26 @@ impl Contract {
     ///    - [Staked](crate::interface::staking_service::events::Staked) event is logged
     /// 5. pop the [StakeBatch](crate::domain::StakeBatch)
     ///
+    /// The staked balances that are locked in are summed across all staking pools.
+    ///
     /// ## Panics
     /// - if not called by self
     /// - if [StakeBatch](crate::domain::StakeBatch) does not exist
     /// - if any of the upstream Promises failed
+    /// - if the staking pool is not registered with the contract
     #[private]
     pub fn on_deposit_and_stake(
         &mut self,
+        staking_pool_id: AccountId,
         near_liquidity: Option<interface::YoctoNear>,
         #[callback] staking_pool_account: StakingPoolAccount,
     ) -> Promise {
+        self.update_staking_pool_balances(&staking_pool_id, &staking_pool_account);
+        let (staked_balance, unstaked_balance) = self.total_staking_pool_balances();
         self.stake_batch_lock = Some(StakeLock::Staked {
             near_liquidity: near_liquidity.map(Into::into),
-            staked_balance: staking_pool_account.staked_balance.0.into(),
-            unstaked_balance: staking_pool_account.unstaked_balance.0.into(),
+            staked_balance,
+            unstaked_balance,
         });
         self.invoke_process_stake_batch()
     }
@@ -92,6 +133,8 @@ impl Contract {
     /// 4. create [StakeBatchReceipt](crate::domain::StakeBatchReceipt)
     ///    - [Staked](crate::interface::staking_service::events::Staked) event is logged
     /// 5. pop the [StakeBatch](crate::domain::StakeBatch)
+    /// 6. allocate NEAR liquidity to the redeem queue
+    /// 7. reward the keeper that ran the batch
     ///
     /// ## Panics
     /// - if not called by self
@@ -111,8 +154,8 @@ impl Contract {
                 if near_liquidity.value() > 0 {
                     *self.near_liquidity_pool += near_liquidity.value();
                     log(NearLiquidityAdded {
-                        amount: near_liquidity.value(),
-                        balance: self.near_liquidity_pool.value(),
+                        amount: near_liquidity.value().into(),
+                        balance: self.near_liquidity_pool.value().into(),
                     });
 
                     // check if liquidity can clear the pending withdrawal
@@ -126,6 +169,7 @@ impl Contract {
                             self.near_liquidity_pool -= stake_near_value;
                             self.total_near.credit(stake_near_value);
                             self.redeem_stake_batch_lock = None;
+                            self.unstaking_pool_id = None;
                             self.pop_redeem_stake_batch();
                         }
                     }
@@ -135,7 +179,11 @@ impl Contract {
             self.mint_stake_and_update_stake_token_value(staked_balance, unstaked_balance, batch);
             self.create_stake_batch_receipt(batch);
             self.pop_stake_batch();
-            self.stake_batch_lock = None
+            self.stake_batch_lock = None;
+            self.allocate_redeem_queue_liquidity();
+
+            let keeper_id = self.keeper_rewards.take_stake_batch_keeper();
+            self.pay_keeper_reward(keeper_id);
         } else {
             panic!("ERROR: illegal state - should only be called when StakeLock::Staked - current state is: {:?}", self.stake_batch_lock);
         }
@@ -143,6 +191,9 @@ impl Contract {
 }
 
 impl Contract {
+    /// the STAKE that is minted for the batch is held by the contract until it is claimed by the
+    /// batch accounts - see [claim_receipt_funds](Contract::claim_receipt_funds)
+    /// - NEP-141 `ft_mint` event is logged
     pub fn mint_stake_and_update_stake_token_value(
         &mut self,
         staked_balance: YoctoNear,
@@ -153,6 +204,7 @@ impl Contract {
         // this is minted using the prior STAKE token value - however, if rewards were issued, then
         // the STAKE token value is stale
         let stake_minted_amount = self.mint_stake(batch);
+        self.accrue_staking_rewards(staked_balance);
         self.update_stake_token_value(staked_balance.into());
         // recompute the batch STAKE value using the updated staked NEAR balance
         let batch_stake_value = self
@@ -164,6 +216,12 @@ impl Contract {
             self.total_stake.credit(batch_stake_value);
             self.update_stake_token_value(staked_balance.into());
         }
+
+        log(FtMint {
+            owner_id: &env::current_account_id(),
+            amount: batch_stake_value.into(),
+            memo: Some(&format!("StakeBatch({})", batch.id().value())),
+        });
     }
 
     /// the staked NEAR balance is total amount of NEAR deposited and staked in the staking pool
@@ -180,14 +238,22 @@ impl Contract {
     ///   count the NEAR that was unstaked due to STAKE that was redeemed. In this case we need to
     ///   subtract the amount that is pending withdrawal and add back in any liquidity (because liquidity
     ///   is derived from restaking unstaked NEAR)
+    ///
+    /// - the NEAR that is pending withdrawal for [UnstakedCohort](crate::domain::UnstakedCohort)s is
+    ///   also still unstaked in the staking pools, and is subtracted
+    ///
+    /// - while staking pools are being migrated, the NEAR being migrated is unstaked and then held by
+    ///   the contract until it is staked with the new staking pool - the NEAR being migrated still
+    ///   counts towards the staked NEAR balance
     pub(crate) fn staked_near_balance(
         &self,
         staked_balance: YoctoNear,
         unstaked_balance: YoctoNear,
     ) -> YoctoNear {
-        if staked_balance.value() == 0 {
+        if staked_balance.value() == 0 && !self.is_migrating_staking_pool() {
             return 0.into();
         }
+        let unstaked_balance = unstaked_balance + self.staking_pool_migration_withdrawn_balance();
         let balance = match self.get_pending_withdrawal() {
             Some(receipt) => {
                 staked_balance.value() + unstaked_balance.value()
@@ -196,7 +262,9 @@ impl Contract {
             }
             _ => staked_balance.value() + unstaked_balance.value(),
         };
-        balance.into()
+        balance
+            .saturating_sub(self.unstaked_cohorts_near_balance().value())
+            .into()
     }
 
     pub(crate) fn is_liquidity_needed(&self) -> bool {
@@ -208,6 +276,7 @@ impl Contract {
 
     fn add_liquidity_then_deposit_and_stake(
         &mut self,
+        staking_pool_id: &str,
         unstaked_balance: u128,
         batch: StakeBatch,
     ) -> Promise {
@@ -220,27 +289,32 @@ impl Contract {
 
         let deposit_amount = batch.balance().amount().value() - near_liquidity;
         if deposit_amount > 0 {
-            self.staking_pool_promise()
+            self.staking_pool_promise(staking_pool_id)
                 .deposit_then_stake(deposit_amount.into(), batch.balance().amount())
                 .get_account()
                 .promise()
-                .then(self.invoke_on_deposit_and_stake(Some(near_liquidity.into())))
+                .then(
+                    self.invoke_on_deposit_and_stake(staking_pool_id, Some(near_liquidity.into())),
+                )
         } else {
-            self.staking_pool_promise()
+            self.staking_pool_promise(staking_pool_id)
                 .stake(batch.balance().amount())
                 .get_account()
                 .promise()
-                .then(self.invoke_on_deposit_and_stake(Some(near_liquidity.into())))
+                .then(
+                    self.invoke_on_deposit_and_stake(staking_pool_id, Some(near_liquidity.into())),
+                )
         }
     }
 
     /// creates a create for the batch and saves it to storage
     /// - [Staked](crate::interface::staking_service::events::Staked) event is logged
-    fn create_stake_batch_receipt(&mut self, batch: domain::StakeBatch) {
+    pub(crate) fn create_stake_batch_receipt(&mut self, batch: domain::StakeBatch) {
         let stake_batch_receipt =
             domain::StakeBatchReceipt::new(batch.balance().amount(), self.stake_token_value);
         self.stake_batch_receipts
             .insert(&batch.id(), &stake_batch_receipt);
+        self.stake_batch_receipt_ids.insert(&batch.id());
 
         log(Staked::new(batch.id(), &stake_batch_receipt));
     }
@@ -255,15 +329,16 @@ impl Contract {
     }
 
     /// moves the next batch into the current batch
-    fn pop_stake_batch(&mut self) {
+    pub(crate) fn pop_stake_batch(&mut self) {
         self.stake_batch = self.next_stake_batch.take();
     }
 }
 
 /// staking NEAR workflow callback invocations
 impl Contract {
-    pub(crate) fn invoke_on_run_stake_batch(&self) -> Promise {
+    pub(crate) fn invoke_on_run_stake_batch(&self, staking_pool_id: &str) -> Promise {
         ext_staking_workflow_callbacks::on_run_stake_batch(
+            staking_pool_id.to_string(),
             &env::current_account_id(),
             NO_DEPOSIT.into(),
             self.config
@@ -282,8 +357,13 @@ impl Contract {
         )
     }
 
-    pub(crate) fn invoke_on_deposit_and_stake(&self, near_liquidity: Option<YoctoNear>) -> Promise {
+    pub(crate) fn invoke_on_deposit_and_stake(
+        &self,
+        staking_pool_id: &str,
+        near_liquidity: Option<YoctoNear>,
+    ) -> Promise {
         ext_staking_workflow_callbacks::on_deposit_and_stake(
+            staking_pool_id.to_string(),
             near_liquidity.map(Into::into),
             &env::current_account_id(),
             NO_DEPOSIT.into(),
@@ -350,7 +430,7 @@ mod test {
             staked_balance: 0.into(),
             can_withdraw: true,
         };
-        contract.on_run_stake_batch(staking_pool_account);
+        contract.on_run_stake_batch(TEST_STAKING_POOL_ID.to_string(), staking_pool_account);
 
         let receipts: Vec<Receipt> = deserialize_receipts();
         assert_eq!(receipts.len(), 2);
@@ -476,7 +556,7 @@ mod test {
             staked_balance: 0.into(),
             can_withdraw: true,
         };
-        contract.on_run_stake_batch(staking_pool_account);
+        contract.on_run_stake_batch(TEST_STAKING_POOL_ID.to_string(), staking_pool_account);
         assert_eq!(contract.near_liquidity_pool.value(), 0);
 
         let receipts: Vec<Receipt> = deserialize_receipts();
@@ -608,7 +688,10 @@ mod test {
             can_withdraw: true,
         };
         // When the callback is invoked
-        contract.on_run_stake_batch(staking_pool_account.clone());
+        contract.on_run_stake_batch(
+            TEST_STAKING_POOL_ID.to_string(),
+            staking_pool_account.clone(),
+        );
 
         // liquidity should not be persisted to state until after the NEAR funds have been staked
         // successfully with the staking pool
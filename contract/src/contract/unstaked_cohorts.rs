@@ -0,0 +1,298 @@
+//! While a [RedeemStakeBatch](crate::domain::RedeemStakeBatch) is pending withdrawal, it can be moved
+//! into an [UnstakedCohort](crate::domain::UnstakedCohort), which unblocks the next batch to be
+//! unstaked from a different staking pool. Each cohort is withdrawn from its staking pool once its
+//! unstaked NEAR unlocks.
+use crate::*;
+use crate::{
+    domain::{RedeemLock, UnstakedCohort},
+    errors::illegal_state::{
+        REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST, REDEEM_STAKE_BATCH_SHOULD_EXIST,
+        UNSTAKING_POOL_SHOULD_EXIST,
+    },
+    interface::staking_service::events::{PendingWithdrawalCleared, UnstakedCohortAdded},
+    near::log,
+};
+
+impl Contract {
+    pub(crate) fn is_unstaked_cohort(&self, batch_id: domain::BatchId) -> bool {
+        self.unstaked_cohorts
+            .iter()
+            .any(|cohort| cohort.batch_id() == batch_id)
+    }
+
+    /// returns true if the staking pool is holding unstaked NEAR that is pending withdrawal, in which
+    /// case the staking pool must not be unstaked from because unstaking resets the unlock epoch
+    pub(crate) fn is_staking_pool_pending_withdrawal(&self, staking_pool_id: &str) -> bool {
+        self.unstaking_pool_id.as_deref() == Some(staking_pool_id)
+            || self
+                .unstaked_cohorts
+                .iter()
+                .any(|cohort| cohort.staking_pool_id() == staking_pool_id)
+    }
+
+    /// total NEAR that is pending withdrawal across all unstaked cohorts
+    pub(crate) fn unstaked_cohorts_near_balance(&self) -> domain::YoctoNear {
+        self.unstaked_cohorts
+            .iter()
+            .map(|cohort| cohort.unstaked_near().value())
+            .sum::<u128>()
+            .into()
+    }
+
+    pub(crate) fn unstaked_cohort_receipt(
+        &self,
+        cohort: &UnstakedCohort,
+    ) -> domain::RedeemStakeBatchReceipt {
+        self.redeem_stake_batch_receipts
+            .get(&cohort.batch_id())
+            .expect(REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST)
+    }
+
+    /// returns the first cohort whose unstaked NEAR is available for withdrawal
+    pub(crate) fn withdrawable_unstaked_cohort(&self) -> Option<UnstakedCohort> {
+        self.unstaked_cohorts
+            .iter()
+            .find(|cohort| {
+                self.unstaked_cohort_receipt(cohort)
+                    .unstaked_funds_available_for_withdrawal()
+            })
+            .cloned()
+    }
+
+    /// NEAR funds cannot be claimed for the batch receipt until the cohort has been withdrawn
+    pub(crate) fn claimable_redeem_stake_batch_receipt(
+        &self,
+        batch_id: domain::BatchId,
+    ) -> Option<domain::RedeemStakeBatchReceipt> {
+        if self.is_unstaked_cohort(batch_id) {
+            None
+        } else {
+            self.redeem_stake_batch_receipts.get(&batch_id)
+        }
+    }
+
+    /// the batch that is pending withdrawal can be moved into an unstaked cohort when:
+    /// - its unstaked NEAR is not yet available for withdrawal
+    /// - there is a next batch waiting to be unstaked
+    /// - there is another staking pool with staked NEAR that is not pending withdrawal
+    pub(crate) fn can_move_pending_withdrawal_to_unstaked_cohort(&self) -> bool {
+        if self.redeem_stake_batch_lock != Some(RedeemLock::PendingWithdrawal)
+            || self.next_redeem_stake_batch.is_none()
+        {
+            return false;
+        }
+        let receipt = match self.get_pending_withdrawal() {
+            Some(receipt) => receipt,
+            None => return false,
+        };
+        !receipt.unstaked_funds_available_for_withdrawal()
+            && self.has_staking_pool_available_to_unstake()
+    }
+
+    /// returns true if the next batch has to wait for the pending withdrawal before it can be
+    /// unstaked because there is no other staking pool available to unstake from
+    /// - NOTE: this is always the case while a batch is pending withdrawal when the contract delegates
+    ///   to a single staking pool, i.e., unstaked cohorts only overlap across staking pools
+    pub(crate) fn is_next_redeem_stake_batch_blocked_by_pending_withdrawal(&self) -> bool {
+        if self.redeem_stake_batch_lock != Some(RedeemLock::PendingWithdrawal)
+            || self.next_redeem_stake_batch.is_none()
+        {
+            return false;
+        }
+        self.get_pending_withdrawal().map_or(false, |receipt| {
+            !receipt.unstaked_funds_available_for_withdrawal()
+        }) && !self.has_staking_pool_available_to_unstake()
+    }
+
+    /// returns true if there is a staking pool with staked NEAR that is not pending withdrawal, i.e.,
+    /// the next batch can be unstaked from it
+    pub(crate) fn has_staking_pool_available_to_unstake(&self) -> bool {
+        self.staking_pools.iter().any(|pool| {
+            pool.staked_balance().value() > 0
+                && !self.is_staking_pool_pending_withdrawal(pool.account_id())
+        })
+    }
+
+    /// moves the batch that is pending withdrawal into an unstaked cohort, and then pops the redeem
+    /// stake batch, which means the next batch can be unstaked
+    /// - NEAR liquidity is applied to the batch first because liquidity is added by restaking the
+    ///   unstaked NEAR, i.e., only the remainder is still unstaked in the staking pool
+    pub(crate) fn move_pending_withdrawal_to_unstaked_cohort(&mut self) {
+        let batch = self
+            .redeem_stake_batch
+            .expect(REDEEM_STAKE_BATCH_SHOULD_EXIST);
+        let receipt = self
+            .redeem_stake_batch_receipts
+            .get(&batch.id())
+            .expect(REDEEM_STAKE_BATCH_RECEIPT_SHOULD_EXIST);
+        let staking_pool_id = self
+            .unstaking_pool_id
+            .take()
+            .expect(UNSTAKING_POOL_SHOULD_EXIST);
+
+        let stake_near_value = receipt.stake_near_value();
+        let near_liquidity = if self.near_liquidity_pool >= stake_near_value {
+            stake_near_value
+        } else {
+            self.near_liquidity_pool
+        };
+        self.near_liquidity_pool -= near_liquidity;
+        self.total_near.credit(near_liquidity);
+
+        let unstaked_near = stake_near_value - near_liquidity;
+        if unstaked_near.value() > 0 {
+            // the NEAR that was haircut from the receipt is still pending withdrawal
+            let mut cohort = UnstakedCohort::new(
+                batch.id(),
+                &staking_pool_id,
+                unstaked_near + receipt.haircut(),
+            );
+            cohort.apply_haircut(receipt.haircut());
+            log(UnstakedCohortAdded {
+                batch_id: batch.id().value().into(),
+                staking_pool_id: &staking_pool_id,
+                near_liquidity: near_liquidity.value().into(),
+                unstaked_near: unstaked_near.value().into(),
+                unstaked_near_withdrawal_availability: receipt
+                    .unstaked_near_withdrawal_availability()
+                    .value()
+                    .into(),
+            });
+            self.unstaked_cohorts.push(cohort);
+        } else {
+            log(PendingWithdrawalCleared::new(&batch, &receipt));
+        }
+
+        self.redeem_stake_batch_lock = None;
+        self.pop_redeem_stake_batch();
+        // requests that are queued up for the batch can no longer be filled from liquidity - they
+        // will be claimed from the batch receipt once the cohort is withdrawn
+        self.allocate_redeem_queue_liquidity();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        domain::{RedeemStakeBatch, StakingPool},
+        near::YOCTO,
+        test_utils::*,
+    };
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    const TEST_STAKING_POOL_ID_2: &str = "staking-pool-2.near";
+
+    fn set_pending_withdrawal(contract: &mut Contract) {
+        contract
+            .staking_pools
+            .push(StakingPool::new(TEST_STAKING_POOL_ID_2, 1));
+        contract.staking_pools[0].update_balances((100 * YOCTO).into(), (10 * YOCTO).into());
+        contract.staking_pools[1].update_balances((100 * YOCTO).into(), 0.into());
+
+        *contract.batch_id_sequence += 1;
+        let batch = RedeemStakeBatch::new(contract.batch_id_sequence, (10 * YOCTO).into());
+        contract.redeem_stake_batch = Some(batch);
+        contract.redeem_stake_batch_receipts.insert(
+            &batch.id(),
+            &batch.create_receipt(contract.stake_token_value),
+        );
+        contract.redeem_stake_batch_lock = Some(RedeemLock::PendingWithdrawal);
+        contract.unstaking_pool_id = Some(TEST_STAKING_POOL_ID.to_string());
+
+        *contract.batch_id_sequence += 1;
+        contract.next_redeem_stake_batch = Some(RedeemStakeBatch::new(
+            contract.batch_id_sequence,
+            (5 * YOCTO).into(),
+        ));
+    }
+
+    #[test]
+    fn move_pending_withdrawal_to_unstaked_cohort() {
+        let mut test_context = TestContext::with_registered_account();
+        let contract = &mut test_context.contract;
+        set_pending_withdrawal(contract);
+        contract.near_liquidity_pool = (2 * YOCTO).into();
+
+        assert!(contract.can_move_pending_withdrawal_to_unstaked_cohort());
+        let batch_id = contract.redeem_stake_batch.unwrap().id();
+        let next_batch_id = contract.next_redeem_stake_batch.unwrap().id();
+        let total_near = contract.total_near.amount();
+        contract.move_pending_withdrawal_to_unstaked_cohort();
+
+        // liquidity is applied to the batch and the remainder is pending withdrawal
+        assert_eq!(contract.near_liquidity_pool, 0.into());
+        assert_eq!(
+            contract.total_near.amount(),
+            total_near + (2 * YOCTO).into()
+        );
+        assert_eq!(
+            contract.unstaked_cohorts,
+            vec![UnstakedCohort::new(
+                batch_id,
+                TEST_STAKING_POOL_ID,
+                (8 * YOCTO).into()
+            )]
+        );
+        assert_eq!(contract.unstaked_cohorts_near_balance(), (8 * YOCTO).into());
+
+        // the next batch is ready to be unstaked from the other staking pool
+        assert!(contract.redeem_stake_batch_lock.is_none());
+        assert!(contract.unstaking_pool_id.is_none());
+        assert_eq!(contract.redeem_stake_batch.unwrap().id(), next_batch_id);
+        assert!(contract.is_staking_pool_pending_withdrawal(TEST_STAKING_POOL_ID));
+        assert_eq!(
+            contract.redeem_stake_batch_staking_pool_id((5 * YOCTO).into()),
+            Some(TEST_STAKING_POOL_ID_2.to_string())
+        );
+
+        // NEAR cannot be claimed until the cohort is withdrawn
+        assert!(contract
+            .claimable_redeem_stake_batch_receipt(batch_id)
+            .is_none());
+        assert!(contract.withdrawable_unstaked_cohort().is_none());
+    }
+
+    /// Given the contract delegates to a single staking pool
+    /// And a batch is pending withdrawal
+    /// And the next batch is waiting to be unstaked
+    /// Then the next batch is blocked until the pending withdrawal can be withdrawn
+    #[test]
+    fn single_staking_pool_with_2_redeem_stake_batches_in_flight() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+        set_pending_withdrawal(contract);
+        contract.staking_pools.truncate(1);
+
+        assert!(!contract.can_move_pending_withdrawal_to_unstaked_cohort());
+        assert!(contract.is_next_redeem_stake_batch_blocked_by_pending_withdrawal());
+        assert!(!contract.can_unstake());
+        assert!(contract.unstaked_cohorts.is_empty());
+
+        // once the unstaked NEAR can be withdrawn, then the next batch is unblocked
+        context.epoch_height += 4;
+        testing_env!(context);
+        assert!(!contract.is_next_redeem_stake_batch_blocked_by_pending_withdrawal());
+        assert!(contract.can_unstake());
+        assert!(contract.unstaked_cohorts.is_empty());
+    }
+
+    #[test]
+    fn cannot_move_pending_withdrawal_without_another_staking_pool() {
+        let mut test_context = TestContext::with_registered_account();
+        let mut context = test_context.context.clone();
+        let contract = &mut test_context.contract;
+        set_pending_withdrawal(contract);
+
+        contract.staking_pools[1].update_balances(0.into(), 0.into());
+        assert!(!contract.can_move_pending_withdrawal_to_unstaked_cohort());
+        contract.staking_pools[1].update_balances((100 * YOCTO).into(), 0.into());
+        assert!(contract.can_move_pending_withdrawal_to_unstaked_cohort());
+
+        // once the unstaked NEAR can be withdrawn, then the pending withdrawal is simply withdrawn
+        context.epoch_height += 4;
+        testing_env!(context);
+        assert!(!contract.can_move_pending_withdrawal_to_unstaked_cohort());
+    }
+}
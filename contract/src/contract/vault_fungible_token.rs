@@ -0,0 +1,557 @@
+//required in order for near_bindgen macro to work outside of lib.rs
+use crate::*;
+use crate::{
+    config::FeeCategory,
+    contract::fungible_token::assert_token_amount_not_zero,
+    domain::{self, YoctoStake},
+    errors::vault::{INSUFFICIENT_STORAGE_ESCROW, VAULT_NOT_FOUND},
+    interface::{
+        fungible_token::events::{FtBurn, FtTransfer},
+        ResolveVault, TokenAmount, TransferCallMessage, VaultFungibleToken, VaultId,
+    },
+    near::{self, assert_yocto_near_attached, NO_DEPOSIT},
+};
+use near_sdk::{env, ext_contract, json_types::ValidAccountId, log, near_bindgen, Promise};
+#[allow(unused_imports)]
+use near_sdk::{AccountId, PromiseOrValue};
+
+#[near_bindgen]
+impl VaultFungibleToken for Contract {
+    #[payable]
+    fn transfer_with_vault(
+        &mut self,
+        receiver_id: ValidAccountId,
+        amount: TokenAmount,
+        payload: TransferCallMessage,
+    ) -> Promise {
+        assert_yocto_near_attached();
+        assert_token_amount_not_zero(&amount);
+
+        let stake_amount: YoctoStake = amount.value().into();
+
+        let mut sender = self.predecessor_registered_account();
+        self.claim_receipt_funds(&mut sender);
+        // the fee is charged on top of the amount that is transferred into the vault, and is not
+        // refunded when the vault is resolved
+        let fee = self.stake_fee(FeeCategory::FtTransfer, &sender.account_id, stake_amount);
+        sender.apply_stake_debit(stake_amount + fee);
+        // apply the 1 yoctoNEAR that was attached to the sender account's NEAR balance
+        sender.apply_near_credit(1.into());
+        // the sender pays for the vault storage until the vault is resolved
+        sender.vaults_len += 1;
+        assert!(
+            sender.storage_escrow.amount() >= self.registered_account_storage_fee(&sender),
+            INSUFFICIENT_STORAGE_ESCROW
+        );
+        self.save_registered_account(&sender);
+
+        *self.vault_id_sequence += 1;
+        let vault_id = self.vault_id_sequence;
+        self.vaults.insert(
+            &vault_id,
+            &domain::Vault::new(receiver_id.as_ref(), stake_amount),
+        );
+
+        near::log(FtTransfer {
+            old_owner_id: &sender.account_id,
+            new_owner_id: &env::current_account_id(),
+            amount: amount.value().into(),
+            memo: Some(&format!("Vault({})", *vault_id)),
+        });
+        self.collect_ft_transfer_fee(&sender.account_id, fee);
+
+        ext_vault_receiver::on_receive_with_vault(
+            sender.account_id.clone(),
+            amount,
+            vault_id.into(),
+            payload,
+            receiver_id.as_ref(),
+            NO_DEPOSIT.value(),
+            self.on_receive_with_vault_gas(),
+        )
+        .then(ext_resolve_vault::resolve_vault(
+            vault_id.into(),
+            sender.account_id,
+            &env::current_account_id(),
+            NO_DEPOSIT.value(),
+            self.resolve_vault_gas(),
+        ))
+    }
+
+    fn withdraw_from_vault(
+        &mut self,
+        vault_id: VaultId,
+        receiver_id: ValidAccountId,
+        amount: TokenAmount,
+    ) {
+        assert_token_amount_not_zero(&amount);
+
+        let vault_id: domain::VaultId = vault_id.into();
+        let mut vault = self.vaults.get(&vault_id).expect(VAULT_NOT_FOUND);
+        let stake_amount: YoctoStake = amount.value().into();
+        vault.withdraw(stake_amount);
+
+        let mut receiver = self.registered_account(receiver_id.as_ref());
+        receiver.apply_stake_credit(stake_amount);
+        self.save_registered_account(&receiver);
+        self.vaults.insert(&vault_id, &vault);
+
+        near::log(FtTransfer {
+            old_owner_id: &env::current_account_id(),
+            new_owner_id: receiver_id.as_ref(),
+            amount: amount.value().into(),
+            memo: Some(&format!("withdraw from Vault({})", *vault_id)),
+        });
+    }
+}
+
+#[near_bindgen]
+impl ResolveVault for Contract {
+    #[private]
+    fn resolve_vault(&mut self, vault_id: VaultId, sender_id: ValidAccountId) -> TokenAmount {
+        let vault_id: domain::VaultId = vault_id.into();
+        let refund_amount = match self.vaults.remove(&vault_id) {
+            Some(vault) => vault.balance(),
+            None => {
+                log!("ERR: vault does not exist: {}", *vault_id);
+                return 0.into();
+            }
+        };
+
+        let memo = format!("refund from Vault({})", *vault_id);
+        match self.lookup_registered_account(sender_id.as_ref()) {
+            Some(mut sender) => {
+                // the vault storage is freed up
+                sender.vaults_len = sender.vaults_len.saturating_sub(1);
+                if refund_amount.value() > 0 {
+                    sender.apply_stake_credit(refund_amount);
+                    near::log(FtTransfer {
+                        old_owner_id: &env::current_account_id(),
+                        new_owner_id: sender_id.as_ref(),
+                        amount: refund_amount.into(),
+                        memo: Some(&memo),
+                    });
+                }
+                self.save_registered_account(&sender);
+            }
+            None if refund_amount.value() == 0 => {}
+            None => {
+                log!(
+                    "ERR: sender account is not registered - refund amount will be burned: {}",
+                    refund_amount
+                );
+                // NOTE: this has the effect of transferring the burned value to the STAKE token,
+                // i.e., STAKE token value will increase when STAKE is burned
+                self.total_stake.debit(refund_amount);
+                near::log(FtBurn {
+                    owner_id: &env::current_account_id(),
+                    amount: refund_amount.into(),
+                    memo: Some(&memo),
+                });
+            }
+        }
+        refund_amount.value().into()
+    }
+}
+
+impl Contract {
+    fn resolve_vault_gas(&self) -> u64 {
+        self.config
+            .gas_config()
+            .callbacks()
+            .resolve_vault_gas()
+            .value()
+    }
+
+    // pass along remainder of prepaid  gas to receiver contract
+    fn on_receive_with_vault_gas(&self) -> u64 {
+        env::prepaid_gas()
+            - env::used_gas()
+            - self.resolve_vault_gas()
+            // on_receive_with_vault
+            - self.config.gas_config().function_call_promise().value()
+            // resolve_vault
+            - self.config.gas_config().function_call_promise().value()
+            // resolve_vault data dependency
+            - self
+            .config
+            .gas_config()
+            .function_call_promise_data_dependency()
+            .value()
+    }
+}
+
+#[ext_contract(ext_vault_receiver)]
+pub trait ExtVaultReceiver {
+    fn on_receive_with_vault(
+        &mut self,
+        sender_id: AccountId,
+        amount: TokenAmount,
+        vault_id: VaultId,
+        payload: TransferCallMessage,
+    ) -> PromiseOrValue<()>;
+}
+
+#[ext_contract(ext_resolve_vault)]
+pub trait ExtResolveVault {
+    fn resolve_vault(&mut self, vault_id: VaultId, sender_id: AccountId) -> TokenAmount;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::TGAS;
+    use crate::interface::{AccountStorage, FungibleToken};
+    use crate::near::YOCTO;
+    use crate::test_utils::*;
+    use near_sdk::test_utils::get_logs;
+    use near_sdk::{serde::Deserialize, serde_json, testing_env, MockedBlockchain};
+
+    const RECEIVER_ID: &str = "receiver.near";
+
+    fn vault_storage_fee(test_ctx: &TestContext) -> u128 {
+        test_ctx.vault_storage_usage.value() as u128
+            * test_ctx.config.storage_cost_per_byte().value()
+    }
+
+    /// registers the receiver account and credits the sender account with 100 STAKE
+    /// - the sender account storage escrow covers the storage for 1 vault
+    fn with_stake<'a>() -> TestContext<'a> {
+        let mut test_ctx = TestContext::with_registered_account();
+        test_ctx.register_account(RECEIVER_ID);
+
+        let mut sender = test_ctx.registered_account(test_ctx.account_id);
+        let vault_storage_fee = vault_storage_fee(&test_ctx);
+        sender.storage_escrow.credit(vault_storage_fee.into());
+        test_ctx.total_account_storage_escrow += domain::YoctoNear(vault_storage_fee);
+        let total_supply = YoctoStake(100 * YOCTO);
+        sender.apply_stake_credit(total_supply);
+        test_ctx.total_stake.credit(total_supply);
+        test_ctx.save_registered_account(&sender);
+        test_ctx
+    }
+
+    fn transfer_with_vault(test_ctx: &mut TestContext, amount: u128) {
+        let sender_id = test_ctx.account_id;
+        let mut context = test_ctx.set_predecessor_account_id(sender_id);
+        context.attached_deposit = 1;
+        testing_env!(context);
+        test_ctx.transfer_with_vault(
+            to_valid_account_id(RECEIVER_ID),
+            amount.into(),
+            "pay".into(),
+        );
+    }
+
+    #[test]
+    fn transfer_with_vault_ok() {
+        // Arrange
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+
+        // Act
+        transfer_with_vault(&mut test_ctx, 10 * YOCTO);
+
+        // Assert - STAKE is locked in the vault
+        assert_eq!(test_ctx.ft_total_supply().value(), 100 * YOCTO);
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id(sender_id))
+                .value(),
+            90 * YOCTO
+        );
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id(RECEIVER_ID))
+                .value(),
+            0
+        );
+        let vault = test_ctx.vaults.get(&domain::VaultId(1)).unwrap();
+        assert_eq!(vault.balance(), (10 * YOCTO).into());
+        let sender = test_ctx.registered_account(sender_id);
+        assert_eq!(sender.near.unwrap().amount().value(), 1,
+                   "expected the attached 1 yoctoNEAR for the transfer to be credited to the account's NEAR balance");
+
+        let logs = get_logs();
+        assert_eq!(
+            &logs[0],
+            &format!(
+                r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{{"old_owner_id":"{}","new_owner_id":"{}","amount":"{}","memo":"Vault(1)"}}]}}"#,
+                sender_id,
+                env::current_account_id(),
+                10 * YOCTO
+            )
+        );
+
+        let receipts = deserialize_receipts();
+        assert_eq!(receipts.len(), 2);
+        {
+            let receipt = &receipts[0];
+            match &receipt.actions[0] {
+                Action::FunctionCall {
+                    method_name,
+                    args,
+                    deposit,
+                    gas,
+                } => {
+                    assert_eq!(method_name, "on_receive_with_vault");
+                    assert_eq!(*deposit, 0);
+                    let args: OnReceiveWithVaultArgs = serde_json::from_str(args).unwrap();
+                    assert_eq!(args.sender_id, to_valid_account_id(sender_id));
+                    assert_eq!(args.amount, (10 * YOCTO).into());
+                    assert_eq!(args.vault_id, domain::VaultId(1).into());
+                    assert_eq!(args.payload, "pay".into());
+                    assert!(*gas >= test_ctx.context.prepaid_gas - (TGAS * 35).value())
+                }
+                _ => panic!("expected `on_receive_with_vault` function call"),
+            }
+        }
+        {
+            let receipt = &receipts[1];
+            match &receipt.actions[0] {
+                Action::FunctionCall {
+                    method_name,
+                    args,
+                    deposit,
+                    gas,
+                } => {
+                    assert_eq!(method_name, "resolve_vault");
+                    assert_eq!(*deposit, 0);
+                    let args: ResolveVaultArgs = serde_json::from_str(args).unwrap();
+                    assert_eq!(args.vault_id, domain::VaultId(1).into());
+                    assert_eq!(args.sender_id, to_valid_account_id(sender_id));
+                    assert_eq!(
+                        *gas,
+                        test_ctx
+                            .config
+                            .gas_config()
+                            .callbacks()
+                            .resolve_vault_gas()
+                            .value()
+                    )
+                }
+                _ => panic!("expected `resolve_vault` function call"),
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exactly 1 yoctoNEAR must be attached")]
+    fn transfer_with_vault_zero_yocto_near_attached() {
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        testing_env!(test_ctx.set_predecessor_account_id(sender_id));
+        test_ctx.transfer_with_vault(to_valid_account_id(RECEIVER_ID), YOCTO.into(), "pay".into());
+    }
+
+    #[test]
+    fn withdraw_and_resolve_vault_with_refund() {
+        // Arrange
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        transfer_with_vault(&mut test_ctx, 10 * YOCTO);
+
+        // Act - receiver withdraws part of the vault balance
+        testing_env!(test_ctx.set_predecessor_account_id(RECEIVER_ID));
+        test_ctx.withdraw_from_vault(
+            domain::VaultId(1).into(),
+            to_valid_account_id(RECEIVER_ID),
+            (6 * YOCTO).into(),
+        );
+
+        // Assert
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id(RECEIVER_ID))
+                .value(),
+            6 * YOCTO
+        );
+        let vault = test_ctx.vaults.get(&domain::VaultId(1)).unwrap();
+        assert_eq!(vault.balance(), (4 * YOCTO).into());
+
+        // Act - resolve the vault
+        testing_env!(test_ctx.set_predecessor_account_id(&env::current_account_id()));
+        let refund_amount =
+            test_ctx.resolve_vault(domain::VaultId(1).into(), to_valid_account_id(sender_id));
+
+        // Assert - remaining vault balance is refunded to the sender
+        assert_eq!(refund_amount.value(), 4 * YOCTO);
+        assert!(test_ctx.vaults.get(&domain::VaultId(1)).is_none());
+        assert_eq!(
+            test_ctx
+                .ft_balance_of(to_valid_account_id(sender_id))
+                .value(),
+            94 * YOCTO
+        );
+        assert_eq!(test_ctx.ft_total_supply().value(), 100 * YOCTO);
+
+        let logs = get_logs();
+        assert_eq!(
+            &logs[0],
+            &format!(
+                r#"EVENT_JSON:{{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{{"old_owner_id":"{}","new_owner_id":"{}","amount":"{}","memo":"refund from Vault(1)"}}]}}"#,
+                env::current_account_id(),
+                sender_id,
+                4 * YOCTO
+            )
+        );
+    }
+
+    #[test]
+    fn sender_pays_for_vault_storage_until_resolved() {
+        // Arrange
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        let storage_balance = test_ctx
+            .storage_balance_of(to_valid_account_id(sender_id))
+            .unwrap();
+        assert_eq!(
+            storage_balance.available.value(),
+            vault_storage_fee(&test_ctx)
+        );
+
+        // Act
+        transfer_with_vault(&mut test_ctx, 10 * YOCTO);
+
+        // Assert - the vault storage is charged to the sender account
+        assert_eq!(test_ctx.registered_account(sender_id).vaults_len, 1);
+        assert_eq!(
+            test_ctx
+                .storage_balance_of(to_valid_account_id(sender_id))
+                .unwrap()
+                .available
+                .value(),
+            0
+        );
+
+        // Act - the receiver withdraws the full vault balance and then the vault is resolved
+        testing_env!(test_ctx.set_predecessor_account_id(RECEIVER_ID));
+        test_ctx.withdraw_from_vault(
+            domain::VaultId(1).into(),
+            to_valid_account_id(RECEIVER_ID),
+            (10 * YOCTO).into(),
+        );
+        testing_env!(test_ctx.set_predecessor_account_id(&env::current_account_id()));
+        let refund_amount =
+            test_ctx.resolve_vault(domain::VaultId(1).into(), to_valid_account_id(sender_id));
+
+        // Assert - the vault storage is freed up even though there is nothing to refund
+        assert_eq!(refund_amount.value(), 0);
+        assert!(test_ctx.vaults.get(&domain::VaultId(1)).is_none());
+        assert_eq!(test_ctx.registered_account(sender_id).vaults_len, 0);
+        assert_eq!(
+            test_ctx
+                .storage_balance_of(to_valid_account_id(sender_id))
+                .unwrap()
+                .available
+                .value(),
+            vault_storage_fee(&test_ctx)
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "account storage balance is insufficient to pay for vault storage - deposit more via storage_deposit"
+    )]
+    fn transfer_with_vault_with_insufficient_storage_escrow() {
+        let mut test_ctx = with_stake();
+        transfer_with_vault(&mut test_ctx, 10 * YOCTO);
+        // the sender storage escrow only covers 1 open vault
+        transfer_with_vault(&mut test_ctx, 10 * YOCTO);
+    }
+
+    #[test]
+    #[should_panic(expected = "all vault transfers must be resolved in order to unregister")]
+    fn unregister_with_open_vault() {
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        transfer_with_vault(&mut test_ctx, 10 * YOCTO);
+        test_ctx.unregister(sender_id, true);
+    }
+
+    #[test]
+    fn resolve_vault_with_sender_not_registered() {
+        // Arrange
+        let mut test_ctx = with_stake();
+        transfer_with_vault(&mut test_ctx, 10 * YOCTO);
+
+        // Act
+        testing_env!(test_ctx.set_predecessor_account_id(&env::current_account_id()));
+        let refund_amount = test_ctx.resolve_vault(
+            domain::VaultId(1).into(),
+            to_valid_account_id("unregistered.near"),
+        );
+
+        // Assert - remaining vault balance is burned
+        assert_eq!(refund_amount.value(), 10 * YOCTO);
+        assert!(test_ctx.vaults.get(&domain::VaultId(1)).is_none());
+        assert_eq!(test_ctx.ft_total_supply().value(), 90 * YOCTO);
+    }
+
+    #[test]
+    fn resolve_vault_that_does_not_exist() {
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        testing_env!(test_ctx.set_predecessor_account_id(&env::current_account_id()));
+        let refund_amount =
+            test_ctx.resolve_vault(domain::VaultId(1).into(), to_valid_account_id(sender_id));
+        assert_eq!(refund_amount.value(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "vault can only be withdrawn from by the vault receiver")]
+    fn withdraw_from_vault_by_non_receiver() {
+        let mut test_ctx = with_stake();
+        let sender_id = test_ctx.account_id;
+        transfer_with_vault(&mut test_ctx, 10 * YOCTO);
+
+        testing_env!(test_ctx.set_predecessor_account_id(sender_id));
+        test_ctx.withdraw_from_vault(
+            domain::VaultId(1).into(),
+            to_valid_account_id(sender_id),
+            YOCTO.into(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "vault balance is insufficient to fulfill withdrawal request")]
+    fn withdraw_from_vault_insufficient_balance() {
+        let mut test_ctx = with_stake();
+        transfer_with_vault(&mut test_ctx, 10 * YOCTO);
+
+        testing_env!(test_ctx.set_predecessor_account_id(RECEIVER_ID));
+        test_ctx.withdraw_from_vault(
+            domain::VaultId(1).into(),
+            to_valid_account_id(RECEIVER_ID),
+            (11 * YOCTO).into(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "vault does not exist")]
+    fn withdraw_from_vault_that_does_not_exist() {
+        let mut test_ctx = with_stake();
+        testing_env!(test_ctx.set_predecessor_account_id(RECEIVER_ID));
+        test_ctx.withdraw_from_vault(
+            domain::VaultId(1).into(),
+            to_valid_account_id(RECEIVER_ID),
+            YOCTO.into(),
+        );
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    struct OnReceiveWithVaultArgs {
+        sender_id: ValidAccountId,
+        amount: TokenAmount,
+        vault_id: VaultId,
+        payload: TransferCallMessage,
+    }
+
+    #[derive(Deserialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    struct ResolveVaultArgs {
+        vault_id: VaultId,
+        sender_id: ValidAccountId,
+    }
+}
@@ -4,40 +4,75 @@
 //! closely mirrors the domain model.
 
 mod account;
+mod allowance;
 mod batch_id;
 mod block_height;
 mod block_time_height;
 mod block_timestamp;
+mod collected_fees;
 mod epoch_height;
+mod fungible_token_metadata;
 mod gas;
+mod insurance_buffer;
+mod keeper_rewards;
+mod limit_order;
+mod liquidity_provider_pool;
 mod lock;
+mod pending_batch;
+mod redeem_beneficiary;
+mod redeem_queue;
 mod redeem_stake_batch;
 mod redeem_stake_batch_receipt;
 mod stake_batch;
 mod stake_batch_receipt;
 mod stake_token_value;
+mod stake_token_value_history;
+mod staking_pool;
+mod staking_pool_migration;
 mod storage_usage;
 mod timestamped_near_balance;
 mod timestamped_stake_balance;
+mod unstaked_cohort;
+mod vault;
 mod yocto_near;
 mod yocto_stake;
 
 pub use crate::interface::contract_state::ContractState;
 pub use account::{Account, RegisteredAccount};
+pub use allowance::Allowance;
 pub use batch_id::BatchId;
 pub use block_height::BlockHeight;
 pub use block_time_height::BlockTimeHeight;
 pub use block_timestamp::BlockTimestamp;
+pub use collected_fees::CollectedFees;
 pub use epoch_height::EpochHeight;
+pub use fungible_token_metadata::{FungibleTokenMetadata, FT_METADATA_SPEC, STAKE_DECIMALS};
 pub use gas::{Gas, TGAS};
+pub use insurance_buffer::InsuranceBuffer;
+pub use keeper_rewards::KeeperRewards;
+pub use limit_order::{
+    LimitOrder, LimitOrderRejection, LimitOrders, MAX_LIMIT_ORDERS_PER_BATCH,
+    MIN_LIMIT_ORDER_AMOUNT,
+};
+pub use liquidity_provider_pool::LiquidityProviderPool;
 pub use lock::{RedeemLock, StakeLock};
+pub use pending_batch::PendingBatch;
+pub use redeem_beneficiary::RedeemBeneficiary;
+pub use redeem_queue::{RedeemQueue, RedeemRequest};
 pub use redeem_stake_batch::RedeemStakeBatch;
 pub use redeem_stake_batch_receipt::RedeemStakeBatchReceipt;
 pub use stake_batch::StakeBatch;
 pub use stake_batch_receipt::StakeBatchReceipt;
 pub use stake_token_value::StakeTokenValue;
+pub use stake_token_value_history::{
+    apy_basis_points, StakeTokenValueHistory, STAKE_TOKEN_VALUE_HISTORY_CAPACITY,
+};
+pub use staking_pool::{StakingPool, DEFAULT_STAKING_POOL_WEIGHT, MAX_STAKING_POOLS};
+pub use staking_pool_migration::{StakingPoolMigration, StakingPoolMigrationStatus};
 pub use storage_usage::StorageUsage;
 pub use timestamped_near_balance::TimestampedNearBalance;
 pub use timestamped_stake_balance::TimestampedStakeBalance;
+pub use unstaked_cohort::UnstakedCohort;
+pub use vault::{Vault, VaultId};
 pub use yocto_near::YoctoNear;
 pub use yocto_stake::YoctoStake;
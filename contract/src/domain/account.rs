@@ -4,10 +4,13 @@ use crate::domain::{
     BatchId, RedeemStakeBatch, TimestampedNearBalance, TimestampedStakeBalance, YoctoNear,
     YoctoStake,
 };
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
 use std::ops::{Deref, DerefMut};
 
-#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+#[derive(BorshSerialize, Clone, Copy)]
 pub struct Account {
     /// account is responsible for paying for its own storage fees
     /// the funds are escrowed and refunded when the account is unregistered
@@ -40,6 +43,63 @@ pub struct Account {
     pub redeem_stake_batch: Option<RedeemStakeBatch>,
     /// if the contract is locked, then deposit the NEAR funds in the next batch
     pub next_redeem_stake_batch: Option<RedeemStakeBatch>,
+
+    /// number of [allowances](crate::domain::Allowance) that the account has approved
+    /// - the account pays for the allowance storage out of its storage escrow
+    pub allowances_len: u32,
+
+    /// number of [pending batches](crate::domain::PendingBatch) that the account holds
+    /// - the account pays for the pending batch storage out of its storage escrow
+    pub pending_batches_len: u32,
+
+    /// number of open [vaults](crate::domain::Vault) that the account has transferred STAKE into
+    /// - the account pays for the vault storage out of its storage escrow until the vault is resolved
+    pub vaults_len: u32,
+
+    /// number of [redeem beneficiaries](crate::domain::RedeemBeneficiary) that the account has
+    /// recorded for its redeem stake batches
+    /// - the account pays for the beneficiary storage out of its storage escrow until the NEAR is
+    ///   paid out to the beneficiary
+    pub redeem_beneficiaries_len: u32,
+
+    /// shares that the account owns in the [LiquidityProviderPool](crate::domain::LiquidityProviderPool)
+    pub liquidity_provider_shares: u128,
+}
+
+/// accounts that were registered before the contract state was versioned are stored without the
+/// fields that were appended to the account since - they are read in with their default values
+impl BorshDeserialize for Account {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let storage_escrow = BorshDeserialize::deserialize(buf)?;
+        let near = BorshDeserialize::deserialize(buf)?;
+        let stake = BorshDeserialize::deserialize(buf)?;
+        let stake_batch = BorshDeserialize::deserialize(buf)?;
+        let next_stake_batch = BorshDeserialize::deserialize(buf)?;
+        let redeem_stake_batch = BorshDeserialize::deserialize(buf)?;
+        let next_redeem_stake_batch = BorshDeserialize::deserialize(buf)?;
+        let mut account = Self {
+            storage_escrow,
+            near,
+            stake,
+            stake_batch,
+            next_stake_batch,
+            redeem_stake_batch,
+            next_redeem_stake_batch,
+            allowances_len: 0,
+            pending_batches_len: 0,
+            vaults_len: 0,
+            redeem_beneficiaries_len: 0,
+            liquidity_provider_shares: 0,
+        };
+        if !buf.is_empty() {
+            account.allowances_len = BorshDeserialize::deserialize(buf)?;
+            account.pending_batches_len = BorshDeserialize::deserialize(buf)?;
+            account.vaults_len = BorshDeserialize::deserialize(buf)?;
+            account.redeem_beneficiaries_len = BorshDeserialize::deserialize(buf)?;
+            account.liquidity_provider_shares = BorshDeserialize::deserialize(buf)?;
+        }
+        Ok(account)
+    }
 }
 
 impl Account {
@@ -52,6 +112,11 @@ impl Account {
             next_stake_batch: None,
             redeem_stake_batch: None,
             next_redeem_stake_batch: None,
+            allowances_len: 0,
+            pending_batches_len: 0,
+            vaults_len: 0,
+            redeem_beneficiaries_len: 0,
+            liquidity_provider_shares: 0,
         }
     }
 
@@ -84,9 +149,19 @@ impl Account {
             next_stake_batch: Some(StakeBatch::new(0.into(), 0.into())),
             redeem_stake_batch: Some(RedeemStakeBatch::new(0.into(), 0.into())),
             next_redeem_stake_batch: Some(RedeemStakeBatch::new(0.into(), 0.into())),
+            allowances_len: 0,
+            pending_batches_len: 0,
+            vaults_len: 0,
+            redeem_beneficiaries_len: 0,
+            liquidity_provider_shares: 0,
         }
     }
 
+    /// returns zero if the account has no STAKE
+    pub fn stake_balance(&self) -> YoctoStake {
+        self.stake.map_or(YoctoStake(0), |stake| stake.amount())
+    }
+
     /// returns false if the account does not have sufficient STAKE funds to fullfill the redeem request
     pub fn can_redeem(&self, amount: YoctoStake) -> bool {
         self.stake.map_or(false, |stake| stake.amount() >= amount)
@@ -95,6 +170,14 @@ impl Account {
     pub fn has_funds(&self) -> bool {
         self.near.map_or(false, |balance| balance > 0)
             || self.stake.map_or(false, |balance| balance > 0)
+            || self.liquidity_provider_shares > 0
+            || self.has_funds_in_batches()
+    }
+
+    /// returns true if the account has funds in any stake or redeem stake batch, including its
+    /// pending batches
+    pub fn has_funds_in_batches(&self) -> bool {
+        self.pending_batches_len > 0
             || self.stake_batch.map_or(false, |batch| batch.balance() > 0)
             || self
                 .next_stake_batch
@@ -107,6 +190,17 @@ impl Account {
                 .map_or(false, |batch| batch.balance() > 0)
     }
 
+    /// returns true if the account has funds in the stake or redeem stake batch with the specified ID
+    ///
+    /// NOTE: pending batches are not checked
+    pub fn has_funds_in_batch(&self, batch_id: BatchId) -> bool {
+        self.stake_batch(batch_id).is_some()
+            || [self.redeem_stake_batch, self.next_redeem_stake_batch]
+                .iter()
+                .flatten()
+                .any(|batch| batch.id() == batch_id)
+    }
+
     pub fn apply_near_credit(&mut self, credit: YoctoNear) {
         self.near
             .get_or_insert_with(|| TimestampedNearBalance::new(YoctoNear(0)))
@@ -147,6 +241,8 @@ impl Account {
 pub struct RegisteredAccount {
     pub account: Account,
     pub id: Hash,
+    /// NEAR account ID that [id](RegisteredAccount::id) was hashed from
+    pub account_id: AccountId,
 }
 
 impl Deref for RegisteredAccount {
@@ -0,0 +1,90 @@
+use crate::domain::{BlockTimestamp, YoctoStake};
+use crate::errors::allowances::INSUFFICIENT_ALLOWANCE;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    env,
+};
+
+/// STAKE that the owner account has approved a spender account to transfer on its behalf
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Allowance {
+    amount: YoctoStake,
+    /// if set, then the allowance can no longer be spent once the block timestamp reaches the expiration
+    expiration: Option<BlockTimestamp>,
+}
+
+impl Allowance {
+    pub fn new(amount: YoctoStake, expiration: Option<BlockTimestamp>) -> Self {
+        Self { amount, expiration }
+    }
+
+    pub fn amount(&self) -> YoctoStake {
+        self.amount
+    }
+
+    pub fn expiration(&self) -> Option<BlockTimestamp> {
+        self.expiration
+    }
+
+    /// uses the current block timestamp from the NEAR runtime environment
+    pub fn expired(&self) -> bool {
+        self.expiration.map_or(false, |expiration| {
+            env::block_timestamp() >= expiration.value()
+        })
+    }
+
+    pub fn increase(&mut self, amount: YoctoStake) {
+        self.amount += amount;
+    }
+
+    /// ## Panics
+    /// if the allowance is less than the amount
+    pub fn decrease(&mut self, amount: YoctoStake) {
+        assert!(self.amount >= amount, INSUFFICIENT_ALLOWANCE);
+        self.amount -= amount;
+    }
+
+    /// the purpose for this constructor is to create a fully allocated [Allowance] object instance
+    /// to be used to measure allowance storage usage when the contract is deployed
+    pub(crate) fn allowance_template_to_measure_storage_usage() -> Self {
+        Self {
+            amount: 0.into(),
+            expiration: Some(0.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn expired() {
+        let mut context = new_context("alfio.near");
+        context.block_timestamp = 100;
+        testing_env!(context);
+
+        assert!(!Allowance::new(1.into(), None).expired());
+        assert!(!Allowance::new(1.into(), Some(101.into())).expired());
+        assert!(Allowance::new(1.into(), Some(100.into())).expired());
+        assert!(Allowance::new(1.into(), Some(99.into())).expired());
+    }
+
+    #[test]
+    fn increase_and_decrease() {
+        let mut allowance = Allowance::new(10.into(), None);
+        allowance.increase(5.into());
+        assert_eq!(allowance.amount(), 15.into());
+        allowance.decrease(15.into());
+        assert_eq!(allowance.amount(), 0.into());
+    }
+
+    #[test]
+    #[should_panic(expected = "allowance is insufficient to fulfill request")]
+    fn decrease_below_zero() {
+        let mut allowance = Allowance::new(10.into(), None);
+        allowance.decrease(11.into());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::interface;
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
 };
@@ -34,3 +35,9 @@ impl From<BlockTimestamp> for u64 {
         value.0
     }
 }
+
+impl From<interface::BlockTimestamp> for BlockTimestamp {
+    fn from(value: interface::BlockTimestamp) -> Self {
+        Self(value.0 .0)
+    }
+}
@@ -0,0 +1,53 @@
+use crate::config::FeeCategory;
+use crate::domain::{YoctoNear, YoctoStake};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// Tracks the fees that are charged per the [FeeSchedule](crate::config::FeeSchedule).
+///
+/// NEAR fees are added to the contract's collected earnings right away. STAKE fees are held by the
+/// contract until they are distributed, which happens when earnings are distributed.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default)]
+pub struct CollectedFees {
+    /// STAKE fees that have not yet been distributed
+    stake: YoctoStake,
+
+    /// total fees collected per category since the contract was deployed
+    /// - STAKE fees are tracked by their NEAR value when they were charged
+    deposit: YoctoNear,
+    redeem: YoctoNear,
+    withdraw: YoctoNear,
+    ft_transfer: YoctoNear,
+}
+
+impl CollectedFees {
+    pub fn stake(&self) -> YoctoStake {
+        self.stake
+    }
+
+    pub fn total(&self, category: FeeCategory) -> YoctoNear {
+        match category {
+            FeeCategory::Deposit => self.deposit,
+            FeeCategory::Redeem => self.redeem,
+            FeeCategory::Withdraw => self.withdraw,
+            FeeCategory::FtTransfer => self.ft_transfer,
+        }
+    }
+
+    /// records the NEAR value of the fee that was charged
+    pub fn record(&mut self, category: FeeCategory, fee: YoctoNear) {
+        match category {
+            FeeCategory::Deposit => self.deposit += fee,
+            FeeCategory::Redeem => self.redeem += fee,
+            FeeCategory::Withdraw => self.withdraw += fee,
+            FeeCategory::FtTransfer => self.ft_transfer += fee,
+        }
+    }
+
+    pub fn credit_stake(&mut self, amount: YoctoStake) {
+        self.stake += amount;
+    }
+
+    pub fn debit_stake(&mut self, amount: YoctoStake) {
+        self.stake -= amount;
+    }
+}
@@ -1,3 +1,4 @@
+use crate::interface;
 use near_sdk::{
     borsh::{self, BorshDeserialize, BorshSerialize},
 };
@@ -24,6 +25,12 @@ impl From<u64> for EpochHeight {
     }
 }
 
+impl From<interface::EpochHeight> for EpochHeight {
+    fn from(value: interface::EpochHeight) -> Self {
+        Self(value.0 .0)
+    }
+}
+
 impl EpochHeight {
     pub fn value(&self) -> u64 {
         self.0
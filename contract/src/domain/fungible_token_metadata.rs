@@ -0,0 +1,163 @@
+use crate::{errors::metadata::*, interface};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// NEP-148 fungible token metadata spec version
+pub const FT_METADATA_SPEC: &str = "ft-1.0.0";
+
+/// STAKE is denominated in yoctoSTAKE, i.e., 1 STAKE = 10^24 yoctoSTAKE
+pub const STAKE_DECIMALS: u8 = 24;
+
+/// sha256 hash length
+const REFERENCE_HASH_LENGTH: usize = 32;
+
+/// STAKE token metadata as defined by [NEP-148](https://github.com/near/NEPs/discussions/148)
+/// - the metadata is stored in contract state and is managed by the operator
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct FungibleTokenMetadata {
+    spec: String,
+    name: String,
+    symbol: String,
+    /// data URL
+    icon: Option<String>,
+    /// link to off-chain JSON file with additional token info
+    reference: Option<String>,
+    /// sha256 hash of the JSON file contained in the [reference](FungibleTokenMetadata::reference) field
+    /// - guards against off-chain tampering
+    reference_hash: Option<Vec<u8>>,
+    decimals: u8,
+}
+
+impl Default for FungibleTokenMetadata {
+    fn default() -> Self {
+        Self {
+            spec: FT_METADATA_SPEC.to_string(),
+            name: "OysterPack STAKE Token".to_string(),
+            symbol: "STAKE".to_string(),
+            icon: None,
+            reference: None,
+            reference_hash: None,
+            decimals: STAKE_DECIMALS,
+        }
+    }
+}
+
+impl FungibleTokenMetadata {
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    pub fn reference(&self) -> Option<&str> {
+        self.reference.as_deref()
+    }
+
+    pub fn reference_hash(&self) -> Option<&[u8]> {
+        self.reference_hash.as_deref()
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// ## Panics
+    /// - if `spec`, `name`, or `symbol` are blank
+    /// - if `decimals` is not 24, i.e., STAKE balances are always tracked in yoctoSTAKE
+    /// - if the `icon` is not a data URL
+    /// - if `reference` is specified without a `reference_hash`, or vice versa
+    /// - if the `reference_hash` is not a 32 byte sha256 hash
+    pub fn assert_valid(&self) {
+        assert!(!self.spec.trim().is_empty(), BLANK_SPEC);
+        assert!(!self.name.trim().is_empty(), BLANK_NAME);
+        assert!(!self.symbol.trim().is_empty(), BLANK_SYMBOL);
+        assert_eq!(self.decimals, STAKE_DECIMALS, "{}", INVALID_DECIMALS);
+        if let Some(icon) = self.icon.as_ref() {
+            assert!(icon.starts_with("data:"), ICON_MUST_BE_DATA_URL);
+        }
+        assert_eq!(
+            self.reference.is_some(),
+            self.reference_hash.is_some(),
+            "{}",
+            REFERENCE_REQUIRES_HASH
+        );
+        if let Some(reference_hash) = self.reference_hash.as_ref() {
+            assert_eq!(
+                reference_hash.len(),
+                REFERENCE_HASH_LENGTH,
+                "{}",
+                INVALID_REFERENCE_HASH
+            );
+        }
+    }
+}
+
+impl From<interface::FungibleTokenMetadata> for FungibleTokenMetadata {
+    fn from(metadata: interface::FungibleTokenMetadata) -> Self {
+        Self {
+            spec: metadata.spec,
+            name: metadata.name,
+            symbol: metadata.symbol,
+            icon: metadata.icon,
+            reference: metadata.reference,
+            reference_hash: metadata.reference_hash.map(|hash| hash.0),
+            decimals: metadata.decimals,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_metadata_is_valid() {
+        let metadata = FungibleTokenMetadata::default();
+        metadata.assert_valid();
+        assert_eq!(metadata.spec(), FT_METADATA_SPEC);
+        assert_eq!(metadata.symbol(), "STAKE");
+        assert_eq!(metadata.decimals(), 24);
+    }
+
+    #[test]
+    #[should_panic(expected = "`reference` and `reference_hash` must both be specified")]
+    fn reference_without_hash() {
+        let mut metadata = FungibleTokenMetadata::default();
+        metadata.reference = Some("https://oysterpack.com/stake.json".to_string());
+        metadata.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "`reference_hash` must be a 32 byte sha256 hash")]
+    fn invalid_reference_hash_length() {
+        let mut metadata = FungibleTokenMetadata::default();
+        metadata.reference = Some("https://oysterpack.com/stake.json".to_string());
+        metadata.reference_hash = Some(vec![1, 2, 3]);
+        metadata.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "`icon` must be a data URL")]
+    fn icon_is_not_data_url() {
+        let mut metadata = FungibleTokenMetadata::default();
+        metadata.icon = Some("https://oysterpack.com/stake.svg".to_string());
+        metadata.assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "`decimals` must be 24")]
+    fn invalid_decimals() {
+        let mut metadata = FungibleTokenMetadata::default();
+        metadata.decimals = 18;
+        metadata.assert_valid();
+    }
+}
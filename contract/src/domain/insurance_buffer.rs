@@ -0,0 +1,79 @@
+use crate::domain::YoctoNear;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// NEAR that is set aside from contract earnings to absorb staking losses, e.g., if a staking pool
+/// is slashed, before the loss is passed on to STAKE holders by lowering the STAKE token value.
+///
+/// The insurance buffer is funded when earnings are distributed - see
+/// [insurance_buffer_earnings_percentage](crate::config::Config::insurance_buffer_earnings_percentage).
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct InsuranceBuffer {
+    /// NEAR that is available to absorb losses
+    balance: YoctoNear,
+    /// total staking losses that were absorbed by the insurance buffer since the contract was deployed
+    total_absorbed_losses: YoctoNear,
+    /// total staking losses that could not be absorbed, and were passed on to STAKE holders and
+    /// redeem stake batch receipts since the contract was deployed
+    total_uncovered_losses: YoctoNear,
+}
+
+impl InsuranceBuffer {
+    pub fn balance(&self) -> YoctoNear {
+        self.balance
+    }
+
+    pub fn total_absorbed_losses(&self) -> YoctoNear {
+        self.total_absorbed_losses
+    }
+
+    pub fn total_uncovered_losses(&self) -> YoctoNear {
+        self.total_uncovered_losses
+    }
+
+    pub fn fund(&mut self, amount: YoctoNear) {
+        self.balance += amount;
+    }
+
+    /// absorbs as much of the loss as the balance covers, and returns the amount that was absorbed
+    /// - the remainder is recorded as an uncovered loss
+    pub fn absorb(&mut self, loss: YoctoNear) -> YoctoNear {
+        let absorbed = loss.min(self.balance);
+        self.balance -= absorbed;
+        self.total_absorbed_losses += absorbed;
+        self.total_uncovered_losses += loss - absorbed;
+        absorbed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::near::YOCTO;
+
+    #[test]
+    fn absorb_loss() {
+        let mut insurance_buffer = InsuranceBuffer::default();
+        insurance_buffer.fund((10 * YOCTO).into());
+
+        assert_eq!(
+            insurance_buffer.absorb((4 * YOCTO).into()),
+            (4 * YOCTO).into()
+        );
+        assert_eq!(insurance_buffer.balance(), (6 * YOCTO).into());
+
+        // the loss is partially covered
+        assert_eq!(
+            insurance_buffer.absorb((10 * YOCTO).into()),
+            (6 * YOCTO).into()
+        );
+        assert_eq!(insurance_buffer.balance(), 0.into());
+        assert_eq!(
+            insurance_buffer.total_absorbed_losses(),
+            (10 * YOCTO).into()
+        );
+        assert_eq!(
+            insurance_buffer.total_uncovered_losses(),
+            (4 * YOCTO).into()
+        );
+    }
+}
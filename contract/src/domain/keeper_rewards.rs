@@ -0,0 +1,113 @@
+use crate::domain::{EpochHeight, YoctoNear};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    env, AccountId,
+};
+
+/// Tracks the keepers that kicked off the batches that are currently running, and the keeper rewards
+/// that are paid out.
+///
+/// Keeper rewards are paid out of the keeper rewards pool, which is funded from contract earnings
+/// when earnings are distributed.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Default)]
+pub struct KeeperRewards {
+    /// account that ran the [StakeBatch](crate::domain::StakeBatch) that is currently running
+    stake_batch_keeper_id: Option<AccountId>,
+    /// account that ran the [RedeemStakeBatch](crate::domain::RedeemStakeBatch) that is currently running
+    redeem_stake_batch_keeper_id: Option<AccountId>,
+
+    /// NEAR that has been set aside from contract earnings to pay keeper rewards
+    balance: YoctoNear,
+    /// total keeper rewards paid out since the contract was deployed
+    total_paid: YoctoNear,
+
+    /// epoch within which the last keeper reward was paid
+    epoch_height: EpochHeight,
+    /// number of keeper rewards paid out within [`KeeperRewards::epoch_height`]
+    epoch_rewards_count: u32,
+}
+
+impl KeeperRewards {
+    pub fn balance(&self) -> YoctoNear {
+        self.balance
+    }
+
+    pub fn total_paid(&self) -> YoctoNear {
+        self.total_paid
+    }
+
+    /// returns the number of keeper rewards that have been paid out in the current epoch
+    pub fn epoch_rewards_count(&self) -> u32 {
+        if self.epoch_height.value() == env::epoch_height() {
+            self.epoch_rewards_count
+        } else {
+            0
+        }
+    }
+
+    pub fn set_stake_batch_keeper(&mut self, keeper_id: AccountId) {
+        self.stake_batch_keeper_id = Some(keeper_id);
+    }
+
+    pub fn take_stake_batch_keeper(&mut self) -> Option<AccountId> {
+        self.stake_batch_keeper_id.take()
+    }
+
+    pub fn set_redeem_stake_batch_keeper(&mut self, keeper_id: AccountId) {
+        self.redeem_stake_batch_keeper_id = Some(keeper_id);
+    }
+
+    pub fn take_redeem_stake_batch_keeper(&mut self) -> Option<AccountId> {
+        self.redeem_stake_batch_keeper_id.take()
+    }
+
+    pub fn fund(&mut self, amount: YoctoNear) {
+        self.balance += amount;
+    }
+
+    /// debits the reward from the keeper rewards pool and counts it towards the current epoch
+    ///
+    /// ## Panics
+    /// if the keeper rewards pool balance is insufficient
+    pub fn pay(&mut self, amount: YoctoNear) {
+        assert!(
+            self.balance >= amount,
+            "keeper rewards pool balance is insufficient"
+        );
+        self.balance -= amount;
+        self.total_paid += amount;
+        self.epoch_rewards_count = self.epoch_rewards_count() + 1;
+        self.epoch_height = env::epoch_height().into();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{near::YOCTO, test_utils::new_context};
+    use near_sdk::{testing_env, MockedBlockchain, VMContext};
+
+    fn context(epoch_height: u64) -> VMContext {
+        let mut context = new_context("stake.oysterpack.near");
+        context.epoch_height = epoch_height;
+        context
+    }
+
+    #[test]
+    fn epoch_rewards_count_is_reset_each_epoch() {
+        testing_env!(context(10));
+        let mut keeper_rewards = KeeperRewards::default();
+        keeper_rewards.fund((10 * YOCTO).into());
+        keeper_rewards.pay(YOCTO.into());
+        keeper_rewards.pay(YOCTO.into());
+        assert_eq!(keeper_rewards.epoch_rewards_count(), 2);
+        assert_eq!(keeper_rewards.balance(), (8 * YOCTO).into());
+        assert_eq!(keeper_rewards.total_paid(), (2 * YOCTO).into());
+
+        testing_env!(context(11));
+        assert_eq!(keeper_rewards.epoch_rewards_count(), 0);
+        keeper_rewards.pay(YOCTO.into());
+        assert_eq!(keeper_rewards.epoch_rewards_count(), 1);
+        assert_eq!(keeper_rewards.total_paid(), (3 * YOCTO).into());
+    }
+}
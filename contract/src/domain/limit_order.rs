@@ -0,0 +1,167 @@
+use crate::{
+    domain::{BatchId, EpochHeight},
+    near::YOCTO,
+};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    collections::LookupMap,
+    env,
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+/// the number of limit orders per batch is bounded to keep the gas required to run the batch in check
+pub const MAX_LIMIT_ORDERS_PER_BATCH: usize = 50;
+
+/// limit orders below 1 NEAR, or 1 STAKE for redeem requests, are not accepted - this keeps the
+/// limit order slots from being filled up cheaply
+pub const MIN_LIMIT_ORDER_AMOUNT: u128 = YOCTO;
+
+/// price and deadline limits that are attached to a deposit or redeem request
+/// - the limits are checked against the STAKE token value that the batch is run at
+/// - if the limits are not met, then the funds are returned to the account
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct LimitOrder {
+    account_id: AccountId,
+    /// NEAR for deposits, and STAKE for redeem requests
+    amount: u128,
+    /// min STAKE out for deposits, and min NEAR out for redeem requests
+    min_amount_out: u128,
+    /// the batch must be run on or before the deadline epoch
+    deadline_epoch: EpochHeight,
+}
+
+impl LimitOrder {
+    pub fn new(
+        account_id: &str,
+        amount: u128,
+        min_amount_out: u128,
+        deadline_epoch: EpochHeight,
+    ) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            amount,
+            min_amount_out,
+            deadline_epoch,
+        }
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn amount(&self) -> u128 {
+        self.amount
+    }
+
+    pub fn min_amount_out(&self) -> u128 {
+        self.min_amount_out
+    }
+
+    pub fn deadline_epoch(&self) -> EpochHeight {
+        self.deadline_epoch
+    }
+
+    /// returns the reason the order is rejected if the batch is run right now at a price that pays
+    /// out `amount_out`
+    pub fn rejection(&self, amount_out: u128) -> Option<LimitOrderRejection> {
+        if env::epoch_height() > self.deadline_epoch.value() {
+            Some(LimitOrderRejection::DeadlinePassed)
+        } else if amount_out < self.min_amount_out {
+            Some(LimitOrderRejection::BelowMinAmountOut)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum LimitOrderRejection {
+    /// the batch was run after the deadline epoch
+    DeadlinePassed,
+    /// the batch price would have paid out less than the min amount out
+    BelowMinAmountOut,
+}
+
+/// [LimitOrder]s grouped by the batch they were submitted into
+/// - orders are taken out when the batch is run
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct LimitOrders {
+    orders: LookupMap<BatchId, Vec<LimitOrder>>,
+}
+
+impl LimitOrders {
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self {
+            orders: LookupMap::new(prefix),
+        }
+    }
+
+    pub fn contains(&self, batch_id: BatchId) -> bool {
+        self.orders.contains_key(&batch_id)
+    }
+
+    pub fn get(&self, batch_id: BatchId) -> Vec<LimitOrder> {
+        self.orders.get(&batch_id).unwrap_or_default()
+    }
+
+    /// returns false if the batch already holds the max number of orders
+    pub fn push(&mut self, batch_id: BatchId, order: LimitOrder) -> bool {
+        let mut orders = self.get(batch_id);
+        if orders.len() == MAX_LIMIT_ORDERS_PER_BATCH {
+            return false;
+        }
+        orders.push(order);
+        self.orders.insert(&batch_id, &orders);
+        true
+    }
+
+    pub fn take(&mut self, batch_id: BatchId) -> Vec<LimitOrder> {
+        self.orders.remove(&batch_id).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn limit_order_rejection() {
+        let mut context = new_context("account.near");
+        context.epoch_height = 10;
+        testing_env!(context.clone());
+
+        let order = LimitOrder::new("account.near", YOCTO, YOCTO, 10.into());
+        assert_eq!(order.rejection(YOCTO), None);
+        assert_eq!(
+            order.rejection(YOCTO - 1),
+            Some(LimitOrderRejection::BelowMinAmountOut)
+        );
+
+        context.epoch_height = 11;
+        testing_env!(context);
+        assert_eq!(
+            order.rejection(YOCTO),
+            Some(LimitOrderRejection::DeadlinePassed)
+        );
+    }
+
+    #[test]
+    fn orders_per_batch_are_bounded() {
+        testing_env!(new_context("account.near"));
+        let mut orders = LimitOrders::new(vec![1]);
+        let batch_id = BatchId(1);
+        for _ in 0..MAX_LIMIT_ORDERS_PER_BATCH {
+            assert!(orders.push(batch_id, LimitOrder::new("account.near", 1, 1, 1.into())));
+        }
+        assert!(!orders.push(batch_id, LimitOrder::new("account.near", 1, 1, 1.into())));
+        assert!(orders.contains(batch_id));
+
+        assert_eq!(orders.take(batch_id).len(), MAX_LIMIT_ORDERS_PER_BATCH);
+        assert!(!orders.contains(batch_id));
+        assert!(orders.get(batch_id).is_empty());
+    }
+}
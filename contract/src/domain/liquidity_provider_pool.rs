@@ -0,0 +1,133 @@
+use crate::core::U256;
+use crate::domain::{Account, YoctoNear};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// NEAR liquidity that is provided by liquidity providers (LPs) to fund instant redemptions.
+///
+/// When an instant redemption is funded by the pool, the pool pays out NEAR for the redeemed STAKE
+/// minus the instant redeem fee, and the STAKE is submitted into the redeem stake batch on behalf
+/// of the pool. Once the batch has been unstaked and withdrawn, the NEAR is claimed back into the pool.
+/// The instant redeem fee is the spread that LPs earn.
+///
+/// The pool value is made up of the NEAR balance plus the NEAR value of the STAKE that is being
+/// redeemed. LPs own the pool via shares, which are issued and redeemed against the pool value.
+/// - LP funds are tracked separately from user account balances and do not back the STAKE token value
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy)]
+pub struct LiquidityProviderPool {
+    /// NEAR that is available to fund instant redemptions and LP withdrawals
+    near: YoctoNear,
+    /// used to track the STAKE that the pool has submitted for redemption using the same batch
+    /// workflow as user accounts
+    account: Account,
+    /// total LP shares that have been issued
+    total_shares: u128,
+    /// total instant redeem fees that have been earned by the pool
+    total_fees_earned: YoctoNear,
+}
+
+impl Default for LiquidityProviderPool {
+    fn default() -> Self {
+        Self {
+            near: 0.into(),
+            account: Account::new(0.into()),
+            total_shares: 0,
+            total_fees_earned: 0.into(),
+        }
+    }
+}
+
+impl LiquidityProviderPool {
+    pub fn near(&self) -> YoctoNear {
+        self.near
+    }
+
+    pub fn account(&self) -> Account {
+        self.account
+    }
+
+    pub fn set_account(&mut self, account: Account) {
+        self.account = account;
+    }
+
+    pub fn total_shares(&self) -> u128 {
+        self.total_shares
+    }
+
+    pub fn total_fees_earned(&self) -> YoctoNear {
+        self.total_fees_earned
+    }
+
+    pub fn credit_near(&mut self, amount: YoctoNear) {
+        self.near += amount;
+    }
+
+    /// ## Panics
+    /// if the pool NEAR balance is insufficient
+    pub fn debit_near(&mut self, amount: YoctoNear) {
+        assert!(
+            self.near >= amount,
+            "liquidity provider pool NEAR balance is insufficient"
+        );
+        self.near -= amount;
+    }
+
+    pub fn record_fee(&mut self, fee: YoctoNear) {
+        self.total_fees_earned += fee;
+    }
+
+    /// returns the number of shares that the NEAR deposit is worth given the current pool value
+    /// - shares are rounded down in favor of the pool
+    pub fn shares_for_deposit(&self, amount: YoctoNear, pool_value: YoctoNear) -> u128 {
+        if self.total_shares == 0 || pool_value.value() == 0 {
+            return amount.value();
+        }
+        (U256::from(amount.value()) * U256::from(self.total_shares)
+            / U256::from(pool_value.value()))
+        .as_u128()
+    }
+
+    /// returns the NEAR value of the shares given the current pool value
+    /// - NEAR is rounded down in favor of the pool
+    pub fn shares_value(&self, shares: u128, pool_value: YoctoNear) -> YoctoNear {
+        if self.total_shares == 0 {
+            return 0.into();
+        }
+        (U256::from(shares) * U256::from(pool_value.value()) / U256::from(self.total_shares))
+            .as_u128()
+            .into()
+    }
+
+    pub fn issue_shares(&mut self, shares: u128) {
+        self.total_shares += shares;
+    }
+
+    pub fn retire_shares(&mut self, shares: u128) {
+        self.total_shares -= shares;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::near::YOCTO;
+
+    #[test]
+    fn shares_are_priced_against_pool_value() {
+        let mut pool = LiquidityProviderPool::default();
+
+        // first deposit is issued shares 1:1
+        let shares = pool.shares_for_deposit((10 * YOCTO).into(), 0.into());
+        assert_eq!(shares, 10 * YOCTO);
+        pool.issue_shares(shares);
+        pool.credit_near((10 * YOCTO).into());
+
+        // pool value has grown from fees - shares are worth more NEAR
+        let pool_value: YoctoNear = (11 * YOCTO).into();
+        assert_eq!(
+            pool.shares_value(5 * YOCTO, pool_value),
+            (11 * YOCTO / 2).into()
+        );
+        let shares = pool.shares_for_deposit((11 * YOCTO).into(), pool_value);
+        assert_eq!(shares, 10 * YOCTO);
+    }
+}
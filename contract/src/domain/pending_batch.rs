@@ -0,0 +1,44 @@
+use crate::domain::{BatchId, RedeemStakeBatch, StakeBatch};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+
+/// an account batch that has not been claimed yet, but is no longer held in the account's batch slots
+/// - the account's batch slots are reserved for the contract's current and next batches - when a slot
+///   is needed while it is still holding an older batch, the older batch is moved into the account's
+///   pending batches
+/// - pending batches are claimed once their batch receipts can be claimed
+/// - the account pays for the pending batch storage out of its storage escrow
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub enum PendingBatch {
+    Stake(StakeBatch),
+    Redeem(RedeemStakeBatch),
+}
+
+impl PendingBatch {
+    pub fn id(&self) -> BatchId {
+        match self {
+            PendingBatch::Stake(batch) => batch.id(),
+            PendingBatch::Redeem(batch) => batch.id(),
+        }
+    }
+
+    pub fn stake_batch(&self) -> Option<StakeBatch> {
+        match self {
+            PendingBatch::Stake(batch) => Some(*batch),
+            PendingBatch::Redeem(_) => None,
+        }
+    }
+
+    pub fn redeem_stake_batch(&self) -> Option<RedeemStakeBatch> {
+        match self {
+            PendingBatch::Stake(_) => None,
+            PendingBatch::Redeem(batch) => Some(*batch),
+        }
+    }
+
+    /// the purpose for this constructor is to create a fully allocated [PendingBatch] object instance
+    /// to be used to measure pending batch storage usage when the contract is deployed
+    /// - both variants serialize to the same size
+    pub(crate) fn pending_batch_template_to_measure_storage_usage() -> Self {
+        PendingBatch::Redeem(RedeemStakeBatch::new(0.into(), 0.into()))
+    }
+}
@@ -0,0 +1,72 @@
+use crate::domain::YoctoStake;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// Tracks STAKE that was redeemed via a transfer call to the STAKE contract on behalf of another
+/// account, i.e., the beneficiary. When the account claims the NEAR for the redeemed STAKE, the NEAR
+/// is transferred to the beneficiary.
+/// - there is at most 1 beneficiary per account per [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct RedeemBeneficiary {
+    account_id: AccountId,
+    stake: YoctoStake,
+}
+
+impl RedeemBeneficiary {
+    pub fn new(account_id: &str) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            stake: 0.into(),
+        }
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// how much redeemed STAKE the beneficiary is owed NEAR for
+    pub fn stake(&self) -> YoctoStake {
+        self.stake
+    }
+
+    pub fn credit(&mut self, amount: YoctoStake) {
+        self.stake += amount;
+    }
+
+    /// returns the amount debited, which is capped by the beneficiary's STAKE balance
+    pub fn debit(&mut self, amount: YoctoStake) -> YoctoStake {
+        let amount = if amount > self.stake {
+            self.stake
+        } else {
+            amount
+        };
+        self.stake -= amount;
+        amount
+    }
+
+    /// the purpose for this constructor is to create a fully allocated [RedeemBeneficiary] object
+    /// instance to be used to measure storage usage when the contract is deployed
+    /// - NEAR account IDs are max 64 chars
+    pub(crate) fn redeem_beneficiary_template_to_measure_storage_usage() -> Self {
+        Self {
+            account_id: "X".repeat(64),
+            stake: 0.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn debit_is_capped_by_stake_balance() {
+        let mut beneficiary = RedeemBeneficiary::new("bob.near");
+        beneficiary.credit(100.into());
+        assert_eq!(beneficiary.debit(60.into()), 60.into());
+        assert_eq!(beneficiary.debit(60.into()), 40.into());
+        assert_eq!(beneficiary.stake(), 0.into());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::core::Hash;
+use crate::domain::{BatchId, BlockTimestamp, YoctoNear, YoctoStake};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    collections::LookupMap,
+    env, AccountId,
+};
+
+/// request to redeem STAKE that is queued up to be filled from the NEAR liquidity pool while the
+/// [RedeemStakeBatch](crate::domain::RedeemStakeBatch) is pending withdrawal
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct RedeemRequest {
+    account_id: AccountId,
+    batch_id: BatchId,
+    /// STAKE that has not yet been filled
+    stake: YoctoStake,
+    /// NEAR that has been paid out from liquidity to partially fill the request
+    filled_near: YoctoNear,
+    requested_at: BlockTimestamp,
+}
+
+impl RedeemRequest {
+    pub fn new(account_id: &str, batch_id: BatchId, stake: YoctoStake) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            batch_id,
+            stake,
+            filled_near: 0.into(),
+            requested_at: env::block_timestamp().into(),
+        }
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn batch_id(&self) -> BatchId {
+        self.batch_id
+    }
+
+    pub fn stake(&self) -> YoctoStake {
+        self.stake
+    }
+
+    pub fn filled_near(&self) -> YoctoNear {
+        self.filled_near
+    }
+
+    pub fn requested_at(&self) -> BlockTimestamp {
+        self.requested_at
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.stake.value() == 0
+    }
+
+    /// ## Panics
+    /// if more STAKE is filled than was requested
+    pub fn fill(&mut self, stake: YoctoStake, near: YoctoNear) {
+        self.stake -= stake;
+        self.filled_near += near;
+    }
+}
+
+/// FIFO queue of [RedeemRequest]s
+/// - requests are ordered by batch and request time because STAKE can only be redeemed into the
+///   next batch while the current batch is being unstaked
+/// - each account holds at most 1 request per batch, which bounds the queue storage because only
+///   the contract's current and next batches can be redeemed into
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct RedeemQueue {
+    requests: LookupMap<u64, RedeemRequest>,
+    /// indexes of the account's requests in FIFO order, keyed by account ID hash
+    account_requests: LookupMap<Hash, Vec<u64>>,
+    /// index of the request at the head of the queue
+    head: u64,
+    /// index that the next request will be pushed to
+    tail: u64,
+}
+
+impl RedeemQueue {
+    pub fn new(prefix: Vec<u8>, account_requests_prefix: Vec<u8>) -> Self {
+        Self {
+            requests: LookupMap::new(prefix),
+            account_requests: LookupMap::new(account_requests_prefix),
+            head: 0,
+            tail: 0,
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.tail - self.head
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// if the account already has a request queued up for the same batch, then the request is merged
+    /// into it, i.e., the STAKE is added to the queued request and keeps its place in the queue
+    pub fn push(&mut self, request: RedeemRequest) {
+        let key = Hash::from(request.account_id());
+        let mut indexes = self.account_requests.get(&key).unwrap_or_default();
+        for index in indexes.iter() {
+            if let Some(mut queued_request) = self.requests.get(index) {
+                if queued_request.batch_id == request.batch_id {
+                    queued_request.stake += request.stake;
+                    self.requests.insert(index, &queued_request);
+                    return;
+                }
+            }
+        }
+        self.requests.insert(&self.tail, &request);
+        indexes.push(self.tail);
+        self.account_requests.insert(&key, &indexes);
+        self.tail += 1;
+    }
+
+    pub fn peek(&self) -> Option<RedeemRequest> {
+        if self.is_empty() {
+            return None;
+        }
+        self.requests.get(&self.head)
+    }
+
+    /// updates the request at the head of the queue
+    pub fn update_head(&mut self, request: &RedeemRequest) {
+        if !self.is_empty() {
+            self.requests.insert(&self.head, request);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<RedeemRequest> {
+        if self.is_empty() {
+            return None;
+        }
+        let request = self.requests.remove(&self.head);
+        if let Some(request) = request.as_ref() {
+            let key = Hash::from(request.account_id());
+            let mut indexes = self.account_requests.get(&key).unwrap_or_default();
+            indexes.retain(|index| *index != self.head);
+            if indexes.is_empty() {
+                self.account_requests.remove(&key);
+            } else {
+                self.account_requests.insert(&key, &indexes);
+            }
+        }
+        self.head += 1;
+        request
+    }
+
+    /// returns the account's requests in FIFO order, along with each request's position in the
+    /// queue, i.e., the number of requests ahead of it
+    pub fn account_requests(&self, account_id: &str) -> Vec<(u64, RedeemRequest)> {
+        self.account_requests
+            .get(&Hash::from(account_id))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|index| {
+                self.requests
+                    .get(&index)
+                    .map(|request| (index - self.head, request))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::new_context;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn requests_are_first_in_first_out() {
+        testing_env!(new_context("stake.oysterpack.near"));
+        let mut queue = RedeemQueue::new(vec![100], vec![101]);
+        queue.push(RedeemRequest::new("alice", 1.into(), 10.into()));
+        queue.push(RedeemRequest::new("bob", 1.into(), 20.into()));
+        // requests for the same account and batch are merged
+        queue.push(RedeemRequest::new("alice", 1.into(), 5.into()));
+        queue.push(RedeemRequest::new("alice", 2.into(), 30.into()));
+        queue.push(RedeemRequest::new("alice", 2.into(), 10.into()));
+        assert_eq!(queue.len(), 3);
+        let alice_requests = queue.account_requests("alice");
+        assert_eq!(alice_requests.len(), 2);
+        assert_eq!(alice_requests[0].0, 0);
+        assert_eq!(alice_requests[0].1.stake(), 15.into());
+        assert_eq!(alice_requests[1].0, 2);
+        assert_eq!(alice_requests[1].1.stake(), 40.into());
+
+        let mut request = queue.peek().unwrap();
+        assert_eq!(request.account_id(), "alice");
+        assert_eq!(request.stake(), 15.into());
+        request.fill(5.into(), 6.into());
+        queue.update_head(&request);
+        assert_eq!(queue.peek().unwrap().stake(), 10.into());
+        assert_eq!(queue.peek().unwrap().filled_near(), 6.into());
+
+        queue.pop();
+        assert_eq!(queue.peek().unwrap().account_id(), "bob");
+        // positions are relative to the head of the queue
+        let alice_requests = queue.account_requests("alice");
+        assert_eq!(alice_requests.len(), 1);
+        assert_eq!(alice_requests[0].0, 1);
+        queue.pop();
+        queue.pop();
+        assert!(queue.is_empty());
+        assert!(queue.pop().is_none());
+        // the account index is cleaned up as requests are popped
+        assert!(queue.account_requests("alice").is_empty());
+        assert!(queue.account_requests.get(&Hash::from("alice")).is_none());
+    }
+}
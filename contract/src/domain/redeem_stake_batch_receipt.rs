@@ -1,3 +1,4 @@
+use crate::core::U256;
 use crate::{
     domain::{EpochHeight, StakeTokenValue, YoctoNear, YoctoStake},
     near::UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK,
@@ -7,10 +8,31 @@ use near_sdk::{
     env,
 };
 
-#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+#[derive(BorshSerialize, Clone, Copy, Debug)]
 pub struct RedeemStakeBatchReceipt {
     redeemed_stake: YoctoStake,
     stake_token_value: StakeTokenValue,
+    /// NEAR that was haircut from the receipt's claims to absorb staking losses while the unstaked
+    /// NEAR was pending withdrawal
+    haircut: YoctoNear,
+}
+
+/// receipts that were created before the contract state was versioned are stored without a haircut
+impl BorshDeserialize for RedeemStakeBatchReceipt {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let redeemed_stake = BorshDeserialize::deserialize(buf)?;
+        let stake_token_value = BorshDeserialize::deserialize(buf)?;
+        let haircut = if buf.is_empty() {
+            0.into()
+        } else {
+            BorshDeserialize::deserialize(buf)?
+        };
+        Ok(Self {
+            redeemed_stake,
+            stake_token_value,
+            haircut,
+        })
+    }
 }
 
 impl RedeemStakeBatchReceipt {
@@ -18,6 +40,7 @@ impl RedeemStakeBatchReceipt {
         Self {
             redeemed_stake,
             stake_token_value,
+            haircut: 0.into(),
         }
     }
 
@@ -33,6 +56,32 @@ impl RedeemStakeBatchReceipt {
         self.stake_token_value
     }
 
+    pub fn haircut(&self) -> YoctoNear {
+        self.haircut
+    }
+
+    /// reduces the NEAR value of the unclaimed STAKE by the specified haircut, i.e., the loss is
+    /// shared proportionally by the accounts that have not yet claimed their NEAR
+    /// - the receipt's STAKE token value is scaled down - the haircut is capped at the receipt's
+    ///   NEAR value
+    pub fn apply_haircut(&mut self, haircut: YoctoNear) {
+        let stake_near_value = self.stake_near_value();
+        if stake_near_value.value() == 0 || haircut.value() == 0 {
+            return;
+        }
+        let haircut = haircut.min(stake_near_value);
+        let total_staked_near_balance =
+            U256::from(self.stake_token_value.total_staked_near_balance())
+                * U256::from((stake_near_value - haircut).value())
+                / U256::from(stake_near_value.value());
+        self.stake_token_value = StakeTokenValue::new(
+            self.stake_token_value.block_time_height(),
+            total_staked_near_balance.as_u128().into(),
+            self.stake_token_value.total_stake_supply(),
+        );
+        self.haircut += haircut;
+    }
+
     /// returns the epoch within which the unstaked NEAR funds will be available for withdrawal from
     /// the staking pool
     pub fn unstaked_near_withdrawal_availability(&self) -> EpochHeight {
@@ -10,6 +10,8 @@ use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 /// STAKE token value = [total_staked_near_balance](StakeTokenValue::total_staked_near_balance) / [total_stake_supply](StakeTokenValue::total_stake_supply)
 ///
 /// NOTE: The STAKE token value is gathered while the contract is locked.
+/// - the STAKE token value may drop below 1 NEAR if the staking pools lose funds, e.g., slashing -
+///   see [update_stake_token_value](crate::Contract::update_stake_token_value)
 #[derive(BorshSerialize, BorshDeserialize, Copy, Clone, Default, Debug)]
 pub struct StakeTokenValue {
     block_time_height: BlockTimeHeight,
@@ -23,13 +25,6 @@ impl StakeTokenValue {
         total_staked_near_balance: YoctoNear,
         total_stake_supply: YoctoStake,
     ) -> Self {
-        assert!(
-            total_staked_near_balance.value() >= total_stake_supply.value(),
-            "total staked NEAR balance ({}) should always >= total STAKE supply ({}) : {}",
-            total_staked_near_balance.value(),
-            total_stake_supply.value(),
-            total_stake_supply.value() - total_staked_near_balance.value()
-        );
         Self {
             block_time_height,
             total_staked_near_balance,
@@ -69,11 +64,7 @@ impl StakeTokenValue {
     /// - we round up because we never want to short change the payout
     /// - this also helps to compensate for rounding down when we convert NEAR -> STAKE
     pub fn stake_to_near(&self, stake: YoctoStake) -> YoctoNear {
-        if self.total_staked_near_balance.value() == 0
-            // when deposit and staked with staking pool, there is a small amount remaining as unstaked
-            // however, STAKE token value should never be less than 1:1 in terms of NEAR
-            || self.total_staked_near_balance.value() < self.total_stake_supply.value()
-        {
+        if self.total_staked_near_balance.value() == 0 {
             return stake.value().into();
         }
 
@@ -155,4 +146,25 @@ mod test {
 
         assert_eq!(near_value, YoctoNear(YOCTO));
     }
+
+    #[test]
+    fn stake_token_value_can_drop_below_1_near() {
+        let account_id = "bob.near";
+        let context = new_context(account_id);
+        testing_env!(context);
+
+        let stake_token_value = StakeTokenValue::new(
+            BlockTimeHeight::from_env(),
+            (90 * YOCTO).into(),
+            (100 * YOCTO).into(),
+        );
+        assert_eq!(
+            stake_token_value.stake_to_near((10 * YOCTO).into()),
+            YoctoNear(9 * YOCTO)
+        );
+        assert_eq!(
+            stake_token_value.near_to_stake((9 * YOCTO).into()),
+            YoctoStake(10 * YOCTO)
+        );
+    }
 }
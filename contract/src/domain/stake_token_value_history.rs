@@ -0,0 +1,230 @@
+use crate::core::U256;
+use crate::domain::{EpochHeight, StakeTokenValue};
+use crate::near::YOCTO;
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    collections::LookupMap,
+};
+
+/// max number of [StakeTokenValue] samples that are retained, i.e., roughly 1 year of epochs
+pub const STAKE_TOKEN_VALUE_HISTORY_CAPACITY: u64 = 730;
+
+const YEAR_NANOS: u128 = 365 * 24 * 60 * 60 * 1_000_000_000;
+
+/// Bounded ring buffer of [StakeTokenValue] samples, which is used to audit past STAKE pricing and
+/// to compute yield on-chain.
+/// - at most 1 sample is retained per epoch - the sample is replaced by the latest STAKE token value
+///   within the epoch
+/// - once the buffer is full, the oldest sample is overwritten
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct StakeTokenValueHistory {
+    samples: LookupMap<u64, StakeTokenValue>,
+    /// total number of samples that have been recorded - the next sample is stored at slot
+    /// `count % STAKE_TOKEN_VALUE_HISTORY_CAPACITY`
+    count: u64,
+}
+
+impl StakeTokenValueHistory {
+    pub fn new(prefix: Vec<u8>) -> Self {
+        Self {
+            samples: LookupMap::new(prefix),
+            count: 0,
+        }
+    }
+
+    /// number of samples that are retained
+    pub fn len(&self) -> u64 {
+        self.count.min(STAKE_TOKEN_VALUE_HISTORY_CAPACITY)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn latest(&self) -> Option<StakeTokenValue> {
+        if self.is_empty() {
+            return None;
+        }
+        self.get(self.len() - 1)
+    }
+
+    /// if a sample has already been recorded for the epoch, then it is replaced
+    pub fn record(&mut self, stake_token_value: StakeTokenValue) {
+        let epoch_height = stake_token_value.block_time_height().epoch_height();
+        match self.latest() {
+            Some(latest) if latest.block_time_height().epoch_height() > epoch_height => {}
+            Some(latest) if latest.block_time_height().epoch_height() == epoch_height => {
+                self.samples.insert(
+                    &((self.count - 1) % STAKE_TOKEN_VALUE_HISTORY_CAPACITY),
+                    &stake_token_value,
+                );
+            }
+            _ => {
+                self.samples.insert(
+                    &(self.count % STAKE_TOKEN_VALUE_HISTORY_CAPACITY),
+                    &stake_token_value,
+                );
+                self.count += 1;
+            }
+        }
+    }
+
+    /// returns up to `limit` samples in epoch order, starting from the first sample that was
+    /// recorded at or after `from_epoch`
+    pub fn page(&self, from_epoch: EpochHeight, limit: u64) -> Vec<StakeTokenValue> {
+        let start =
+            self.partition_point(|sample| sample.block_time_height().epoch_height() < from_epoch);
+        (start..self.len().min(start.saturating_add(limit)))
+            .filter_map(|index| self.get(index))
+            .collect()
+    }
+
+    /// returns the latest sample that was recorded at or before the specified epoch
+    pub fn at_or_before(&self, epoch_height: EpochHeight) -> Option<StakeTokenValue> {
+        let end = self
+            .partition_point(|sample| sample.block_time_height().epoch_height() <= epoch_height);
+        if end == 0 {
+            None
+        } else {
+            self.get(end - 1)
+        }
+    }
+
+    /// `index` is relative to the oldest sample that is retained
+    fn get(&self, index: u64) -> Option<StakeTokenValue> {
+        let oldest = self.count - self.len();
+        self.samples
+            .get(&((oldest + index) % STAKE_TOKEN_VALUE_HISTORY_CAPACITY))
+    }
+
+    /// returns the index of the first sample for which the predicate is false - samples are
+    /// ordered by epoch, i.e., the predicate must be true for a prefix of the samples
+    fn partition_point<P: Fn(&StakeTokenValue) -> bool>(&self, predicate: P) -> u64 {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.get(mid) {
+                Some(sample) if predicate(&sample) => low = mid + 1,
+                _ => high = mid,
+            }
+        }
+        low
+    }
+}
+
+/// annualizes the change in the NEAR value of 1 STAKE between the 2 samples, i.e., the change is
+/// extrapolated linearly over a year
+/// - the APY is returned in basis points, and is negative if the STAKE token value dropped
+///
+/// Returns None if no time has elapsed between the samples
+pub fn apy_basis_points(from: &StakeTokenValue, to: &StakeTokenValue) -> Option<i64> {
+    let elapsed = to
+        .block_time_height()
+        .block_timestamp()
+        .value()
+        .checked_sub(from.block_time_height().block_timestamp().value())
+        .filter(|elapsed| *elapsed > 0)?;
+    let from_value = from.stake_to_near(YOCTO.into()).value();
+    let to_value = to.stake_to_near(YOCTO.into()).value();
+
+    let change = if to_value >= from_value {
+        to_value - from_value
+    } else {
+        from_value - to_value
+    };
+    let basis_points = (U256::from(change) * U256::from(10_000) * U256::from(YEAR_NANOS)
+        / (U256::from(from_value) * U256::from(elapsed)))
+    .min(U256::from(i64::MAX))
+    .as_u64() as i64;
+    if to_value >= from_value {
+        Some(basis_points)
+    } else {
+        Some(-basis_points)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{domain::BlockTimeHeight, test_utils::new_context};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    const EPOCH_NANOS: u64 = 12 * 60 * 60 * 1_000_000_000;
+
+    fn stake_token_value(epoch_height: u64, total_staked_near_balance: u128) -> StakeTokenValue {
+        let mut context = new_context("stake.oysterpack.near");
+        context.epoch_height = epoch_height;
+        context.block_index = epoch_height * 1000;
+        context.block_timestamp = epoch_height * EPOCH_NANOS;
+        testing_env!(context);
+        StakeTokenValue::new(
+            BlockTimeHeight::from_env(),
+            total_staked_near_balance.into(),
+            (100 * YOCTO).into(),
+        )
+    }
+
+    #[test]
+    fn one_sample_is_retained_per_epoch() {
+        testing_env!(new_context("stake.oysterpack.near"));
+        let mut history = StakeTokenValueHistory::new(vec![100]);
+        assert!(history.latest().is_none());
+
+        history.record(stake_token_value(1, 100 * YOCTO));
+        history.record(stake_token_value(1, 101 * YOCTO));
+        history.record(stake_token_value(3, 102 * YOCTO));
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history.latest().unwrap().total_staked_near_balance(),
+            (102 * YOCTO).into()
+        );
+
+        let page = history.page(0.into(), 10);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].total_staked_near_balance(), (101 * YOCTO).into());
+        assert_eq!(history.page(2.into(), 10).len(), 1);
+        assert_eq!(history.page(0.into(), 1).len(), 1);
+
+        assert_eq!(
+            history
+                .at_or_before(2.into())
+                .unwrap()
+                .block_time_height()
+                .epoch_height(),
+            1.into()
+        );
+        assert!(history.at_or_before(0.into()).is_none());
+    }
+
+    #[test]
+    fn oldest_sample_is_overwritten_when_full() {
+        testing_env!(new_context("stake.oysterpack.near"));
+        let mut history = StakeTokenValueHistory::new(vec![100]);
+        for epoch_height in 1..=STAKE_TOKEN_VALUE_HISTORY_CAPACITY + 5 {
+            history.record(stake_token_value(epoch_height, 100 * YOCTO));
+        }
+        assert_eq!(history.len(), STAKE_TOKEN_VALUE_HISTORY_CAPACITY);
+
+        let page = history.page(0.into(), 2);
+        assert_eq!(page[0].block_time_height().epoch_height(), 6.into());
+        assert_eq!(page[1].block_time_height().epoch_height(), 7.into());
+        assert_eq!(
+            history.latest().unwrap().block_time_height().epoch_height(),
+            (STAKE_TOKEN_VALUE_HISTORY_CAPACITY + 5).into()
+        );
+    }
+
+    #[test]
+    fn apy() {
+        // 730 epochs is 1 year
+        let from = stake_token_value(10, 100 * YOCTO);
+        let to = stake_token_value(740, 110 * YOCTO);
+        assert_eq!(apy_basis_points(&from, &to), Some(1000));
+
+        // STAKE token value dropped
+        let to = stake_token_value(375, 95 * YOCTO);
+        assert_eq!(apy_basis_points(&from, &to), Some(-1000));
+
+        assert!(apy_basis_points(&from, &from).is_none());
+    }
+}
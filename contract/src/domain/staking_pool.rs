@@ -0,0 +1,102 @@
+use crate::{core::U256, domain::YoctoNear};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// weight that is assigned to the staking pool that is specified when the contract is deployed
+pub const DEFAULT_STAKING_POOL_WEIGHT: u32 = 100;
+
+/// the contract queries every staking pool when the STAKE token value is refreshed, which means
+/// the number of staking pools is bounded by the gas limit
+pub const MAX_STAKING_POOLS: usize = 10;
+
+/// Staking pool that the contract delegates NEAR to.
+///
+/// The pool balances are cached from the most recent `get_account` result that was returned by the
+/// staking pool while processing a batch or refreshing the STAKE token value.
+/// - the target weight is relative to the sum of all staking pool weights
+/// - a zero weight means no new NEAR will be staked with the pool, and its stake will be unstaked
+///   first when STAKE is redeemed
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct StakingPool {
+    account_id: AccountId,
+    weight: u32,
+    staked_balance: YoctoNear,
+    unstaked_balance: YoctoNear,
+}
+
+impl StakingPool {
+    pub fn new(account_id: &str, weight: u32) -> Self {
+        Self {
+            account_id: account_id.to_string(),
+            weight,
+            staked_balance: 0.into(),
+            unstaked_balance: 0.into(),
+        }
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    pub fn set_weight(&mut self, weight: u32) {
+        self.weight = weight;
+    }
+
+    pub fn staked_balance(&self) -> YoctoNear {
+        self.staked_balance
+    }
+
+    pub fn unstaked_balance(&self) -> YoctoNear {
+        self.unstaked_balance
+    }
+
+    pub fn update_balances(&mut self, staked_balance: YoctoNear, unstaked_balance: YoctoNear) {
+        self.staked_balance = staked_balance;
+        self.unstaked_balance = unstaked_balance;
+    }
+
+    /// the staked balance that the pool should hold based on its weight
+    pub fn target_staked_balance(
+        &self,
+        total_staked_balance: YoctoNear,
+        total_weight: u32,
+    ) -> YoctoNear {
+        if total_weight == 0 {
+            return 0.into();
+        }
+        let target = U256::from(total_staked_balance.value()) * U256::from(self.weight)
+            / U256::from(total_weight);
+        target.as_u128().into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::near::YOCTO;
+
+    #[test]
+    fn target_staked_balance() {
+        let pool = StakingPool::new("pool.near", 1);
+        assert_eq!(
+            pool.target_staked_balance((300 * YOCTO).into(), 3),
+            (100 * YOCTO).into()
+        );
+        assert_eq!(
+            pool.target_staked_balance((300 * YOCTO).into(), 0),
+            0.into()
+        );
+
+        let pool = StakingPool::new("pool.near", 0);
+        assert_eq!(
+            pool.target_staked_balance((300 * YOCTO).into(), 3),
+            0.into()
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{
+    domain::{EpochHeight, YoctoNear},
+    near::UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK,
+};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    env,
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+/// [`StakingPoolMigrationStatus::Unstaking`] -> [`StakingPoolMigrationStatus::PendingWithdrawal`]
+/// -> [`StakingPoolMigrationStatus::Withdrawing`] -> [`StakingPoolMigrationStatus::Withdrawn`]
+/// -> [`StakingPoolMigrationStatus::Staking`] -> DONE
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum StakingPoolMigrationStatus {
+    /// request has been submitted to unstake all NEAR from the staking pool that is being migrated
+    Unstaking,
+    /// all NEAR has been unstaked, and is locked in the staking pool until it is available for
+    /// withdrawal
+    PendingWithdrawal,
+    /// request has been submitted to withdraw all unstaked NEAR from the staking pool that is being
+    /// migrated
+    Withdrawing,
+    /// the unstaked NEAR has been withdrawn and is held by the contract until it is staked with the
+    /// staking pool that is being migrated to
+    Withdrawn,
+    /// request has been submitted to deposit and stake the withdrawn NEAR with the staking pool
+    /// that is being migrated to
+    Staking,
+}
+
+/// Moves all NEAR staked with a staking pool over to another staking pool.
+///
+/// While the migration is in progress, deposits and redemptions are queued in the next batches,
+/// which are run once the migration completes.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct StakingPoolMigration {
+    from_staking_pool_id: AccountId,
+    to_staking_pool_id: AccountId,
+    status: StakingPoolMigrationStatus,
+    /// NEAR that was unstaked from the staking pool that is being migrated
+    unstaked_balance: YoctoNear,
+    /// epoch within which the NEAR was unstaked
+    unstaked_epoch_height: EpochHeight,
+}
+
+impl StakingPoolMigration {
+    pub fn new(from_staking_pool_id: &str, to_staking_pool_id: &str) -> Self {
+        Self {
+            from_staking_pool_id: from_staking_pool_id.to_string(),
+            to_staking_pool_id: to_staking_pool_id.to_string(),
+            status: StakingPoolMigrationStatus::Unstaking,
+            unstaked_balance: 0.into(),
+            unstaked_epoch_height: env::epoch_height().into(),
+        }
+    }
+
+    pub fn from_staking_pool_id(&self) -> &str {
+        &self.from_staking_pool_id
+    }
+
+    pub fn to_staking_pool_id(&self) -> &str {
+        &self.to_staking_pool_id
+    }
+
+    pub fn status(&self) -> StakingPoolMigrationStatus {
+        self.status
+    }
+
+    pub fn unstaked_balance(&self) -> YoctoNear {
+        self.unstaked_balance
+    }
+
+    /// returns true if a request has been submitted to a staking pool and the migration is waiting
+    /// on the result
+    pub fn is_running(&self) -> bool {
+        match self.status {
+            StakingPoolMigrationStatus::Unstaking
+            | StakingPoolMigrationStatus::Withdrawing
+            | StakingPoolMigrationStatus::Staking => true,
+            StakingPoolMigrationStatus::PendingWithdrawal
+            | StakingPoolMigrationStatus::Withdrawn => false,
+        }
+    }
+
+    /// NEAR that has been withdrawn from the staking pool being migrated, but is not yet staked with
+    /// the staking pool being migrated to
+    pub fn withdrawn_balance(&self) -> YoctoNear {
+        match self.status {
+            StakingPoolMigrationStatus::Withdrawn | StakingPoolMigrationStatus::Staking => {
+                self.unstaked_balance
+            }
+            _ => 0.into(),
+        }
+    }
+
+    /// returns the epoch within which the unstaked NEAR funds will be available for withdrawal from
+    /// the staking pool
+    pub fn unstaked_near_withdrawal_availability(&self) -> EpochHeight {
+        self.unstaked_epoch_height + UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK
+    }
+
+    pub fn unstaked_funds_available_for_withdrawal(&self) -> bool {
+        self.unstaked_near_withdrawal_availability().value() <= env::epoch_height()
+    }
+
+    pub fn unstaked(&mut self, unstaked_balance: YoctoNear) {
+        self.unstaked_balance = unstaked_balance;
+        self.unstaked_epoch_height = env::epoch_height().into();
+        self.status = StakingPoolMigrationStatus::PendingWithdrawal;
+    }
+
+    pub fn set_status(&mut self, status: StakingPoolMigrationStatus) {
+        self.status = status;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{near::YOCTO, test_utils::new_context};
+    use near_sdk::{testing_env, MockedBlockchain, VMContext};
+
+    fn context(epoch_height: u64) -> VMContext {
+        let mut context = new_context("stake.oysterpack.near");
+        context.epoch_height = epoch_height;
+        context
+    }
+
+    #[test]
+    fn unstaked_funds_available_for_withdrawal() {
+        testing_env!(context(10));
+        let mut migration = StakingPoolMigration::new("pool-1.near", "pool-2.near");
+        assert!(migration.is_running());
+
+        migration.unstaked((100 * YOCTO).into());
+        assert_eq!(
+            migration.status(),
+            StakingPoolMigrationStatus::PendingWithdrawal
+        );
+        assert!(!migration.is_running());
+        assert_eq!(migration.withdrawn_balance(), 0.into());
+        assert_eq!(migration.unstaked_near_withdrawal_availability(), 14.into());
+        assert!(!migration.unstaked_funds_available_for_withdrawal());
+
+        testing_env!(context(14));
+        assert!(migration.unstaked_funds_available_for_withdrawal());
+
+        migration.set_status(StakingPoolMigrationStatus::Withdrawn);
+        assert_eq!(migration.withdrawn_balance(), (100 * YOCTO).into());
+    }
+}
@@ -0,0 +1,60 @@
+use crate::domain::{BatchId, YoctoNear};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    AccountId,
+};
+
+/// [RedeemStakeBatch](crate::domain::RedeemStakeBatch) whose NEAR was unstaked and is waiting to be
+/// withdrawn from the staking pool, while the next redeem stake batch moves ahead and is unstaked
+/// from a different staking pool.
+///
+/// Unstaking resets the unlock epoch for all unstaked NEAR that is held by the staking pool. Thus,
+/// each staking pool holds the unstaked NEAR for at most 1 cohort, which means the NEAR that is
+/// withdrawn from a staking pool is attributed to the cohort that was unstaked from it.
+/// - the unlock epoch is tracked by the cohort's [RedeemStakeBatchReceipt](crate::domain::RedeemStakeBatchReceipt)
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct UnstakedCohort {
+    batch_id: BatchId,
+    staking_pool_id: AccountId,
+    /// NEAR that is pending withdrawal from the staking pool
+    /// - any NEAR liquidity that was added for the batch has already been applied
+    /// - net of the [haircut](UnstakedCohort::haircut)
+    unstaked_near: YoctoNear,
+    /// NEAR that is pending withdrawal, but was haircut from the cohort's claims to absorb staking
+    /// losses - once withdrawn, it is added to the NEAR liquidity pool
+    haircut: YoctoNear,
+}
+
+impl UnstakedCohort {
+    pub fn new(batch_id: BatchId, staking_pool_id: &str, unstaked_near: YoctoNear) -> Self {
+        Self {
+            batch_id,
+            staking_pool_id: staking_pool_id.to_string(),
+            unstaked_near,
+            haircut: 0.into(),
+        }
+    }
+
+    pub fn batch_id(&self) -> BatchId {
+        self.batch_id
+    }
+
+    pub fn staking_pool_id(&self) -> &str {
+        &self.staking_pool_id
+    }
+
+    pub fn unstaked_near(&self) -> YoctoNear {
+        self.unstaked_near
+    }
+
+    pub fn haircut(&self) -> YoctoNear {
+        self.haircut
+    }
+
+    /// ## Panics
+    /// if the haircut is greater than the unstaked NEAR
+    pub fn apply_haircut(&mut self, haircut: YoctoNear) {
+        self.unstaked_near -= haircut;
+        self.haircut += haircut;
+    }
+}
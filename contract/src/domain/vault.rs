@@ -0,0 +1,112 @@
+use crate::{
+    core::Hash,
+    domain::YoctoStake,
+    errors::vault::{INSUFFICIENT_VAULT_BALANCE, PREDECESSOR_MUST_BE_VAULT_RECEIVER},
+    interface,
+};
+use near_sdk::{
+    borsh::{self, BorshDeserialize, BorshSerialize},
+    env,
+};
+use std::ops::{Deref, DerefMut};
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Default,
+)]
+pub struct VaultId(pub u128);
+
+impl From<u128> for VaultId {
+    fn from(value: u128) -> Self {
+        Self(value)
+    }
+}
+
+impl From<interface::VaultId> for VaultId {
+    fn from(value: interface::VaultId) -> Self {
+        VaultId(value.0 .0)
+    }
+}
+
+impl Deref for VaultId {
+    type Target = u128;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for VaultId {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// temporary vault that locks STAKE that was transferred via [NEP-122](https://github.com/near/NEPs/issues/122)
+/// - the vault receiver withdraws from the vault
+/// - the vault only lives for the duration of the transfer - when the transfer is resolved, the
+///   vault is deleted and the remaining balance is refunded back to the sender
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Vault {
+    receiver_id: Hash,
+    balance: YoctoStake,
+}
+
+impl Vault {
+    pub fn new(receiver_id: &str, balance: YoctoStake) -> Self {
+        Self {
+            receiver_id: receiver_id.into(),
+            balance,
+        }
+    }
+
+    pub fn balance(&self) -> YoctoStake {
+        self.balance
+    }
+
+    /// ## Panics
+    /// - if the predecessor account is not the vault receiver
+    /// - if the vault balance is less than the amount
+    pub fn withdraw(&mut self, amount: YoctoStake) {
+        assert_eq!(
+            Hash::from(env::predecessor_account_id().as_str()),
+            self.receiver_id,
+            "{}",
+            PREDECESSOR_MUST_BE_VAULT_RECEIVER
+        );
+        assert!(self.balance >= amount, INSUFFICIENT_VAULT_BALANCE);
+        self.balance -= amount;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::*;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn withdraw() {
+        testing_env!(new_context("receiver.near"));
+        let mut vault = Vault::new("receiver.near", 100.into());
+        vault.withdraw(60.into());
+        assert_eq!(vault.balance(), 40.into());
+        vault.withdraw(40.into());
+        assert_eq!(vault.balance(), 0.into());
+    }
+
+    #[test]
+    #[should_panic(expected = "vault can only be withdrawn from by the vault receiver")]
+    fn withdraw_by_non_receiver() {
+        testing_env!(new_context("bob.near"));
+        let mut vault = Vault::new("receiver.near", 100.into());
+        vault.withdraw(60.into());
+    }
+
+    #[test]
+    #[should_panic(expected = "vault balance is insufficient to fulfill withdrawal request")]
+    fn withdraw_insufficient_balance() {
+        testing_env!(new_context("receiver.near"));
+        let mut vault = Vault::new("receiver.near", 100.into());
+        vault.withdraw(101.into());
+    }
+}
@@ -9,6 +9,8 @@ pub mod asserts {
         "operator account ID must not be the contract account ID";
     pub const PREDECESSOR_MUST_BE_OWNER: &str =
         "contract call is only allowed by the contract owner";
+    pub const PREDECESSOR_MUST_BE_SELF_OR_OWNER: &str =
+        "contract call is only allowed by the contract account or the contract owner";
     pub const ATTACHED_DEPOSIT_IS_REQUIRED: &str = "attached deposit is required";
 }
 
@@ -22,13 +24,40 @@ pub mod staking_pool_failures {
         "failed to withdraw all unstaked funds from staking pool";
 }
 
+pub mod staking_pools {
+    pub const STAKING_POOL_NOT_FOUND: &str = "staking pool is not registered with the contract";
+
+    pub const STAKING_POOL_ALREADY_REGISTERED: &str =
+        "staking pool is already registered with the contract";
+
+    pub const MAX_STAKING_POOLS_EXCEEDED: &str = "maximum number of staking pools has been reached";
+
+    pub const ZERO_TOTAL_STAKING_POOL_WEIGHT: &str =
+        "at least one staking pool must have a non-zero weight";
+
+    pub const STAKING_POOL_MIGRATION_IN_PROGRESS: &str =
+        "a staking pool migration is already in progress";
+
+    pub const NO_STAKING_POOL_MIGRATION: &str = "there is no staking pool migration in progress";
+
+    pub const STAKING_POOL_MIGRATION_TO_SELF: &str = "staking pool cannot be migrated to itself";
+
+    pub const STAKING_POOL_MIGRATION_RUNNING: &str =
+        "staking pool migration is waiting on a staking pool request to complete";
+}
+
 pub mod staking_errors {
     pub const BLOCKED_BY_BATCH_RUNNING: &str = "action is blocked because a batch is running";
 
     pub const BLOCKED_BY_STAKE_TOKEN_VALUE_REFRESH: &str =
         "action is blocked because STAKE token value is being refreshed";
 
+    pub const BLOCKED_BY_STAKING_POOL_MIGRATION: &str =
+        "action is blocked because a staking pool migration is in progress";
+
     pub const NO_FUNDS_IN_STAKE_BATCH_TO_WITHDRAW: &str = "there are no funds in stake batch";
+
+    pub const NO_STAKE_BATCH_TO_RUN: &str = "there is no stake batch";
 }
 
 pub mod redeeming_stake_errors {
@@ -39,6 +68,9 @@ pub mod redeeming_stake_errors {
 
     pub const UNSTAKED_FUNDS_NOT_AVAILABLE_FOR_WITHDRAWAL: &str =
         "unstaked NEAR funds are not yet available for withdrawal";
+
+    pub const NETTING_BLOCKED_BY_STAKING_POOL_BALANCE: &str =
+        "no single staking pool can cover the net NEAR to unstake - run the batches separately";
 }
 
 pub mod staking_service {
@@ -50,6 +82,34 @@ pub mod staking_service {
         "account STAKE balance is insufficient to fulfill request";
 
     pub const BATCH_BALANCE_INSUFFICIENT: &str = "batch balance is insufficient to fulfill request";
+
+    pub const REDEEM_BENEFICIARY_CONFLICT: &str =
+        "redeem stake batch already has a different beneficiary for the account";
+
+    pub const INSUFFICIENT_STORAGE_ESCROW_FOR_REDEEM_BENEFICIARY: &str =
+        "account storage balance is insufficient to pay for redeem beneficiary storage - deposit more via storage_deposit";
+
+    pub const CLAIM_RECEIPTS_FOR_NOT_PERMITTED: &str =
+        "receipts can only be claimed by the account or by a beneficiary that the account redeemed STAKE for";
+
+    pub const INSUFFICIENT_LIQUIDITY_FOR_INSTANT_REDEEM: &str =
+        "NEAR liquidity is insufficient to fulfill instant redeem request";
+
+    pub const INSTANT_REDEEM_BELOW_MIN_NEAR_OUT: &str =
+        "instant redeem NEAR amount after fees is less than the specified min NEAR out";
+
+    pub const INSUFFICIENT_DEPOSIT_TO_REGISTER_BENEFICIARY: &str =
+        "beneficiary account is not registered - the attached deposit must cover the account storage fee plus the NEAR to stake";
+
+    pub const AMOUNT_DOES_NOT_COVER_FEE: &str = "amount must be greater than the fee";
+
+    pub const LIMIT_ORDER_DEADLINE_PASSED: &str = "limit order deadline epoch has already passed";
+
+    pub const LIMIT_ORDER_BELOW_MIN_AMOUNT: &str =
+        "limit order amount is below the minimum - see MIN_LIMIT_ORDER_AMOUNT";
+
+    pub const MAX_LIMIT_ORDERS_PER_BATCH_EXCEEDED: &str =
+        "the batch has reached the maximum number of limit orders - try again with the next batch";
 }
 
 pub mod illegal_state {
@@ -62,6 +122,12 @@ pub mod illegal_state {
         "ILLEGAL STATE : redeem stake batch receipt should exist";
 
     pub const ILLEGAL_REDEEM_LOCK_STATE: &str = "ILLEGAL STATE : illegal redeem lock state";
+
+    pub const UNSTAKING_POOL_SHOULD_EXIST: &str =
+        "ILLEGAL STATE : staking pool holding the unstaked NEAR should exist";
+
+    pub const STAKING_POOL_MIGRATION_SHOULD_EXIST: &str =
+        "ILLEGAL STATE : staking pool migration should exist";
 }
 
 pub mod account_management {
@@ -74,6 +140,12 @@ pub mod account_management {
         "all funds must be withdrawn from the account in order to unregister";
 
     pub const ACCOUNT_NOT_REGISTERED: &str = "account is not registered";
+
+    pub const UNREGISTER_REQUIRES_NO_FUNDS_IN_BATCHES: &str =
+        "all funds must be withdrawn or claimed from stake and redeem batches in order to unregister";
+
+    pub const INSUFFICIENT_STORAGE_ESCROW_FOR_PENDING_BATCH: &str =
+        "account storage balance is insufficient to pay for pending batch storage - deposit more via storage_deposit";
 }
 
 pub mod contract_owner {
@@ -87,3 +159,95 @@ pub mod contract_owner {
     pub const TRANSFER_TO_NON_REGISTERED_ACCOUNT: &str =
         "contract ownership can only be transferred to a registered account";
 }
+
+pub mod fungible_token {
+    pub const BATCH_TRANSFER_IS_EMPTY: &str = "batch transfer must have at least 1 transfer";
+
+    pub const BATCH_TRANSFER_EXCEEDS_GAS_LIMIT: &str =
+        "batch transfer has too many transfers for the prepaid gas - attach more gas or split up the batch";
+
+    pub const SENDER_CANNOT_BE_RECEIVER: &str = "sender cannot transfer to itself";
+
+    pub const INVALID_CONTRACT_TRANSFER_CALL_MESSAGE: &str = r#"invalid msg for transfer call to the STAKE contract - expected: {"redeem":{"beneficiary":"<account_id>"}}"#;
+}
+
+pub mod allowances {
+    pub const ALLOWANCE_NOT_FOUND: &str = "allowance does not exist";
+
+    pub const ALLOWANCE_EXPIRED: &str = "allowance has expired";
+
+    pub const INSUFFICIENT_ALLOWANCE: &str = "allowance is insufficient to fulfill request";
+
+    pub const EXPIRATION_MUST_BE_IN_FUTURE: &str = "allowance expiration must be in the future";
+
+    pub const SELF_APPROVAL: &str = "account cannot approve itself as a spender";
+
+    pub const INSUFFICIENT_STORAGE_ESCROW: &str =
+        "account storage balance is insufficient to pay for allowance storage - attach more deposit";
+
+    pub const UNREGISTER_REQUIRES_NO_ALLOWANCES: &str =
+        "all allowances must be revoked in order to unregister";
+}
+
+pub mod metadata {
+    pub const BLANK_SPEC: &str = "`spec` must not be blank";
+
+    pub const BLANK_NAME: &str = "`name` must not be blank";
+
+    pub const BLANK_SYMBOL: &str = "`symbol` must not be blank";
+
+    pub const INVALID_DECIMALS: &str = "`decimals` must be 24";
+
+    pub const ICON_MUST_BE_DATA_URL: &str = "`icon` must be a data URL";
+
+    pub const REFERENCE_REQUIRES_HASH: &str =
+        "`reference` and `reference_hash` must both be specified";
+
+    pub const INVALID_REFERENCE_HASH: &str = "`reference_hash` must be a 32 byte sha256 hash";
+}
+
+pub mod vault {
+    pub const VAULT_NOT_FOUND: &str = "vault does not exist";
+
+    pub const PREDECESSOR_MUST_BE_VAULT_RECEIVER: &str =
+        "vault can only be withdrawn from by the vault receiver";
+
+    pub const INSUFFICIENT_VAULT_BALANCE: &str =
+        "vault balance is insufficient to fulfill withdrawal request";
+
+    pub const INSUFFICIENT_STORAGE_ESCROW: &str =
+        "account storage balance is insufficient to pay for vault storage - deposit more via storage_deposit";
+
+    pub const UNREGISTER_REQUIRES_NO_VAULTS: &str =
+        "all vault transfers must be resolved in order to unregister";
+}
+
+pub mod migration {
+    pub const CONTRACT_STATE_NOT_FOUND: &str = "there is no contract state to migrate";
+
+    pub const MIGRATION_BLOCKED_BY_BATCH_RUNNING: &str =
+        "contract state cannot be migrated while a batch is running - migrate once the batch completes";
+}
+
+pub mod liquidity_provider {
+    pub const ZERO_LIQUIDITY_DEPOSIT: &str = "deposit is required to add liquidity";
+
+    pub const ZERO_SHARES: &str = "shares amount must not be zero";
+
+    pub const INSUFFICIENT_SHARES: &str = "account shares balance is insufficient";
+
+    pub const INSUFFICIENT_LIQUIDITY_PROVIDER_POOL_NEAR: &str =
+        "liquidity provider pool NEAR balance is insufficient while STAKE is being redeemed - try again later or remove fewer shares";
+
+    pub const UNREGISTER_REQUIRES_NO_LIQUIDITY_PROVIDER_SHARES: &str =
+        "all liquidity must be removed in order to unregister";
+}
+
+pub mod receipts {
+    pub const RECEIPT_NOT_FOUND: &str = "batch receipt does not exist";
+
+    pub const RECEIPT_NOT_EXPIRED: &str = "batch receipt has not expired yet";
+
+    pub const RECEIPT_NOT_CLAIMABLE: &str =
+        "batch receipt cannot be claimed until the unstaked NEAR has been withdrawn";
+}
@@ -2,19 +2,28 @@
 
 pub mod account_management;
 pub mod account_storage;
+pub mod allowances;
 pub mod contract_owner;
 pub mod financials;
 pub mod fungible_token;
+pub mod keeper;
+pub mod liquidity_provider;
 pub mod metadata;
 pub mod model;
 pub mod operator;
 pub mod staking_service;
+pub mod vault_fungible_token;
 
 pub use account_management::*;
 pub use account_storage::*;
+pub use allowances::*;
 pub use contract_owner::*;
 pub use financials::*;
 pub use fungible_token::*;
+pub use keeper::*;
+pub use liquidity_provider::*;
+pub use metadata::*;
 pub use model::*;
 pub use operator::*;
 pub use staking_service::*;
+pub use vault_fungible_token::*;
@@ -1,5 +1,8 @@
 use crate::interface::{StakeAccount, YoctoNear};
-use near_sdk::json_types::{ValidAccountId, U128};
+use near_sdk::{
+    json_types::{ValidAccountId, U128},
+    AccountId,
+};
 
 /// Used to manage user accounts. The main use cases supported by this interface are:
 /// 1. Users can register with the contract. Users are required to pay for account storage usage at
@@ -9,6 +12,7 @@ use near_sdk::json_types::{ValidAccountId, U128};
 /// 3. The total number of registered users is tracked.
 /// 4. Users can withdraw unstaked NEAR from STAKE that has been redeemed.
 /// 5. User account info can be looked up.
+/// 6. Registered accounts can be enumerated.
 pub trait AccountManagement {
     /// Creates and registers a new account for the predecessor account ID.
     /// - the account is required to pay for its storage. Storage fees will be escrowed and then refunded
@@ -30,6 +34,8 @@ pub trait AccountManagement {
     /// ## Panics
     /// - if account is not registered
     /// - if registered account has funds
+    /// - if the account has open vaults, i.e., vault transfers that have not been resolved yet
+    /// - if registered account holds liquidity provider shares
     fn unregister_account(&mut self);
 
     /// Returns the required deposit amount that is required for account registration.
@@ -49,4 +55,27 @@ pub trait AccountManagement {
     ///
     /// Gas Requirements: 4 TGas
     fn lookup_account(&self, account_id: ValidAccountId) -> Option<StakeAccount>;
+
+    /// Returns a page of registered accounts from the account index.
+    ///
+    /// Accounts are indexed in registration order. When an account is unregistered, the last account
+    /// in the index is moved into the unregistered account's position, i.e., the index positions
+    /// are not stable across unregistrations.
+    ///
+    /// ##### Arguments
+    /// - `from_index` - account index position to start from
+    /// - `limit` - max number of accounts to return - capped at [MAX_ACCOUNTS_PAGE_LIMIT](crate::contract::account_management::MAX_ACCOUNTS_PAGE_LIMIT)
+    fn accounts(&self, from_index: u64, limit: u64) -> Vec<(AccountId, StakeAccount)>;
+
+    /// Returns a page of STAKE token holders, i.e., registered accounts with a STAKE balance, from
+    /// the holder index.
+    ///
+    /// The holder index is kept in sync with the accounts' STAKE balances - STAKE that is held by the
+    /// contract for unclaimed batch receipts is not counted until it is claimed. Like the account
+    /// index, the index positions are not stable as holders are added and removed.
+    ///
+    /// ##### Arguments
+    /// - `from_index` - holder index position to start from
+    /// - `limit` - max number of holders to return - capped at [MAX_ACCOUNTS_PAGE_LIMIT](crate::contract::account_management::MAX_ACCOUNTS_PAGE_LIMIT)
+    fn ft_holders(&self, from_index: u64, limit: u64) -> Vec<(AccountId, StakeAccount)>;
 }
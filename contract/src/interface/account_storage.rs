@@ -3,13 +3,22 @@ use near_sdk::{json_types::ValidAccountId, serde::Serialize};
 
 #[derive(Serialize, Default, PartialEq, Debug)]
 #[serde(crate = "near_sdk::serde")]
-pub struct AccountStorageBalance {
+pub struct StorageBalance {
     pub total: YoctoNear,
     /// represents portion of the account's total balance that is available for withdrawal
     pub available: YoctoNear,
 }
 
-/// Account Storage Standard API - NEP-145
+#[derive(Serialize, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    /// the amount of tokens required to register the account, i.e., the account storage fee
+    pub min: YoctoNear,
+    /// there is no max because the account storage grows with each allowance the account approves
+    pub max: Option<YoctoNear>,
+}
+
+/// Account Storage Standard API - [NEP-145](https://github.com/near/NEPs/blob/master/neps/nep-0145.md)
 ///
 /// On NEAR, the contract is responsible to pay for its long term persistent storage. Thus, multi-user contracts should be designed to pass on storage costs to its user accounts. The account storage API provides the following:
 /// 1. Accounts can lookup the minimum required account storage balance for the initial deposit in order to be able to use the contract.
@@ -18,7 +27,8 @@ pub struct AccountStorageBalance {
 /// 4. Accounts can withdraw NEAR from the account's storage available balance.
 ///
 /// ### NOTES
-/// - Use [unregister_account()][crate::AccountManagement::unregister_account] to close the account and withdraw all funds.
+/// - Use [storage_unregister()][AccountStorage::storage_unregister] to close the account and withdraw all funds.
+///   [unregister_account()][crate::interface::AccountManagement::unregister_account] is still supported.
 /// - STAKE contract accounts for changes in price for storage on the NEAR blockchain over time.
 pub trait AccountStorage {
     /// Used by accounts to deposit funds to pay for account storage staking fees when registering the account.
@@ -31,9 +41,13 @@ pub trait AccountStorage {
     /// If this is the initial deposit for the account, then the deposit must be enough to cover the minimum required balance.
     /// If the attached deposit is more than the required minimum balance, then the funds are credited to the account storage available balance.
     ///
+    /// If `registration_only` is true, then only the minimum required balance is deposited and the
+    /// rest of the attached deposit is refunded. If the account is already registered, then the full
+    /// attached deposit is refunded.
     ///
     /// ##### Arguments
     /// - `account_id` - optional NEAR account ID. If not specified, then predecessor account ID will be used.
+    /// - `registration_only` - defaults to false
     ///
     /// ##### Returns
     /// The account's updated storage balance.
@@ -43,7 +57,11 @@ pub trait AccountStorage {
     /// - If `account_id` is not a valid NEAR account ID
     ///
     /// `#[payable]`
-    fn storage_deposit(&mut self, account_id: Option<ValidAccountId>) -> AccountStorageBalance;
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<ValidAccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance;
 
     /// Used to withdraw NEAR from the predecessor account's storage available balance.
     /// If amount is not specified, then all of the account's storage available balance will be withdrawn.
@@ -63,15 +81,45 @@ pub trait AccountStorage {
     /// - If the attached deposit does not equal 1 yoctoNEAR
     /// - If the account is not registered with the contract
     /// - If the specified withdrawal amount is greater than the account's available storage balance
-    fn storage_withdraw(&mut self, amount: Option<YoctoNear>) -> AccountStorageBalance;
+    fn storage_withdraw(&mut self, amount: Option<YoctoNear>) -> StorageBalance;
+
+    /// Unregisters the predecessor account and refunds the account storage escrow balance.
+    ///
+    /// The account is required to attach exactly 1 yoctoNEAR to the function call to prevent
+    /// restricted function-call access-key calls.
+    ///
+    /// If `force` is true, then the account's STAKE balance is burned and the account's available
+    /// NEAR balance is refunded along with the account storage escrow balance. Burning STAKE
+    /// increases the STAKE token value for the remaining STAKE holders.
+    ///
+    /// ##### Returns
+    /// true if the account was unregistered, false if the account is not registered - in which case
+    /// the attached yoctoNEAR is refunded
+    ///
+    /// ##### Panics
+    /// - If the attached deposit does not equal 1 yoctoNEAR
+    /// - If `force` is not true and the account has funds
+    /// - If the account has funds in stake or redeem batches - even when forced
+    /// - If the account has allowances - even when forced
+    /// - If the account has open vaults, i.e., vault transfers that have not been resolved yet
+    /// - If the account holds liquidity provider shares - even when forced, i.e., liquidity must be
+    ///   removed first via [remove_liquidity](crate::interface::LiquidityProvider::remove_liquidity)
+    ///
+    /// `#[payable]`
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool;
+
+    /// Returns the minimum and maximum allowed storage balance amounts for an account.
+    /// - there is no maximum because account storage usage grows with each approved allowance
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds;
 
     /// Used to look up the minimum balance required for the initial deposit.
+    /// - superseded by [storage_balance_bounds](AccountStorage::storage_balance_bounds)
     fn storage_minimum_balance(&self) -> YoctoNear;
 
     /// Used to lookup the account storage balance for the specified account.
-    /// If the account is unknown to the contract then the total account storage balance returned will be zero.
+    /// If the account is unknown to the contract then None is returned.
     ///
     /// ##### Panics
     /// - If `account_id` is not a valid NEAR account ID
-    fn storage_balance_of(&self, account_id: ValidAccountId) -> AccountStorageBalance;
+    fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance>;
 }
@@ -0,0 +1,147 @@
+use crate::interface::{Allowance, BlockTimestamp, Memo, TokenAmount};
+use near_sdk::json_types::ValidAccountId;
+
+/// Enables an account to approve another account, i.e., the spender, to transfer STAKE on its behalf.
+/// This is the spender model that DeFi contracts integrate with: the owner approves the contract for
+/// an amount, and the contract then pulls the STAKE via [ft_transfer_from](FungibleTokenAllowances::ft_transfer_from).
+///
+/// Allowances are stored per (owner, spender). The owner account pays for the allowance storage
+/// out of its [account storage escrow](crate::interface::AccountStorage). Any NEAR attached to
+/// the approve calls is deposited into the owner's account storage escrow. When the allowance is
+/// revoked or fully spent, the storage is freed and the escrowed funds become available for
+/// withdrawal via [storage_withdraw](crate::interface::AccountStorage::storage_withdraw).
+///
+/// ## NOTES
+/// - allowances must be revoked before the account can be unregistered
+pub trait FungibleTokenAllowances {
+    /// Sets the spender's allowance to the specified amount, replacing any existing allowance.
+    ///
+    /// ##### Arguments
+    /// - `spender_id` - account that is approved to transfer STAKE on behalf of the predecessor account
+    /// - `amount` - amount of yoctoSTAKE the spender is allowed to transfer
+    /// - `expiration` - optional block timestamp, after which the allowance can no longer be spent
+    ///
+    /// ##### Panics
+    /// - if the predecessor account is not registered
+    /// - if no deposit is attached
+    /// - if `amount` is zero - use [ft_revoke_allowance](FungibleTokenAllowances::ft_revoke_allowance)
+    /// - if the spender is the predecessor account
+    /// - if `expiration` is not in the future
+    /// - if the account storage escrow balance is insufficient to pay for the allowance storage
+    ///
+    /// `#[payable]`
+    fn ft_approve(
+        &mut self,
+        spender_id: ValidAccountId,
+        amount: TokenAmount,
+        expiration: Option<BlockTimestamp>,
+    ) -> Allowance;
+
+    /// Increases the spender's allowance. If no allowance exists, then one is created without an
+    /// expiration.
+    ///
+    /// ##### Panics
+    /// - if the predecessor account is not registered
+    /// - if no deposit is attached
+    /// - if `amount` is zero
+    /// - if the spender is the predecessor account
+    /// - if the allowance has expired - use [ft_approve](FungibleTokenAllowances::ft_approve) to renew it
+    /// - if the account storage escrow balance is insufficient to pay for the allowance storage
+    ///
+    /// `#[payable]`
+    fn ft_increase_allowance(
+        &mut self,
+        spender_id: ValidAccountId,
+        amount: TokenAmount,
+    ) -> Allowance;
+
+    /// Decreases the spender's allowance. If the allowance is decreased to zero, then it is deleted.
+    ///
+    /// The account is required to attach exactly 1 yoctoNEAR to the function call to prevent
+    /// restricted function-call access-key calls.
+    ///
+    /// ##### Panics
+    /// - if the attached deposit does not equal 1 yoctoNEAR
+    /// - if the allowance does not exist
+    /// - if `amount` is greater than the allowance
+    ///
+    /// `#[payable]`
+    fn ft_decrease_allowance(
+        &mut self,
+        spender_id: ValidAccountId,
+        amount: TokenAmount,
+    ) -> Option<Allowance>;
+
+    /// Deletes the spender's allowance. No-op if the allowance does not exist, i.e., no
+    /// [AllowanceUpdated](events::AllowanceUpdated) event is logged.
+    ///
+    /// The account is required to attach exactly 1 yoctoNEAR to the function call to prevent
+    /// restricted function-call access-key calls.
+    ///
+    /// ##### Panics
+    /// - if the attached deposit does not equal 1 yoctoNEAR
+    ///
+    /// `#[payable]`
+    fn ft_revoke_allowance(&mut self, spender_id: ValidAccountId);
+
+    /// Transfers STAKE from the owner account to the receiver account. The predecessor account is
+    /// the spender, and the transfer amount is debited from the spender's allowance.
+    ///
+    /// The owner account is charged the [FtTransfer](crate::config::FeeCategory::FtTransfer) fee on
+    /// top of the transfer amount, same as for [ft_transfer](crate::interface::FungibleToken::ft_transfer).
+    /// The fee is debited from the spender's allowance along with the transfer amount.
+    ///
+    /// The spender is required to attach exactly 1 yoctoNEAR to the function call to prevent
+    /// restricted function-call access-key calls.
+    ///
+    /// ##### Panics
+    /// - if the attached deposit does not equal 1 yoctoNEAR
+    /// - if `amount` is zero
+    /// - if the owner or receiver account is not registered
+    /// - if the allowance does not exist, has expired, or does not cover the transfer amount plus
+    ///   the fee
+    /// - if the owner STAKE balance is insufficient
+    ///
+    /// `#[payable]`
+    fn ft_transfer_from(
+        &mut self,
+        owner_id: ValidAccountId,
+        receiver_id: ValidAccountId,
+        amount: TokenAmount,
+        memo: Option<Memo>,
+    );
+
+    /// returns the spender's allowance, if it exists
+    /// - expired allowances are returned until they are revoked
+    fn ft_allowance(
+        &self,
+        owner_id: ValidAccountId,
+        spender_id: ValidAccountId,
+    ) -> Option<Allowance>;
+}
+
+pub mod events {
+    use crate::near::{Event, STAKE_TOKEN_EVENT_STANDARD, STAKE_TOKEN_EVENT_VERSION};
+    use near_sdk::{
+        json_types::{U128, U64},
+        serde::Serialize,
+    };
+
+    /// logged whenever an allowance is approved, increased, decreased, revoked, or spent
+    /// - `amount` is zero when the allowance has been deleted
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct AllowanceUpdated<'a> {
+        pub owner_id: &'a str,
+        pub spender_id: &'a str,
+        pub amount: U128,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub expiration: Option<U64>,
+    }
+
+    impl Event for AllowanceUpdated<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "allowance_updated";
+    }
+}
@@ -21,6 +21,20 @@ pub trait ContractOwner {
     /// - new operator account must be registered
     fn set_operator_id(&mut self, account_id: ValidAccountId);
 
+    /// returns the account that the [performance fee](crate::interface::Config::performance_fee_basis_points)
+    /// on staking rewards is minted to as STAKE - defaults to the owner account
+    fn treasury_id(&self) -> AccountId;
+
+    /// Sets the account that the performance fee is minted to. If None is specified, then the
+    /// performance fee is minted to the owner account.
+    ///
+    /// NOTE: if the treasury account is not registered when the fee is minted, then no fee is minted
+    ///
+    /// ## Panics
+    /// - if the predecessor account is not the owner account
+    /// - treasury account must be registered
+    fn set_treasury_id(&mut self, account_id: Option<ValidAccountId>);
+
     /// Deposits the owner's balance into the owners STAKE account
     ///
     /// NOTE: contract owner will need to register his account beforehand
@@ -55,9 +69,19 @@ pub trait ContractOwner {
 }
 
 pub mod events {
-    #[derive(Debug)]
+    use crate::near::{Event, STAKE_TOKEN_EVENT_STANDARD, STAKE_TOKEN_EVENT_VERSION};
+    use near_sdk::serde::Serialize;
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct OwnershipTransferred<'a> {
         pub from: &'a str,
         pub to: &'a str,
     }
+
+    impl Event for OwnershipTransferred<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "ownership_transferred";
+    }
 }
@@ -1,4 +1,6 @@
 use crate::interface::{ContractBalances, YoctoNear};
+use crate::near::{Event, STAKE_TOKEN_EVENT_STANDARD, STAKE_TOKEN_EVENT_VERSION};
+use near_sdk::{json_types::U128, serde::Serialize};
 
 pub trait ContractFinancials {
     /// returns consolidated view of contract balances
@@ -10,13 +12,26 @@ pub trait ContractFinancials {
     /// Returns the updated STAKE fund balance.
     ///
     /// NOTE: The STAKE funds will be staked the next time the [StakeBatch](crate::domain::StakeBatch) is run.
+    /// If there is a pending withdrawal, then earnings are distributed right away, which adds NEAR
+    /// liquidity that is allocated to the [redeem queue](crate::interface::StakingService::redeem_queue_position).
     ///
     /// #\[payable\]
     fn deposit_earnings(&mut self) -> YoctoNear;
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
 pub struct EarningsDistribution {
-    pub contract_owner_earnings: u128,
-    pub user_accounts_earnings: u128,
+    pub contract_owner_earnings: U128,
+    pub user_accounts_earnings: U128,
+    /// earnings that were set aside to pay keeper rewards
+    pub keeper_rewards: U128,
+    /// earnings that were set aside to fund the insurance buffer
+    pub insurance_buffer: U128,
+}
+
+impl Event for EarningsDistribution {
+    const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+    const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+    const EVENT: &'static str = "earnings_distribution";
 }
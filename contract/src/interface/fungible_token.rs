@@ -1,7 +1,7 @@
 use near_sdk::{
     json_types::{ValidAccountId, U128},
     serde::{Deserialize, Serialize},
-    Promise, PromiseOrValue,
+    PromiseOrValue,
 };
 use std::{
     cmp::Ordering,
@@ -98,11 +98,17 @@ pub trait FungibleToken {
     ///
     /// Returns a promise to resolve transfer call which will return the used amount - [`ResolveTransferCall`]
     ///
+    /// ## Transfer Call To The STAKE Contract
+    /// If `receiver_id` is the STAKE contract itself, then the transfer is handled directly by the
+    /// contract and `msg` must be a JSON encoded [`ContractTransferCallMessage`]. The full transfer
+    /// amount is used, i.e., the amount is returned as a value.
+    ///
     /// ## Panics
     /// - if the attached deposit is not exactly 1 yoctoNEAR
     /// - if either sender or receiver accounts are not registered
     /// - if amount is zero
     /// - if the sender account has insufficient funds to fulfill the transfer request
+    /// - if `receiver_id` is the STAKE contract and `msg` is not a valid [`ContractTransferCallMessage`]
     ///
     /// GAS REQUIREMENTS: 40 TGas + gas for receiver call
     /// #\[payable\]
@@ -112,7 +118,7 @@ pub trait FungibleToken {
         amount: TokenAmount,
         msg: TransferCallMessage,
         memo: Option<Memo>,
-    ) -> Promise;
+    ) -> PromiseOrValue<TokenAmount>;
 
     fn ft_total_supply(&self) -> TokenAmount;
 
@@ -120,6 +126,37 @@ pub trait FungibleToken {
     fn ft_balance_of(&self, account_id: ValidAccountId) -> TokenAmount;
 }
 
+/// Enables STAKE to be transferred to multiple receivers in a single function call, e.g., payroll
+/// and airdrops.
+pub trait FungibleTokenBatchTransfer {
+    /// Transfers STAKE from the `env::predecessor_account_id` to each of the receivers.
+    ///
+    /// - the sender's unclaimed receipt funds are claimed first
+    /// - the sender account is debited once for the total transfer amount, and then each receiver
+    ///   account is credited - if any transfer fails, then the whole batch is rolled back
+    /// - the [FtTransfer](crate::config::FeeCategory::FtTransfer) fee is charged per transfer on top
+    ///   of the transfer amount, same as for [ft_transfer](FungibleToken::ft_transfer)
+    /// - an `ft_transfer` event is logged per receiver
+    /// - sender account is required to attach exactly 1 yoctoNEAR to the function call - see
+    ///   security section of the standard
+    ///   - the yoctoNEAR will be credited to the sender account's NEAR balance
+    ///
+    /// Arguments:
+    /// - `transfers` - list of (receiver account ID, amount, optional memo)
+    ///
+    /// ## Panics
+    /// - if the attached deposit does not equal 1 yoctoNEAR
+    /// - if `transfers` is empty
+    /// - if there are more transfers than the prepaid gas can cover - see [GasConfig::ft_batch_transfer_receiver](crate::config::GasConfig::ft_batch_transfer_receiver)
+    /// - if the sender or any of the receiver accounts are not registered
+    /// - if the sender is one of the receivers
+    /// - if any amount is zero
+    /// - if the sender account has insufficient funds to fulfill the request
+    ///
+    /// #\[payable\]
+    fn ft_batch_transfer(&mut self, transfers: Vec<(ValidAccountId, TokenAmount, Option<Memo>)>);
+}
+
 /// Receiver of the Fungible Token for [`FungibleToken::ft_transfer_call`] calls.
 pub trait TransferReceiver {
     /// Callback to receive tokens.
@@ -265,3 +302,114 @@ impl Display for TransferCallMessage {
         self.0.fmt(f)
     }
 }
+
+/// [`FungibleToken::ft_transfer_call`] message that is handled when the STAKE is transferred to the
+/// STAKE contract itself
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum ContractTransferCallMessage {
+    /// The transferred STAKE is added to the [RedeemStakeBatch](crate::interface::RedeemStakeBatch)
+    /// on behalf of the sender. When the NEAR for the redeemed STAKE is claimed, it is transferred to
+    /// the beneficiary - see [StakingService::claim_receipts_for](crate::interface::StakingService::claim_receipts_for)
+    ///
+    /// - if the beneficiary is not specified, then the NEAR is transferred to the sender
+    /// - the beneficiary is recorded per redeem batch - the sender's storage escrow pays for the
+    ///   beneficiary storage until the NEAR is transferred to the beneficiary
+    /// - the NEAR is only transferred when receipts are claimed explicitly, i.e., receipts that are
+    ///   claimed implicitly by other account actions hold back the batch
+    ///
+    /// ```json
+    /// {"redeem":{"beneficiary":"bob.near"}}
+    /// ```
+    Redeem { beneficiary: Option<ValidAccountId> },
+}
+
+/// standard NEP-141 events
+/// - https://github.com/near/NEPs/blob/master/neps/nep-0141.md#events
+pub mod events {
+    use crate::near::Event;
+    use near_sdk::{json_types::U128, serde::Serialize};
+
+    pub const NEP141_STANDARD: &str = "nep141";
+    pub const NEP141_VERSION: &str = "1.0.0";
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct FtMint<'a> {
+        pub owner_id: &'a str,
+        pub amount: U128,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub memo: Option<&'a str>,
+    }
+
+    impl Event for FtMint<'_> {
+        const STANDARD: &'static str = NEP141_STANDARD;
+        const VERSION: &'static str = NEP141_VERSION;
+        const EVENT: &'static str = "ft_mint";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct FtBurn<'a> {
+        pub owner_id: &'a str,
+        pub amount: U128,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub memo: Option<&'a str>,
+    }
+
+    impl Event for FtBurn<'_> {
+        const STANDARD: &'static str = NEP141_STANDARD;
+        const VERSION: &'static str = NEP141_VERSION;
+        const EVENT: &'static str = "ft_burn";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct FtTransfer<'a> {
+        pub old_owner_id: &'a str,
+        pub new_owner_id: &'a str,
+        pub amount: U128,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub memo: Option<&'a str>,
+    }
+
+    impl Event for FtTransfer<'_> {
+        const STANDARD: &'static str = NEP141_STANDARD;
+        const VERSION: &'static str = NEP141_VERSION;
+        const EVENT: &'static str = "ft_transfer";
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::near::event_json;
+
+        #[test]
+        fn ft_transfer_log_fmt() {
+            let log = event_json(&[FtTransfer {
+                old_owner_id: "alfio.near",
+                new_owner_id: "bob.near",
+                amount: 100.into(),
+                memo: None,
+            }]);
+            assert_eq!(
+                log,
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{"old_owner_id":"alfio.near","new_owner_id":"bob.near","amount":"100"}]}"#
+            );
+        }
+
+        #[test]
+        fn ft_mint_log_fmt_with_memo() {
+            let log = event_json(&[FtMint {
+                owner_id: "stake.oysterpack.near",
+                amount: 100.into(),
+                memo: Some("StakeBatch(1)"),
+            }]);
+            assert_eq!(
+                log,
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_mint","data":[{"owner_id":"stake.oysterpack.near","amount":"100","memo":"StakeBatch(1)"}]}"#
+            );
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::interface::{BatchId, ReceiptStorageReport, RunnableBatches};
+use near_sdk::json_types::ValidAccountId;
+
+/// Batches only run when someone calls [stake()](crate::interface::StakingService::stake) or
+/// [unstake()](crate::interface::StakingService::unstake). Anyone can act as a keeper and run the
+/// batches. When the batch that the keeper kicked off completes successfully, the keeper is paid
+/// a reward out of the contract earnings.
+///
+/// Keeper rewards are configured via [KeeperRewardConfig](crate::interface::KeeperRewardConfig):
+/// - the reward can be paid out in NEAR or STAKE
+/// - the number of rewards that are paid out per epoch is rate limited
+/// - the keeper must be a registered account in order to be rewarded
+/// - rewards are paid out of the keeper rewards pool, which is funded when contract earnings are
+///   distributed - if the pool balance is insufficient, then the reward is reduced to the pool balance
+pub trait Keeper {
+    /// returns which batches can be run right now, and the reward that would be paid out if the
+    /// batch completes successfully
+    fn runnable_batches(&self) -> RunnableBatches;
+
+    /// Batch receipts are held in contract storage until every account that has funds in the batch
+    /// has claimed them. Once a receipt is older than [receipt_expiry_epochs](crate::interface::Config::receipt_expiry_epochs),
+    /// anyone can settle it by claiming the receipt funds on behalf of the specified accounts, i.e.,
+    /// the funds are credited to the account balances. Once all funds have been claimed, the receipt
+    /// is deleted and its storage is freed.
+    ///
+    /// - accounts that are not registered or have no funds in the batch are skipped
+    /// - all receipt funds that the account can claim are claimed, not only the funds for the batch
+    /// - [receipt_storage_report](Keeper::receipt_storage_report) lists the receipts that can be settled
+    ///
+    /// Returns true if the receipt was fully claimed and deleted
+    ///
+    /// ## Panics
+    /// - if the receipt does not exist
+    /// - if the receipt has not expired
+    /// - if the receipt is for a redeem stake batch whose unstaked NEAR has not yet been withdrawn
+    fn settle_receipts(&mut self, batch_id: BatchId, accounts: Vec<ValidAccountId>) -> bool;
+
+    /// reports on the batch receipts that have not been fully claimed, which the contract pays the
+    /// storage for
+    /// - the receipt count and storage usage cover all receipts
+    /// - the oldest receipt epoch and expired receipts only cover the page of receipt IDs - the page
+    ///   is applied to both the stake batch and redeem stake batch receipt IDs
+    ///
+    /// ##### Arguments
+    /// - `from_index` - receipt ID index position to start from
+    /// - `limit` - max number of receipt IDs to scan
+    fn receipt_storage_report(&self, from_index: u64, limit: u64) -> ReceiptStorageReport;
+}
+
+pub mod events {
+    use crate::config::KeeperRewardPayout;
+    use crate::near::{Event, STAKE_TOKEN_EVENT_STANDARD, STAKE_TOKEN_EVENT_VERSION};
+    use near_sdk::{json_types::U128, serde::Serialize};
+
+    /// logged when a keeper is rewarded for running a batch that completed successfully
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct KeeperRewarded<'a> {
+        pub keeper_id: &'a str,
+        pub payout: KeeperRewardPayout,
+        /// NEAR value of the reward
+        pub amount: U128,
+        /// amount of STAKE that was minted for the keeper if the payout is in STAKE
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub stake: Option<U128>,
+    }
+
+    impl Event for KeeperRewarded<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "keeper_rewarded";
+    }
+
+    /// logged when an expired batch receipt is settled on behalf of accounts
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct ReceiptsSettled {
+        pub batch_id: U128,
+        /// number of accounts that the receipt funds were claimed for
+        pub accounts: u32,
+        /// true if all funds have been claimed and the receipt was deleted
+        pub receipt_deleted: bool,
+    }
+
+    impl Event for ReceiptsSettled {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "receipts_settled";
+    }
+}
@@ -0,0 +1,79 @@
+use crate::interface::{LiquidityProviderPool, LiquidityProviderPosition, YoctoNear};
+use near_sdk::json_types::{ValidAccountId, U128};
+
+/// Liquidity providers (LPs) deposit NEAR into the liquidity provider pool, which is used to fund
+/// [instant redemptions](crate::interface::StakingService::instant_redeem). In return, LPs are issued
+/// shares in the pool.
+///
+/// When an instant redemption is funded by the pool, the pool buys the redeemed STAKE at a discount,
+/// i.e., minus the instant redeem fee, and then redeems the STAKE via the batch workflow. The fee is
+/// the spread that LPs earn. LP shares are priced against the pool value, which is the pool NEAR
+/// balance plus the NEAR value of the STAKE that the pool is redeeming.
+///
+/// LP funds are accounted for separately from user account balances - they do not back the STAKE
+/// token value.
+pub trait LiquidityProvider {
+    /// Deposits the attached NEAR into the liquidity provider pool.
+    ///
+    /// Returns the number of shares that were issued to the predecessor account.
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if no deposit is attached
+    ///
+    /// #\[payable\]
+    fn add_liquidity(&mut self) -> U128;
+
+    /// Redeems the shares for their NEAR value, which is transferred to the predecessor account.
+    /// - NEAR can only be withdrawn from the pool NEAR balance - while the pool's STAKE is being
+    ///   redeemed, withdrawals are limited by the pool utilization
+    ///
+    /// Returns the amount of NEAR that was transferred.
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if shares is zero or the account shares balance is insufficient
+    /// - if the pool NEAR balance is insufficient
+    fn remove_liquidity(&mut self, shares: U128) -> YoctoNear;
+
+    fn liquidity_provider_pool(&self) -> LiquidityProviderPool;
+
+    /// returns None if the account is not registered or does not own any shares
+    fn liquidity_provider_position(
+        &self,
+        account_id: ValidAccountId,
+    ) -> Option<LiquidityProviderPosition>;
+}
+
+pub mod events {
+    use crate::near::{Event, STAKE_TOKEN_EVENT_STANDARD, STAKE_TOKEN_EVENT_VERSION};
+    use near_sdk::{json_types::U128, serde::Serialize};
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct LiquidityAdded<'a> {
+        pub account_id: &'a str,
+        pub near: U128,
+        pub shares: U128,
+    }
+
+    impl Event for LiquidityAdded<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "liquidity_added";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct LiquidityRemoved<'a> {
+        pub account_id: &'a str,
+        pub near: U128,
+        pub shares: U128,
+    }
+
+    impl Event for LiquidityRemoved<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "liquidity_removed";
+    }
+}
@@ -1,7 +1,23 @@
+use crate::interface::FungibleTokenMetadata;
 use near_sdk::serde_json::Value;
 
+/// Fungible token metadata standard - [NEP-148](https://github.com/near/NEPs/discussions/148)
+///
+/// The metadata is stored in contract state and is managed by the operator via
+/// [update_ft_metadata](crate::interface::Operator::update_ft_metadata)
+pub trait FungibleTokenMetadataProvider {
+    fn ft_metadata(&self) -> FungibleTokenMetadata;
+}
+
+/// Legacy metadata interface which predates [NEP-148](https://github.com/near/NEPs/discussions/148)
+///
+/// ## Deprecated
+/// Use [ft_metadata](FungibleTokenMetadataProvider::ft_metadata) instead. [metadata_uris](MetaData::metadata_uris)
+/// and [metadata](MetaData::metadata) are only retained to support older clients.
 pub trait MetaData {
     /// returns None if the contract does not support the requested metadata
+    /// - the legacy token metadata shape is retained, i.e., `name`, `symbol`, `ref_url`, `ref_hash`,
+    ///   `granularity`, and `decimals`
     fn metadata(uri: String) -> Option<Value>;
 
     /// returns the metadata that this contract exposes
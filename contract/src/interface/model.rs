@@ -1,41 +1,73 @@
+mod allowance;
 mod batch_id;
 mod block_height;
 mod block_time_height;
 mod block_timestamp;
+mod collected_fees;
 mod config;
 mod contract_balances;
 pub mod contract_state;
 mod epoch_height;
+mod fee_quote;
+mod fungible_token_metadata;
 mod gas;
+mod insurance_buffer;
+mod liquidity_provider_pool;
 mod lock;
+mod pending_batch;
+mod quote;
+mod receipt_storage_report;
+mod redeem_queue_position;
 mod redeem_stake_batch;
 mod redeem_stake_batch_receipt;
+mod runnable_batches;
 mod stake_account;
 mod stake_batch;
 mod stake_batch_receipt;
 mod stake_token_value;
+mod stake_token_value_apy;
+mod staking_pool;
+mod staking_pool_migration;
 mod storage_usage;
 mod timestamped_near_balance;
 mod timestamped_stake_balance;
+mod unstaked_cohort;
+mod vault_id;
 mod yocto_near;
 mod yocto_stake;
 
+pub use allowance::*;
 pub use batch_id::*;
 pub use block_height::*;
 pub use block_time_height::*;
 pub use block_timestamp::*;
+pub use collected_fees::CollectedFees;
 pub use config::*;
 pub use contract_balances::*;
 pub use epoch_height::*;
+pub use fee_quote::FeeQuote;
+pub use fungible_token_metadata::FungibleTokenMetadata;
 pub use gas::*;
+pub use insurance_buffer::InsuranceBuffer;
+pub use liquidity_provider_pool::{LiquidityProviderPool, LiquidityProviderPosition};
+pub use pending_batch::PendingBatch;
+pub use quote::{QuoteBatch, RedeemQuote, StakeQuote};
+pub use receipt_storage_report::{ReceiptBacklog, ReceiptStorageReport};
+pub use redeem_queue_position::RedeemQueuePosition;
 pub use redeem_stake_batch::RedeemStakeBatch;
 pub use redeem_stake_batch_receipt::RedeemStakeBatchReceipt;
+pub use runnable_batches::RunnableBatches;
 pub use stake_account::StakeAccount;
 pub use stake_batch::StakeBatch;
 pub use stake_batch_receipt::StakeBatchReceipt;
 pub use stake_token_value::StakeTokenValue;
+pub use stake_token_value_apy::StakeTokenValueApy;
+pub use staking_pool::StakingPool;
+pub use staking_pool_migration::StakingPoolMigration;
 pub use storage_usage::*;
 pub use timestamped_near_balance::TimestampedNearBalance;
 pub use timestamped_stake_balance::TimestampedStakeBalance;
+pub use unstaked_cohort::UnstakedCohort;
+pub use vault_id::*;
 pub use yocto_near::*;
 pub use yocto_stake::*;
@@ -0,0 +1,23 @@
+use crate::{
+    domain,
+    interface::{BlockTimestamp, TokenAmount},
+};
+use near_sdk::serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Allowance {
+    /// how much STAKE the spender is allowed to transfer on behalf of the owner
+    pub amount: TokenAmount,
+    /// once the block timestamp reaches the expiration, the allowance can no longer be spent
+    pub expiration: Option<BlockTimestamp>,
+}
+
+impl From<domain::Allowance> for Allowance {
+    fn from(allowance: domain::Allowance) -> Self {
+        Self {
+            amount: allowance.amount().value().into(),
+            expiration: allowance.expiration().map(Into::into),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::config::FeeCategory;
+use crate::domain;
+use crate::interface::{YoctoNear, YoctoStake};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// total fees that have been collected per category since the contract was deployed - see
+/// [FeeSchedule](crate::config::FeeSchedule)
+/// - STAKE fees are reported by their NEAR value when they were charged
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CollectedFees {
+    pub deposit: YoctoNear,
+    pub redeem: YoctoNear,
+    pub withdraw: YoctoNear,
+    pub ft_transfer: YoctoNear,
+    /// STAKE fees that are held by the contract until they are distributed when earnings are
+    /// distributed
+    pub undistributed_stake: YoctoStake,
+}
+
+impl From<domain::CollectedFees> for CollectedFees {
+    fn from(fees: domain::CollectedFees) -> Self {
+        Self {
+            deposit: fees.total(FeeCategory::Deposit).into(),
+            redeem: fees.total(FeeCategory::Redeem).into(),
+            withdraw: fees.total(FeeCategory::Withdraw).into(),
+            ft_transfer: fees.total(FeeCategory::FtTransfer).into(),
+            undistributed_stake: fees.stake().into(),
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use crate::{
-    config,
+    config::{self, KeeperRewardPayout},
     interface::{Gas, YoctoNear},
 };
-use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    json_types::ValidAccountId,
+    serde::{Deserialize, Serialize},
+};
+use std::convert::TryFrom;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
@@ -13,6 +17,61 @@ pub struct Config {
     /// - the rest of the contract earnings are staked to boost the staking rewards for user accounts
     /// - must be a number between 0-100
     pub contract_owner_earnings_percentage: Option<u8>,
+    pub keeper_reward: Option<KeeperRewardConfig>,
+    /// fee that is charged for [instant_redeem](crate::interface::StakingService::instant_redeem)
+    /// - the fee is specified in basis points, i.e., 1/100th of a percent
+    /// - must be a number between 0-1000, i.e., max fee is 10%
+    pub instant_redeem_fee_basis_points: Option<u16>,
+    /// protocol fee that is charged on staking rewards - the fee is minted as STAKE to the
+    /// [treasury](crate::interface::ContractOwner::treasury_id)
+    /// - the fee is specified in basis points, i.e., 1/100th of a percent
+    /// - must be a number between 0-2000, i.e., max fee is 20%
+    pub performance_fee_basis_points: Option<u16>,
+    /// percentage of contract earnings that are set aside to fund the insurance buffer, which absorbs
+    /// staking losses before they are passed on to STAKE holders
+    /// - must be a number between 0-100
+    pub insurance_buffer_earnings_percentage: Option<u8>,
+    /// fees that are charged on deposit, redeem, withdraw and ft_transfer
+    pub fee_schedule: Option<FeeSchedule>,
+    /// number of epochs after which a batch receipt that has not been fully claimed can be settled
+    /// on behalf of the accounts via [settle_receipts](crate::interface::Keeper::settle_receipts)
+    /// - must be > 0
+    pub receipt_expiry_epochs: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeSchedule {
+    /// charged on the NEAR that is deposited to be staked
+    pub deposit: Option<Fee>,
+    /// charged on the STAKE that is redeemed
+    pub redeem: Option<Fee>,
+    /// charged on the NEAR that is withdrawn or transferred out of the account
+    pub withdraw: Option<Fee>,
+    /// charged on the STAKE that is transferred - debited from the sender on top of the transfer amount
+    pub ft_transfer: Option<Fee>,
+}
+
+/// fee = flat fee + basis points on the NEAR value of the amount
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Fee {
+    pub flat: Option<YoctoNear>,
+    /// must be a number between 0-1000, i.e., max fee is 10%
+    pub basis_points: Option<u16>,
+    /// accounts that are not charged the fee - replaces the current allowlist
+    /// - max number of accounts is [MAX_FEE_EXEMPT_ACCOUNTS](crate::config::MAX_FEE_EXEMPT_ACCOUNTS)
+    pub exempt_account_ids: Option<Vec<ValidAccountId>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct KeeperRewardConfig {
+    /// NEAR value of the reward that is paid per completed batch - zero disables keeper rewards
+    pub amount: Option<YoctoNear>,
+    pub payout: Option<KeeperRewardPayout>,
+    /// max number of keeper rewards that are paid out per epoch
+    pub max_rewards_per_epoch: Option<u32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,6 +82,9 @@ pub struct GasConfig {
 
     pub function_call_promise: Option<Gas>,
     pub function_call_promise_data_dependency: Option<Gas>,
+
+    /// gas required to credit a single receiver in a batch transfer
+    pub ft_batch_transfer_receiver: Option<Gas>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -52,8 +114,19 @@ pub struct CallBacksGasConfig {
 
     /// used by FungibleToken transfer call workflow
     pub resolve_transfer_gas: Option<Gas>,
+    /// used by VaultFungibleToken transfer with vault workflow
+    pub resolve_vault_gas: Option<Gas>,
 
     pub refresh_stake_token_value: Option<Gas>,
+
+    /// used by staking pool migration workflow
+    pub on_staking_pool_migration_unstaked: Option<Gas>,
+    pub on_staking_pool_migration_withdrawn: Option<Gas>,
+    pub on_staking_pool_migration_staked: Option<Gas>,
+
+    /// used by netted batches workflow
+    pub on_run_netted_batches: Option<Gas>,
+    pub on_netted_batches_settled: Option<Gas>,
 }
 
 impl From<config::Config> for Config {
@@ -62,6 +135,54 @@ impl From<config::Config> for Config {
             storage_cost_per_byte: Some(value.storage_cost_per_byte().into()),
             gas_config: Some(value.gas_config().into()),
             contract_owner_earnings_percentage: Some(value.contract_owner_earnings_percentage()),
+            keeper_reward: Some(value.keeper_reward().into()),
+            instant_redeem_fee_basis_points: Some(value.instant_redeem_fee_basis_points()),
+            performance_fee_basis_points: Some(value.performance_fee_basis_points()),
+            insurance_buffer_earnings_percentage: Some(
+                value.insurance_buffer_earnings_percentage(),
+            ),
+            fee_schedule: Some(value.fee_schedule().into()),
+            receipt_expiry_epochs: Some(value.receipt_expiry_epochs()),
+        }
+    }
+}
+
+impl From<&config::FeeSchedule> for FeeSchedule {
+    fn from(value: &config::FeeSchedule) -> Self {
+        Self {
+            deposit: Some(value.deposit().into()),
+            redeem: Some(value.redeem().into()),
+            withdraw: Some(value.withdraw().into()),
+            ft_transfer: Some(value.ft_transfer().into()),
+        }
+    }
+}
+
+impl From<&config::Fee> for Fee {
+    fn from(value: &config::Fee) -> Self {
+        Self {
+            flat: Some(value.flat().into()),
+            basis_points: Some(value.basis_points()),
+            exempt_account_ids: Some(
+                value
+                    .exempt_account_ids()
+                    .iter()
+                    .map(|account_id| {
+                        ValidAccountId::try_from(account_id.as_str())
+                            .expect("exempt account ID should be valid")
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<config::KeeperRewardConfig> for KeeperRewardConfig {
+    fn from(value: config::KeeperRewardConfig) -> Self {
+        Self {
+            amount: Some(value.amount().into()),
+            payout: Some(value.payout()),
+            max_rewards_per_epoch: Some(value.max_rewards_per_epoch()),
         }
     }
 }
@@ -75,6 +196,7 @@ impl From<config::GasConfig> for GasConfig {
             function_call_promise_data_dependency: Some(
                 value.function_call_promise_data_dependency().into(),
             ),
+            ft_batch_transfer_receiver: Some(value.ft_batch_transfer_receiver().into()),
         }
     }
 }
@@ -108,7 +230,17 @@ impl From<config::CallBacksGasConfig> for CallBacksGasConfig {
                 value.on_redeeming_stake_post_withdrawal().into(),
             ),
             resolve_transfer_gas: Some(value.resolve_transfer_gas().into()),
+            resolve_vault_gas: Some(value.resolve_vault_gas().into()),
             refresh_stake_token_value: Some(value.on_refresh_stake_token_value().into()),
+            on_staking_pool_migration_unstaked: Some(
+                value.on_staking_pool_migration_unstaked().into(),
+            ),
+            on_staking_pool_migration_withdrawn: Some(
+                value.on_staking_pool_migration_withdrawn().into(),
+            ),
+            on_staking_pool_migration_staked: Some(value.on_staking_pool_migration_staked().into()),
+            on_run_netted_batches: Some(value.on_run_netted_batches().into()),
+            on_netted_batches_settled: Some(value.on_netted_batches_settled().into()),
         }
     }
 }
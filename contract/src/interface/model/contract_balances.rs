@@ -1,6 +1,6 @@
 use crate::{
     domain,
-    interface::{BlockHeight, BlockTimestamp, YoctoNear},
+    interface::{BlockHeight, BlockTimestamp, CollectedFees, InsuranceBuffer, YoctoNear},
 };
 use near_sdk::serde::{Deserialize, Serialize};
 
@@ -22,6 +22,9 @@ pub struct ContractBalances {
     /// amount of NEAR in the liquidity pool that user accounts can draw against to claim funds for
     /// [RedeemStakeBatchReceipts](crate::domain::RedeemStakeBatch)
     pub near_liquidity_pool: YoctoNear,
+    /// fees collected from instant redemptions, which are distributed to the user accounts when
+    /// earnings are distributed
+    pub instant_redeem_fees: YoctoNear,
     /// total balance that has been escrowed to pay for user account storage
     pub total_account_storage_escrow: YoctoNear,
 
@@ -37,7 +40,17 @@ pub struct ContractBalances {
     pub user_accounts_earnings: YoctoNear,
 
     /// funds that have been deposited for boosting staking, but not yet staked
+    /// - includes NEAR fees that have been collected, which are distributed along with the earnings
     pub collected_earnings: YoctoNear,
+    /// fees that have been collected per category
+    pub collected_fees: CollectedFees,
+    /// earnings that have been set aside to pay keepers for running batches
+    pub keeper_rewards_pool: YoctoNear,
+    /// earnings that have been set aside to absorb staking losses
+    pub insurance_buffer: InsuranceBuffer,
+    /// NEAR that liquidity providers have deposited to fund instant redemptions, which is not
+    /// included in `total_user_accounts_balance`
+    pub liquidity_provider_pool: YoctoNear,
 
     /// portion of the locked contract account balance that the contract owner is responsible for
     /// to pay for contract storage usage - based on the contract storage usage when first deployed
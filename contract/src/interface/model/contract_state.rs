@@ -3,8 +3,8 @@ use crate::interface::{BlockHeight, ContractBalances, StorageUsage};
 use crate::{
     domain::RedeemLock,
     interface::{
-        BatchId, BlockTimeHeight, RedeemStakeBatch, StakeBatch, StakeTokenValue,
-        TimestampedNearBalance, TimestampedStakeBalance,
+        BatchId, BlockTimeHeight, RedeemStakeBatch, StakeBatch, StakeTokenValue, StakingPool,
+        StakingPoolMigration, TimestampedNearBalance, TimestampedStakeBalance,
     },
 };
 use near_sdk::{
@@ -19,7 +19,12 @@ pub struct ContractState {
     pub block: BlockTimeHeight,
     pub config_change_block_height: BlockHeight,
 
-    pub staking_pool_id: AccountId,
+    /// per staking pool target weight and staked / unstaked balance breakdown
+    pub staking_pools: Vec<StakingPool>,
+    /// staking pool that holds the unstaked NEAR that is pending withdrawal
+    pub unstaking_pool_id: Option<AccountId>,
+    /// reports the progress of the staking pool migration that is in progress
+    pub staking_pool_migration: Option<StakingPoolMigration>,
 
     pub registered_accounts_count: U128,
 
@@ -39,6 +44,14 @@ pub struct ContractState {
 
     pub stake_batch_lock: Option<StakeLock>,
     pub redeem_stake_batch_lock: Option<RedeemLock>,
+    /// true if the [next_redeem_stake_batch](ContractState::next_redeem_stake_batch) cannot be
+    /// unstaked until the [redeem_stake_batch](ContractState::redeem_stake_batch) that is pending
+    /// withdrawal is withdrawn
+    /// - the pending withdrawal can only be moved into an unstaked cohort when another staking pool
+    ///   has staked NEAR that is not pending withdrawal
+    /// - NOTE: redeem stake batches never overlap when the contract delegates to a single staking
+    ///   pool, i.e., the next batch always waits for the pending withdrawal
+    pub next_redeem_stake_batch_blocked_by_pending_withdrawal: bool,
 
     pub balances: ContractBalances,
     /// total contract storage usage = [initial_storage_usage](ContractState::initial_storage_usage) + [storage_usage_growth](ContractState::storage_usage_growth)
@@ -0,0 +1,26 @@
+use crate::config::FeeCategory;
+use crate::interface::YoctoNear;
+use near_sdk::{
+    json_types::U128,
+    serde::{Deserialize, Serialize},
+};
+
+/// see [quote_fee](crate::interface::StakingService::quote_fee)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeeQuote {
+    pub category: FeeCategory,
+    /// NEAR for deposit and withdraw, and STAKE for redeem and ft_transfer
+    pub amount: U128,
+    /// fee that is charged, denominated in the same token as the amount
+    pub fee: U128,
+    pub fee_near_value: YoctoNear,
+    /// fee-inclusive result:
+    /// - deposit: NEAR that is put into the stake batch
+    /// - redeem: STAKE that is put into the redeem stake batch
+    /// - withdraw: NEAR that is transferred
+    /// - ft_transfer: STAKE that is debited from the sender
+    pub amount_with_fee: U128,
+    /// true if the account is on the fee exemption allowlist
+    pub exempt: bool,
+}
@@ -0,0 +1,38 @@
+use crate::domain;
+use near_sdk::{
+    json_types::Base64VecU8,
+    serde::{Deserialize, Serialize},
+};
+
+/// [NEP-148](https://github.com/near/NEPs/discussions/148) fungible token metadata
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FungibleTokenMetadata {
+    /// metadata spec version, e.g., "ft-1.0.0"
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    /// data URL, e.g., `data:image/svg+xml,...`
+    pub icon: Option<String>,
+    /// link to off-chain JSON file with additional token info
+    pub reference: Option<String>,
+    /// base64 encoded sha256 hash of the JSON file contained in the `reference` field
+    pub reference_hash: Option<Base64VecU8>,
+    pub decimals: u8,
+}
+
+impl From<domain::FungibleTokenMetadata> for FungibleTokenMetadata {
+    fn from(metadata: domain::FungibleTokenMetadata) -> Self {
+        Self {
+            spec: metadata.spec().to_string(),
+            name: metadata.name().to_string(),
+            symbol: metadata.symbol().to_string(),
+            icon: metadata.icon().map(ToString::to_string),
+            reference: metadata.reference().map(ToString::to_string),
+            reference_hash: metadata
+                .reference_hash()
+                .map(|hash| Base64VecU8(hash.to_vec())),
+            decimals: metadata.decimals(),
+        }
+    }
+}
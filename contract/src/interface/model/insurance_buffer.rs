@@ -0,0 +1,26 @@
+use crate::domain;
+use crate::interface::YoctoNear;
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// NEAR that is set aside from contract earnings to absorb staking losses before they are passed on
+/// to STAKE holders
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InsuranceBuffer {
+    pub balance: YoctoNear,
+    /// total staking losses that were absorbed since the contract was deployed
+    pub total_absorbed_losses: YoctoNear,
+    /// total staking losses that were passed on to STAKE holders and redeem stake batch receipts
+    /// since the contract was deployed
+    pub total_uncovered_losses: YoctoNear,
+}
+
+impl From<domain::InsuranceBuffer> for InsuranceBuffer {
+    fn from(insurance_buffer: domain::InsuranceBuffer) -> Self {
+        Self {
+            balance: insurance_buffer.balance().into(),
+            total_absorbed_losses: insurance_buffer.total_absorbed_losses().into(),
+            total_uncovered_losses: insurance_buffer.total_uncovered_losses().into(),
+        }
+    }
+}
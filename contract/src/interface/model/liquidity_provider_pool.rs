@@ -0,0 +1,29 @@
+use crate::interface::{YoctoNear, YoctoStake};
+use near_sdk::{
+    json_types::U128,
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LiquidityProviderPool {
+    /// NEAR that is available to fund instant redemptions and LP withdrawals
+    pub near: YoctoNear,
+    /// STAKE that the pool bought from instant redemptions, which is being redeemed via the batch workflow
+    pub redeeming_stake: YoctoStake,
+    /// pool NEAR balance plus the NEAR value of the STAKE that is being redeemed
+    pub total_value: YoctoNear,
+    pub total_shares: U128,
+    /// percentage of the pool value that is tied up in STAKE that is being redeemed, in basis points
+    pub utilization_basis_points: u16,
+    /// total instant redeem fees that have been earned by the pool
+    pub total_fees_earned: YoctoNear,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LiquidityProviderPosition {
+    pub shares: U128,
+    /// NEAR value of the shares based on the current pool value
+    pub near_value: YoctoNear,
+}
@@ -0,0 +1,11 @@
+use crate::interface::{RedeemStakeBatch, StakeBatch};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// an account batch that has not been claimed yet, but is no longer the contract's current or next batch
+/// - see [PendingBatch](crate::domain::PendingBatch)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum PendingBatch {
+    Stake(StakeBatch),
+    Redeem(RedeemStakeBatch),
+}
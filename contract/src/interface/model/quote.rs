@@ -0,0 +1,61 @@
+use crate::interface::{BatchId, EpochHeight, FeeQuote, StakeTokenValue, YoctoNear, YoctoStake};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// the batch that a request would be added to if it was submitted right now
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum QuoteBatch {
+    /// the request is added to the current batch, which will be run next
+    Current,
+    /// the current batch is locked, i.e., it is running or is pending withdrawal, or a staking pool
+    /// migration is in progress - the request is added to the next batch
+    Next,
+}
+
+/// see [quote_stake](crate::interface::StakingService::quote_stake)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeQuote {
+    /// NEAR that would be deposited
+    pub amount: YoctoNear,
+    pub fee: FeeQuote,
+    /// STAKE that would be issued for the deposit after fees based on [stake_token_value](StakeQuote::stake_token_value)
+    /// - the exact amount is only known once the batch is staked, i.e., when the STAKE token value
+    ///   is refreshed
+    pub stake: YoctoStake,
+    /// the cached STAKE token value that the quote is based on
+    pub stake_token_value: StakeTokenValue,
+    /// number of epochs since the STAKE token value was last updated
+    pub stake_token_value_age: EpochHeight,
+    pub batch: QuoteBatch,
+    /// None if the batch does not exist yet, i.e., the request would create it
+    pub batch_id: Option<BatchId>,
+}
+
+/// see [quote_redeem](crate::interface::StakingService::quote_redeem)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedeemQuote {
+    /// STAKE that would be redeemed
+    pub amount: YoctoStake,
+    pub fee: FeeQuote,
+    /// NEAR value of the STAKE after fees based on [stake_token_value](RedeemQuote::stake_token_value)
+    /// - the exact amount is only known once the batch is unstaked, i.e., when the STAKE token value
+    ///   is refreshed
+    pub near: YoctoNear,
+    /// the cached STAKE token value that the quote is based on
+    pub stake_token_value: StakeTokenValue,
+    /// number of epochs since the STAKE token value was last updated
+    pub stake_token_value_age: EpochHeight,
+    pub batch: QuoteBatch,
+    /// None if the batch does not exist yet, i.e., the request would create it
+    pub batch_id: Option<BatchId>,
+    /// the earliest epoch at which the NEAR is expected to be available for withdrawal, assuming
+    /// that batches are run as soon as they are runnable
+    /// - the NEAR may become available sooner if the request is filled from NEAR liquidity while
+    ///   the batch is pending withdrawal
+    pub estimated_withdrawable_epoch: EpochHeight,
+    /// NEAR liquidity that is currently available to redeem the STAKE right away via
+    /// [instant_redeem](crate::interface::StakingService::instant_redeem)
+    pub instant_redeem_liquidity: YoctoNear,
+}
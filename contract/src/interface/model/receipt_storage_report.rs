@@ -0,0 +1,28 @@
+use crate::interface::{BatchId, EpochHeight, StorageUsage};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// see [receipt_storage_report](crate::interface::Keeper::receipt_storage_report)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReceiptStorageReport {
+    /// receipts that are older than the configured number of epochs can be settled via
+    /// [settle_receipts](crate::interface::Keeper::settle_receipts)
+    pub receipt_expiry_epochs: u32,
+    pub stake_batch_receipts: ReceiptBacklog,
+    pub redeem_stake_batch_receipts: ReceiptBacklog,
+}
+
+/// batch receipts that have not been fully claimed by the accounts
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReceiptBacklog {
+    /// number of receipts that are held in contract storage
+    pub count: u32,
+    /// storage that the contract pays for across all receipts, including the receipt IDs that are
+    /// tracked for the backlog
+    pub storage_usage: StorageUsage,
+    /// epoch at which the oldest receipt within the page was created
+    pub oldest_receipt_epoch: Option<EpochHeight>,
+    /// receipts within the page that have expired and can be settled, sorted by batch ID
+    pub expired: Vec<BatchId>,
+}
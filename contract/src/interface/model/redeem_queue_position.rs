@@ -0,0 +1,20 @@
+use crate::interface::{BatchId, BlockTimestamp, YoctoNear, YoctoStake};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// account's position in the redeem queue - see [redeem_queue_position](crate::interface::StakingService::redeem_queue_position)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RedeemQueuePosition {
+    /// number of requests ahead of the account's first request - zero means the account is at
+    /// the head of the queue
+    pub position: u32,
+    pub queue_length: u32,
+    /// batch that the account's first request belongs to
+    pub batch_id: BatchId,
+    /// when the account's first request was queued
+    pub requested_at: BlockTimestamp,
+    /// STAKE across the account's requests that is still waiting to be filled
+    pub queued_stake: YoctoStake,
+    /// NEAR that has been paid out to partially fill the account's requests
+    pub filled_near: YoctoNear,
+}
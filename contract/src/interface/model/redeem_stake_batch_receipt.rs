@@ -17,6 +17,8 @@ pub struct RedeemStakeBatchReceipt {
     pub stake_token_value: StakeTokenValue,
     /// the NEAR value of the redeemed STAKE computed from [stake_token_value](RedeemStakeBatchReceipt::stake_token_value)
     pub redeemed_stake_value: YoctoNear,
+    /// NEAR that was haircut from the receipt's claims to absorb staking losses
+    pub haircut: YoctoNear,
 }
 
 impl From<domain::RedeemStakeBatchReceipt> for RedeemStakeBatchReceipt {
@@ -25,6 +27,7 @@ impl From<domain::RedeemStakeBatchReceipt> for RedeemStakeBatchReceipt {
             redeemed_stake: receipt.redeemed_stake().into(),
             stake_token_value: receipt.stake_token_value().into(),
             redeemed_stake_value: receipt.stake_near_value().into(),
+            haircut: receipt.haircut().into(),
         }
     }
 }
@@ -0,0 +1,31 @@
+use crate::{
+    config::KeeperRewardPayout,
+    interface::{BatchId, YoctoNear},
+};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// tells keepers which batches can be run right now
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RunnableBatches {
+    /// set if the [StakeBatch](crate::domain::StakeBatch) can be run via
+    /// [stake()](crate::interface::StakingService::stake)
+    pub stake_batch: Option<BatchId>,
+    /// set if the [RedeemStakeBatch](crate::domain::RedeemStakeBatch) can be run via
+    /// [unstake()](crate::interface::StakingService::unstake), i.e., to either unstake the batch or
+    /// to withdraw the unstaked NEAR that is pending withdrawal
+    pub redeem_stake_batch: Option<BatchId>,
+    /// set if the unstaked NEAR for an [UnstakedCohort](crate::interface::UnstakedCohort) can be
+    /// withdrawn via [withdraw_unstaked_cohort()](crate::interface::StakingService::withdraw_unstaked_cohort)
+    pub unstaked_cohort: Option<BatchId>,
+
+    /// NEAR value of the reward that would be paid out per completed batch
+    /// - zero if keeper rewards are disabled, the rewards limit for the current epoch has been
+    ///   reached, or the keeper rewards pool is empty
+    pub keeper_reward: YoctoNear,
+    pub keeper_reward_payout: KeeperRewardPayout,
+    /// number of keeper rewards that can still be paid out within the current epoch
+    pub remaining_epoch_rewards: u32,
+    /// NEAR that has been set aside from contract earnings to pay keeper rewards
+    pub keeper_rewards_pool: YoctoNear,
+}
@@ -1,5 +1,6 @@
 use crate::interface::{
-    RedeemStakeBatch, StakeBatch, TimestampedNearBalance, TimestampedStakeBalance, YoctoNear,
+    PendingBatch, RedeemStakeBatch, StakeBatch, TimestampedNearBalance, TimestampedStakeBalance,
+    YoctoNear,
 };
 use near_sdk::serde::{Deserialize, Serialize};
 
@@ -29,6 +30,11 @@ pub struct StakeAccount {
     /// to redeem STAKE tokens into the next batch while the contract is locked.
     pub next_redeem_stake_batch: Option<RedeemStakeBatch>,
 
+    /// older batches that the account has not claimed yet, e.g., STAKE that is being unstaked as
+    /// part of an unstaked cohort
+    /// - the account pays for the pending batch storage out of its storage escrow
+    pub pending_batches: Vec<PendingBatch>,
+
     /// only applies if the account has a [RedeemStakeBatch](crate::domain::RedeemStakeBatch) with a
     /// [RedeemStakeBatchReceipt](crate::domain::RedeemStakeBatchReceipt) that is pending withdrawal
     /// from the staking pool. If the contract has liquidity, then this returns the current liquidity
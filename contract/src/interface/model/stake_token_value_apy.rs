@@ -0,0 +1,17 @@
+use crate::interface::{EpochHeight, StakeTokenValue};
+use near_sdk::serde::{Deserialize, Serialize};
+
+/// annualized change in the STAKE token value between 2 [StakeTokenValue] samples - see
+/// [apy](crate::interface::StakingService::apy)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeTokenValueApy {
+    /// number of epochs between the samples
+    pub epochs: EpochHeight,
+    pub from: StakeTokenValue,
+    pub to: StakeTokenValue,
+    /// the change in the NEAR value of 1 STAKE extrapolated linearly over a year
+    /// - specified in basis points, i.e., 1/100th of a percent
+    /// - negative if the STAKE token value dropped, e.g., because of staking losses
+    pub apy_basis_points: i64,
+}
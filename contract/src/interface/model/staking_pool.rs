@@ -0,0 +1,28 @@
+use crate::{domain, interface::YoctoNear};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingPool {
+    pub account_id: AccountId,
+    /// target weight relative to the sum of all staking pool weights
+    pub weight: u32,
+    /// staked balance that was returned by the staking pool the last time the account was retrieved
+    pub staked_balance: YoctoNear,
+    /// unstaked balance that was returned by the staking pool the last time the account was retrieved
+    pub unstaked_balance: YoctoNear,
+}
+
+impl From<&domain::StakingPool> for StakingPool {
+    fn from(pool: &domain::StakingPool) -> Self {
+        Self {
+            account_id: pool.account_id().to_string(),
+            weight: pool.weight(),
+            staked_balance: pool.staked_balance().into(),
+            unstaked_balance: pool.unstaked_balance().into(),
+        }
+    }
+}
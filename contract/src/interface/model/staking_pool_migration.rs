@@ -0,0 +1,36 @@
+use crate::{
+    domain::{self, StakingPoolMigrationStatus},
+    interface::{EpochHeight, YoctoNear},
+};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingPoolMigration {
+    /// staking pool that NEAR is being migrated from
+    pub from_staking_pool_id: AccountId,
+    /// staking pool that NEAR is being migrated to
+    pub to_staking_pool_id: AccountId,
+    pub status: StakingPoolMigrationStatus,
+    /// NEAR that was unstaked from the staking pool that is being migrated
+    pub unstaked_balance: YoctoNear,
+    /// epoch within which the unstaked NEAR will be available for withdrawal
+    pub unstaked_near_withdrawal_availability: EpochHeight,
+}
+
+impl From<&domain::StakingPoolMigration> for StakingPoolMigration {
+    fn from(migration: &domain::StakingPoolMigration) -> Self {
+        Self {
+            from_staking_pool_id: migration.from_staking_pool_id().to_string(),
+            to_staking_pool_id: migration.to_staking_pool_id().to_string(),
+            status: migration.status(),
+            unstaked_balance: migration.unstaked_balance().into(),
+            unstaked_near_withdrawal_availability: migration
+                .unstaked_near_withdrawal_availability()
+                .into(),
+        }
+    }
+}
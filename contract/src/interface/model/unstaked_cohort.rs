@@ -0,0 +1,39 @@
+use crate::{
+    domain,
+    interface::{BatchId, EpochHeight, YoctoNear},
+};
+use near_sdk::{
+    serde::{Deserialize, Serialize},
+    AccountId,
+};
+
+/// redeem stake batch that was unstaked and is waiting to be withdrawn from the staking pool - see
+/// [withdraw_unstaked_cohort](crate::interface::StakingService::withdraw_unstaked_cohort)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UnstakedCohort {
+    pub batch_id: BatchId,
+    /// staking pool that holds the unstaked NEAR
+    pub staking_pool_id: AccountId,
+    /// NEAR that is pending withdrawal from the staking pool - net of the haircut
+    pub unstaked_near: YoctoNear,
+    /// NEAR that was haircut from the cohort's claims to absorb staking losses - it is added to the
+    /// NEAR liquidity pool once withdrawn
+    pub haircut: YoctoNear,
+    /// epoch within which the unstaked NEAR will be available for withdrawal
+    pub unstaked_near_withdrawal_availability: EpochHeight,
+}
+
+impl UnstakedCohort {
+    pub fn new(cohort: &domain::UnstakedCohort, receipt: &domain::RedeemStakeBatchReceipt) -> Self {
+        Self {
+            batch_id: cohort.batch_id().into(),
+            staking_pool_id: cohort.staking_pool_id().to_string(),
+            unstaked_near: cohort.unstaked_near().into(),
+            haircut: cohort.haircut().into(),
+            unstaked_near_withdrawal_availability: receipt
+                .unstaked_near_withdrawal_availability()
+                .into(),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use crate::domain;
+use near_sdk::{
+    json_types::U128,
+    serde::{Deserialize, Serialize},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VaultId(pub U128);
+
+impl From<domain::VaultId> for VaultId {
+    fn from(value: domain::VaultId) -> Self {
+        Self(value.0.into())
+    }
+}
+
+impl From<VaultId> for u128 {
+    fn from(value: VaultId) -> Self {
+        value.0 .0
+    }
+}
@@ -1,5 +1,8 @@
-use crate::interface::{model::contract_state::ContractState, Config};
-use near_sdk::AccountId;
+use crate::interface::{
+    model::contract_state::ContractState, Config, FungibleTokenMetadata, StakingPool,
+    StakingPoolMigration,
+};
+use near_sdk::{json_types::ValidAccountId, AccountId, Promise};
 
 /// provides functions to support DevOps
 pub trait Operator {
@@ -37,6 +40,95 @@ pub trait Operator {
     /// - if not invoked by the operator account
     fn force_update_config(&mut self, config: Config) -> Config;
 
+    /// replaces the STAKE token [NEP-148](https://github.com/near/NEPs/discussions/148) metadata,
+    /// which is returned by [ft_metadata](crate::interface::FungibleTokenMetadataProvider::ft_metadata)
+    /// - the contract owner pays for the metadata storage
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if metadata validation fails, e.g., `reference` is specified without a `reference_hash`
+    fn update_ft_metadata(&mut self, metadata: FungibleTokenMetadata) -> FungibleTokenMetadata;
+
+    /// registers a new staking pool with the specified target weight, which is relative to the sum
+    /// of all staking pool weights
+    /// - new batches are staked with the most underweight staking pool, which means the new staking
+    ///   pool will be filled up by subsequent stake batches
+    ///
+    /// Returns the registered staking pools
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if the staking pool is already registered
+    /// - if the max number of staking pools has been reached
+    fn add_staking_pool(
+        &mut self,
+        staking_pool_id: ValidAccountId,
+        weight: u32,
+    ) -> Vec<StakingPool>;
+
+    /// updates the staking pool's target weight
+    /// - setting the weight to zero stops staking with the staking pool, and its stake will be
+    ///   unstaked first when STAKE is redeemed
+    ///
+    /// Returns the registered staking pools
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if the staking pool is not registered
+    /// - if all staking pool weights would be zero
+    fn set_staking_pool_weight(
+        &mut self,
+        staking_pool_id: ValidAccountId,
+        weight: u32,
+    ) -> Vec<StakingPool>;
+
+    /// kicks off the workflow to migrate all NEAR staked with the `from_staking_pool_id` staking pool
+    /// over to the `to_staking_pool_id` staking pool:
+    /// 1. all NEAR is unstaked from the `from_staking_pool_id` staking pool
+    /// 2. once the unstaked NEAR is available for withdrawal, i.e., after [UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK](crate::near::UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK)
+    ///    epochs, [run_staking_pool_migration](Operator::run_staking_pool_migration) withdraws all
+    ///    unstaked NEAR and then deposits and stakes it with the `to_staking_pool_id` staking pool
+    /// 3. the `from_staking_pool_id` staking pool is unregistered, and its weight is transferred
+    ///    to the `to_staking_pool_id` staking pool - if the `to_staking_pool_id` staking pool is not
+    ///    registered, then it is registered
+    ///
+    /// While the migration is in progress:
+    /// - deposits and redemptions are queued in the next stake and redeem stake batches, which are
+    ///   run once the migration completes
+    /// - the STAKE token value can be refreshed - the NEAR being migrated is included in the
+    ///   staked NEAR balance
+    /// - migration progress is reported by [contract_state](Operator::contract_state) and logged
+    ///   as events
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if a staking pool migration is already in progress
+    /// - if a batch is running or there is a pending withdrawal, including unstaked cohorts
+    /// - if the `from_staking_pool_id` staking pool is not registered
+    /// - if the staking pools are the same
+    fn migrate_staking_pool(
+        &mut self,
+        from_staking_pool_id: ValidAccountId,
+        to_staking_pool_id: ValidAccountId,
+    ) -> Promise;
+
+    /// moves the staking pool migration forward from where it left off:
+    /// - if the unstaked NEAR is pending withdrawal, then all unstaked NEAR is withdrawn and then
+    ///   deposited and staked with the staking pool being migrated to
+    /// - if the NEAR has been withdrawn, but failed to be staked, then it is deposited and staked
+    ///   with the staking pool being migrated to
+    ///
+    /// ## Panics
+    /// - if not invoked by the operator account
+    /// - if there is no staking pool migration in progress
+    /// - if the migration is waiting on a staking pool request to complete
+    /// - if the STAKE token value is being refreshed
+    /// - if the unstaked NEAR is not yet available for withdrawal
+    fn run_staking_pool_migration(&mut self) -> Promise;
+
+    /// returns the staking pool migration that is in progress
+    fn staking_pool_migration(&self) -> Option<StakingPoolMigration>;
+
     /// unlocks the contract if the [StakeLock](crate::domain::StakeLock) state is
     /// [StakeLock::Staking](crate::domain::StakeLock::Staking)
     ///
@@ -50,3 +142,56 @@ pub trait Operator {
     /// if not invoked by self as callback or the operator account
     fn clear_redeem_lock(&mut self);
 }
+
+pub mod events {
+    use crate::domain::StakingPoolMigrationStatus;
+    use crate::near::{Event, STAKE_TOKEN_EVENT_STANDARD, STAKE_TOKEN_EVENT_VERSION};
+    use near_sdk::{json_types::U128, serde::Serialize};
+
+    /// logged each time the staking pool migration transitions to a new status
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct StakingPoolMigrationProgress<'a> {
+        pub from_staking_pool_id: &'a str,
+        pub to_staking_pool_id: &'a str,
+        pub status: StakingPoolMigrationStatus,
+        /// NEAR that was unstaked from the staking pool being migrated
+        pub unstaked_balance: U128,
+    }
+
+    impl Event for StakingPoolMigrationProgress<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "staking_pool_migration_progress";
+    }
+
+    /// logged when the migrated NEAR has been staked with the staking pool being migrated to
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct StakingPoolMigrationCompleted<'a> {
+        pub from_staking_pool_id: &'a str,
+        pub to_staking_pool_id: &'a str,
+        /// how much NEAR was staked with the staking pool being migrated to
+        pub staked_balance: U128,
+    }
+
+    impl Event for StakingPoolMigrationCompleted<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "staking_pool_migration_completed";
+    }
+
+    /// logged when the staking pool failed to unstake all NEAR, which cancels the migration
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct StakingPoolMigrationAborted<'a> {
+        pub from_staking_pool_id: &'a str,
+        pub to_staking_pool_id: &'a str,
+    }
+
+    impl Event for StakingPoolMigrationAborted<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "staking_pool_migration_aborted";
+    }
+}
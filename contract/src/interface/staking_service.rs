@@ -1,7 +1,13 @@
+use crate::config::FeeCategory;
 use crate::interface::{
-    BatchId, RedeemStakeBatchReceipt, StakeBatchReceipt, StakeTokenValue, YoctoNear, YoctoStake,
+    BatchId, EpochHeight, FeeQuote, RedeemQueuePosition, RedeemQuote, RedeemStakeBatchReceipt,
+    StakeBatchReceipt, StakeQuote, StakeTokenValue, StakeTokenValueApy, StakingPool,
+    UnstakedCohort, YoctoNear, YoctoStake,
+};
+use near_sdk::{
+    json_types::{ValidAccountId, U128},
+    Promise, PromiseOrValue,
 };
-use near_sdk::{json_types::ValidAccountId, AccountId, Promise, PromiseOrValue};
 
 /// Integrates with the staking pool contract and manages STAKE token assets. The main use
 /// cases supported by this interface are:
@@ -53,9 +59,11 @@ use near_sdk::{json_types::ValidAccountId, AccountId, Promise, PromiseOrValue};
 ///   funds and they will be queued into the next batch
 /// - batch receipts will be deleted from storage once all funds on the receipt are claimed
 pub trait StakingService {
-    /// returns the staking pool account ID used for the STAKE token
-    /// - this is the staking pool that this contract is linked to
-    fn staking_pool_id(&self) -> AccountId;
+    /// returns the staking pools that NEAR is delegated to, in the order they were registered
+    /// - each batch is staked with the most underweight staking pool and unstaked from the most
+    ///   overweight staking pool - see [Operator::add_staking_pool](crate::interface::Operator::add_staking_pool)
+    /// - the balances are cached from the latest staking pool account lookup
+    fn staking_pools(&self) -> Vec<StakingPool>;
 
     /// looks up the receipt for the specified batch ID
     /// - when a batch is successfully processed a receipt is created, meaning the NEAR funds have
@@ -91,10 +99,12 @@ pub trait StakingService {
     ///   - this protects against the scenario of issuing zero STAKE tokens - we never want to issue
     ///     zero yoctoSTAKE tokens if NEAR is deposited and staked
     ///   - in addition because of rounding issues when
+    /// - the [deposit fee](crate::config::FeeSchedule) is deducted from the attached deposit
     ///
     /// ## Panics
     /// - if account is not registered
     /// - if no deposit is attached
+    /// - if the attached deposit does not cover the deposit fee
     /// - if less than the minimum required deposit was attached
     ///
     /// ## Notes
@@ -105,6 +115,29 @@ pub trait StakingService {
     /// GAS REQUIREMENTS: 10 TGas
     fn deposit(&mut self) -> BatchId;
 
+    /// Deposits the attached deposit the same way as [deposit](StakingService::deposit), but with limits
+    /// that protect the account against the STAKE token value that the batch is run at:
+    /// - `min_stake_out` - the min amount of STAKE that must be issued for the NEAR, after fees
+    /// - `deadline_epoch` - the batch must be run on or before the specified epoch
+    ///
+    /// When the batch is run, the STAKE token value is refreshed before the NEAR is deposited into
+    /// the staking pool. If the limits are not met, then the NEAR is returned to the account's NEAR
+    /// balance and the [DepositLimitOrderRejected](crate::interface::staking_service::events::DepositLimitOrderRejected)
+    /// event is logged. The deposit fee is not refunded.
+    ///
+    /// ## Panics
+    /// - same as [deposit](StakingService::deposit)
+    /// - if the deadline epoch has already passed
+    /// - if the amount is below the minimum limit order amount - see [MIN_LIMIT_ORDER_AMOUNT](crate::domain::MIN_LIMIT_ORDER_AMOUNT)
+    /// - if the batch has reached the max number of limit orders - see [MAX_LIMIT_ORDERS_PER_BATCH](crate::domain::MAX_LIMIT_ORDERS_PER_BATCH)
+    ///
+    /// #\[payable\]
+    fn deposit_with_limits(
+        &mut self,
+        min_stake_out: YoctoStake,
+        deadline_epoch: EpochHeight,
+    ) -> BatchId;
+
     /// If there is pending unstaked NEAR awaiting to become available for withdrawal, then the the
     /// NEAR deposits stored in the [StakeBatch](crate::domain::StakeBatch) will provide liquidity
     /// to enable NEAR funds to be withdrawn sooner than the lockup period imposed by the staking pool.
@@ -128,11 +161,14 @@ pub trait StakingService {
     /// ## Notes
     /// [contract_state](crate::interface::Operator::contract_state) can be queried to check if the
     /// batch cab be run, i.e., to check if there is a batch to run and that the contract is not locked.
+    /// - the account that runs the batch is rewarded when the batch completes successfully - see
+    ///   [Keeper](crate::interface::Keeper)
     ///
     /// ## Panics
     /// - if contract is locked for
     ///   - staking batch is in progress
     ///   - unstaking is in progress
+    /// - if a staking pool migration is in progress - see [migrate_staking_pool](crate::interface::Operator::migrate_staking_pool)
     /// - if there is no stake batch to run
     /// - if the attached deposit is less than the [minimum required deposit](StakingService::min_required_deposit_to_stake)
     ///
@@ -157,6 +193,32 @@ pub trait StakingService {
     /// GAS REQUIREMENTS: 225 TGas
     fn deposit_and_stake(&mut self) -> PromiseOrValue<BatchId>;
 
+    /// Works the same as [deposit](StakingService::deposit), except that the attached deposit is
+    /// added to the beneficiary account's [StakeBatch](crate::domain::StakeBatch). When the batch is
+    /// staked, the STAKE is minted for the beneficiary account.
+    ///
+    /// If the beneficiary account is not registered, then the beneficiary account is registered and
+    /// the [account storage fee](crate::interface::AccountManagement::account_storage_fee) is paid
+    /// out of the attached deposit. The remaining deposit is staked.
+    ///
+    /// ## Panics
+    /// - if the beneficiary account is not registered and the attached deposit does not cover the
+    ///   account storage fee plus the NEAR to stake
+    /// - if less than the minimum required deposit was attached
+    ///
+    /// #\[payable\]
+    ///
+    /// GAS REQUIREMENTS: 10 TGas
+    fn deposit_for(&mut self, beneficiary_id: ValidAccountId) -> BatchId;
+
+    /// Combines [deposit_for](StakingService::deposit_for) and [stake](StakingService::stake) calls
+    /// together - see [deposit_and_stake](StakingService::deposit_and_stake)
+    ///
+    /// #\[payable\]
+    ///
+    /// GAS REQUIREMENTS: 225 TGas
+    fn deposit_and_stake_for(&mut self, beneficiary_id: ValidAccountId) -> PromiseOrValue<BatchId>;
+
     /// withdraws specified amount from uncommitted stake batch and refunds the account
     ///
     /// NOTE: all batch receipts are first claimed
@@ -191,13 +253,40 @@ pub trait StakingService {
     /// If the contract is not locked for redeeming, then the request is put into the current batch,
     /// i.e. the amount is added to the current batch.
     ///
+    /// The [redeem fee](crate::config::FeeSchedule) is deducted from the amount, i.e., the STAKE that
+    /// is put into the batch is the amount minus the fee. The fee is not refunded if the STAKE is
+    /// removed from the batch.
+    ///
     /// Returns the batch ID that the request is batched into.
     ///
     /// ## Panics
     /// - if account is not registered
     /// - if there is not enough STAKE in the account to fulfill the request
+    /// - if the amount does not cover the redeem fee
     fn redeem(&mut self, amount: YoctoStake) -> BatchId;
 
+    /// Submits a request to redeem STAKE the same way as [redeem](StakingService::redeem), but with
+    /// limits that protect the account against the STAKE token value that the batch is run at:
+    /// - `min_near_out` - the min amount of NEAR that the STAKE must be redeemed for, after fees
+    /// - `deadline_epoch` - the batch must be run on or before the specified epoch
+    ///
+    /// When the batch is run, the limits are checked against the refreshed STAKE token value before
+    /// the NEAR is unstaked. If the limits are not met, then the STAKE is returned to the account's
+    /// STAKE balance and the [RedeemLimitOrderRejected](crate::interface::staking_service::events::RedeemLimitOrderRejected)
+    /// event is logged. The redeem fee is not refunded.
+    ///
+    /// ## Panics
+    /// - same as [redeem](StakingService::redeem)
+    /// - if the deadline epoch has already passed
+    /// - if the amount is below the minimum limit order amount - see [MIN_LIMIT_ORDER_AMOUNT](crate::domain::MIN_LIMIT_ORDER_AMOUNT)
+    /// - if the batch has reached the max number of limit orders - see [MAX_LIMIT_ORDERS_PER_BATCH](crate::domain::MAX_LIMIT_ORDERS_PER_BATCH)
+    fn redeem_with_limits(
+        &mut self,
+        amount: YoctoStake,
+        min_near_out: YoctoNear,
+        deadline_epoch: EpochHeight,
+    ) -> BatchId;
+
     /// Redeems all available STAKE - see [redeem](StakingService::redeem)
     ///
     /// Returns None if there are no STAKE funds to redeem
@@ -250,17 +339,32 @@ pub trait StakingService {
     ///   requests which will be run in the next batch
     /// - while awaiting the unstaked NEAR funds to be withdrawn, NEAR funds can continue to be staked,
     ///   i.e., it is legal to invoke [stake](StakingService::stake)
-    /// - because unstaked NEAR funds are locked for 4 epochs, depending on unstake workflows that are
-    ///   in progress, it may take a user 4-8 epochs to get access to their NEAR tokens for the STAKE
-    ///   tokens they have redeemed. For example, user-1 unstakes at epoch 100, which means the next
-    ///   unstaking is not eligible until epoch 104. If user-2 redeems STAKE in epoch 100, but after
-    ///   the unstake workflow was run, then user-2 will need to wait until epoch 104 to run the unstake
-    ///   workflow.
+    /// - while awaiting the unstaked NEAR funds to be withdrawn, if there is a next batch to redeem
+    ///   and another staking pool is available to unstake from, then the batch that is pending
+    ///   withdrawal is moved into an [UnstakedCohort](crate::interface::UnstakedCohort) and the next
+    ///   batch is unstaked right away. Thus, redeemed STAKE does not need to wait for the prior batch
+    ///   to be withdrawn before it starts unstaking. Each staking pool holds the unstaked NEAR for at
+    ///   most 1 cohort because unstaking resets the staking pool's unlock epoch - see
+    ///   [withdraw_unstaked_cohort](StakingService::withdraw_unstaked_cohort)
+    /// - if no other staking pool is available, then it may take a user 4-8 epochs to get access to
+    ///   their NEAR tokens for the STAKE tokens they have redeemed. For example, user-1 unstakes at
+    ///   epoch 100, which means the next unstaking is not eligible until epoch 104. If user-2 redeems
+    ///   STAKE in epoch 100, but after the unstake workflow was run, then user-2 will need to wait
+    ///   until epoch 104 to run the unstake workflow.
+    /// - NOTE: when the contract delegates to a single staking pool, there is never another staking
+    ///   pool available, i.e., redeem stake batches do not overlap and the next batch always waits
+    ///   for the pending withdrawal - unstaked cohorts require at least 2 staking pools - see
+    ///   [add_staking_pool](crate::interface::Operator::add_staking_pool). [contract_state](crate::interface::Operator::contract_state)
+    ///   reports when the next batch is blocked by the pending withdrawal.
+    /// - the account that runs the batch is rewarded when the unstaking completes and when the
+    ///   unstaked NEAR is withdrawn - see [Keeper](crate::interface::Keeper)
     ///
     /// ## Panics
     /// - if staking is in progress
     /// - if the redeem stake batch is already in progress
-    /// - if pending withdrawal and unstaked funds are not available for withdrawal
+    /// - if pending withdrawal and unstaked funds are not available for withdrawal, and the pending
+    ///   withdrawal cannot be moved into an unstaked cohort
+    /// - if a staking pool migration is in progress
     ///
     /// ## FAQ
     /// ### Why are the unstaked NEAR funds locked for 2 days?
@@ -276,6 +380,26 @@ pub trait StakingService {
     /// GAS REQUIREMENTS: 150 TGas
     fn unstake(&mut self) -> Promise;
 
+    /// Withdraws the unstaked NEAR for the first [UnstakedCohort](crate::interface::UnstakedCohort)
+    /// whose unstaked NEAR is available for withdrawal. Once withdrawn, accounts can claim the NEAR
+    /// for the STAKE they redeemed in the cohort's batch.
+    ///
+    /// ## workflow
+    /// 1. get account info from the cohort's staking pool
+    /// 2. if unstaked balance is > 0, then withdraw all
+    /// 3. remove the cohort and update the total NEAR available balance
+    ///
+    /// ## Notes
+    /// - [runnable_batches](crate::interface::Keeper::runnable_batches) can be queried to check if
+    ///   there is a cohort to withdraw
+    /// - the account that runs the withdrawal is rewarded - see [Keeper](crate::interface::Keeper)
+    ///
+    /// ## Panics
+    /// - if there are no unstaked cohorts whose unstaked NEAR is available for withdrawal
+    ///
+    /// GAS REQUIREMENTS: 150 TGas
+    fn withdraw_unstaked_cohort(&mut self) -> Promise;
+
     /// combines the [redeem](StakingService::redeem) and [unstake](StakingService::unstake) calls
     ///
     /// GAS REQUIREMENTS: 150 TGas
@@ -290,24 +414,122 @@ pub trait StakingService {
     /// GAS REQUIREMENTS: 150 TGas
     fn redeem_all_and_unstake(&mut self) -> PromiseOrValue<Option<BatchId>>;
 
+    /// Runs the [StakeBatch](crate::domain::StakeBatch) and the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+    /// together by netting them against each other at the current STAKE token value. Only the
+    /// net difference is deposited and staked, or unstaked, with the staking pool.
+    ///
+    /// ## workflow
+    /// 1. locks the contract for staking and unstaking
+    /// 2. get account from the staking pool
+    /// 3. update the STAKE token value and check the limit orders for both batches - see
+    ///    [deposit_with_limits](StakingService::deposit_with_limits) and [redeem_with_limits](StakingService::redeem_with_limits)
+    ///    - if all funds in either batch are returned to the accounts, then the batch is cancelled,
+    ///      the locks are released, and the workflow ends - the remaining batch is run separately
+    /// 4. net the NEAR value of the redeemed STAKE against the NEAR being staked
+    /// 5. if more NEAR is being staked, then deposit and stake the difference
+    ///    - the redeemed STAKE NEAR value is fully covered by the NEAR being staked and becomes
+    ///      immediately available to be claimed
+    /// 6. if more NEAR is being redeemed, then unstake the difference
+    ///    - the NEAR being staked is added as liquidity for the pending withdrawal, which makes
+    ///      it immediately available to be claimed
+    ///    - the redeem lock is set to `PendingWithdrawal` and the unstaked NEAR is withdrawn via
+    ///      [unstake](StakingService::unstake)
+    /// 7. create the [StakeBatchReceipt](crate::domain::StakeBatchReceipt) and the
+    ///    [RedeemStakeBatchReceipt](crate::domain::RedeemStakeBatchReceipt)
+    /// 8. release the locks
+    ///
+    /// ## Notes
+    /// - accounts claim their batch receipts the same way as if the batches were run separately
+    /// - the account that runs the batches is rewarded for each batch - see [Keeper](crate::interface::Keeper)
+    ///
+    /// ## Panics
+    /// - if a batch is running
+    /// - if there is a pending withdrawal
+    /// - if a staking pool migration is in progress
+    /// - if there is no stake batch or no redeem stake batch to run
+    /// - if more NEAR is being redeemed and no single staking pool can cover the net NEAR to unstake
+    ///
+    /// GAS REQUIREMENTS: 150 TGas
+    fn run_netted_batches(&mut self) -> Promise;
+
+    /// Redeems STAKE instantly against NEAR liquidity, i.e., the NEAR is transferred to the predecessor
+    /// account right away without waiting for a [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+    /// to be unstaked.
+    /// - the STAKE is redeemed at the current [STAKE token value](StakingService::stake_token_value)
+    /// - the [instant redeem fee](crate::interface::Config::instant_redeem_fee_basis_points) is
+    ///   deducted from the NEAR that is paid out
+    /// - the [liquidity provider pool](crate::interface::LiquidityProvider) is drawn on first - the
+    ///   pool takes over the STAKE and redeems it via the batch workflow, and LPs earn the fee
+    /// - otherwise, the STAKE is burned against the contract's NEAR liquidity pool, and the fee is
+    ///   distributed to the user accounts when earnings are distributed. Liquidity that is needed to
+    ///   cover the pending withdrawal is reserved and cannot be used for instant redemptions.
+    ///
+    /// Returns the amount of NEAR that was transferred to the account.
+    ///
+    /// NOTE: all batch receipts are first claimed
+    ///
+    /// ## Panics
+    /// - if the account is not registered
+    /// - if the amount is zero
+    /// - if there is not enough STAKE in the account to fulfill the request
+    /// - if the NEAR liquidity is insufficient to fulfill the request
+    /// - if the NEAR amount after fees is less than `min_near_out`
+    /// - if a batch is running or the STAKE token value is being refreshed
+    fn instant_redeem(&mut self, amount: YoctoStake, min_near_out: YoctoNear) -> YoctoNear;
+
+    /// returns the max amount of NEAR liquidity that is available for a single [instant_redeem](StakingService::instant_redeem)
+    fn instant_redeem_liquidity(&self) -> YoctoNear;
+
     /// Returns the batch that is awaiting for funds to be available to be withdrawn.
     ///
-    /// NOTE: pending withdrawals blocks [RedeemStakeBatch](crate::domain::RedeemStakeBatch) to run
+    /// NOTE: pending withdrawals blocks [RedeemStakeBatch](crate::domain::RedeemStakeBatch) to run,
+    /// unless the pending withdrawal can be moved into an [UnstakedCohort](crate::interface::UnstakedCohort)
     fn pending_withdrawal(&self) -> Option<RedeemStakeBatchReceipt>;
 
-    /// Enables the user to claim receipts explicitly, which will also claim any available NEAR
-    /// liquidity to settle [RedeemStakeBatchReceipts](crate::domain::RedeemStakeBatchReceipt) that
-    /// have unstaked NEAR tokens locked in the staking pool and pending withdrawal
+    /// Returns the batches that were unstaked and are waiting for their unstaked NEAR to be withdrawn
+    /// from the staking pool, ordered by batch.
+    ///
+    /// NOTE: NEAR funds for the batch cannot be claimed until the cohort is withdrawn - see
+    /// [withdraw_unstaked_cohort](StakingService::withdraw_unstaked_cohort)
+    fn unstaked_cohorts(&self) -> Vec<UnstakedCohort>;
+
+    /// Requests to redeem STAKE are queued up in FIFO order, i.e., ordered by batch and request time.
+    /// While the batch is pending withdrawal, any NEAR liquidity that arrives is allocated to the
+    /// requests at the head of the queue, which may be partially filled. The NEAR is credited to the
+    /// account's NEAR balance.
+    ///
+    /// An account holds at most 1 request per batch - STAKE that is redeemed into a batch that the
+    /// account already has a queued request for is added to that request.
+    ///
+    /// Returns None if the account has no requests in the queue.
+    fn redeem_queue_position(&self, account_id: ValidAccountId) -> Option<RedeemQueuePosition>;
+
+    /// Enables the user to claim receipts explicitly. Any available NEAR liquidity is first allocated
+    /// to the redeem queue to settle [RedeemStakeBatchReceipts](crate::domain::RedeemStakeBatchReceipt)
+    /// that have unstaked NEAR tokens locked in the staking pool and pending withdrawal - see
+    /// [redeem_queue_position](StakingService::redeem_queue_position)
     ///
     /// ## Notes
-    /// Receipts will also be claimed implicitly when the user submits any transactions.
+    /// - Receipts will also be claimed implicitly when the user submits any transactions.
+    /// - NEAR for STAKE that was redeemed on behalf of a beneficiary is only transferred to the
+    ///   beneficiary when receipts are claimed explicitly - implicit claims hold back those batches
     ///
     /// ## Panics
     /// if account is not registered
     fn claim_receipts(&mut self);
 
+    /// Claims receipts on behalf of the specified account. This enables NEAR for STAKE that was
+    /// redeemed on behalf of a beneficiary to be delivered to the beneficiary without requiring
+    /// any action by the account that redeemed the STAKE - see [ContractTransferCallMessage::Redeem](crate::interface::ContractTransferCallMessage::Redeem)
+    ///
+    /// ## Panics
+    /// - if account is not registered
+    /// - if the predecessor is neither the account nor a beneficiary that the account redeemed STAKE for
+    fn claim_receipts_for(&mut self, account_id: ValidAccountId);
+
     /// Withdraws the specified amount from the account's available NEAR balance and transfers the
     /// funds to the account.
+    /// - the [withdraw fee](crate::config::FeeSchedule) is deducted from the amount that is transferred
     ///
     /// ## Panics
     /// - if the account is not registered
@@ -316,7 +538,7 @@ pub trait StakingService {
 
     /// Withdraws all available NEAR funds from the account and transfers the funds to the account.
     ///
-    /// Returns the amount withdrawn.
+    /// Returns the amount transferred, i.e., after the withdraw fee was deducted.
     ///
     /// ## Panics
     /// - if the account is not registered
@@ -324,6 +546,7 @@ pub trait StakingService {
 
     /// Transfers the specified amount from the account's available NEAR balance to the specified
     /// recipient account.
+    /// - the [withdraw fee](crate::config::FeeSchedule) is deducted from the amount that is transferred
     ///
     /// ## Panics
     /// - if the account is not registered
@@ -344,6 +567,34 @@ pub trait StakingService {
     /// only be known when the deposit is staked into the staking pool
     fn min_required_deposit_to_stake(&self) -> YoctoNear;
 
+    /// quotes the fee that the account would be charged per the [FeeSchedule](crate::config::FeeSchedule)
+    /// - `amount` is specified in NEAR for deposit and withdraw, and in STAKE for redeem and ft_transfer
+    /// - STAKE fees are computed using the cached STAKE token value
+    fn quote_fee(
+        &self,
+        category: FeeCategory,
+        account_id: ValidAccountId,
+        amount: U128,
+    ) -> FeeQuote;
+
+    /// quotes a deposit of `amount` NEAR, i.e., the STAKE that would be issued after fees and the
+    /// batch that the NEAR would be added to if it was deposited right now
+    /// - `account_id` is used to check for fee exemptions
+    /// - STAKE is quoted using the cached STAKE token value - the quote includes how many epochs old
+    ///   the STAKE token value is
+    fn quote_stake(&self, amount: YoctoNear, account_id: Option<ValidAccountId>) -> StakeQuote;
+
+    /// quotes a redeem request for `amount` STAKE, i.e., the NEAR value after fees, the batch that
+    /// the STAKE would be added to if it was redeemed right now, and the epoch at which the NEAR
+    /// is estimated to become available for withdrawal
+    /// - `account_id` is used to check for fee exemptions
+    /// - NEAR is quoted using the cached STAKE token value - the quote includes how many epochs old
+    ///   the STAKE token value is
+    /// - the withdrawal estimate takes into account the batch that is pending withdrawal, whether it
+    ///   can be cleared by NEAR liquidity or moved into an [UnstakedCohort], and the number of epochs
+    ///   it takes for unstaked NEAR to unlock
+    fn quote_redeem(&self, amount: YoctoStake, account_id: Option<ValidAccountId>) -> RedeemQuote;
+
     /// The only reliable way to get an accurate STAKE token value is to lock the balances on the contract
     /// while retrieving the updated staking pool account balances. The cached STAKE token value is
     /// considered current if the lookup is within the same epoch period because staking rewards are
@@ -358,14 +609,15 @@ pub trait StakingService {
     ///
     /// ### [`StakeTokenValue`] Refresh Workflow
     /// 1. Lock the contract to lock the balances while refreshing the STAKE token value
-    /// 2. Submit as batch transaction to staking pool:
+    /// 2. Submit as batch transaction to each staking pool:
     ///    2.1 Ping the staking pool contract to distribute rewards
     ///    2.2 Get updated staking account balances from the staking pool
-    /// 3. Update the cached [`StakeTokenValue`]
+    /// 3. Update the cached [`StakeTokenValue`] using the balances summed across all staking pools
     /// 4. Unlock the contract
     ///
     /// ### Panics
     /// - if the contract is locked
+    /// - if a staking pool migration is waiting on a staking pool request to complete
     fn refresh_stake_token_value(&mut self) -> Promise;
 
     /// Returns the latest cached STAKE token value
@@ -377,45 +629,68 @@ pub trait StakingService {
     ///   STAKE token value then use [`StakingService::refresh_stake_token_value`].
     /// - The STAKE token value is refreshed each time the NEAR is staked and when STAKE is redeemed.
     fn stake_token_value(&self) -> StakeTokenValue;
+
+    /// Returns up to `limit` STAKE token value samples in epoch order, starting from the first
+    /// sample at or after `from_epoch`.
+    ///
+    /// A sample is recorded each time the STAKE token value is updated - at most 1 sample is
+    /// retained per epoch, and only the most recent [STAKE_TOKEN_VALUE_HISTORY_CAPACITY](crate::domain::STAKE_TOKEN_VALUE_HISTORY_CAPACITY)
+    /// samples are retained.
+    fn stake_token_value_history(&self, from_epoch: u64, limit: u64) -> Vec<StakeTokenValue>;
+
+    /// Annualizes the change in the NEAR value of 1 STAKE from the sample that was recorded
+    /// `epochs` before the latest sample up until the latest sample.
+    ///
+    /// Returns None if there is not enough history.
+    fn apy(&self, epochs: u64) -> Option<StakeTokenValueApy>;
 }
 
 pub mod events {
-    use crate::domain::{self, BatchId, RedeemStakeBatchReceipt, StakeBatchReceipt};
-    use crate::near::YOCTO;
+    use crate::config::FeeCategory;
+    use crate::domain::{
+        self, BatchId, LimitOrderRejection, RedeemStakeBatchReceipt, StakeBatchReceipt,
+    };
+    use crate::near::{Event, STAKE_TOKEN_EVENT_STANDARD, STAKE_TOKEN_EVENT_VERSION, YOCTO};
+    use near_sdk::{
+        json_types::{U128, U64},
+        serde::Serialize,
+    };
 
-    #[derive(Debug)]
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct StakeTokenValue {
-        pub total_staked_near_balance: u128,
-        pub total_stake_supply: u128,
+        pub total_staked_near_balance: U128,
+        pub total_stake_supply: U128,
         /// the value of 1 STAKE token in NEAR
-        pub stake_value: u128,
+        pub stake_value: U128,
         /// blockchain point in time
-        pub block_height: u64,
-        pub block_timestamp: u64,
-        pub epoch_height: u64,
+        pub block_height: U64,
+        pub block_timestamp: U64,
+        pub epoch_height: U64,
     }
 
     impl From<domain::StakeTokenValue> for StakeTokenValue {
         fn from(value: domain::StakeTokenValue) -> Self {
             Self {
-                total_staked_near_balance: value.total_staked_near_balance().value(),
-                total_stake_supply: value.total_stake_supply().value(),
-                stake_value: value.stake_to_near(YOCTO.into()).value(),
-                block_height: value.block_time_height().block_height().value(),
-                block_timestamp: value.block_time_height().block_timestamp().value(),
-                epoch_height: value.block_time_height().epoch_height().value(),
+                total_staked_near_balance: value.total_staked_near_balance().value().into(),
+                total_stake_supply: value.total_stake_supply().value().into(),
+                stake_value: value.stake_to_near(YOCTO.into()).value().into(),
+                block_height: value.block_time_height().block_height().value().into(),
+                block_timestamp: value.block_time_height().block_timestamp().value().into(),
+                epoch_height: value.block_time_height().epoch_height().value().into(),
             }
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct Unstaked {
         /// corresponds to the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
-        pub batch_id: u128,
+        pub batch_id: U128,
         /// how much STAKE was redeemed in the batch
-        pub stake: u128,
+        pub stake: U128,
         /// how much NEAR was unstaked for the redeemed STAKE
-        pub near: u128,
+        pub near: U128,
         /// STAKE token value used to compute amount of NEAR to unstake for redeemed STAKE tokens
         pub stake_token_value: StakeTokenValue,
     }
@@ -423,31 +698,260 @@ pub mod events {
     impl Unstaked {
         pub fn new(batch_id: BatchId, receipt: &RedeemStakeBatchReceipt) -> Self {
             Self {
-                batch_id: batch_id.value(),
+                batch_id: batch_id.value().into(),
 
-                stake: receipt.redeemed_stake().value(),
-                near: receipt.stake_near_value().value(),
+                stake: receipt.redeemed_stake().value().into(),
+                near: receipt.stake_near_value().value().into(),
                 stake_token_value: receipt.stake_token_value().into(),
             }
         }
     }
 
-    #[derive(Debug)]
+    impl Event for Unstaked {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "unstaked";
+    }
+
+    /// logged when the stake batch and the redeem stake batch are netted against each other
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct BatchesNetted {
+        /// corresponds to the [StakeBatch](crate::domain::StakeBatch)
+        pub stake_batch_id: U128,
+        /// corresponds to the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+        pub redeem_stake_batch_id: U128,
+        /// NEAR amount that was offset between the batches without calling the staking pool
+        pub offset_near: U128,
+        /// net NEAR amount that was deposited and staked with the staking pool
+        pub staked_near: U128,
+        /// net NEAR amount that was unstaked with the staking pool
+        pub unstaked_near: U128,
+    }
+
+    impl Event for BatchesNetted {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "batches_netted";
+    }
+
+    /// logged when the batch that is pending withdrawal is moved into an unstaked cohort, which
+    /// unblocks the next batch to be unstaked
+    /// - also logged for each staking pool that the batch is unstaked from when no single staking
+    ///   pool can cover the batch
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct UnstakedCohortAdded<'a> {
+        /// corresponds to the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+        pub batch_id: U128,
+        pub staking_pool_id: &'a str,
+        /// NEAR liquidity that was applied to the batch
+        pub near_liquidity: U128,
+        /// NEAR that is pending withdrawal from the staking pool
+        pub unstaked_near: U128,
+        /// epoch within which the unstaked NEAR will be available for withdrawal
+        pub unstaked_near_withdrawal_availability: U64,
+    }
+
+    impl Event for UnstakedCohortAdded<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "unstaked_cohort_added";
+    }
+
+    /// logged when the unstaked NEAR for an unstaked cohort has been withdrawn from the staking pool
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct UnstakedCohortWithdrawn<'a> {
+        /// corresponds to the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+        pub batch_id: U128,
+        pub staking_pool_id: &'a str,
+        /// NEAR that is now available to be claimed
+        pub near: U128,
+    }
+
+    impl Event for UnstakedCohortWithdrawn<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "unstaked_cohort_withdrawn";
+    }
+
+    /// logged when the STAKE token value is updated and the staked NEAR balance has grown since it
+    /// was last updated
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct StakingRewardsAccrued {
+        /// NEAR staking rewards that were earned since the STAKE token value was last updated
+        pub rewards: U128,
+        /// NEAR value of the performance fee that is charged on the rewards
+        pub performance_fee: U128,
+        /// total staked NEAR balance, including the rewards
+        pub total_staked_near: U128,
+    }
+
+    impl Event for StakingRewardsAccrued {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "staking_rewards_accrued";
+    }
+
+    /// logged when the performance fee on staking rewards is minted as STAKE to the treasury
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct PerformanceFeeMinted<'a> {
+        pub treasury_id: &'a str,
+        /// NEAR value of the performance fee
+        pub near: U128,
+        /// STAKE that was minted for the performance fee
+        pub stake: U128,
+    }
+
+    impl Event for PerformanceFeeMinted<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "performance_fee_minted";
+    }
+
+    /// logged when the staked NEAR balance has dropped below the NEAR value of the STAKE supply,
+    /// e.g., because a staking pool was slashed
+    /// - the insurance buffer absorbs the loss first
+    /// - the uncovered loss is shared proportionally between STAKE holders, whose STAKE token value
+    ///   drops, and the redeem stake batch receipts whose unstaked NEAR is pending withdrawal
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct StakingLoss {
+        /// NEAR that was lost since the STAKE token value was last updated
+        pub loss: U128,
+        /// portion of the loss that was absorbed by the insurance buffer
+        pub insurance_buffer_absorbed: U128,
+        /// portion of the loss that was haircut from redeem stake batch receipts
+        pub receipts_haircut: U128,
+        /// portion of the loss that was passed on to STAKE holders
+        pub stake_holders_loss: U128,
+        /// NEAR value of 1 STAKE after the loss
+        pub stake_token_value: U128,
+    }
+
+    impl Event for StakingLoss {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "staking_loss";
+    }
+
+    /// logged when a redeem stake batch receipt's NEAR claims are haircut to share a staking loss
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct RedeemStakeBatchReceiptHaircut {
+        pub batch_id: U128,
+        pub haircut: U128,
+        /// NEAR value of the unclaimed STAKE after the haircut
+        pub redeemed_stake_value: U128,
+    }
+
+    impl Event for RedeemStakeBatchReceiptHaircut {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "redeem_stake_batch_receipt_haircut";
+    }
+
+    /// logged when NEAR liquidity is allocated to a request in the redeem queue
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct RedeemRequestFilled<'a> {
+        pub account_id: &'a str,
+        pub batch_id: U128,
+        /// how much STAKE was filled
+        pub stake: U128,
+        /// how much NEAR was credited to the account
+        pub near: U128,
+        /// STAKE that is still waiting to be filled
+        pub remaining_stake: U128,
+    }
+
+    impl Event for RedeemRequestFilled<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "redeem_request_filled";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct FeeCharged<'a> {
+        pub account_id: &'a str,
+        pub category: FeeCategory,
+        /// NEAR for deposit and withdraw, and STAKE for redeem and ft_transfer
+        pub fee: U128,
+        pub fee_near_value: U128,
+    }
+
+    impl Event for FeeCharged<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "fee_charged";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct StakeFeesDistributed {
+        /// STAKE fees that were transferred to the contract owner account
+        pub contract_owner_stake: U128,
+        /// STAKE fees that were burned, which distributes their value to the STAKE holders
+        pub burned_stake: U128,
+    }
+
+    impl Event for StakeFeesDistributed {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "stake_fees_distributed";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct InstantRedeemed<'a> {
+        pub account_id: &'a str,
+        /// how much STAKE was redeemed
+        pub stake: U128,
+        /// how much NEAR was paid out to the account
+        pub near: U128,
+        /// fee that was deducted from the NEAR value of the redeemed STAKE
+        pub fee: U128,
+        /// true if the NEAR was paid out of the liquidity provider pool, in which case the fee is
+        /// earned by the liquidity providers
+        pub from_liquidity_provider_pool: bool,
+        /// updated liquidity balance
+        pub near_liquidity_pool: U128,
+    }
+
+    impl Event for InstantRedeemed<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "instant_redeemed";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct NearLiquidityAdded {
         /// how liquidity was added
-        pub amount: u128,
+        pub amount: U128,
         /// updated liquidity balance
-        pub balance: u128,
+        pub balance: U128,
     }
 
-    #[derive(Debug)]
+    impl Event for NearLiquidityAdded {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "near_liquidity_added";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct Staked {
         /// corresponds to the [StakeBatch](crate::domain::StakeBatch)
-        pub batch_id: u128,
+        pub batch_id: U128,
         /// how much NEAR was staked
-        pub near: u128,
+        pub near: U128,
         /// how much STAKE was minted for the staked NEAR
-        pub stake: u128,
+        pub stake: U128,
         /// STAKE token value used to mint new STAKE
         pub stake_token_value: StakeTokenValue,
     }
@@ -455,22 +959,29 @@ pub mod events {
     impl Staked {
         pub fn new(batch_id: BatchId, receipt: &StakeBatchReceipt) -> Self {
             Self {
-                batch_id: batch_id.value(),
-                stake: receipt.near_stake_value().value(),
-                near: receipt.staked_near().value(),
+                batch_id: batch_id.value().into(),
+                stake: receipt.near_stake_value().value().into(),
+                near: receipt.staked_near().value().into(),
                 stake_token_value: receipt.stake_token_value().into(),
             }
         }
     }
 
-    #[derive(Debug)]
+    impl Event for Staked {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "staked";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct PendingWithdrawalCleared {
         /// corresponds to the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
-        pub batch_id: u128,
+        pub batch_id: U128,
         /// how much STAKE was redeemed in the batch
-        pub stake: u128,
+        pub stake: U128,
         /// how much NEAR was unstaked for the redeemed STAKE
-        pub near: u128,
+        pub near: U128,
         /// STAKE token value used to compute amount of NEAR to unstake for redeemed STAKE tokens
         pub stake_token_value: StakeTokenValue,
     }
@@ -478,61 +989,162 @@ pub mod events {
     impl PendingWithdrawalCleared {
         pub fn new(batch: &domain::RedeemStakeBatch, receipt: &RedeemStakeBatchReceipt) -> Self {
             Self {
-                batch_id: batch.id().value(),
-                stake: batch.balance().amount().value(),
+                batch_id: batch.id().value().into(),
+                stake: batch.balance().amount().value().into(),
                 near: receipt
                     .stake_token_value()
                     .stake_to_near(batch.balance().amount())
-                    .value(),
+                    .value()
+                    .into(),
                 stake_token_value: receipt.stake_token_value().into(),
             }
         }
     }
 
-    #[derive(Debug)]
+    impl Event for PendingWithdrawalCleared {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "pending_withdrawal_cleared";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct StakeBatch {
         /// corresponds to the [StakeBatch](crate::domain::StakeBatch)
-        pub batch_id: u128,
+        pub batch_id: U128,
         /// how much NEAR to staked is in the batch
-        pub near: u128,
+        pub near: U128,
     }
 
     impl From<domain::StakeBatch> for StakeBatch {
         fn from(batch: domain::StakeBatch) -> Self {
             Self {
-                batch_id: batch.id().value(),
-                near: batch.balance().amount().value(),
+                batch_id: batch.id().value().into(),
+                near: batch.balance().amount().value().into(),
             }
         }
     }
 
+    impl Event for StakeBatch {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "stake_batch";
+    }
+
     /// batch is cancelled if all funds are withdrawn
-    #[derive(Debug)]
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct StakeBatchCancelled {
-        pub batch_id: u128,
+        pub batch_id: U128,
     }
 
-    #[derive(Debug)]
+    impl Event for StakeBatchCancelled {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "stake_batch_cancelled";
+    }
+
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct RedeemStakeBatch {
         /// corresponds to the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
-        pub batch_id: u128,
+        pub batch_id: U128,
         /// how much STAKE to redeem is in the batch
-        pub stake: u128,
+        pub stake: U128,
     }
 
     impl From<domain::RedeemStakeBatch> for RedeemStakeBatch {
         fn from(batch: domain::RedeemStakeBatch) -> Self {
             Self {
-                batch_id: batch.id().value(),
-                stake: batch.balance().amount().value(),
+                batch_id: batch.id().value().into(),
+                stake: batch.balance().amount().value().into(),
             }
         }
     }
 
+    impl Event for RedeemStakeBatch {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "redeem_stake_batch";
+    }
+
     /// batch is cancelled if all funds are withdrawn
-    #[derive(Debug)]
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
     pub struct RedeemStakeBatchCancelled {
-        pub batch_id: u128,
+        pub batch_id: U128,
+    }
+
+    impl Event for RedeemStakeBatchCancelled {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "redeem_stake_batch_cancelled";
+    }
+
+    /// a deposit limit order was not filled when the stake batch was run - the NEAR was returned to
+    /// the account's NEAR balance
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct DepositLimitOrderRejected<'a> {
+        pub account_id: &'a str,
+        pub batch_id: U128,
+        /// NEAR that was returned to the account
+        pub near: U128,
+        /// STAKE that the deposit would have been issued at the batch STAKE token value
+        pub stake_out: U128,
+        pub min_stake_out: U128,
+        pub deadline_epoch: U64,
+        pub reason: LimitOrderRejection,
+    }
+
+    impl Event for DepositLimitOrderRejected<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "deposit_limit_order_rejected";
+    }
+
+    /// a redeem limit order was not filled when the redeem stake batch was run - the STAKE was
+    /// returned to the account's STAKE balance
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct RedeemLimitOrderRejected<'a> {
+        pub account_id: &'a str,
+        pub batch_id: U128,
+        /// STAKE that was returned to the account
+        pub stake: U128,
+        /// NEAR that the STAKE would have been redeemed for at the batch STAKE token value
+        pub near_out: U128,
+        pub min_near_out: U128,
+        pub deadline_epoch: U64,
+        pub reason: LimitOrderRejection,
+    }
+
+    impl Event for RedeemLimitOrderRejected<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "redeem_limit_order_rejected";
+    }
+
+    /// NEAR that was claimed for STAKE that was redeemed on behalf of a beneficiary was transferred
+    /// to the beneficiary
+    #[derive(Serialize, Debug)]
+    #[serde(crate = "near_sdk::serde")]
+    pub struct RedeemBeneficiaryPaid<'a> {
+        /// account that redeemed the STAKE
+        pub account_id: &'a str,
+        pub beneficiary_id: &'a str,
+        /// corresponds to the [RedeemStakeBatch](crate::domain::RedeemStakeBatch)
+        pub batch_id: U128,
+        /// how much redeemed STAKE was settled
+        pub stake: U128,
+        /// how much NEAR was transferred to the beneficiary
+        pub near: U128,
+    }
+
+    impl Event for RedeemBeneficiaryPaid<'_> {
+        const STANDARD: &'static str = STAKE_TOKEN_EVENT_STANDARD;
+        const VERSION: &'static str = STAKE_TOKEN_EVENT_VERSION;
+        const EVENT: &'static str = "redeem_beneficiary_paid";
     }
 
     #[cfg(test)]
@@ -540,7 +1152,7 @@ pub mod events {
 
         use super::*;
         use crate::domain::{RedeemStakeBatch, StakeTokenValue};
-        use crate::near::YOCTO;
+        use crate::near::{event_json, YOCTO};
         use crate::test_utils::*;
         use near_sdk::{testing_env, MockedBlockchain};
 
@@ -553,7 +1165,20 @@ pub mod events {
             let batch = RedeemStakeBatch::new(1.into(), (10 * YOCTO).into());
             let receipt = batch.create_receipt(StakeTokenValue::default());
             let event = Unstaked::new(batch.id(), &receipt);
-            println!("{:#?}", event);
+            let log = event_json(&[event]);
+            println!("{}", log);
+            assert!(log.starts_with(
+                r#"EVENT_JSON:{"standard":"oysterpack-stake-token","version":"1.0.0","event":"unstaked","data":[{"batch_id":"1","stake":"10000000000000000000000000","near":"10000000000000000000000000","#
+            ));
+        }
+
+        #[test]
+        fn stake_batch_cancelled_log_fmt() {
+            let log = event_json(&[StakeBatchCancelled { batch_id: 1.into() }]);
+            assert_eq!(
+                log,
+                r#"EVENT_JSON:{"standard":"oysterpack-stake-token","version":"1.0.0","event":"stake_batch_cancelled","data":[{"batch_id":"1"}]}"#
+            );
         }
     }
 }
@@ -0,0 +1,120 @@
+use crate::interface::{TokenAmount, TransferCallMessage, VaultId};
+use near_sdk::{json_types::ValidAccountId, Promise, PromiseOrValue};
+
+/// Vault based token transfers between contracts
+/// - [NEP-122 Vault-based fungible token standard](https://github.com/near/NEPs/issues/122)
+///
+/// The transferred STAKE is locked in a temporary vault instead of being credited to the receiver
+/// account. The receiver contract withdraws exactly what it needs from the vault while processing
+/// the transfer. When the transfer is resolved, the vault is deleted and whatever STAKE remains in
+/// the vault is refunded back to the sender. Because the receiver can only withdraw what is in the
+/// vault, it is not possible for the receiver to overspend the transfer amount and then return an
+/// invalid unused amount - see [FungibleToken](crate::interface::FungibleToken) "Transfer Call Refunds".
+///
+/// ## NOTES
+/// - vault storage is temporary and is paid for by the sender account out of its storage escrow -
+///   the vault is deleted and its storage is freed when the transfer is resolved
+/// - while the STAKE is locked in the vault, it is held by the contract account
+pub trait VaultFungibleToken {
+    /// Transfers positive `amount` of tokens from the `env::predecessor_account_id` into a new
+    /// temporary vault. Then calls [`VaultReceiver::on_receive_with_vault`] on the `receiver_id`
+    /// contract and attaches a callback to resolve the vault.
+    ///
+    /// The receiver contract is passed all the remaining unused gas.
+    ///
+    /// Sender must attach exactly 1 yoctoNEAR - see security section of the [FungibleToken](crate::interface::FungibleToken)
+    /// standard.
+    /// - the yoctoNEAR will be credited to the sender account's NEAR balance
+    ///
+    /// The sender is charged the [FtTransfer](crate::config::FeeCategory::FtTransfer) fee on top of
+    /// the `amount` - the fee is not refunded when the vault is resolved.
+    ///
+    /// Arguments:
+    /// - `receiver_id` - the account ID of the receiver contract. This contract will be called.
+    /// - `amount` - the amount of tokens to lock in the vault
+    /// - `payload` - a string message that will be passed to `on_receive_with_vault` contract call.
+    ///
+    /// Returns a promise to resolve the vault which will return the amount that was refunded back
+    /// to the sender - [`ResolveVault`]
+    ///
+    /// ## Panics
+    /// - if the attached deposit is not exactly 1 yoctoNEAR
+    /// - if the sender account is not registered
+    /// - if amount is zero
+    /// - if the sender account has insufficient funds to fulfill the transfer request
+    /// - if the sender account storage balance is insufficient to pay for the vault storage - deposit
+    ///   more via `storage_deposit`
+    ///
+    /// GAS REQUIREMENTS: 40 TGas + gas for receiver call
+    /// #\[payable\]
+    fn transfer_with_vault(
+        &mut self,
+        receiver_id: ValidAccountId,
+        amount: TokenAmount,
+        payload: TransferCallMessage,
+    ) -> Promise;
+
+    /// Withdraws `amount` of tokens from the vault and transfers them to `receiver_id`.
+    ///
+    /// Can only be called by the vault receiver, i.e., the contract that was called via
+    /// [`VaultReceiver::on_receive_with_vault`] while the vault is still open.
+    ///
+    /// Arguments:
+    /// - `vault_id` - vault that was passed to the receiver contract
+    /// - `receiver_id` - the account ID that the withdrawn tokens are transferred to
+    /// - `amount` - the amount of tokens to withdraw
+    ///
+    /// ## Panics
+    /// - if the vault does not exist
+    /// - if the predecessor account is not the vault receiver
+    /// - if `receiver_id` is not registered
+    /// - if amount is zero
+    /// - if the vault balance is insufficient
+    fn withdraw_from_vault(
+        &mut self,
+        vault_id: VaultId,
+        receiver_id: ValidAccountId,
+        amount: TokenAmount,
+    );
+}
+
+/// Receiver of the Fungible Token for [`VaultFungibleToken::transfer_with_vault`] calls.
+pub trait VaultReceiver {
+    /// Callback to receive tokens in a vault.
+    ///
+    /// Called by fungible token contract `env::predecessor_account_id` after `transfer_with_vault`
+    /// was initiated by `sender_id` of the given `amount` with the `payload`. The tokens are locked
+    /// in the vault and can be withdrawn via [`VaultFungibleToken::withdraw_from_vault`] - any
+    /// tokens that are not withdrawn are refunded back to the sender.
+    ///
+    /// Arguments:
+    /// - `sender_id` - the account ID that initiated the transfer.
+    /// - `amount` - the amount of tokens that were locked in the vault.
+    /// - `vault_id` - the vault that holds the tokens
+    /// - `payload` - a string message that was passed with this transfer.
+    fn on_receive_with_vault(
+        &mut self,
+        sender_id: ValidAccountId,
+        amount: TokenAmount,
+        vault_id: VaultId,
+        payload: TransferCallMessage,
+    ) -> PromiseOrValue<()>;
+}
+
+/// Callback on fungible token contract to resolve the vault.
+pub trait ResolveVault {
+    /// Callback to resolve the vault.
+    /// Private method (`env::predecessor_account_id == env::current_account_id`).
+    ///
+    /// Called after the receiver handles the transfer - the receiver promise result is ignored.
+    /// The vault is deleted and the remaining vault balance is refunded back to the `sender_id`
+    /// account.
+    ///
+    /// Returns amount that was refunded back to the sender.
+    ///
+    /// The callback should be designed to never panic.
+    /// - if the `sender_id` is not registered, then the remaining STAKE tokens will be burned
+    ///
+    /// #\[private\]
+    fn resolve_vault(&mut self, vault_id: VaultId, sender_id: ValidAccountId) -> TokenAmount;
+}
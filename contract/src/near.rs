@@ -3,8 +3,7 @@
 pub mod storage_keys;
 
 use crate::domain::{EpochHeight, YoctoNear};
-use near_sdk::env;
-use std::fmt::Debug;
+use near_sdk::{env, serde::Serialize, serde_json};
 
 /// YOCTO = 10^24
 pub const YOCTO: u128 = 1_000_000_000_000_000_000_000_000;
@@ -22,9 +21,63 @@ pub const NO_DEPOSIT: YoctoNear = YoctoNear(0);
 ///  - `const NUM_EPOCHS_TO_UNLOCK: EpochHeight = 4;`
 pub const UNSTAKED_NEAR_FUNDS_NUM_EPOCHS_TO_UNLOCK: EpochHeight = EpochHeight(4);
 
-/// wrapper around `near_sdk::env::log()` which supports structured logging
-pub fn log<T: Debug>(event: T) {
-    env::log(format!("{:#?}", event).as_bytes());
+/// [NEP-297](https://github.com/near/NEPs/blob/master/neps/nep-0297.md) event logs are prefixed with
+/// this marker, which indexers use to pick out events from regular log lines
+pub const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+/// NEP-297 `standard` for the contract's own events, i.e., events not defined by a NEP standard
+pub const STAKE_TOKEN_EVENT_STANDARD: &str = "oysterpack-stake-token";
+/// NEP-297 `version` for the contract's own events
+pub const STAKE_TOKEN_EVENT_VERSION: &str = "1.0.0";
+
+/// Events are logged as [NEP-297](https://github.com/near/NEPs/blob/master/neps/nep-0297.md) JSON:
+///
+/// `EVENT_JSON:{"standard":"...","version":"...","event":"...","data":[...]}`
+///
+/// - the (`STANDARD`, `VERSION`, `EVENT`) triple identifies the event data schema - any breaking
+///   change to the event data schema must bump the `VERSION`
+/// - `data` is always an array of events, per the NEP-141 event convention
+pub trait Event: Serialize {
+    const STANDARD: &'static str;
+    const VERSION: &'static str;
+    const EVENT: &'static str;
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a, T: Event> {
+    standard: &'static str,
+    version: &'static str,
+    event: &'static str,
+    data: &'a [T],
+}
+
+/// formats the events as a NEP-297 log line
+pub fn event_json<T: Event>(events: &[T]) -> String {
+    let log = EventLog {
+        standard: T::STANDARD,
+        version: T::VERSION,
+        event: T::EVENT,
+        data: events,
+    };
+    format!(
+        "{}{}",
+        EVENT_JSON_PREFIX,
+        serde_json::to_string(&log).expect("event should serialize to JSON")
+    )
+}
+
+/// logs the event as a NEP-297 JSON event
+pub fn log<T: Event>(event: T) {
+    log_events(&[event]);
+}
+
+/// logs the events as a single NEP-297 JSON event log line
+/// - no-op if `events` is empty
+pub fn log_events<T: Event>(events: &[T]) {
+    if !events.is_empty() {
+        env::log(event_json(events).as_bytes());
+    }
 }
 
 /// used to protect functions that transfer value against FCAK calls
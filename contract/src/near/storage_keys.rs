@@ -7,3 +7,16 @@
 pub const ACCOUNTS_KEY_PREFIX: [u8; 1] = [0];
 pub const STAKE_BATCH_RECEIPTS_KEY_PREFIX: [u8; 1] = [1];
 pub const REDEEM_STAKE_BATCH_RECEIPTS_KEY_PREFIX: [u8; 1] = [2];
+pub const ALLOWANCES_KEY_PREFIX: [u8; 1] = [3];
+pub const VAULTS_KEY_PREFIX: [u8; 1] = [4];
+pub const REDEEM_BENEFICIARIES_KEY_PREFIX: [u8; 1] = [5];
+pub const ACCOUNT_IDS_KEY_PREFIX: [u8; 1] = [6];
+pub const REDEEM_QUEUE_KEY_PREFIX: [u8; 1] = [7];
+pub const STAKE_TOKEN_VALUE_HISTORY_KEY_PREFIX: [u8; 1] = [8];
+pub const DEPOSIT_LIMIT_ORDERS_KEY_PREFIX: [u8; 1] = [9];
+pub const REDEEM_LIMIT_ORDERS_KEY_PREFIX: [u8; 1] = [10];
+pub const STAKE_BATCH_RECEIPT_IDS_KEY_PREFIX: [u8; 1] = [11];
+pub const REDEEM_STAKE_BATCH_RECEIPT_IDS_KEY_PREFIX: [u8; 1] = [12];
+pub const PENDING_BATCHES_KEY_PREFIX: [u8; 1] = [13];
+pub const REDEEM_QUEUE_ACCOUNT_REQUESTS_KEY_PREFIX: [u8; 1] = [14];
+pub const FT_HOLDER_IDS_KEY_PREFIX: [u8; 1] = [15];
@@ -25,7 +25,7 @@ pub fn to_valid_account_id(account_id: &str) -> ValidAccountId {
 }
 
 const TEST_ACCOUNT_ID: &str = "oysterpack.near";
-const TEST_STAKING_POOL_ID: &str = "staking-pool.near";
+pub const TEST_STAKING_POOL_ID: &str = "staking-pool.near";
 pub const TEST_OWNER_ID: &str = "owner.stake.oysterpack.near";
 pub const TEST_OPERATOR_ID: &str = "operator.stake.oysterpack.near";
 
@@ -20,10 +20,10 @@ impl StakingServiceClient {
         }
     }
 
-    pub fn staking_pool_id(&self, user: &UserAccount) -> AccountId {
+    pub fn staking_pools(&self, user: &UserAccount) -> Vec<interface::StakingPool> {
         let result = user.view(PendingContractTx::new(
             &self.contract_account_id,
-            "staking_pool_id",
+            "staking_pools",
             json!({}),
             true,
         ));